@@ -52,6 +52,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "1/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         };
         let unspents = vec![utxo];
         let input = BtcForkTxInput {
@@ -63,6 +64,12 @@ mod tests {
             change_address: "".to_string(),
             network: "MAINNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("BITCOINCASH", "MAINNET", "NONE", "").unwrap();
         let tran = BitcoinForkSinger::<BchAddress, LegacyTransactionSignComponent<BchSignHasher>> {
@@ -16,10 +16,12 @@ use core::result;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use tcx_chain::Address;
-use tcx_constants::btc_fork_network::{network_form_hrp, network_from_coin, BtcForkNetwork};
+use tcx_constants::btc_fork_network::{
+    network_form_hrp, network_from_coin, network_from_param, BtcForkNetwork,
+};
 use tcx_constants::coin_info::coin_info_from_param;
 use tcx_constants::CoinInfo;
-use tcx_primitive::{Ss58Codec, TypedPrivateKey, TypedPublicKey};
+use tcx_primitive::{PublicKey, Secp256k1PublicKey, Ss58Codec, TypedPrivateKey, TypedPublicKey};
 // use bitcoin::hash_types::PubkeyHash;
 
 pub trait WifDisplay {
@@ -49,10 +51,11 @@ impl Address for BtcForkAddress {
         tcx_ensure!(network.is_some(), Error::MissingNetwork);
         let network = network.expect("network");
 
-        let addr = if coin.seg_wit.as_str() == "P2WPKH" {
-            BtcForkAddress::p2shwpkh(&public_key.to_bytes(), &network)?.to_string()
-        } else {
-            BtcForkAddress::p2pkh(&public_key.to_bytes(), &network)?.to_string()
+        let addr = match coin.seg_wit.as_str() {
+            "P2WPKH" => BtcForkAddress::p2shwpkh(&public_key.to_bytes(), &network)?.to_string(),
+            "SEGWIT" => BtcForkAddress::p2wpkh(&public_key.to_bytes(), &network)?.to_string(),
+            "P2TR" => BtcForkAddress::p2tr(&public_key.to_bytes(), &network)?.to_string(),
+            _ => BtcForkAddress::p2pkh(&public_key.to_bytes(), &network)?.to_string(),
         };
         Ok(addr.to_string())
     }
@@ -96,15 +99,45 @@ impl BtcForkAddress {
         })
     }
 
+    /// BIP-86 key path only taproot address
+    pub fn p2tr(pub_key: &[u8], network: &BtcForkNetwork) -> Result<BtcForkAddress> {
+        let pub_key = Secp256k1PublicKey::from_slice(&pub_key)?;
+        Ok(BtcForkAddress {
+            payload: Payload::WitnessProgram {
+                version: bech32::u5::try_from_u8(1).expect("witness_v1"),
+                program: pub_key.tap_tweak()?,
+            },
+            network: network.clone(),
+        })
+    }
+
+    pub fn is_p2tr(&self) -> bool {
+        match self.payload {
+            Payload::WitnessProgram { version, .. } => version.to_u8() == 1,
+            _ => false,
+        }
+    }
+
     pub fn script_pubkey(&self) -> Script {
         self.payload.script_pubkey()
     }
 
+    /// The address of the standard script, None for the others such as OP_RETURN
+    pub fn from_script(script: &Script, network: &BtcForkNetwork) -> Option<BtcForkAddress> {
+        BtcAddress::from_script(script, Network::Bitcoin).map(|addr| BtcForkAddress {
+            payload: addr.payload,
+            network: network.clone(),
+        })
+    }
+
     pub fn address_like(target_addr: &str, pub_key: &[u8]) -> Result<BtcForkAddress> {
         let target = BtcForkAddress::from_str(target_addr)?;
         match target.payload {
             Payload::PubkeyHash(_) => BtcForkAddress::p2pkh(pub_key, &target.network),
             Payload::ScriptHash(_) => BtcForkAddress::p2shwpkh(pub_key, &target.network),
+            Payload::WitnessProgram { version, .. } if version.to_u8() == 1 => {
+                BtcForkAddress::p2tr(pub_key, &target.network)
+            }
             Payload::WitnessProgram {
                 version: _ver,
                 program: ref _prog,
@@ -144,6 +177,81 @@ fn bech32_network(bech32: &str) -> Option<BtcForkNetwork> {
     }
 }
 
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GEN: [u32; 5] = [
+        0x3b6a_57b2,
+        0x2650_8e6d,
+        0x1ea1_19fa,
+        0x3d42_33dd,
+        0x2a14_62b3,
+    ];
+    let mut chk: u32 = 1;
+    for v in values {
+        let b = chk >> 25;
+        chk = ((chk & 0x01ff_ffff) << 5) ^ u32::from(*v);
+        for (i, g) in GEN.iter().enumerate() {
+            if (b >> i) & 1 == 1 {
+                chk ^= g;
+            }
+        }
+    }
+    chk
+}
+
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut ret: Vec<u8> = hrp.bytes().map(|x| x >> 5).collect();
+    ret.push(0);
+    ret.extend(hrp.bytes().map(|x| x & 0x1f));
+    ret
+}
+
+/// Encode with the bech32m checksum used by the witness version 1+ addresses, bech32 0.7 only
+/// knows the original checksum.
+/// ref: https://github.com/bitcoin/bips/blob/master/bip-0350.mediawiki
+fn encode_bech32m(hrp: &str, data: &[bech32::u5]) -> String {
+    let values: Vec<u8> = data.iter().map(|x| x.to_u8()).collect();
+    let checksum_input = [bech32_hrp_expand(hrp), values.clone(), vec![0u8; 6]].concat();
+    let polymod = bech32_polymod(&checksum_input) ^ BECH32M_CONST;
+    let checksum = (0..6).map(|i| ((polymod >> (5 * (5 - i))) & 0x1f) as u8);
+
+    let charset = BECH32_CHARSET.as_bytes();
+    let encoded: String = values
+        .into_iter()
+        .chain(checksum)
+        .map(|x| charset[x as usize] as char)
+        .collect();
+    format!("{}1{}", hrp, encoded)
+}
+
+fn decode_bech32m(s: &str) -> Option<(String, Vec<bech32::u5>)> {
+    if s.to_lowercase() != s && s.to_uppercase() != s {
+        return None;
+    }
+    let s = s.to_lowercase();
+    let sep = s.rfind('1')?;
+    let (hrp, data) = (&s[..sep], &s[sep + 1..]);
+    if hrp.is_empty() || data.len() < 6 {
+        return None;
+    }
+
+    let values = data
+        .chars()
+        .map(|c| BECH32_CHARSET.find(c).map(|x| x as u8))
+        .collect::<Option<Vec<u8>>>()?;
+    if bech32_polymod(&[bech32_hrp_expand(hrp), values.clone()].concat()) != BECH32M_CONST {
+        return None;
+    }
+
+    let payload = values[..values.len() - 6]
+        .iter()
+        .map(|x| bech32::u5::try_from_u8(*x).expect("u5"))
+        .collect();
+    Some((hrp.to_string(), payload))
+}
+
 fn decode_base58(addr: &str) -> result::Result<Vec<u8>, BtcAddressError> {
     // Base58
     if addr.len() > 50 {
@@ -168,8 +276,14 @@ impl FromStr for BtcForkAddress {
         // try bech32
         let bech32_network = bech32_network(s);
         if let Some(network) = bech32_network {
-            // decode as bech32
-            let (_, payload) = bech32::decode(s)?;
+            // decode as bech32, then as bech32m for the witness version 1+ addresses
+            let (payload, is_bech32m) = match bech32::decode(s) {
+                Ok((_, payload)) => (payload, false),
+                Err(err) => match decode_bech32m(s) {
+                    Some((_, payload)) => (payload, true),
+                    None => return Err(err.into()),
+                },
+            };
             if payload.is_empty() {
                 return Err(BtcAddressError::EmptyBech32Payload);
             }
@@ -193,6 +307,17 @@ impl FromStr for BtcForkAddress {
                 return Err(BtcAddressError::InvalidSegwitV0ProgramLength(program.len()));
             }
 
+            // BIP-350, version 0 uses bech32 and the others use bech32m
+            if (version.to_u8() == 0) == is_bech32m {
+                return Err(BtcAddressError::InvalidWitnessVersion(version.to_u8()));
+            }
+
+            let network = if version.to_u8() == 1 {
+                network_from_param(network.coin, network.network, "P2TR").unwrap_or(network)
+            } else {
+                network
+            };
+
             return Ok(BtcForkAddress {
                 payload: Payload::WitnessProgram { version, program },
                 network,
@@ -283,6 +408,13 @@ impl Display for BtcForkAddress {
                 prefixed[1..].copy_from_slice(&hash[..]);
                 base58::check_encode_slice_to_fmt(fmt, &prefixed[..])
             }
+            Payload::WitnessProgram {
+                version: ver,
+                program: ref prog,
+            } if ver.to_u8() > 0 => {
+                let data = [vec![ver], bech32::ToBase32::to_base32(prog)].concat();
+                fmt.write_str(&encode_bech32m(self.network.hrp, &data))
+            }
             Payload::WitnessProgram {
                 version: ver,
                 program: ref prog,
@@ -329,7 +461,9 @@ mod tests {
     use tcx_constants::btc_fork_network::network_from_param;
 
     use tcx_constants::{CoinInfo, CurveType};
-    use tcx_primitive::{Bip32DeterministicPrivateKey, Derive, DeterministicPrivateKey, Ss58Codec};
+    use tcx_primitive::{
+        Bip32DeterministicPrivateKey, Derive, DeterministicPrivateKey, Ss58Codec, TypedPublicKey,
+    };
 
     #[test]
     pub fn test_btc_fork_address() {
@@ -360,6 +494,97 @@ mod tests {
         assert_eq!(addr, "bc1qum864wd9nwsc0u9ytkctz6wzrw6g7zdntm7f4e");
     }
 
+    #[test]
+    pub fn test_btc_address_from_public_key() {
+        let pub_key =
+            hex::decode("02506bc1dc099358e5137292f4efdd57e400f29ba5132aa5d12b18dac1c1f6aaba")
+                .unwrap();
+        let pub_key = TypedPublicKey::from_slice(CurveType::SECP256k1, &pub_key).unwrap();
+        let expects = vec![
+            ("MAINNET", "NONE", "1N3RC53vbaDNrziTdWmctBEeQ4fo4quNpq"),
+            ("MAINNET", "P2WPKH", "3Js9bGaZSQCNLudeGRHL4NExVinc25RbuG"),
+            (
+                "MAINNET",
+                "SEGWIT",
+                "bc1qum864wd9nwsc0u9ytkctz6wzrw6g7zdntm7f4e",
+            ),
+            ("TESTNET", "NONE", "n2ZNV88uQbede7C5M5jzi6SyG4GVuPpng6"),
+            ("TESTNET", "P2WPKH", "2NARMf1Wb3rhiYhGBwYuCgKEDi4zmojTsvk"),
+            (
+                "TESTNET",
+                "SEGWIT",
+                "tb1qum864wd9nwsc0u9ytkctz6wzrw6g7zdnpa96w2",
+            ),
+            (
+                "MAINNET",
+                "P2TR",
+                "bc1pqpae4d6594jj3yueluku5tlu7r6nqwm24xc8thk5g396s9e5anvq6x4n33",
+            ),
+            (
+                "TESTNET",
+                "P2TR",
+                "tb1pqpae4d6594jj3yueluku5tlu7r6nqwm24xc8thk5g396s9e5anvqdwrut7",
+            ),
+        ];
+        for (network, seg_wit, expected) in expects {
+            let coin = coin_info_from_param("BITCOIN", network, seg_wit, "").unwrap();
+            let addr = BtcForkAddress::from_public_key(&pub_key, &coin).unwrap();
+            assert_eq!(addr, expected);
+            assert!(BtcForkAddress::is_valid(&addr, &coin));
+
+            let addr = BtcForkAddress::from_str(&addr).unwrap();
+            assert_eq!(addr.network.coin, "BITCOIN");
+            assert_eq!(addr.network.network, network);
+            assert_eq!(addr.network.seg_wit, seg_wit);
+        }
+    }
+
+    #[test]
+    pub fn test_p2tr_address() {
+        // BIP-86 test vector, m/86'/0'/0'/0/0 of "abandon ... about"
+        let pub_key =
+            hex::decode("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+                .unwrap();
+        let network = network_from_param("BITCOIN", "MAINNET", "P2TR").unwrap();
+        let addr = BtcForkAddress::p2tr(&pub_key, &network).unwrap();
+        assert_eq!(
+            addr.to_string(),
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr"
+        );
+        assert!(addr.is_p2tr());
+        assert_eq!(
+            hex::encode(addr.script_pubkey().as_bytes()),
+            "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+
+        let addr = BtcForkAddress::from_str(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+        )
+        .unwrap();
+        assert!(addr.is_p2tr());
+        assert_eq!(addr.network.seg_wit, "P2TR");
+
+        // BIP-350 invalid addresses, version 1 with bech32 checksum and version 0 with bech32m
+        assert!(BtcForkAddress::from_str(
+            "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqr9a0ap"
+        )
+        .is_err());
+        assert!(BtcForkAddress::from_str("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kemeawh").is_err());
+
+        let script = BtcForkAddress::address_script_like(
+            "tb1pqpae4d6594jj3yueluku5tlu7r6nqwm24xc8thk5g396s9e5anvqdwrut7",
+            &bitcoin::PublicKey::from_str(
+                "02506bc1dc099358e5137292f4efdd57e400f29ba5132aa5d12b18dac1c1f6aaba",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(script.as_bytes()),
+            "5120007b9ab7542d65289399ff2dca2ffcf0f5303b6aa9b075ded4444ba81734ecd8"
+        );
+    }
+
     #[test]
     pub fn test_btc_fork_address_from_str() {
         let addr = BtcForkAddress::from_str("MR5Hu9zXPX3o9QuYNJGft1VMpRP418QDfW").unwrap();
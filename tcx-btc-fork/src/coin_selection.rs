@@ -0,0 +1,378 @@
+use crate::signer::DUST;
+use crate::transaction::Utxo;
+use crate::{Error, Result};
+use bitcoin::{Script, TxOut};
+
+/// The upper bound of the branches explored by branch and bound
+const BNB_TOTAL_TRIES: usize = 100_000;
+/// version, lock time, and the count of inputs and outputs
+const TX_OVERHEAD_WEIGHT: u64 = 10 * 4;
+/// segwit marker and flag
+const SEGWIT_MARKER_WEIGHT: u64 = 2;
+/// the empty witness of a legacy input in a segwit transaction
+const EMPTY_WITNESS_WEIGHT: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScriptType {
+    P2PKH,
+    P2SHP2WPKH,
+    P2WPKH,
+    P2TR,
+}
+
+impl ScriptType {
+    pub fn from_script(script: &Script) -> Result<ScriptType> {
+        let bytes = script.as_bytes();
+        if script.is_p2pkh() {
+            Ok(ScriptType::P2PKH)
+        } else if script.is_p2sh() {
+            Ok(ScriptType::P2SHP2WPKH)
+        } else if script.is_v0_p2wpkh() {
+            Ok(ScriptType::P2WPKH)
+        } else if bytes.len() == 34 && bytes[0] == 0x51 && bytes[1] == 0x20 {
+            Ok(ScriptType::P2TR)
+        } else {
+            Err(Error::UnsupportedScriptType.into())
+        }
+    }
+
+    pub fn is_witness(self) -> bool {
+        self != ScriptType::P2PKH
+    }
+
+    /// The estimated weight of an input spending this type, assuming 72 bytes DER signatures
+    /// and compressed public keys
+    pub fn input_weight(self) -> u64 {
+        // outpoint and sequence
+        let base = 36 + 4;
+        match self {
+            // script sig: <sig> <pubkey>
+            ScriptType::P2PKH => (base + 1 + 107) * 4,
+            // script sig: <0 <pubkey_hash>>, witness: <sig> <pubkey>
+            ScriptType::P2SHP2WPKH => (base + 1 + 23) * 4 + 108,
+            ScriptType::P2WPKH => (base + 1) * 4 + 108,
+            // witness: <schnorr_sig>
+            ScriptType::P2TR => (base + 1) * 4 + 66,
+        }
+    }
+}
+
+/// The weight of the output paying to the script
+pub fn output_weight(script: &Script) -> u64 {
+    let len = script.len() as u64;
+    (8 + var_int_len(len) + len) * 4
+}
+
+fn var_int_len(n: u64) -> u64 {
+    match n {
+        0..=0xfc => 1,
+        0xfd..=0xffff => 3,
+        0x10000..=0xffff_ffff => 5,
+        _ => 9,
+    }
+}
+
+/// The estimated weight of a transaction spending the inputs to the outputs
+pub fn estimate_weight(input_types: &[ScriptType], outputs_weight: u64) -> u64 {
+    let inputs_weight = input_types.iter().map(|x| x.input_weight()).sum::<u64>();
+    let mut weight = TX_OVERHEAD_WEIGHT + inputs_weight + outputs_weight;
+    if input_types.iter().any(|x| x.is_witness()) {
+        let legacy_inputs = input_types.iter().filter(|x| !x.is_witness()).count() as u64;
+        weight += SEGWIT_MARKER_WEIGHT + legacy_inputs * EMPTY_WITNESS_WEIGHT;
+    }
+    weight
+}
+
+/// The fee of the weight at the rate of sat/vB
+pub fn weight_to_fee(weight: u64, fee_rate: i64) -> i64 {
+    ((weight + 3) / 4) as i64 * fee_rate
+}
+
+#[derive(Debug, Clone)]
+pub struct CoinSelection {
+    pub unspents: Vec<Utxo>,
+    /// The fee of the transaction, the remaining less than dust is also paid to the fee
+    pub fee: i64,
+}
+
+/// Select the inputs from the candidates to pay the outputs at the fee rate of sat/vB.
+/// Branch and bound is tried first to find a changeless selection, the largest candidates
+/// are selected if there is no such selection
+pub fn select_coins(
+    candidates: &[(Utxo, ScriptType)],
+    outputs: &[TxOut],
+    change_script: &Script,
+    fee_rate: i64,
+) -> Result<CoinSelection> {
+    ensure!(fee_rate > 0, "invalid_fee_rate");
+    let target = outputs.iter().map(|x| x.value as i64).sum::<i64>();
+    let outputs_weight = outputs
+        .iter()
+        .map(|x| output_weight(&x.script_pubkey))
+        .sum::<u64>();
+    let change_weight = output_weight(change_script);
+
+    let selector = CoinSelector {
+        candidates,
+        target,
+        outputs_weight,
+        change_weight,
+        fee_rate,
+    };
+
+    if let Some(selected) = selector.branch_and_bound() {
+        if let Some(selection) = selector.finalize(&selected) {
+            return Ok(selection);
+        }
+    }
+
+    let selected = selector.largest_first().ok_or(Error::InsufficientFunds)?;
+    selector
+        .finalize(&selected)
+        .ok_or_else(|| Error::InsufficientFunds.into())
+}
+
+struct CoinSelector<'a> {
+    candidates: &'a [(Utxo, ScriptType)],
+    target: i64,
+    outputs_weight: u64,
+    change_weight: u64,
+    fee_rate: i64,
+}
+
+impl<'a> CoinSelector<'a> {
+    fn fee(&self, selected: &[usize], outputs_weight: u64) -> i64 {
+        let input_types: Vec<ScriptType> = selected.iter().map(|i| self.candidates[*i].1).collect();
+        weight_to_fee(estimate_weight(&input_types, outputs_weight), self.fee_rate)
+    }
+
+    fn total(&self, selected: &[usize]) -> i64 {
+        selected.iter().map(|i| self.candidates[*i].0.amount).sum()
+    }
+
+    /// The value of the candidate minus the fee of spending it
+    fn effective_value(&self, index: usize) -> i64 {
+        let (utxo, script_type) = &self.candidates[index];
+        utxo.amount - (script_type.input_weight() as i64 * self.fee_rate + 3) / 4
+    }
+
+    /// Search for the selection whose effective value exceeds the target by less than the
+    /// cost of a change output, ref: https://murch.one/wp-content/uploads/2016/11/erhardt2016coinselection.pdf
+    fn branch_and_bound(&self) -> Option<Vec<usize>> {
+        let mut pool: Vec<(usize, i64)> = (0..self.candidates.len())
+            .map(|i| (i, self.effective_value(i)))
+            .filter(|(_, value)| *value > 0)
+            .collect();
+        pool.sort_by(|a, b| b.1.cmp(&a.1));
+
+        let target =
+            self.target + weight_to_fee(TX_OVERHEAD_WEIGHT + self.outputs_weight, self.fee_rate);
+        let cost_of_change = weight_to_fee(self.change_weight, self.fee_rate);
+
+        let mut best: Option<(Vec<bool>, i64)> = None;
+        let mut selection: Vec<bool> = vec![];
+        let mut value = 0;
+        let mut available = pool.iter().map(|x| x.1).sum::<i64>();
+        for _ in 0..BNB_TOTAL_TRIES {
+            let backtrack = if value + available < target || value > target + cost_of_change {
+                true
+            } else if value >= target {
+                let waste = value - target;
+                if best
+                    .as_ref()
+                    .map_or(true, |(_, best_waste)| waste < *best_waste)
+                {
+                    best = Some((selection.clone(), waste));
+                }
+                true
+            } else {
+                false
+            };
+
+            if backtrack {
+                // the excluded candidates are undecided again
+                while let Some(false) = selection.last() {
+                    selection.pop();
+                    available += pool[selection.len()].1;
+                }
+                // exclude the last included candidate
+                match selection.last_mut() {
+                    Some(included) => *included = false,
+                    None => break,
+                }
+                value -= pool[selection.len() - 1].1;
+            } else {
+                let depth = selection.len();
+                available -= pool[depth].1;
+                value += pool[depth].1;
+                selection.push(true);
+            }
+        }
+
+        best.map(|(selection, _)| {
+            let mut selected: Vec<usize> = selection
+                .iter()
+                .enumerate()
+                .filter(|(_, included)| **included)
+                .map(|(depth, _)| pool[depth].0)
+                .collect();
+            selected.sort();
+            selected
+        })
+    }
+
+    fn largest_first(&self) -> Option<Vec<usize>> {
+        let mut pool: Vec<usize> = (0..self.candidates.len()).collect();
+        pool.sort_by(|a, b| {
+            self.candidates[*b]
+                .0
+                .amount
+                .cmp(&self.candidates[*a].0.amount)
+        });
+
+        let mut selected = vec![];
+        for index in pool {
+            selected.push(index);
+            if self.total(&selected) >= self.target + self.fee(&selected, self.outputs_weight) {
+                selected.sort();
+                return Some(selected);
+            }
+        }
+        None
+    }
+
+    /// Compute the fee of the selection, the change output is only added if it is not dust
+    fn finalize(&self, selected: &[usize]) -> Option<CoinSelection> {
+        let total = self.total(selected);
+        let fee = self.fee(selected, self.outputs_weight);
+        if total < self.target + fee {
+            return None;
+        }
+
+        let fee_with_change = self.fee(selected, self.outputs_weight + self.change_weight);
+        let change = total - self.target - fee_with_change;
+        let fee = if change >= DUST as i64 {
+            fee_with_change
+        } else {
+            total - self.target
+        };
+        Some(CoinSelection {
+            unspents: selected
+                .iter()
+                .map(|i| self.candidates[*i].0.clone())
+                .collect(),
+            fee,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin_hashes::hex::FromHex;
+
+    fn p2wpkh_script() -> Script {
+        Script::from(Vec::from_hex("00141a7a98a2b9fa09685d28edecb2741250e85882c3").unwrap())
+    }
+
+    fn utxo(vout: i32, amount: i64) -> (Utxo, ScriptType) {
+        (
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout,
+                amount,
+                address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                script_pub_key: "00141a7a98a2b9fa09685d28edecb2741250e85882c3".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+            ScriptType::P2WPKH,
+        )
+    }
+
+    #[test]
+    fn test_estimate_weight() {
+        let p2pkh = Script::from(
+            Vec::from_hex("76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac").unwrap(),
+        );
+        let p2wpkh = p2wpkh_script();
+        assert_eq!(ScriptType::from_script(&p2pkh).unwrap(), ScriptType::P2PKH);
+        assert_eq!(
+            ScriptType::from_script(&p2wpkh).unwrap(),
+            ScriptType::P2WPKH
+        );
+
+        let weight = estimate_weight(&[ScriptType::P2PKH], output_weight(&p2pkh) * 2);
+        assert_eq!((weight + 3) / 4, 226);
+        let weight = estimate_weight(&[ScriptType::P2WPKH], output_weight(&p2wpkh) * 2);
+        assert_eq!((weight + 3) / 4, 141);
+        let weight = estimate_weight(
+            &[ScriptType::P2SHP2WPKH, ScriptType::P2PKH],
+            output_weight(&p2wpkh),
+        );
+        assert_eq!(weight, 40 + 364 + 592 + 124 + 2 + 1);
+
+        let op_return = Script::from(Vec::from_hex("6a0568656c6c6f").unwrap());
+        assert_eq!(
+            format!("{}", ScriptType::from_script(&op_return).err().unwrap()),
+            "unsupported_script_type"
+        );
+    }
+
+    #[test]
+    fn test_select_coins_without_change() {
+        let candidates = vec![
+            utxo(0, 100000),
+            utxo(1, 30000),
+            utxo(2, 50000),
+            utxo(3, 20000),
+        ];
+        // 1 P2WPKH input: 10.5 + 68 + 31 = 109.5 vB, 2 inputs: 177.5 vB
+        let outputs = vec![TxOut {
+            value: 50000 - 110,
+            script_pubkey: p2wpkh_script(),
+        }];
+        let selection = select_coins(&candidates, &outputs, &p2wpkh_script(), 1).unwrap();
+        assert_eq!(selection.unspents.len(), 1);
+        assert_eq!(selection.unspents[0].vout, 2);
+        assert_eq!(selection.fee, 110);
+
+        let outputs = vec![TxOut {
+            value: 80000 - 178,
+            script_pubkey: p2wpkh_script(),
+        }];
+        let selection = select_coins(&candidates, &outputs, &p2wpkh_script(), 1).unwrap();
+        let vouts: Vec<i32> = selection.unspents.iter().map(|x| x.vout).collect();
+        assert_eq!(vouts, vec![1, 2]);
+        assert_eq!(selection.fee, 178);
+    }
+
+    #[test]
+    fn test_select_coins_with_change() {
+        let candidates = vec![utxo(0, 100000), utxo(1, 30000), utxo(2, 50000)];
+        let outputs = vec![TxOut {
+            value: 120000,
+            script_pubkey: p2wpkh_script(),
+        }];
+        let selection = select_coins(&candidates, &outputs, &p2wpkh_script(), 10).unwrap();
+        let vouts: Vec<i32> = selection.unspents.iter().map(|x| x.vout).collect();
+        assert_eq!(vouts, vec![0, 2]);
+        // 10.5 + 68 * 2 + 31 * 2 = 208.5 vB
+        assert_eq!(selection.fee, 2090);
+    }
+
+    #[test]
+    fn test_select_coins_insufficient_funds() {
+        let candidates = vec![utxo(0, 100000), utxo(1, 30000)];
+        let outputs = vec![TxOut {
+            value: 130000,
+            script_pubkey: p2wpkh_script(),
+        }];
+        let ret = select_coins(&candidates, &outputs, &p2wpkh_script(), 1);
+        assert_eq!(format!("{}", ret.err().unwrap()), "insufficient_funds");
+        let ret = select_coins(&candidates, &outputs, &p2wpkh_script(), 0);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_fee_rate");
+    }
+}
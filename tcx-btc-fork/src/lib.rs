@@ -1,5 +1,8 @@
 pub mod address;
 pub mod bip143_with_forkid;
+pub mod coin_selection;
+pub mod message;
+pub mod psbt;
 pub mod signer;
 pub mod transaction;
 
@@ -19,9 +22,14 @@ extern crate tcx_chain;
 pub type Result<T> = result::Result<T, failure::Error>;
 
 pub use signer::{BitcoinForkSinger, BtcForkSegWitTransaction, BtcForkTransaction};
-pub use transaction::{BtcForkSignedTxOutput, BtcForkTxInput, Utxo};
+pub use transaction::{
+    BtcForkMessageInput, BtcForkMessageOutput, BtcForkSignedTxOutput, BtcForkTxInput,
+    BtcForkTxOutput, PsbtInput, PsbtOutput, Utxo,
+};
 
 pub use address::{BtcForkAddress, PubKeyScript, WifDisplay};
+pub use coin_selection::{select_coins, CoinSelection, ScriptType};
+pub use psbt::create_psbt;
 pub use signer::ScriptPubKeyComponent;
 
 #[derive(Fail, Debug)]
@@ -32,6 +40,24 @@ pub enum Error {
     UnsupportedChain,
     #[fail(display = "missing_network")]
     MissingNetwork,
+    #[fail(display = "invalid_psbt")]
+    InvalidPsbt,
+    #[fail(display = "psbt_utxo_missing")]
+    PsbtUtxoMissing,
+    #[fail(display = "psbt_utxo_mismatch")]
+    PsbtUtxoMismatch,
+    #[fail(display = "psbt_redeem_script_missing")]
+    PsbtRedeemScriptMissing,
+    #[fail(display = "psbt_key_mismatch")]
+    PsbtKeyMismatch,
+    #[fail(display = "unsupported_sighash_type")]
+    UnsupportedSighashType,
+    #[fail(display = "unsupported_script_type")]
+    UnsupportedScriptType,
+    #[fail(display = "insufficient_funds")]
+    InsufficientFunds,
+    #[fail(display = "invalid_tx_hash")]
+    InvalidTxHash,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -0,0 +1,163 @@
+//! Sign the message like the `signmessage` of Bitcoin Core
+//! ref:
+//! - https://github.com/bitcoin/bips/blob/master/bip-0137.mediawiki
+
+use crate::transaction::{BtcForkMessageInput, BtcForkMessageOutput};
+use crate::{Error, Result};
+
+use bitcoin::consensus::encode::VarInt;
+use bitcoin::consensus::serialize;
+use bitcoin_hashes::{sha256d, Hash};
+use tcx_chain::{ChainSigner, Keystore, MessageSigner};
+
+const BITCOIN_MESSAGE_PREFIX: &str = "Bitcoin Signed Message:\n";
+const LITECOIN_MESSAGE_PREFIX: &str = "Litecoin Signed Message:\n";
+
+/// The hash to sign, both the prefix and the message are prefixed by their length
+pub fn signed_message_hash(symbol: &str, message: &[u8]) -> Vec<u8> {
+    let prefix = if symbol == "LITECOIN" {
+        LITECOIN_MESSAGE_PREFIX
+    } else {
+        BITCOIN_MESSAGE_PREFIX
+    };
+
+    let mut data = serialize(&VarInt(prefix.len() as u64));
+    data.extend_from_slice(prefix.as_bytes());
+    data.extend(serialize(&VarInt(message.len() as u64)));
+    data.extend_from_slice(message);
+    sha256d::Hash::hash(&data).into_inner().to_vec()
+}
+
+/// The first byte of the signature is the recovery id plus the offset of the address type,
+/// all the keys of the wallet are compressed
+fn header_offset(seg_wit: &str) -> Result<u8> {
+    match seg_wit {
+        "NONE" => Ok(31),
+        "P2WPKH" => Ok(35),
+        "SEGWIT" => Ok(39),
+        _ => Err(Error::UnsupportedScriptType.into()),
+    }
+}
+
+impl MessageSigner<BtcForkMessageInput, BtcForkMessageOutput> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &BtcForkMessageInput,
+    ) -> Result<BtcForkMessageOutput> {
+        let offset = {
+            let account = self
+                .account(symbol, address)
+                .ok_or_else(|| format_err!("account_not_found"))?;
+            header_offset(&account.seg_wit)?
+        };
+
+        let data = if message.is_hex {
+            let value = message.message.as_str();
+            if value.starts_with("0x") || value.starts_with("0X") {
+                hex::decode(&value[2..])?
+            } else {
+                hex::decode(value)?
+            }
+        } else {
+            message.message.as_bytes().to_vec()
+        };
+
+        let hash = signed_message_hash(symbol, &data);
+        let sign_result = self.sign_recoverable_hash(&hash, symbol, address, None)?;
+        let mut signature = vec![sign_result[64] + offset];
+        signature.extend_from_slice(&sign_result[..64]);
+        Ok(BtcForkMessageOutput {
+            signature: base64::encode(&signature),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::address::BtcForkAddress;
+    use crate::transaction::BtcForkMessageInput;
+    use tcx_chain::{Keystore, MessageSigner, Metadata};
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::{TEST_MNEMONIC, TEST_PASSWORD};
+
+    fn sign(chain_type: &str, seg_wit: &str, path: &str, message: &str, is_hex: bool) -> String {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let network = if chain_type == "LITECOIN" {
+            "MAINNET"
+        } else {
+            "TESTNET"
+        };
+        let mut coin_info = coin_info_from_param(chain_type, network, seg_wit, "").unwrap();
+        coin_info.derivation_path = path.to_string();
+        let address = keystore
+            .derive_coin::<BtcForkAddress>(&coin_info)
+            .unwrap()
+            .address
+            .to_string();
+
+        let input = BtcForkMessageInput {
+            message: message.to_string(),
+            is_hex,
+        };
+        keystore
+            .sign_message(chain_type, &address, &input)
+            .unwrap()
+            .signature
+    }
+
+    #[test]
+    fn sign_message() {
+        assert_eq!(
+            sign("BITCOIN", "NONE", "m/44'/1'/0'/0/0", "hello world", false),
+            "H+MGzZzNkCApBSqSpn4O1h5c2hIdIPNFF/fb7+0uPoMwK21FCd8pkLFWZH1KYltM0mKGFQys30MRSH8QaZ2Z5ic="
+        );
+        assert_eq!(
+            sign("BITCOIN", "P2WPKH", "m/49'/1'/0'/0/0", "hello world", false),
+            "JLLkxalupiPEGbO3RW9s0eXHZ1QuJlkjYi9/J/AV+xDhG+T4xeJujl3meJhb0wXaK7vP6fR8vElz8WslPd2S1OE="
+        );
+        assert_eq!(
+            sign("BITCOIN", "SEGWIT", "m/84'/1'/0'/0/0", "hello world", false),
+            "Jz0h04r2n2BqTDA0aVyIv5K2mVS6Au2jWfgcRCixtRYEGtWF7OFxbaWXxXjL3EL0rwpDDGS2iysJH7JqW0/wFO4="
+        );
+        assert_eq!(
+            sign("LITECOIN", "NONE", "m/44'/2'/0'/0/0", "hello world", false),
+            "IPq5fY/jKapUNS+bzjbIWA1/y5osA0WReB+YfR5gfMt3McMNiBVAeEiezmm6oQ41dmOBCYy06gQAbn1VvETBPrY="
+        );
+    }
+
+    #[test]
+    fn sign_hex_message() {
+        assert_eq!(
+            sign("BITCOIN", "NONE", "m/44'/1'/0'/0/0", "0x68656c6c6f20776f726c64", true),
+            "H+MGzZzNkCApBSqSpn4O1h5c2hIdIPNFF/fb7+0uPoMwK21FCd8pkLFWZH1KYltM0mKGFQys30MRSH8QaZ2Z5ic="
+        );
+    }
+
+    #[test]
+    fn sign_message_with_taproot_address() {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let coin_info = coin_info_from_param("BITCOIN", "MAINNET", "P2TR", "").unwrap();
+        let address = keystore
+            .derive_coin::<BtcForkAddress>(&coin_info)
+            .unwrap()
+            .address
+            .to_string();
+        let input = BtcForkMessageInput {
+            message: "hello world".to_string(),
+            is_hex: false,
+        };
+        let actual = keystore.sign_message("BITCOIN", &address, &input);
+        assert_eq!(
+            format!("{}", actual.err().unwrap()),
+            "unsupported_script_type"
+        );
+    }
+}
@@ -0,0 +1,511 @@
+//! Create and sign the partially signed bitcoin transaction
+//! ref:
+//! - https://github.com/bitcoin/bips/blob/master/bip-0174.mediawiki
+
+use crate::signer::{
+    BitcoinForkSinger, BitcoinTransactionSignComponent, ScriptPubKeyComponent, SIGHASH_ALL,
+};
+use crate::transaction::{PsbtInput, PsbtOutput};
+use crate::{Error, Result};
+
+use bitcoin::blockdata::script::Builder;
+use bitcoin::consensus::{deserialize, serialize};
+use bitcoin::util::bip143::SighashComponents;
+use bitcoin::util::bip32::{DerivationPath, Fingerprint};
+use bitcoin::util::psbt::PartiallySignedTransaction;
+use bitcoin::{Script, SigHashType, TxOut};
+use bitcoin_hashes::{hash160, Hash};
+use std::str::FromStr;
+use tcx_chain::{self_check_signature_by, Address, Keystore, TransactionSigner};
+use tcx_primitive::{get_account_path, PrivateKey, PublicKey, Secp256k1PrivateKey};
+
+/// Create the PSBT of a transaction for the signer holding the keys, e.g. a cold storage device
+/// of a watch-only wallet. The previous transactions are not known, so each input carries the
+/// spent output as the witness utxo, and the BIP-32 derivation of its key unless the master
+/// fingerprint of an extended key is unknown
+pub fn create_psbt<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>(
+    keystore: &mut Keystore,
+    symbol: &str,
+    address: &str,
+    tx: &BitcoinForkSinger<S, T>,
+) -> Result<PsbtOutput> {
+    let change_script_pubkey = tx.change_script_pubkey(keystore, symbol, address)?;
+    let tx = tx.select_coins(&change_script_pubkey)?;
+    let unsigned_tx = tx.unsigned_transaction(change_script_pubkey)?;
+    let mut psbt = PartiallySignedTransaction::from_unsigned_tx(unsigned_tx)
+        .map_err(|_| Error::InvalidPsbt)?;
+    let master_fingerprint = keystore.master_fingerprint().ok();
+
+    for (index, unspent) in tx.tx_input.unspents.iter().enumerate() {
+        let script_pubkey = if unspent.script_pub_key.is_empty() {
+            S::address_script_pub_key(&unspent.address)?
+        } else {
+            Script::from(hex::decode(&unspent.script_pub_key)?)
+        };
+
+        // the key of the utxo is derived from the account like the signer does
+        let (account, pub_key, path) = if unspent.derived_path.is_empty() {
+            let account = keystore
+                .account(symbol, &unspent.address)
+                .ok_or_else(|| format_err!("account_not_found"))?;
+            let public_key = hex::decode(account.public_key.as_ref().map_or("", |x| x.as_str()))?;
+            let pub_key =
+                bitcoin::PublicKey::from_slice(&public_key).map_err(|_| Error::InvalidPsbt)?;
+            (account, pub_key, account.derivation_path.to_string())
+        } else {
+            let account = keystore
+                .account(symbol, address)
+                .ok_or_else(|| format_err!("account_not_found"))?;
+            let pub_key = BitcoinForkSinger::<S, T>::derive_pub_key_at_path(
+                &account.ext_pub_key,
+                &unspent.derived_path,
+            )?;
+            let account_path = get_account_path(&account.derivation_path)?;
+            let path = format!("{}/{}", account_path, unspent.derived_path);
+            (account, pub_key, path)
+        };
+
+        let pub_key_hash = hash160::Hash::hash(&pub_key.to_bytes());
+        let key_source = match &master_fingerprint {
+            Some(fingerprint) => Some((Fingerprint::from(&fingerprint[..]), path)),
+            // a single key is the master key of itself
+            None if account.ext_pub_key.is_empty() => {
+                Some((Fingerprint::from(&pub_key_hash[..4]), "m".to_string()))
+            }
+            None => None,
+        };
+
+        let input = &mut psbt.inputs[index];
+        if script_pubkey.is_p2sh() {
+            input.redeem_script = Some(
+                Builder::new()
+                    .push_int(0)
+                    .push_slice(&pub_key_hash[..])
+                    .into_script(),
+            );
+        }
+        if let Some((fingerprint, path)) = key_source {
+            let path = DerivationPath::from_str(&path).map_err(|_| Error::InvalidPsbt)?;
+            input.hd_keypaths.insert(pub_key, (fingerprint, path));
+        }
+        input.witness_utxo = Some(TxOut {
+            value: unspent.amount as u64,
+            script_pubkey,
+        });
+    }
+
+    Ok(PsbtOutput {
+        psbt: base64::encode(&serialize(&psbt)),
+        is_finalized: false,
+    })
+}
+
+impl TransactionSigner<PsbtInput, PsbtOutput> for Keystore {
+    fn sign_transaction(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        tx: &PsbtInput,
+    ) -> Result<PsbtOutput> {
+        let psbt_bytes = base64::decode(&tx.psbt).map_err(|_| Error::InvalidPsbt)?;
+        let mut psbt: PartiallySignedTransaction =
+            deserialize(&psbt_bytes).map_err(|_| Error::InvalidPsbt)?;
+
+        for index in 0..psbt.inputs.len() {
+            let derivations: Vec<(bitcoin::PublicKey, Fingerprint, DerivationPath)> = psbt.inputs
+                [index]
+                .hd_keypaths
+                .iter()
+                .map(|(pub_key, (fingerprint, path))| (*pub_key, *fingerprint, path.clone()))
+                .collect();
+
+            for (pub_key, fingerprint, path) in derivations {
+                if psbt.inputs[index].partial_sigs.contains_key(&pub_key) {
+                    continue;
+                }
+                if let Some(key) =
+                    find_signing_key(self, symbol, address, &pub_key, &fingerprint, &path)?
+                {
+                    let hash = signature_hash(&psbt, index)?;
+                    let signature = key.sign(&hash)?;
+                    self_check_signature_by(|| key.public_key().verify(&hash, &signature))?;
+                    let sig = [signature, vec![SIGHASH_ALL]].concat();
+                    psbt.inputs[index].partial_sigs.insert(pub_key, sig);
+                }
+            }
+        }
+
+        if tx.auto_finalize {
+            for index in 0..psbt.inputs.len() {
+                finalize_input(&mut psbt, index)?;
+            }
+        }
+
+        let is_finalized = psbt
+            .inputs
+            .iter()
+            .all(|x| x.final_script_sig.is_some() || x.final_script_witness.is_some());
+        Ok(PsbtOutput {
+            psbt: base64::encode(&serialize(&psbt)),
+            is_finalized,
+        })
+    }
+}
+
+/// Find the private key of a BIP-32 derivation in the input, the hd keystore only signs with the
+/// keys under the accounts derived for the chain, the private key keystore matches the public key
+fn find_signing_key(
+    keystore: &mut Keystore,
+    symbol: &str,
+    address: &str,
+    pub_key: &bitcoin::PublicKey,
+    fingerprint: &Fingerprint,
+    path: &DerivationPath,
+) -> Result<Option<Secp256k1PrivateKey>> {
+    let private_key = if keystore.determinable() {
+        if keystore.master_fingerprint()? != fingerprint[..] {
+            return Ok(None);
+        }
+
+        let path = path.to_string();
+        let mut found = None;
+        for account in keystore.accounts().iter().filter(|x| x.coin == symbol) {
+            let account_path = get_account_path(&account.derivation_path)?;
+            if path.starts_with(&format!("{}/", account_path)) {
+                found = Some((
+                    account.address.to_string(),
+                    path[account_path.len() + 1..].to_string(),
+                ));
+                break;
+            }
+        }
+
+        match found {
+            Some((main_address, relative_path)) => {
+                let key =
+                    keystore.find_private_key_by_path(symbol, &main_address, &relative_path)?;
+                let key = key.as_secp256k1()?.clone();
+                tcx_ensure!(
+                    key.public_key().to_bytes() == pub_key.to_bytes(),
+                    Error::PsbtKeyMismatch
+                );
+                key
+            }
+            None => return Ok(None),
+        }
+    } else {
+        let key = keystore
+            .find_private_key(symbol, address)?
+            .as_secp256k1()?
+            .clone();
+        if key.public_key().to_bytes() != pub_key.to_bytes() {
+            return Ok(None);
+        }
+        key
+    };
+
+    Ok(Some(private_key))
+}
+
+fn spent_output(psbt: &PartiallySignedTransaction, index: usize) -> Result<TxOut> {
+    let input = &psbt.inputs[index];
+    let prev_out = &psbt.global.unsigned_tx.input[index].previous_output;
+
+    if let Some(utxo) = &input.witness_utxo {
+        return Ok(utxo.clone());
+    }
+
+    let prev_tx = input
+        .non_witness_utxo
+        .as_ref()
+        .ok_or(Error::PsbtUtxoMissing)?;
+    tcx_ensure!(prev_tx.txid() == prev_out.txid, Error::PsbtUtxoMismatch);
+    prev_tx
+        .output
+        .get(prev_out.vout as usize)
+        .cloned()
+        .ok_or_else(|| Error::PsbtUtxoMismatch.into())
+}
+
+fn signature_hash(psbt: &PartiallySignedTransaction, index: usize) -> Result<Vec<u8>> {
+    let input = &psbt.inputs[index];
+    tcx_ensure!(
+        input.sighash_type.is_none() || input.sighash_type == Some(SigHashType::All),
+        Error::UnsupportedSighashType
+    );
+
+    let tx = &psbt.global.unsigned_tx;
+    let prev_out = spent_output(psbt, index)?;
+    let script_pubkey = if prev_out.script_pubkey.is_p2sh() {
+        input
+            .redeem_script
+            .clone()
+            .ok_or(Error::PsbtRedeemScriptMissing)?
+    } else {
+        prev_out.script_pubkey.clone()
+    };
+
+    let hash = if let Some(witness_script) = &input.witness_script {
+        SighashComponents::new(tx).sighash_all(&tx.input[index], witness_script, prev_out.value)
+    } else if script_pubkey.is_v0_p2wpkh() {
+        let script_code = format!("76a914{}88ac", hex::encode(&script_pubkey.as_bytes()[2..]));
+        let script_code = Script::from(hex::decode(script_code)?);
+        SighashComponents::new(tx).sighash_all(&tx.input[index], &script_code, prev_out.value)
+    } else {
+        tx.signature_hash(index, &script_pubkey, u32::from(SIGHASH_ALL))
+    };
+    Ok(hash.into_inner().to_vec())
+}
+
+/// Finalize the single key inputs(P2PKH, P2WPKH and P2SH-P2WPKH), the others are left to the
+/// finalizer who knows the script
+fn finalize_input(psbt: &mut PartiallySignedTransaction, index: usize) -> Result<()> {
+    let input = &psbt.inputs[index];
+    if input.final_script_sig.is_some()
+        || input.final_script_witness.is_some()
+        || input.partial_sigs.len() != 1
+    {
+        return Ok(());
+    }
+
+    let script_pubkey = spent_output(psbt, index)?.script_pubkey;
+    let input = &mut psbt.inputs[index];
+    let (pub_key, sig) = input
+        .partial_sigs
+        .iter()
+        .next()
+        .map(|(pub_key, sig)| (pub_key.to_bytes(), sig.clone()))
+        .expect("partial_sig");
+
+    if script_pubkey.is_p2pkh() {
+        input.final_script_sig = Some(
+            Builder::new()
+                .push_slice(&sig)
+                .push_slice(&pub_key)
+                .into_script(),
+        );
+    } else if script_pubkey.is_v0_p2wpkh() {
+        input.final_script_witness = Some(vec![sig, pub_key]);
+    } else if script_pubkey.is_p2sh()
+        && input
+            .redeem_script
+            .as_ref()
+            .map_or(false, |x| x.is_v0_p2wpkh())
+    {
+        let redeem_script = input.redeem_script.clone().expect("redeem_script");
+        input.final_script_sig = Some(
+            Builder::new()
+                .push_slice(redeem_script.as_bytes())
+                .into_script(),
+        );
+        input.final_script_witness = Some(vec![sig, pub_key]);
+    } else {
+        return Ok(());
+    }
+
+    input.partial_sigs.clear();
+    input.hd_keypaths.clear();
+    input.sighash_type = None;
+    input.redeem_script = None;
+    input.witness_script = None;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::BtcForkAddress;
+    use crate::transaction::{BtcForkSignedTxOutput, BtcForkTxInput, Utxo};
+    use crate::BtcForkSegWitTransaction;
+    use tcx_chain::{Metadata, WatchKeystore};
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::{TEST_MNEMONIC, TEST_PASSWORD};
+    use tcx_crypto::TypedKdfParams;
+
+    const UNSIGNED_PSBT: &str = "cHNidP8BAMYCAAAAA61iQcPcjTA1s1To1oPcPQEIsTsM4bUzbY5h27ww5K49AAAAAAD9////rWJBw9yNMDWzVOjWg9w9AQixOwzhtTNtjmHbvDDkrj0BAAAAAP3///+tYkHD3I0wNbNU6NaD3D0BCLE7DOG1M22OYdu8MOSuPQIAAAAA/f///wKQ0AMAAAAAABYAFBp6mKK5+gloXSjt7LJ0ElDoWILDaL8AAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrAAAAAAAAQCVAgAAAAEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAP////8BUf////8DoIYBAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrKCGAQAAAAAAF6kULSse9e5M9sPryM9mpgJ4N5j3h1mHoIYBAAAAAAAWABQaepiiufoJaF0o7eyydBJQ6FiCwwAAAAAiBgM9cQq0W7VKyZYYrSOzwdpmFjGqJfI7/p0itBh28dRuThgUaNupLAAAgAEAAIAAAACAAAAAAAAAAAAAAQEgoIYBAAAAAAAXqRQtKx717kz2w+vIz2amAng3mPeHWYcBBBYAFGVPuwgmfz1Q1xWo8au1WXmxYN1bIgYDGu5eIDmdaM8ANdGiFWSGjyK8RIqyBSkrQnkTaxXsrrwYFGjbqTEAAIABAACAAAAAgAAAAAAAAAAAAAEBH6CGAQAAAAAAFgAUGnqYorn6CWhdKO3ssnQSUOhYgsMiBgLiT2JaMcmouuQiOfK/lFowbAGkUKA/0SMxbbDoN6ZgwBgUaNupVAAAgAEAAIAAAACAAAAAAAAAAAAAAAA=";
+
+    fn hd_keystore() -> Keystore {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let derivations = vec![
+            ("m/44'/1'/0'/0/0", "NONE"),
+            ("m/49'/1'/0'/0/0", "P2WPKH"),
+            ("m/84'/1'/0'/0/0", "SEGWIT"),
+        ];
+        for (path, seg_wit) in derivations {
+            let mut coin_info = coin_info_from_param("BITCOIN", "TESTNET", seg_wit, "").unwrap();
+            coin_info.derivation_path = path.to_string();
+            keystore.derive_coin::<BtcForkAddress>(&coin_info).unwrap();
+        }
+        keystore
+    }
+
+    fn watch_keystore() -> Keystore {
+        let mut keystore = Keystore::Watch(
+            WatchKeystore::from_key_expression(
+                "[1468dba9/49h/1h/0h]tpubDCwNET9ErXmBracx3ZBfi6rXQZRjYkpitFe23FAW9M3RcCw4aveNC4SAV5yYrFDjtP3b46eFfv4VtiYP3EXoTZsbnJia2yNznExS8EEcACv",
+                TEST_PASSWORD,
+                Metadata::default(),
+                TypedKdfParams::default(),
+            )
+            .unwrap(),
+        );
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let mut coin_info = coin_info_from_param("BITCOIN", "TESTNET", "P2WPKH", "").unwrap();
+        coin_info.derivation_path = "m/49'/1'/0'/0/0".to_string();
+        keystore.derive_coin::<BtcForkAddress>(&coin_info).unwrap();
+        keystore
+    }
+
+    fn decode_psbt(psbt: &str) -> PartiallySignedTransaction {
+        deserialize(&base64::decode(psbt).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn sign_psbt() {
+        let mut keystore = hd_keystore();
+        let input = PsbtInput {
+            psbt: UNSIGNED_PSBT.to_string(),
+            auto_finalize: false,
+        };
+        let output = keystore
+            .sign_transaction("BITCOIN", "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", &input)
+            .unwrap();
+        assert!(!output.is_finalized);
+
+        let psbt = decode_psbt(&output.psbt);
+        let expected_sigs = vec![
+            ("033d710ab45bb54ac99618ad23b3c1da661631aa25f23bfe9d22b41876f1d46e4e", "3045022100ce930c4a3da5a69814c6aac9fad6f456341bf0792a8090591acaae9bc0c525df022022ba3cd34d43c2ac10b37ad3428f86351466da292c74c380166a53c01e235ceb01"),
+            ("031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc", "304402207202947dfcb9238a7d28a7cfc26031c8e96571b5b719a4aa447012ac22a3690f02205cf08c477de6197cac2db8db8d327826a24ebef8a223133bfb37512ab7fcce1e01"),
+            ("02e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c0", "304402203512d140b197f7b99a781ba6a5d9f48f1d8fb26333151b92bd8f57300e3fb9d302207a3f40e8df6cee4eab71798477f24a6a400d5825284f15b55baafccc951e0a0a01"),
+        ];
+        for (index, (pub_key, sig)) in expected_sigs.iter().enumerate() {
+            let partial_sigs = &psbt.inputs[index].partial_sigs;
+            assert_eq!(partial_sigs.len(), 1);
+            let (k, v) = partial_sigs.iter().next().unwrap();
+            assert_eq!(&hex::encode(k.to_bytes()), pub_key);
+            assert_eq!(&hex::encode(v), sig);
+        }
+
+        // signing again keeps the existing signatures and finalizes the inputs
+        let input = PsbtInput {
+            psbt: output.psbt,
+            auto_finalize: true,
+        };
+        let output = keystore
+            .sign_transaction("BITCOIN", "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", &input)
+            .unwrap();
+        assert!(output.is_finalized);
+
+        let tx = decode_psbt(&output.psbt).extract_tx();
+        assert_eq!(hex::encode(serialize(&tx)), "02000000000103ad6241c3dc8d3035b354e8d683dc3d0108b13b0ce1b5336d8e61dbbc30e4ae3d000000006b483045022100ce930c4a3da5a69814c6aac9fad6f456341bf0792a8090591acaae9bc0c525df022022ba3cd34d43c2ac10b37ad3428f86351466da292c74c380166a53c01e235ceb0121033d710ab45bb54ac99618ad23b3c1da661631aa25f23bfe9d22b41876f1d46e4efdffffffad6241c3dc8d3035b354e8d683dc3d0108b13b0ce1b5336d8e61dbbc30e4ae3d0100000017160014654fbb08267f3d50d715a8f1abb55979b160dd5bfdffffffad6241c3dc8d3035b354e8d683dc3d0108b13b0ce1b5336d8e61dbbc30e4ae3d0200000000fdffffff0290d00300000000001600141a7a98a2b9fa09685d28edecb2741250e85882c368bf0000000000001976a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac000247304402207202947dfcb9238a7d28a7cfc26031c8e96571b5b719a4aa447012ac22a3690f02205cf08c477de6197cac2db8db8d327826a24ebef8a223133bfb37512ab7fcce1e0121031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc0247304402203512d140b197f7b99a781ba6a5d9f48f1d8fb26333151b92bd8f57300e3fb9d302207a3f40e8df6cee4eab71798477f24a6a400d5825284f15b55baafccc951e0a0a012102e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c000000000");
+    }
+
+    #[test]
+    fn sign_psbt_skips_foreign_inputs() {
+        let foreign_psbt = "cHNidP8BAMYCAAAAA61iQcPcjTA1s1To1oPcPQEIsTsM4bUzbY5h27ww5K49AAAAAAD9////rWJBw9yNMDWzVOjWg9w9AQixOwzhtTNtjmHbvDDkrj0BAAAAAP3///+tYkHD3I0wNbNU6NaD3D0BCLE7DOG1M22OYdu8MOSuPQIAAAAA/f///wKQ0AMAAAAAABYAFBp6mKK5+gloXSjt7LJ0ElDoWILDaL8AAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrAAAAAAAAQCVAgAAAAEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAP////8BUf////8DoIYBAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrKCGAQAAAAAAF6kULSse9e5M9sPryM9mpgJ4N5j3h1mHoIYBAAAAAAAWABQaepiiufoJaF0o7eyydBJQ6FiCwwAAAAAiBgM9cQq0W7VKyZYYrSOzwdpmFjGqJfI7/p0itBh28dRuThgBAgMELAAAgAEAAIAAAACAAAAAAAAAAAAAAQEgoIYBAAAAAAAXqRQtKx717kz2w+vIz2amAng3mPeHWYcBBBYAFGVPuwgmfz1Q1xWo8au1WXmxYN1bIgYDGu5eIDmdaM8ANdGiFWSGjyK8RIqyBSkrQnkTaxXsrrwYAQIDBDEAAIABAACAAAAAgAAAAAAAAAAAAAEBH6CGAQAAAAAAFgAUGnqYorn6CWhdKO3ssnQSUOhYgsMiBgLiT2JaMcmouuQiOfK/lFowbAGkUKA/0SMxbbDoN6ZgwBgBAgMEVAAAgAEAAIAAAACAAAAAAAAAAAAAAAA=";
+        let mut keystore = hd_keystore();
+        let input = PsbtInput {
+            psbt: foreign_psbt.to_string(),
+            auto_finalize: true,
+        };
+        let output = keystore
+            .sign_transaction("BITCOIN", "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", &input)
+            .unwrap();
+        assert!(!output.is_finalized);
+        let psbt = decode_psbt(&output.psbt);
+        assert!(psbt.inputs.iter().all(|x| x.partial_sigs.is_empty()));
+
+        // the keys are only used for the accounts of the chain
+        let input = PsbtInput {
+            psbt: UNSIGNED_PSBT.to_string(),
+            auto_finalize: false,
+        };
+        let output = keystore
+            .sign_transaction("LITECOIN", "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", &input)
+            .unwrap();
+        let psbt = decode_psbt(&output.psbt);
+        assert!(psbt.inputs.iter().all(|x| x.partial_sigs.is_empty()));
+    }
+
+    #[test]
+    fn create_psbt_by_watch_keystore() {
+        let input = BtcForkTxInput {
+            to: "mpke4CzhBTV2dFZpnABT9EN1kPc4vDWZxw".to_string(),
+            amount: 50000,
+            unspents: vec![Utxo {
+                tx_hash: "3dae4e30bcdb618e6d33b5e10c3bb108013ddc83d6e854b335308ddcc34162ad"
+                    .to_string(),
+                vout: 1,
+                amount: 100000,
+                address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                derived_path: "0/0".to_string(),
+                ..Utxo::default()
+            }],
+            fee: 2000,
+            change_address: "2N3wqj1hfobkc7tNazut4dZH8KgWbVH4sJc".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "P2WPKH".to_string(),
+            ..BtcForkTxInput::default()
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "P2WPKH", "").unwrap();
+        let tx = BtcForkSegWitTransaction::new(input, coin_info);
+
+        let mut watch_keystore = watch_keystore();
+        let ret: Result<BtcForkSignedTxOutput> =
+            watch_keystore.sign_transaction("BITCOIN", "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB", &tx);
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "watch_only_keystore_has_no_private_key"
+        );
+
+        let output = create_psbt(
+            &mut watch_keystore,
+            "BITCOIN",
+            "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB",
+            &tx,
+        )
+        .unwrap();
+        assert!(!output.is_finalized);
+        let psbt = decode_psbt(&output.psbt);
+        let (pub_key, (fingerprint, path)) = psbt.inputs[0].hd_keypaths.iter().next().unwrap();
+        assert_eq!(
+            hex::encode(pub_key.to_bytes()),
+            "031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc"
+        );
+        assert_eq!(fingerprint.to_string(), "1468dba9");
+        assert_eq!(path.to_string(), "m/49'/1'/0'/0/0");
+
+        // the cold wallet signs the same transaction as signing it directly
+        let mut keystore = hd_keystore();
+        let input = PsbtInput {
+            psbt: output.psbt,
+            auto_finalize: true,
+        };
+        let output = keystore
+            .sign_transaction("BITCOIN", "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB", &input)
+            .unwrap();
+        assert!(output.is_finalized);
+
+        let signed: BtcForkSignedTxOutput = keystore
+            .sign_transaction("BITCOIN", "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB", &tx)
+            .unwrap();
+        let tx = decode_psbt(&output.psbt).extract_tx();
+        assert_eq!(hex::encode(serialize(&tx)), signed.signature);
+    }
+
+    #[test]
+    fn sign_invalid_psbt() {
+        let mut keystore = hd_keystore();
+        let input = PsbtInput {
+            psbt: "cHNidP8B".to_string(),
+            auto_finalize: false,
+        };
+        let ret =
+            keystore.sign_transaction("BITCOIN", "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", &input);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_psbt");
+    }
+}
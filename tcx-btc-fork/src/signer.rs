@@ -1,15 +1,17 @@
-use tcx_chain::{Keystore, TransactionSigner};
+use tcx_chain::{self_check_signature_by, Keystore, TransactionSigner, TxRecipient, TxSummary};
 
 use bitcoin::{OutPoint, Script, Transaction, TxIn, TxOut};
 use bitcoin_hashes::sha256d::Hash as Hash256;
-use bitcoin_hashes::{sha256d, Hash};
+use bitcoin_hashes::{sha256, sha256d, Hash};
 
-use crate::Result;
+use crate::{Error, Result};
+use bitcoin::blockdata::opcodes;
 use bitcoin::blockdata::script::Builder;
 use bitcoin::consensus::serialize;
 use std::str::FromStr;
 
 use crate::address::BtcForkAddress;
+use crate::coin_selection::{select_coins, ScriptType};
 use crate::transaction::{BtcForkSignedTxOutput, BtcForkTxInput, Utxo};
 use bitcoin::util::bip143::SighashComponents;
 use bitcoin_hashes::hash160;
@@ -17,14 +19,21 @@ use bitcoin_hashes::hex::FromHex as HashFromHex;
 use bitcoin_hashes::hex::ToHex as HashToHex;
 use std::marker::PhantomData;
 use tcx_chain::Address;
+use tcx_constants::btc_fork_network::network_from_coin;
 use tcx_constants::CoinInfo;
 use tcx_primitive::{
-    Bip32DeterministicPublicKey, Derive, DeterministicPublicKey, FromHex, PrivateKey, PublicKey,
-    TypedDeterministicPublicKey,
+    tagged_hash, Bip32DeterministicPublicKey, Derive, DeterministicPublicKey, FromHex, PrivateKey,
+    PublicKey, Secp256k1PrivateKey, TypedDeterministicPublicKey,
 };
 
-const DUST: u64 = 546;
-const SIGHASH_ALL: u8 = 0x01;
+pub(crate) const DUST: u64 = 546;
+const MAX_OP_RETURN_SIZE: usize = 80;
+const SEQUENCE_FINAL: u32 = 0xFFFF_FFFF;
+const SEQUENCE_MAX_NON_FINAL: u32 = 0xFFFF_FFFE;
+const SEQUENCE_RBF: u32 = 0xFFFF_FFFD;
+/// BIP-68 relative lock time is disabled if this bit is set
+const SEQUENCE_LOCKTIME_DISABLE_FLAG: u32 = 1 << 31;
+pub(crate) const SIGHASH_ALL: u8 = 0x01;
 
 pub trait ScriptPubKeyComponent {
     fn address_script_like(target_addr: &str, pub_key: &bitcoin::PublicKey) -> Result<Script>;
@@ -48,12 +57,8 @@ impl<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>
         address: &str,
         tx: &BitcoinForkSinger<S, T>,
     ) -> Result<BtcForkSignedTxOutput> {
-        let change_address = if self.determinable() {
-            let dpk = self.find_deterministic_public_key(symbol, address)?;
-            tx.change_address(&dpk)?
-        } else {
-            S::address_script_pub_key(&address)?
-        };
+        let change_address = tx.change_script_pubkey(self, symbol, address)?;
+        let tx = tx.select_coins(&change_address)?;
 
         let mut sks = vec![];
 
@@ -89,20 +94,184 @@ impl<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>
         }
     }
 
-    fn receive_script_pubkey(&self) -> Result<Script> {
-        S::address_script_pub_key(&self.tx_input.to)
+    /// The script receiving the change, it is derived from the wallet if `change_address` is empty
+    pub fn change_script_pubkey(
+        &self,
+        keystore: &mut Keystore,
+        symbol: &str,
+        address: &str,
+    ) -> Result<Script> {
+        if keystore.determinable() {
+            let dpk = keystore.find_deterministic_public_key(symbol, address)?;
+            self.change_address(&dpk)
+        } else {
+            S::address_script_pub_key(&address)
+        }
+    }
+
+    /// Summarize what the transaction pays, the change output is listed as a recipient marked
+    /// with `is_change`
+    pub fn decode_transaction(&self, change_script_pubkey: Script) -> Result<TxSummary> {
+        let tx = self.select_coins(&change_script_pubkey)?;
+        let tx_outs = tx.tx_outs(change_script_pubkey)?;
+        let network = network_from_coin(&self.coin_info);
+        let script_address = |script: &Script| {
+            network
+                .as_ref()
+                .and_then(|network| BtcForkAddress::from_script(script, network))
+                .map(|addr| addr.to_string())
+                .unwrap_or_else(|| hex::encode(script.as_bytes()))
+        };
+
+        let mut senders: Vec<String> = vec![];
+        for unspent in &tx.tx_input.unspents {
+            if !senders.contains(&unspent.address) {
+                senders.push(unspent.address.clone());
+            }
+        }
+
+        let mut addresses = vec![];
+        if !tx.tx_input.to.is_empty() {
+            addresses.push(tx.tx_input.to.clone());
+        }
+        for output in &tx.tx_input.outputs {
+            addresses.push(output.address.clone());
+        }
+        let recipients = tx_outs
+            .iter()
+            .filter(|x| !x.script_pubkey.is_op_return())
+            .enumerate()
+            .map(|(i, x)| {
+                let address = match addresses.get(i) {
+                    Some(address) if !address.is_empty() => address.clone(),
+                    // the change output is the one after the payments
+                    None if !tx.tx_input.change_address.is_empty() => {
+                        tx.tx_input.change_address.clone()
+                    }
+                    _ => script_address(&x.script_pubkey),
+                };
+                TxRecipient {
+                    address,
+                    amount: x.value.to_string(),
+                    is_change: i >= addresses.len(),
+                }
+            })
+            .collect();
+
+        let total_amount = tx.tx_input.unspents.iter().map(|x| x.amount).sum::<i64>();
+        let out_amount = tx_outs.iter().map(|x| x.value as i64).sum::<i64>();
+        Ok(TxSummary {
+            senders,
+            recipients,
+            fee: (total_amount - out_amount).to_string(),
+            data: tx.tx_input.op_return.clone(),
+            ..TxSummary::default()
+        })
+    }
+
+    /// Select the inputs from the unspents and compute the fee if the fee rate is set,
+    /// otherwise the unspents and the fee are kept as they are
+    pub fn select_coins(&self, change_script_pubkey: &Script) -> Result<Self> {
+        if self.tx_input.fee_rate == 0 {
+            return Ok(Self::new(self.tx_input.clone(), self.coin_info.clone()));
+        }
+        ensure!(self.tx_input.fee == 0, "fee_conflicts_with_fee_rate");
+
+        let mut candidates = vec![];
+        for unspent in &self.tx_input.unspents {
+            let script_pubkey = if unspent.script_pub_key.is_empty() {
+                S::address_script_pub_key(&unspent.address)?
+            } else {
+                Script::from(hex::decode(&unspent.script_pub_key)?)
+            };
+            candidates.push((unspent.clone(), ScriptType::from_script(&script_pubkey)?));
+        }
+        let mut outputs = self.receive_tx_outs()?;
+        outputs.extend(self.op_return_tx_out()?);
+
+        let selection = select_coins(
+            &candidates,
+            &outputs,
+            change_script_pubkey,
+            self.tx_input.fee_rate,
+        )?;
+        let tx_input = BtcForkTxInput {
+            unspents: selection.unspents,
+            fee: selection.fee,
+            ..self.tx_input.clone()
+        };
+        Ok(Self::new(tx_input, self.coin_info.clone()))
+    }
+
+    /// The payments of `to`/`amount` and the `outputs`
+    fn receive_tx_outs(&self) -> Result<Vec<TxOut>> {
+        let mut tx_outs = vec![];
+        if !self.tx_input.to.is_empty() {
+            tx_outs.push((
+                S::address_script_pub_key(&self.tx_input.to)?,
+                self.tx_input.amount,
+            ));
+        }
+        for output in &self.tx_input.outputs {
+            let script_pubkey = if output.script_pub_key.is_empty() {
+                S::address_script_pub_key(&output.address)?
+            } else {
+                Script::from(hex::decode(&output.script_pub_key)?)
+            };
+            tx_outs.push((script_pubkey, output.amount));
+        }
+
+        let mut ret = vec![];
+        for (script_pubkey, amount) in tx_outs {
+            ensure!(amount >= DUST as i64, "amount_less_than_minimum");
+            ret.push(TxOut {
+                value: amount as u64,
+                script_pubkey,
+            });
+        }
+        Ok(ret)
+    }
+
+    fn op_return_tx_out(&self) -> Result<Option<TxOut>> {
+        if self.tx_input.op_return.is_empty() {
+            return Ok(None);
+        }
+
+        let data = hex::decode(&self.tx_input.op_return)?;
+        ensure!(data.len() <= MAX_OP_RETURN_SIZE, "op_return_data_too_long");
+        let script_pubkey = Builder::new()
+            .push_opcode(opcodes::all::OP_RETURN)
+            .push_slice(&data)
+            .into_script();
+        Ok(Some(TxOut {
+            value: 0,
+            script_pubkey,
+        }))
     }
 
+    /// The change goes to `1/{change_address_index}` of the account unless `change_address` is
+    /// set, its script type follows the script of the first input
     fn change_address(&self, dpk: &TypedDeterministicPublicKey) -> Result<Script> {
         if !self.tx_input.change_address.is_empty() {
-            S::address_script_pub_key(&self.tx_input.change_address)
-        } else {
-            let from = &self.tx_input.unspents.first().expect("first_utxo").address;
-            // todo: address is error
-            let _change_path = format!("1/{}", &self.tx_input.change_address_index);
-            let pub_key = dpk.public_key().as_secp256k1()?.0;
-            S::address_script_like(&from, &pub_key)
+            return S::address_script_pub_key(&self.tx_input.change_address);
         }
+
+        let first = self
+            .tx_input
+            .unspents
+            .first()
+            .ok_or(Error::InsufficientFunds)?;
+        let from = network_from_coin(&self.coin_info)
+            .filter(|_| !first.script_pub_key.is_empty())
+            .and_then(|network| {
+                let script = Script::from(hex::decode(&first.script_pub_key).ok()?);
+                BtcForkAddress::from_script(&script, &network)
+            })
+            .map(|addr| addr.to_string())
+            .unwrap_or_else(|| first.address.clone());
+        let change_path = format!("1/{}", self.tx_input.change_address_index);
+        let pub_key = dpk.derive(&change_path)?.public_key().as_secp256k1()?.0;
+        S::address_script_like(&from, &pub_key)
     }
 
     pub fn derive_pub_key_at_path(xpub: &str, child_path: &str) -> Result<bitcoin::PublicKey> {
@@ -120,25 +289,21 @@ impl<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>
             total_amount += unspent.amount;
         }
 
+        let mut tx_outs: Vec<TxOut> = self.receive_tx_outs()?;
+        let op_return_tx_out = self.op_return_tx_out()?;
         ensure!(
-            self.tx_input.amount >= DUST as i64,
-            "amount_less_than_minimum"
+            !tx_outs.is_empty() || op_return_tx_out.is_some(),
+            "outputs_empty"
         );
 
+        let amount = tx_outs.iter().map(|x| x.value as i64).sum::<i64>();
         ensure!(
-            total_amount >= (self.tx_input.amount + self.tx_input.fee),
+            total_amount >= (amount + self.tx_input.fee),
             "total amount must ge amount + fee"
         );
 
-        let mut tx_outs: Vec<TxOut> = vec![];
-
-        let receive_script_pubkey = self.receive_script_pubkey()?;
-        let receiver_tx_out = TxOut {
-            value: self.tx_input.amount as u64,
-            script_pubkey: receive_script_pubkey,
-        };
-        tx_outs.push(receiver_tx_out);
-        let change_amount = total_amount - self.tx_input.amount - self.tx_input.fee;
+        tx_outs.extend(op_return_tx_out);
+        let change_amount = total_amount - amount - self.tx_input.fee;
 
         if change_amount >= DUST as i64 {
             let change_tx_out = TxOut {
@@ -150,21 +315,88 @@ impl<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>
         Ok(tx_outs)
     }
 
-    fn tx_inputs(&self) -> Vec<TxIn> {
-        let mut tx_inputs: Vec<TxIn> = vec![];
+    fn tx_version(&self) -> Result<i32> {
+        match self.tx_input.version {
+            0 => Ok(T::tx_version()),
+            1 | 2 => Ok(self.tx_input.version),
+            _ => Err(format_err!("unsupported_tx_version")),
+        }
+    }
 
+    /// The sequence of the inputs without an explicit one, the lock time only takes effect
+    /// if any of the inputs is not final
+    fn default_sequence(&self) -> u32 {
+        if self.tx_input.rbf {
+            SEQUENCE_RBF
+        } else if self.tx_input.lock_time > 0 {
+            SEQUENCE_MAX_NON_FINAL
+        } else {
+            SEQUENCE_FINAL
+        }
+    }
+
+    fn sequences(&self, tx_version: i32) -> Result<Vec<u32>> {
+        let default_sequence = self.default_sequence();
+        let mut sequences = vec![];
         for unspent in &self.tx_input.unspents {
+            ensure!(
+                unspent.sequence >= 0 && unspent.sequence <= i64::from(SEQUENCE_FINAL),
+                "invalid_sequence"
+            );
+            let sequence = if unspent.has_sequence || unspent.sequence != 0 {
+                unspent.sequence as u32
+            } else {
+                default_sequence
+            };
+            ensure!(
+                !self.tx_input.rbf || sequence < SEQUENCE_MAX_NON_FINAL,
+                "sequence_not_signal_rbf"
+            );
+            ensure!(
+                tx_version >= 2
+                    || sequence >= SEQUENCE_MAX_NON_FINAL
+                    || sequence & SEQUENCE_LOCKTIME_DISABLE_FLAG != 0,
+                "relative_lock_time_requires_version_2"
+            );
+            sequences.push(sequence);
+        }
+        ensure!(
+            self.tx_input.lock_time == 0 || sequences.iter().any(|x| *x != SEQUENCE_FINAL),
+            "lock_time_requires_non_final_sequence"
+        );
+        Ok(sequences)
+    }
+
+    fn tx_inputs(&self, sequences: &[u32]) -> Result<Vec<TxIn>> {
+        let mut tx_inputs: Vec<TxIn> = vec![];
+
+        for (unspent, sequence) in self.tx_input.unspents.iter().zip(sequences) {
             tx_inputs.push(TxIn {
                 previous_output: OutPoint {
-                    txid: bitcoin::hash_types::Txid::from_hex(&unspent.tx_hash).expect("tx_hash"),
+                    txid: bitcoin::hash_types::Txid::from_hex(&unspent.tx_hash)
+                        .map_err(|_| Error::InvalidTxHash)?,
                     vout: unspent.vout as u32,
                 },
                 script_sig: Script::new(),
-                sequence: 0xFFFF_FFFF,
+                sequence: *sequence,
                 witness: vec![],
             });
         }
-        tx_inputs
+        Ok(tx_inputs)
+    }
+
+    /// The transaction before the inputs are signed, the unspents must be selected already
+    pub fn unsigned_transaction(&self, change_addr_pubkey: Script) -> Result<Transaction> {
+        let tx_outs = self.tx_outs(change_addr_pubkey)?;
+        let version = self.tx_version()?;
+        let sequences = self.sequences(version)?;
+        let tx_inputs = self.tx_inputs(&sequences)?;
+        Ok(Transaction {
+            version,
+            lock_time: self.tx_input.lock_time,
+            input: tx_inputs,
+            output: tx_outs,
+        })
     }
 
     pub fn sign_transaction(
@@ -172,15 +404,7 @@ impl<S: ScriptPubKeyComponent + Address, T: BitcoinTransactionSignComponent>
         keys: &[impl PrivateKey],
         change_addr_pubkey: Script,
     ) -> Result<BtcForkSignedTxOutput> {
-        let tx_outs = self.tx_outs(change_addr_pubkey)?;
-        let tx_inputs = self.tx_inputs();
-        let tx = Transaction {
-            version: T::tx_version(),
-            lock_time: 0,
-            input: tx_inputs,
-            output: tx_outs,
-        };
-
+        let tx = self.unsigned_transaction(change_addr_pubkey)?;
         let signed_tx = T::sign_inputs(&tx, &self.tx_input.unspents, &keys)?;
         let tx_bytes = serialize(&signed_tx);
 
@@ -197,6 +421,7 @@ pub trait BitcoinTransactionSignComponent {
         unspents: &[Utxo],
         keys: &[impl PrivateKey],
     ) -> Result<Transaction>;
+    /// The default version of the transaction
     fn tx_version() -> i32;
 
     fn sign_hash_and_pub_key(
@@ -205,40 +430,123 @@ pub trait BitcoinTransactionSignComponent {
         sign_hash: u8,
     ) -> Result<(Vec<u8>, Vec<u8>)> {
         let signature_bytes = pri_key.sign(&hash)?;
+        let pub_key = pri_key.public_key();
+        self_check_signature_by(|| pub_key.verify(&hash, &signature_bytes))?;
         let raw_bytes: Vec<u8> = vec![sign_hash];
         let sig_bytes: Vec<u8> = [signature_bytes, raw_bytes].concat();
-        let pub_key = pri_key.public_key();
         let pub_key_bytes = pub_key.to_bytes();
         Ok((sig_bytes, pub_key_bytes.to_vec()))
     }
 }
 
+/// BIP-341 signature hash of a key path spending with SIGHASH_DEFAULT
+/// ref: https://github.com/bitcoin/bips/blob/master/bip-0341.mediawiki#common-signature-message
+fn taproot_sighash(tx: &Transaction, index: usize, prevouts: &[TxOut]) -> Vec<u8> {
+    let hash = |data: Vec<u8>| sha256::Hash::hash(&data).into_inner().to_vec();
+    let sha_prevouts = hash(
+        tx.input
+            .iter()
+            .flat_map(|x| serialize(&x.previous_output))
+            .collect(),
+    );
+    let sha_amounts = hash(prevouts.iter().flat_map(|x| serialize(&x.value)).collect());
+    let sha_script_pubkeys = hash(
+        prevouts
+            .iter()
+            .flat_map(|x| serialize(&x.script_pubkey))
+            .collect(),
+    );
+    let sha_sequences = hash(
+        tx.input
+            .iter()
+            .flat_map(|x| serialize(&x.sequence))
+            .collect(),
+    );
+    let sha_outputs = hash(tx.output.iter().flat_map(serialize).collect());
+
+    let sig_msg = [
+        // epoch and hash type
+        vec![0x00, 0x00],
+        serialize(&tx.version),
+        serialize(&tx.lock_time),
+        sha_prevouts,
+        sha_amounts,
+        sha_script_pubkeys,
+        sha_sequences,
+        sha_outputs,
+        // spend type, key path without annex
+        vec![0x00],
+        serialize(&(index as u32)),
+    ]
+    .concat();
+    tagged_hash("TapSighash", &sig_msg)
+}
+
 pub struct SegWitTransactionSignComponent {}
 
 impl SegWitTransactionSignComponent {
+    /// Returns the script sig and witness of each input, every input is signed according to
+    /// the type of its address, so P2PKH, P2SH-P2WPKH, P2WPKH and P2TR inputs can be mixed
     fn witness_sign(
         tx: &Transaction,
         unspents: &[Utxo],
         keys: &[impl PrivateKey],
-    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
-        let mut witnesses: Vec<(Vec<u8>, Vec<u8>)> = vec![];
+    ) -> Result<Vec<(Script, Vec<Vec<u8>>)>> {
+        let mut addresses = vec![];
+        let mut prevouts = vec![];
+        for unspent in unspents {
+            let addr = BtcForkAddress::from_str(&unspent.address)?;
+            prevouts.push(TxOut {
+                value: unspent.amount as u64,
+                script_pubkey: addr.script_pubkey(),
+            });
+            addresses.push(addr);
+        }
+
+        let mut witnesses: Vec<(Script, Vec<Vec<u8>>)> = vec![];
         let shc = SighashComponents::new(&tx);
         for i in 0..tx.input.len() {
+            let script_pubkey = &prevouts[i].script_pubkey;
+            if addresses[i].is_p2tr() {
+                let prv_key = Secp256k1PrivateKey::from_slice(&keys[i].to_bytes())?.tap_tweak()?;
+                let hash = taproot_sighash(tx, i, &prevouts);
+                let signature = prv_key.sign_schnorr(&hash)?;
+                self_check_signature_by(|| prv_key.public_key().verify_schnorr(&hash, &signature))?;
+                witnesses.push((Script::new(), vec![signature]));
+                continue;
+            }
+
+            let prv_key = &keys[i];
+            if script_pubkey.is_p2pkh() {
+                let hash = tx.signature_hash(i, script_pubkey, u32::from(SIGHASH_ALL));
+                let (sig, pub_key) =
+                    Self::sign_hash_and_pub_key(prv_key, &hash.into_inner(), SIGHASH_ALL)?;
+                let script_sig = Builder::new()
+                    .push_slice(&sig)
+                    .push_slice(&pub_key)
+                    .into_script();
+                witnesses.push((script_sig, vec![]));
+                continue;
+            }
+
             let tx_in = &tx.input[i];
             let unspent = &unspents[i];
-            let pub_key = &keys[i].public_key();
+            let pub_key = &prv_key.public_key();
             let pub_key_bytes = pub_key.to_bytes();
             let pub_key_hash = hash160::Hash::hash(&pub_key_bytes).into_inner();
             let script_hex = format!("76a914{}88ac", hex::encode(pub_key_hash));
             let script = Script::from(hex::decode(script_hex)?);
             let hash = shc.sighash_all(tx_in, &script, unspent.amount as u64);
 
-            let prv_key = &keys[i];
-            witnesses.push(Self::sign_hash_and_pub_key(
-                prv_key,
-                &hash.into_inner(),
-                SIGHASH_ALL,
-            )?);
+            let (sig, pub_key) =
+                Self::sign_hash_and_pub_key(prv_key, &hash.into_inner(), SIGHASH_ALL)?;
+            let script_sig = if script_pubkey.is_v0_p2wpkh() {
+                Script::new()
+            } else {
+                let script_sig = format!("160014{}", hex::encode(&pub_key_hash));
+                Script::from(hex::decode(script_sig)?)
+            };
+            witnesses.push((script_sig, vec![sig, pub_key]));
         }
         Ok(witnesses)
     }
@@ -250,26 +558,19 @@ impl BitcoinTransactionSignComponent for SegWitTransactionSignComponent {
         unspents: &[Utxo],
         keys: &[impl PrivateKey],
     ) -> Result<Transaction> {
-        let witnesses: Vec<(Vec<u8>, Vec<u8>)> = Self::witness_sign(tx, unspents, keys)?;
+        let witnesses: Vec<(Script, Vec<Vec<u8>>)> = Self::witness_sign(tx, unspents, keys)?;
         let input_with_sigs = tx
             .input
             .iter()
-            .enumerate()
-            .map(|(i, txin)| {
-                let pub_key = &keys[i].public_key();
-                let pub_key_bytes = pub_key.to_bytes();
-                let hash = hash160::Hash::hash(&pub_key_bytes).into_inner();
-                let hex = format!("160014{}", hex::encode(&hash));
-
-                TxIn {
-                    script_sig: Script::from(hex::decode(hex).expect("script_sig")),
-                    witness: vec![witnesses[i].0.clone(), witnesses[i].1.clone()],
-                    ..*txin
-                }
+            .zip(witnesses.into_iter())
+            .map(|(txin, (script_sig, witness))| TxIn {
+                script_sig,
+                witness,
+                ..*txin
             })
             .collect();
         Ok(Transaction {
-            version: Self::tx_version(),
+            version: tx.version,
             lock_time: tx.lock_time,
             input: input_with_sigs,
             output: tx.output.clone(),
@@ -350,7 +651,7 @@ impl<H: SignHasher> BitcoinTransactionSignComponent for LegacyTransactionSignCom
             })
             .collect();
         Ok(Transaction {
-            version: Self::tx_version(),
+            version: tx.version,
             lock_time: tx.lock_time,
             input: input_with_sigs,
             output: tx.output.clone(),
@@ -373,9 +674,13 @@ mod tests {
     use super::*;
 
     use super::BitcoinForkSinger;
+    use crate::transaction::BtcForkTxOutput;
     use tcx_chain::{Keystore, TransactionSigner};
     use tcx_constants::coin_info::coin_info_from_param;
-    use tcx_primitive::Secp256k1PrivateKey;
+    use tcx_constants::TEST_MNEMONIC;
+    use tcx_primitive::{
+        Bip32DeterministicPrivateKey, DeterministicPrivateKey, Secp256k1PrivateKey,
+    };
 
     #[test]
     fn test_sign_ltc() {
@@ -387,6 +692,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
@@ -397,6 +703,12 @@ mod tests {
             change_address: "".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -430,6 +742,7 @@ mod tests {
             script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mmuf77YiGckWgfvd32viaj7EKfrUN1FdAz".to_string(),
@@ -440,6 +753,12 @@ mod tests {
             change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -477,6 +796,7 @@ mod tests {
                 script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
                 derived_path: "0/0".to_string(),
                 sequence: 0,
+                has_sequence: false,
             },
             Utxo {
                 tx_hash: "57c935201d6abf4b32151f9d96bfb51b058824a601011c3432e751b0a6d4a100"
@@ -487,6 +807,7 @@ mod tests {
                 script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
                 derived_path: "0/0".to_string(),
                 sequence: 0,
+                has_sequence: false,
             },
         ];
         let tx_input = BtcForkTxInput {
@@ -498,6 +819,12 @@ mod tests {
             change_address: "".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -533,6 +860,7 @@ mod tests {
             script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
             derived_path: "0/1".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mmuf77YiGckWgfvd32viaj7EKfrUN1FdAz".to_string(),
@@ -543,6 +871,12 @@ mod tests {
             change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -578,6 +912,7 @@ mod tests {
             script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
             derived_path: "hello//ggg".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mmuf77YiGckWgfvd32viaj7EKfrUN1FdAz".to_string(),
@@ -588,6 +923,12 @@ mod tests {
             change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -622,6 +963,7 @@ mod tests {
             script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mmuf77YiGckWgfvd32viaj7EKfrUN1FdAz".to_string(),
@@ -632,6 +974,12 @@ mod tests {
             change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -660,6 +1008,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tx_input = BtcForkTxInput {
@@ -671,6 +1020,12 @@ mod tests {
             change_address: "".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let tran =
             BitcoinForkSinger::<BtcForkAddress, LegacyTransactionSignComponent<LegacySignHasher>> {
@@ -703,6 +1058,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tx_input = BtcForkTxInput {
@@ -714,6 +1070,12 @@ mod tests {
             change_address: "".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let tran =
             BitcoinForkSinger::<BtcForkAddress, LegacyTransactionSignComponent<LegacySignHasher>> {
@@ -748,6 +1110,7 @@ mod tests {
                 script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
                 derived_path: "0/0".to_string(),
                 sequence: 0,
+                has_sequence: false,
             }];
             let coin_info = coin_info_from_param(chain_type, "TESTNET", "NONE", "").unwrap();
             let tx_input = BtcForkTxInput {
@@ -759,6 +1122,12 @@ mod tests {
                 change_address: "".to_string(),
                 network: "TESTNET".to_string(),
                 seg_wit: "NONE".to_string(),
+                outputs: vec![],
+                op_return: "".to_string(),
+                lock_time: 0,
+                rbf: false,
+                version: 0,
+                fee_rate: 0,
             };
             let tran = BitcoinForkSinger::<
                 BtcForkAddress,
@@ -791,6 +1160,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "0/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
@@ -801,6 +1171,12 @@ mod tests {
             change_address: "mszYqVnqKoQx4jcTdJXxwKAissE3Jbrrc1".to_string(),
             network: "TESTNET".to_string(),
             seg_wit: "NONE".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "TESTNET", "NONE", "").unwrap();
         let tran =
@@ -831,6 +1207,7 @@ mod tests {
             script_pub_key: "76a91488d9931ea73d60eaf7e5671efc0552b912911f2a88ac".to_string(),
             derived_path: "1/0".to_string(),
             sequence: 0,
+            has_sequence: false,
         }];
         let tx_input = BtcForkTxInput {
             to: "M7xo1Mi1gULZSwgvu7VVEvrwMRqngmFkVd".to_string(),
@@ -841,6 +1218,12 @@ mod tests {
             change_address: "".to_string(),
             network: "".to_string(),
             seg_wit: "".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
         };
         let coin_info = coin_info_from_param("LITECOIN", "MAINNET", "NONE", "").unwrap();
         let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
@@ -862,4 +1245,603 @@ mod tests {
             .unwrap();
         assert_eq!(expected.signature, "020000000001018bba45b98e54a14d79ca2a5e253f727bff45cf58b5ac5421dd6a37756eb668e801000000171600147b03478d2f7c984179084baa38f790ed1d37629bffffffff01c01f2e010000000017a91400aff21f24bc08af58e41e4186d8492a10b84f9e8702483045022100d0cc3d94c7b7b34fdcc2adc4fd3f735560407581afd6caa11c8d04b963a048a00220777d98e0122fe97206875f49556a401dfc449739ec30e44cb9ed9b92a0b3ff1b01210209c629c64829ec2e99703600ee86c7161a9ed13213e714726210274c29cf780900000000");
     }
+
+    #[test]
+    fn test_sign_segwit_with_taproot_input() {
+        let unspents = vec![
+            Utxo {
+                tx_hash: "c2ceb5088cf39b677705526065667a3992c68cc18593a9af12607e057672717f"
+                    .to_string(),
+                vout: 0,
+                amount: 100000,
+                address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                script_pub_key: "a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout: 1,
+                amount: 50000,
+                address: "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4"
+                    .to_string(),
+                script_pub_key:
+                    "51208f4ca6a7384f50a1fe00cba593d5a834b480c65692a76ae6202e1ce46cb1c233"
+                        .to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+        ];
+        let tx_input = BtcForkTxInput {
+            to: "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4".to_string(),
+            amount: 120000,
+            unspents,
+            fee: 1000,
+            change_address_index: 0u32,
+            change_address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "P2TR".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "P2TR", "").unwrap();
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
+            tx_input,
+            coin_info,
+            _marker_s: PhantomData,
+            _marker_t: PhantomData,
+        };
+
+        // m/49'/1'/0'/0/0 and m/86'/1'/0'/0/0 of TEST_MNEMONIC
+        let keys = vec![
+            Secp256k1PrivateKey::from_slice(
+                &hex::decode("e9f576d8e0f64fd251ae1466591c858a5eae70179919df7f50061a3a7616f1ab")
+                    .unwrap(),
+            )
+            .unwrap(),
+            Secp256k1PrivateKey::from_slice(
+                &hex::decode("ae2057d39690a49d4c4e2d5d3c4b9afa75375da400601642290bbee89a64edae")
+                    .unwrap(),
+            )
+            .unwrap(),
+        ];
+
+        let change_addr = BtcForkAddress::from_str("2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB").unwrap();
+        let expected = tran
+            .sign_transaction(&keys, change_addr.script_pubkey())
+            .unwrap();
+        assert_eq!(expected.signature, "020000000001027f717276057e6012afa99385c18cc692397a666560520577679bf38c08b5cec20000000017160014654fbb08267f3d50d715a8f1abb55979b160dd5bffffffff5884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a40100000000ffffffff02c0d40100000000002251208f4ca6a7384f50a1fe00cba593d5a834b480c65692a76ae6202e1ce46cb1c233487100000000000017a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f78759870247304402201a722d2638c4dddd9ad9467fd25c36e8b72027ac0dc5efb3bf37e28e84da95e002207ac427e8b0c02731d3e24cca717ee7dcb730bf40ca4ac888ffeefca382fa286c0121031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc0140c2dd2253829ca7a6afb7a45440be0ba5736548ecfdbd8f9d1011fe959a6e74b803d68df931cad2905914dca23478a264ab4a1e7f22dbb75e07af7c9b4a0a8bcd00000000");
+        assert_eq!(
+            expected.tx_hash,
+            "7750fc9ac4db29020df01528fb2dc7d40cef411daadaed039f3be38a1784fd50"
+        );
+    }
+
+    #[test]
+    fn test_sign_segwit_with_mixed_inputs() {
+        let unspents = vec![
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout: 0,
+                amount: 100000,
+                address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+                script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+            Utxo {
+                tx_hash: "c2ceb5088cf39b677705526065667a3992c68cc18593a9af12607e057672717f"
+                    .to_string(),
+                vout: 0,
+                amount: 100000,
+                address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                script_pub_key: "a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout: 2,
+                amount: 100000,
+                address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                script_pub_key: "00141a7a98a2b9fa09685d28edecb2741250e85882c3".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+        ];
+        let tx_input = BtcForkTxInput {
+            to: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            amount: 250000,
+            unspents,
+            fee: 2000,
+            change_address_index: 0u32,
+            change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "SEGWIT", "").unwrap();
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
+            tx_input,
+            coin_info,
+            _marker_s: PhantomData,
+            _marker_t: PhantomData,
+        };
+
+        // m/44'/1'/0'/0/0, m/49'/1'/0'/0/0 and m/84'/1'/0'/0/0 of TEST_MNEMONIC
+        let keys: Vec<Secp256k1PrivateKey> = vec![
+            "8df6685d55ee76cc8f814a71e009985bfdd372d9373ca5078987ea356d6de246",
+            "e9f576d8e0f64fd251ae1466591c858a5eae70179919df7f50061a3a7616f1ab",
+            "891ad0a5fe5d655fa73894123fd3710a70c90cbdb269aed661893258ee40f173",
+        ]
+        .iter()
+        .map(|x| Secp256k1PrivateKey::from_slice(&hex::decode(x).unwrap()).unwrap())
+        .collect();
+
+        let change_addr = BtcForkAddress::from_str("mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN").unwrap();
+        let expected = tran
+            .sign_transaction(&keys, change_addr.script_pubkey())
+            .unwrap();
+        assert_eq!(expected.signature, "020000000001035884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a4000000006a47304402207316b6b7ffa5ef528f32a45a494fc6663ac651acb45917b7526dc424f60db61102205024d01f404dab6b1a4bb2474e6446ea89c3f9ae1c69868dcd71b29928cdb05f0121033d710ab45bb54ac99618ad23b3c1da661631aa25f23bfe9d22b41876f1d46e4effffffff7f717276057e6012afa99385c18cc692397a666560520577679bf38c08b5cec20000000017160014654fbb08267f3d50d715a8f1abb55979b160dd5bffffffff5884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a40200000000ffffffff0290d00300000000001600141a7a98a2b9fa09685d28edecb2741250e85882c380bb0000000000001976a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac0002483045022100b907bd76f11ccfec524a07401cfd48491b224948bd6de40c86d9d8b6f9690306022015156b094d085bce2dcd3e3efa2867d17fe00221829fe046ceda36046c9dab160121031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc02473044022019f2fca25fc463239703ee513bed80e1e94bad43d619b3a5d3750480ab512d0102200d7e0ec57b8453807a017176dd0a28669ffe7d30d08f454ea4b05fe1bd1b709e012102e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c000000000");
+        assert_eq!(
+            expected.tx_hash,
+            "b6679507271262cdb549b4a2a1e1c0d01e6597b05d8ec815d5af56af8b15f849"
+        );
+    }
+
+    #[test]
+    fn test_sign_with_multiple_outputs_and_op_return() {
+        let unspents = vec![Utxo {
+            tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458".to_string(),
+            vout: 0,
+            amount: 1000000,
+            address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+            script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac".to_string(),
+            derived_path: "0/0".to_string(),
+            sequence: 0,
+            has_sequence: false,
+        }];
+        let tx_input = BtcForkTxInput {
+            to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+            amount: 100000,
+            unspents: unspents.clone(),
+            fee: 10000,
+            change_address_index: 0u32,
+            change_address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "NONE".to_string(),
+            outputs: vec![
+                BtcForkTxOutput {
+                    address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                    script_pub_key: "".to_string(),
+                    amount: 200000,
+                },
+                BtcForkTxOutput {
+                    address: "".to_string(),
+                    script_pub_key: "a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987".to_string(),
+                    amount: 300000,
+                },
+            ],
+            op_return: "68656c6c6f".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "NONE", "").unwrap();
+        let tran =
+            BitcoinForkSinger::<BtcForkAddress, LegacyTransactionSignComponent<LegacySignHasher>> {
+                tx_input: tx_input.clone(),
+                coin_info: coin_info.clone(),
+                _marker_s: PhantomData,
+                _marker_t: PhantomData,
+            };
+
+        let prv_key = Secp256k1PrivateKey::from_slice(
+            &hex::decode("8df6685d55ee76cc8f814a71e009985bfdd372d9373ca5078987ea356d6de246")
+                .unwrap(),
+        )
+        .unwrap();
+        let change_addr = BtcForkAddress::from_str("mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN").unwrap();
+        let expected = tran
+            .sign_transaction(&vec![prv_key.clone()], change_addr.script_pubkey())
+            .unwrap();
+        assert_eq!(expected.signature, "01000000015884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a4000000006b483045022100c0913273bf05137644781572d495b8902467deea26ac4dc832c2dddba6f4bcb6022007bcdda6674a56dbdeb42277397c71a9f05ea5a80ebbce077f5e4ce982143b7e0121033d710ab45bb54ac99618ad23b3c1da661631aa25f23bfe9d22b41876f1d46e4effffffff05a0860100000000001976a9147821c0a3768aa9d1a37e16cf76002aef5373f1a888ac400d0300000000001600141a7a98a2b9fa09685d28edecb2741250e85882c3e09304000000000017a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f78759870000000000000000076a0568656c6c6f70f30500000000001976a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac00000000");
+        assert_eq!(
+            expected.tx_hash,
+            "ee303a80adcb25cc5b79d5f638124da675d59210242ed526d9dd157ec8b41bf0"
+        );
+
+        let summary = tran
+            .decode_transaction(change_addr.script_pubkey())
+            .unwrap();
+        assert_eq!(summary.senders, vec!["mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN"]);
+        let recipients: Vec<(&str, &str)> = summary
+            .recipients
+            .iter()
+            .map(|x| (x.address.as_str(), x.amount.as_str()))
+            .collect();
+        assert_eq!(
+            recipients,
+            vec![
+                ("mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc", "100000"),
+                ("tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95", "200000"),
+                ("2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB", "300000"),
+                ("mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN", "390000"),
+            ]
+        );
+        let is_change: Vec<bool> = summary.recipients.iter().map(|x| x.is_change).collect();
+        assert_eq!(is_change, vec![false, false, false, true]);
+        assert_eq!(summary.fee, "10000");
+        assert_eq!(summary.data, "68656c6c6f");
+
+        let invalid_inputs = vec![
+            (
+                BtcForkTxInput {
+                    op_return: "00".repeat(81),
+                    ..tx_input.clone()
+                },
+                "op_return_data_too_long",
+            ),
+            (
+                BtcForkTxInput {
+                    outputs: vec![BtcForkTxOutput {
+                        address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                        script_pub_key: "".to_string(),
+                        amount: 545,
+                    }],
+                    ..tx_input.clone()
+                },
+                "amount_less_than_minimum",
+            ),
+            (
+                BtcForkTxInput {
+                    to: "".to_string(),
+                    amount: 0,
+                    outputs: vec![],
+                    op_return: "".to_string(),
+                    ..tx_input.clone()
+                },
+                "outputs_empty",
+            ),
+            (
+                BtcForkTxInput {
+                    unspents: vec![Utxo {
+                        tx_hash: "not a tx hash".to_string(),
+                        ..tx_input.unspents[0].clone()
+                    }],
+                    ..tx_input.clone()
+                },
+                "invalid_tx_hash",
+            ),
+        ];
+        for (tx_input, err) in invalid_inputs {
+            let tran = BitcoinForkSinger::<
+                BtcForkAddress,
+                LegacyTransactionSignComponent<LegacySignHasher>,
+            > {
+                tx_input,
+                coin_info: coin_info.clone(),
+                _marker_s: PhantomData,
+                _marker_t: PhantomData,
+            };
+            let ret = tran.sign_transaction(&vec![prv_key.clone()], change_addr.script_pubkey());
+            assert_eq!(format!("{}", ret.err().unwrap()), err);
+        }
+    }
+
+    #[test]
+    fn test_sign_with_lock_time_and_rbf() {
+        let unspents = vec![
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout: 2,
+                amount: 100000,
+                address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                script_pub_key: "00141a7a98a2b9fa09685d28edecb2741250e85882c3".to_string(),
+                derived_path: "0/0".to_string(),
+                sequence: 0,
+                has_sequence: false,
+            },
+            Utxo {
+                tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                    .to_string(),
+                vout: 3,
+                amount: 100000,
+                address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                script_pub_key: "00141a7a98a2b9fa09685d28edecb2741250e85882c3".to_string(),
+                derived_path: "0/0".to_string(),
+                // relative lock time of 16 blocks
+                sequence: 0x10,
+                has_sequence: true,
+            },
+        ];
+        let tx_input = BtcForkTxInput {
+            to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+            amount: 150000,
+            unspents,
+            fee: 2000,
+            change_address_index: 0u32,
+            change_address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 2000000,
+            rbf: true,
+            version: 0,
+            fee_rate: 0,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "SEGWIT", "").unwrap();
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
+            tx_input: tx_input.clone(),
+            coin_info: coin_info.clone(),
+            _marker_s: PhantomData,
+            _marker_t: PhantomData,
+        };
+
+        let prv_key = Secp256k1PrivateKey::from_slice(
+            &hex::decode("891ad0a5fe5d655fa73894123fd3710a70c90cbdb269aed661893258ee40f173")
+                .unwrap(),
+        )
+        .unwrap();
+        let keys = vec![prv_key.clone(), prv_key.clone()];
+        let change_addr =
+            BtcForkAddress::from_str("tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95").unwrap();
+        let expected = tran
+            .sign_transaction(&keys, change_addr.script_pubkey())
+            .unwrap();
+        assert_eq!(expected.signature, "020000000001025884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a40200000000fdffffff5884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a403000000001000000002f0490200000000001976a9147821c0a3768aa9d1a37e16cf76002aef5373f1a888ac80bb0000000000001600141a7a98a2b9fa09685d28edecb2741250e85882c302473044022068ea56e00c736f1ad684841e098022f59c15d82716d57b86bc11755ffb38663302205596e24f495bcfd150679ee4c8286813e48bd15bc4826538a34afe49751c5d06012102e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c002483045022100da2d5cb325c1d7451a24d74ca133b1e78fee13eb333f4a9f8cb91b73040e2178022026081b3c67630d30ca511ddf537d0765a9a177dac4024a571ed503835cbb0b5a012102e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c080841e00");
+        assert_eq!(
+            expected.tx_hash,
+            "dbcc2285dfc25fde84321ffc221ebe18525767a3e8c0f97fe996cdca46d4a0b4"
+        );
+
+        let mut final_unspents = tx_input.unspents.clone();
+        final_unspents[0].sequence = 0xFFFF_FFFF;
+        final_unspents[1].sequence = 0xFFFF_FFFF;
+        let invalid_inputs = vec![
+            (
+                BtcForkTxInput {
+                    version: 3,
+                    ..tx_input.clone()
+                },
+                "unsupported_tx_version",
+            ),
+            (
+                BtcForkTxInput {
+                    version: 1,
+                    ..tx_input.clone()
+                },
+                "relative_lock_time_requires_version_2",
+            ),
+            (
+                BtcForkTxInput {
+                    unspents: final_unspents.clone(),
+                    ..tx_input.clone()
+                },
+                "sequence_not_signal_rbf",
+            ),
+            (
+                BtcForkTxInput {
+                    unspents: final_unspents,
+                    rbf: false,
+                    ..tx_input.clone()
+                },
+                "lock_time_requires_non_final_sequence",
+            ),
+        ];
+        for (tx_input, err) in invalid_inputs {
+            let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
+                tx_input,
+                coin_info: coin_info.clone(),
+                _marker_s: PhantomData,
+                _marker_t: PhantomData,
+            };
+            let ret = tran.sign_transaction(&keys, change_addr.script_pubkey());
+            assert_eq!(format!("{}", ret.err().unwrap()), err);
+        }
+    }
+
+    #[test]
+    fn test_explicit_zero_sequence() {
+        let unspent = Utxo {
+            tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458".to_string(),
+            vout: 2,
+            amount: 100000,
+            address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            derived_path: "0/0".to_string(),
+            ..Utxo::default()
+        };
+        let tx_input = BtcForkTxInput {
+            to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+            amount: 50000,
+            unspents: vec![
+                unspent.clone(),
+                Utxo {
+                    has_sequence: true,
+                    ..unspent
+                },
+            ],
+            fee: 2000,
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+            rbf: true,
+            ..BtcForkTxInput::default()
+        };
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent> {
+            tx_input,
+            coin_info: coin_info_from_param("BITCOIN", "TESTNET", "SEGWIT", "").unwrap(),
+            _marker_s: PhantomData,
+            _marker_t: PhantomData,
+        };
+        assert_eq!(tran.sequences(2).unwrap(), vec![SEQUENCE_RBF, 0]);
+        assert_eq!(
+            format!("{}", tran.sequences(1).err().unwrap()),
+            "relative_lock_time_requires_version_2"
+        );
+    }
+
+    #[test]
+    fn test_change_address_follows_first_input() {
+        let dpk = TypedDeterministicPublicKey::Bip32Sepc256k1(
+            Bip32DeterministicPrivateKey::from_mnemonic(TEST_MNEMONIC)
+                .unwrap()
+                .derive("m/86'/1'/0'")
+                .unwrap()
+                .deterministic_public_key(),
+        );
+        let taproot_utxo = Utxo {
+            tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458".to_string(),
+            vout: 1,
+            amount: 50000,
+            address: "".to_string(),
+            script_pub_key: "51208f4ca6a7384f50a1fe00cba593d5a834b480c65692a76ae6202e1ce46cb1c233"
+                .to_string(),
+            derived_path: "0/0".to_string(),
+            sequence: 0,
+            has_sequence: false,
+        };
+        let segwit_utxo = Utxo {
+            address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            script_pub_key: "".to_string(),
+            ..taproot_utxo.clone()
+        };
+        let tx_input = BtcForkTxInput {
+            to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+            amount: 20000,
+            unspents: vec![taproot_utxo, segwit_utxo.clone()],
+            fee: 1000,
+            change_address_index: 3u32,
+            change_address: "".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "P2TR".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 0,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "P2TR", "").unwrap();
+
+        // the change of a taproot input is the BIP-86 address of m/86'/1'/0'/1/3
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent>::new(
+            tx_input.clone(),
+            coin_info.clone(),
+        );
+        let change = tran.change_address(&dpk).unwrap();
+        assert_eq!(
+            change,
+            BtcForkAddress::from_str(
+                "tb1p3j66w4lnn0uqdvfjp0mm0fpeuqlvu9y6l9hvfl2qy23v5qkjkayqrl352r"
+            )
+            .unwrap()
+            .script_pubkey()
+        );
+        let summary = tran.decode_transaction(change).unwrap();
+        assert_eq!(summary.recipients.len(), 2);
+        assert!(!summary.recipients[0].is_change);
+        assert!(summary.recipients[1].is_change);
+        assert_eq!(summary.recipients[1].amount, "79000");
+
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent>::new(
+            BtcForkTxInput {
+                unspents: vec![segwit_utxo],
+                ..tx_input.clone()
+            },
+            coin_info.clone(),
+        );
+        assert_eq!(
+            tran.change_address(&dpk).unwrap(),
+            BtcForkAddress::from_str("tb1qc5u33qve4v2g2sq0y2uvcxce8746kjcqzmtpfm")
+                .unwrap()
+                .script_pubkey()
+        );
+
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent>::new(
+            BtcForkTxInput {
+                unspents: vec![],
+                ..tx_input
+            },
+            coin_info,
+        );
+        assert_eq!(
+            format!("{}", tran.change_address(&dpk).err().unwrap()),
+            "insufficient_funds"
+        );
+    }
+
+    #[test]
+    fn test_sign_with_fee_rate() {
+        let utxo = |vout: i32, amount: i64| Utxo {
+            tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458".to_string(),
+            vout,
+            amount,
+            address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            script_pub_key: "".to_string(),
+            derived_path: "0/0".to_string(),
+            sequence: 0,
+            has_sequence: false,
+        };
+        let tx_input = BtcForkTxInput {
+            to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+            amount: 120000,
+            unspents: vec![utxo(0, 100000), utxo(1, 30000), utxo(2, 50000)],
+            fee: 0,
+            change_address_index: 0u32,
+            change_address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+            outputs: vec![],
+            op_return: "".to_string(),
+            lock_time: 0,
+            rbf: false,
+            version: 0,
+            fee_rate: 10,
+        };
+        let coin_info = coin_info_from_param("BITCOIN", "TESTNET", "SEGWIT", "").unwrap();
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent>::new(
+            tx_input.clone(),
+            coin_info.clone(),
+        );
+        let change_addr =
+            BtcForkAddress::from_str("tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95").unwrap();
+        let selected = tran.select_coins(&change_addr.script_pubkey()).unwrap();
+        let vouts: Vec<i32> = selected.tx_input.unspents.iter().map(|x| x.vout).collect();
+        assert_eq!(vouts, vec![0, 2]);
+        // 10.5 + 68 * 2 + 34 + 31 = 211.5 vB
+        assert_eq!(selected.tx_input.fee, 2120);
+
+        let tran = BitcoinForkSinger::<BtcForkAddress, SegWitTransactionSignComponent>::new(
+            BtcForkTxInput {
+                fee: 1000,
+                ..tx_input
+            },
+            coin_info,
+        );
+        let ret = tran.select_coins(&change_addr.script_pubkey());
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "fee_conflicts_with_fee_rate"
+        );
+    }
 }
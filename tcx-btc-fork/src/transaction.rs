@@ -18,8 +18,23 @@ pub struct Utxo {
     pub script_pub_key: std::string::String,
     #[prost(string, tag = "6")]
     pub derived_path: std::string::String,
+    /// nSequence of the input, 0 means the default sequence of the transaction unless
+    /// has_sequence is set
     #[prost(int64, tag = "7")]
     pub sequence: i64,
+    /// the sequence is set explicitly, so nSequence 0 could be requested
+    #[prost(bool, tag = "8")]
+    pub has_sequence: bool,
+}
+/// The payment output, the scriptPubKey in hex takes precedence over the address
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcForkTxOutput {
+    #[prost(string, tag = "1")]
+    pub address: std::string::String,
+    #[prost(string, tag = "2")]
+    pub script_pub_key: std::string::String,
+    #[prost(int64, tag = "3")]
+    pub amount: i64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct BtcForkTxInput {
@@ -39,6 +54,24 @@ pub struct BtcForkTxInput {
     pub network: std::string::String,
     #[prost(string, tag = "8")]
     pub seg_wit: std::string::String,
+    /// paid after to/amount, if it is set
+    #[prost(message, repeated, tag = "9")]
+    pub outputs: ::std::vec::Vec<BtcForkTxOutput>,
+    /// hex encoded data of the OP_RETURN output, at most 80 bytes
+    #[prost(string, tag = "10")]
+    pub op_return: std::string::String,
+    /// nLockTime, a block height if it is less than 500000000, otherwise a unix timestamp
+    #[prost(uint32, tag = "11")]
+    pub lock_time: u32,
+    /// signal BIP-125 replaceability, the default sequence of the inputs is 0xfffffffd
+    #[prost(bool, tag = "12")]
+    pub rbf: bool,
+    /// 0 means the default version of the transaction, 1 for legacy and 2 for segwit
+    #[prost(int32, tag = "13")]
+    pub version: i32,
+    /// sat/vB, if it is set the inputs are selected from the unspents and the fee is computed
+    #[prost(int64, tag = "14")]
+    pub fee_rate: i64,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct BtcForkSignedTxOutput {
@@ -47,3 +80,44 @@ pub struct BtcForkSignedTxOutput {
     #[prost(string, tag = "2")]
     pub tx_hash: std::string::String,
 }
+/// FUNCTION: sign_psbt(SignParam{input: PsbtInput}): PsbtOutput
+///
+/// Fill in the partial signatures for the inputs whose BIP-32 derivation
+/// belongs to the wallet, only BITCOIN and LITECOIN are supported
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PsbtInput {
+    /// base64 encoded PSBT
+    #[prost(string, tag = "1")]
+    pub psbt: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub auto_finalize: bool,
+}
+/// FUNCTION: create_psbt(SignParam{input: BtcForkTxInput}): PsbtOutput
+///
+/// Create the unsigned PSBT of the transaction for the device holding the keys,
+/// only BITCOIN and LITECOIN are supported
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PsbtOutput {
+    /// base64 encoded PSBT
+    #[prost(string, tag = "1")]
+    pub psbt: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_finalized: bool,
+}
+/// FUNCTION: sign_message(SignParam{input: BtcForkMessageInput}): BtcForkMessageOutput
+///
+/// Sign the message in the format of the `Signed Message` of Bitcoin Core,
+/// the header of the signature tells the address type like BIP-137
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcForkMessageInput {
+    #[prost(string, tag = "1")]
+    pub message: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_hex: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct BtcForkMessageOutput {
+    /// base64 encoded compact recoverable signature
+    #[prost(string, tag = "1")]
+    pub signature: std::string::String,
+}
@@ -0,0 +1,83 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use tcx_chain::{Address, Keystore, Metadata, Result};
+use tcx_constants::{CoinInfo, CurveType, TEST_MNEMONIC, TEST_PASSWORD};
+use tcx_primitive::{Derive, TypedDeterministicPrivateKey, TypedPublicKey};
+
+struct MockAddress {}
+impl Address for MockAddress {
+    fn from_public_key(public_key: &TypedPublicKey, _coin: &CoinInfo) -> Result<String> {
+        Ok(hex::encode(public_key.to_bytes()))
+    }
+
+    fn is_valid(_address: &str, _coin: &CoinInfo) -> bool {
+        true
+    }
+}
+
+fn unlocked_keystore() -> (Keystore, String) {
+    let mut keystore =
+        Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+    keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+    let coin_info = CoinInfo {
+        coin: "BITCOIN".to_string(),
+        derivation_path: "m/44'/0'/0'/0/0".to_string(),
+        curve: CurveType::SECP256k1,
+        network: "MAINNET".to_string(),
+        seg_wit: "NONE".to_string(),
+    };
+    let account = keystore.derive_coin::<MockAddress>(&coin_info).unwrap();
+
+    (keystore, account.address)
+}
+
+/// The keys of a 50 inputs transaction, every key took the seed generation before the cache
+fn find_private_keys(c: &mut Criterion) {
+    let mut group = c.benchmark_group("derive 50 keys");
+
+    group.bench_function("from mnemonic", |b| {
+        b.iter(|| {
+            for index in 0..50 {
+                let root = TypedDeterministicPrivateKey::from_mnemonic(
+                    CurveType::SECP256k1,
+                    TEST_MNEMONIC,
+                )
+                .unwrap();
+                let path = format!("m/44'/0'/0'/0/{}", index);
+                black_box(root.derive(&path).unwrap().private_key());
+            }
+        })
+    });
+
+    let (mut keystore, address) = unlocked_keystore();
+    group.bench_function("find_private_key_by_path", |b| {
+        b.iter(|| {
+            for index in 0..50 {
+                let path = format!("0/{}", index);
+                black_box(
+                    keystore
+                        .find_private_key_by_path("BITCOIN", &address, &path)
+                        .unwrap(),
+                );
+            }
+        })
+    });
+
+    group.bench_function("find_private_key", |b| {
+        b.iter(|| {
+            for _ in 0..50 {
+                black_box(keystore.find_private_key("BITCOIN", &address).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = find_private_keys
+}
+criterion_main!(benches);
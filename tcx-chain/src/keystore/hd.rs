@@ -1,6 +1,9 @@
-use bip39::{Language, Mnemonic, Seed};
+use bip39::{Language, Mnemonic};
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
 
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
 use super::Account;
 use super::Address;
@@ -10,18 +13,191 @@ use super::{Error, Metadata};
 use crate::keystore::{transform_mnemonic_error, Store};
 
 use std::collections::HashMap;
+use std::str::FromStr;
+
+use bitcoin_hashes::hash160;
+use bitcoin_hashes::Hash;
 
 use tcx_constants::{CoinInfo, CurveType};
 use tcx_crypto::hash::dsha256;
-use tcx_crypto::{Crypto, Key, Pbkdf2Params};
+use tcx_crypto::{Crypto, Key, TypedKdfParams};
 use tcx_primitive::{
-    generate_mnemonic, get_account_path, Derive, ToHex, TypedDeterministicPrivateKey,
-    TypedDeterministicPublicKey, TypedPrivateKey,
+    bip39_seed, combine_shares, czech_mnemonic_to_entropy, generate_czech_mnemonic,
+    generate_mnemonic_with, generate_shares, get_account_path, Derive, Sr25519PrivateKey, ToHex,
+    TypedDeterministicPrivateKey, TypedDeterministicPublicKey, TypedPrivateKey,
+    DEFAULT_ITERATION_EXPONENT,
 };
 
+/// The BIP-39 word list of the mnemonic
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MnemonicLanguage {
+    English,
+    ChineseSimplified,
+    ChineseTraditional,
+    Japanese,
+    Korean,
+    Spanish,
+    French,
+    Italian,
+    Czech,
+}
+
+impl MnemonicLanguage {
+    /// None for czech, tiny-bip39 does not ship the czech word list and tcx-primitive handles it
+    fn bip39_language(self) -> Option<Language> {
+        match self {
+            MnemonicLanguage::English => Some(Language::English),
+            MnemonicLanguage::ChineseSimplified => Some(Language::ChineseSimplified),
+            MnemonicLanguage::ChineseTraditional => Some(Language::ChineseTraditional),
+            MnemonicLanguage::Japanese => Some(Language::Japanese),
+            MnemonicLanguage::Korean => Some(Language::Korean),
+            MnemonicLanguage::Spanish => Some(Language::Spanish),
+            MnemonicLanguage::French => Some(Language::French),
+            MnemonicLanguage::Italian => Some(Language::Italian),
+            MnemonicLanguage::Czech => None,
+        }
+    }
+
+    /// The normalized phrase and its entropy, the words and the checksum are verified
+    fn parse_mnemonic(self, mnemonic: &str) -> Result<(Zeroizing<String>, Zeroizing<Vec<u8>>)> {
+        let mnemonic = normalize_mnemonic(mnemonic);
+        let entropy = match self.bip39_language() {
+            Some(language) => Mnemonic::from_phrase(&mnemonic, language)
+                .map_err(transform_mnemonic_error)?
+                .entropy()
+                .to_vec(),
+            None => czech_mnemonic_to_entropy(&mnemonic).map_err(transform_mnemonic_error)?,
+        };
+        Ok((Zeroizing::new(mnemonic), Zeroizing::new(entropy)))
+    }
+
+    fn generate_mnemonic(self, word_count: usize) -> Result<String> {
+        let mnemonic = match self.bip39_language() {
+            Some(language) => generate_mnemonic_with(language, word_count),
+            None => generate_czech_mnemonic(word_count),
+        }
+        .map_err(transform_mnemonic_error)?;
+        Ok(mnemonic)
+    }
+
+    /// Japanese mnemonic is presented with the ideographic space
+    fn word_separator(self) -> &'static str {
+        match self {
+            MnemonicLanguage::Japanese => "\u{3000}",
+            _ => " ",
+        }
+    }
+}
+
+impl Default for MnemonicLanguage {
+    fn default() -> Self {
+        MnemonicLanguage::English
+    }
+}
+
+impl FromStr for MnemonicLanguage {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ENGLISH" => Ok(MnemonicLanguage::English),
+            "CHINESE_SIMPLIFIED" => Ok(MnemonicLanguage::ChineseSimplified),
+            "CHINESE_TRADITIONAL" => Ok(MnemonicLanguage::ChineseTraditional),
+            "JAPANESE" => Ok(MnemonicLanguage::Japanese),
+            "KOREAN" => Ok(MnemonicLanguage::Korean),
+            "SPANISH" => Ok(MnemonicLanguage::Spanish),
+            "FRENCH" => Ok(MnemonicLanguage::French),
+            "ITALIAN" => Ok(MnemonicLanguage::Italian),
+            "CZECH" => Ok(MnemonicLanguage::Czech),
+            _ => Err(Error::UnsupportedLanguage.into()),
+        }
+    }
+}
+
+/// NFKD normalized words separated by a single space, BIP-39 takes this form to generate the seed
+fn normalize_mnemonic(mnemonic: &str) -> String {
+    let normalized = mnemonic.nfkd().collect::<String>();
+    normalized
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// The mnemonic of the keystore and the BIP-39 passphrase
+struct MnemonicSecret {
+    phrase: Zeroizing<String>,
+    entropy: Zeroizing<Vec<u8>>,
+    language: MnemonicLanguage,
+    passphrase: Zeroizing<String>,
+}
+
+/// The seed is generated once on unlock, the master and account keys are derived on first use,
+/// so signing with many keys of the same account does not run PBKDF2 and the hardened derivation
+/// again and again. The seed of a keystore recovered from SLIP-39 shares is the master secret, it
+/// has no mnemonic. The mnemonic, the entropy, the passphrase and the seed are wiped from the
+/// memory when the cache is dropped, so are the secrets of the secp256k1 keys. The other derived
+/// keys are held in the types of the curve crates, the ed25519, sr25519, BLS and ed25519 BIP-32
+/// keys do not support wiping and are released as they are
 struct Cache {
-    mnemonic: String,
-    keys: HashMap<String, TypedDeterministicPrivateKey>,
+    mnemonic: Option<MnemonicSecret>,
+    seed: Zeroizing<Vec<u8>>,
+    roots: HashMap<CurveType, TypedDeterministicPrivateKey>,
+    account_keys: HashMap<(CurveType, String), TypedDeterministicPrivateKey>,
+}
+
+impl Cache {
+    fn root(&mut self, curve: CurveType) -> Result<&TypedDeterministicPrivateKey> {
+        if !self.roots.contains_key(&curve) {
+            let root = if curve == CurveType::SubSr25519 {
+                // sr25519 keys come from the entropy of the mnemonic instead of the seed
+                let mnemonic = self.mnemonic.as_ref().ok_or(Error::CannotDeriveKey)?;
+                TypedDeterministicPrivateKey::SubSr25519(Sr25519PrivateKey::from_entropy(
+                    &mnemonic.entropy,
+                    &mnemonic.passphrase,
+                ))
+            } else {
+                TypedDeterministicPrivateKey::from_bip39_seed(curve, &self.seed)?
+            };
+            self.roots.insert(curve, root);
+        }
+
+        Ok(&self.roots[&curve])
+    }
+
+    fn account_key(
+        &mut self,
+        curve: CurveType,
+        account_path: &str,
+    ) -> Result<&TypedDeterministicPrivateKey> {
+        let key = (curve, account_path.to_owned());
+        if !self.account_keys.contains_key(&key) {
+            let account_key = self.root(curve)?.derive(account_path)?;
+            self.account_keys.insert(key.clone(), account_key);
+        }
+
+        Ok(&self.account_keys[&key])
+    }
+
+    /// The keys in the form of `m/purpose'/coin'/account'/...` are derived from the cached account
+    /// key of any curve, the others such as the substrate junctions are derived from the master key
+    fn private_key(&mut self, curve: CurveType, path: &str) -> Result<TypedPrivateKey> {
+        let account_path = match get_account_path(path) {
+            Ok(account_path) => account_path,
+            Err(_) => return Ok(self.root(curve)?.derive(path)?.private_key()),
+        };
+        let relative_path = path[account_path.len()..].trim_start_matches('/');
+        let account_key = self.account_key(curve, &account_path)?;
+
+        if relative_path.is_empty() {
+            Ok(account_key.private_key())
+        } else {
+            // the substrate junctions are parsed after the slashes, so the path keeps the `m/`
+            Ok(account_key
+                .derive(&format!("m/{}", relative_path))?
+                .private_key())
+        }
+    }
 }
 
 pub struct HdKeystore {
@@ -30,13 +206,30 @@ pub struct HdKeystore {
 }
 
 pub fn key_hash_from_mnemonic(mnemonic: &str) -> Result<String> {
-    let mn =
-        Mnemonic::from_phrase(mnemonic, Language::English).map_err(transform_mnemonic_error)?;
+    key_hash_from_mnemonic_with_passphrase(mnemonic, "")
+}
 
-    let seed = Seed::new(&mn, "");
+/// The same mnemonic with another passphrase is another wallet, so the hash is taken from the seed
+pub fn key_hash_from_mnemonic_with_passphrase(mnemonic: &str, passphrase: &str) -> Result<String> {
+    key_hash_from_mnemonic_in_language(mnemonic, MnemonicLanguage::English, passphrase)
+}
+
+pub fn key_hash_from_mnemonic_in_language(
+    mnemonic: &str,
+    language: MnemonicLanguage,
+    passphrase: &str,
+) -> Result<String> {
+    let (mnemonic, _) = language.parse_mnemonic(mnemonic)?;
+    let seed = Zeroizing::new(bip39_seed(
+        &mnemonic,
+        &passphrase.nfkd().collect::<String>(),
+    ));
+
+    Ok(key_hash_from_seed(&seed))
+}
 
-    let bytes = dsha256(seed.as_bytes())[..20].to_vec();
-    Ok(hex::encode(bytes))
+fn key_hash_from_seed(seed: &[u8]) -> String {
+    hex::encode(&dsha256(seed)[..20])
 }
 
 impl HdKeystore {
@@ -55,30 +248,60 @@ impl HdKeystore {
     }
 
     pub(crate) fn unlock_by_password(&mut self, password: &str) -> Result<()> {
-        let mnemonic_bytes = self
-            .store
-            .crypto
-            .decrypt(Key::Password(password.to_owned()))?;
-        self.cache_mnemonic(mnemonic_bytes)
+        self.unlock(|| Key::Password(password.to_owned()))
     }
 
     pub(crate) fn unlock_by_derived_key(&mut self, derived_key: &str) -> Result<()> {
-        let mnemonic_bytes = self
-            .store
-            .crypto
-            .decrypt(Key::DerivedKey(derived_key.to_owned()))?;
-        self.cache_mnemonic(mnemonic_bytes)
+        self.unlock(|| Key::DerivedKey(derived_key.to_owned()))
+    }
+
+    /// The crypto holds the SLIP-39 master secret of a keystore recovered from shares, or the
+    /// mnemonic otherwise
+    fn unlock(&mut self, key: impl Fn() -> Key) -> Result<()> {
+        let secret = self.store.crypto.decrypt(key())?;
+        if self.store.slip39 {
+            self.cache = Some(Cache {
+                mnemonic: None,
+                seed: secret,
+                roots: HashMap::new(),
+                account_keys: HashMap::new(),
+            });
+            return Ok(());
+        }
+
+        let passphrase_bytes = self.decrypt_passphrase(key())?;
+        self.cache_mnemonic(secret, passphrase_bytes)
     }
 
-    fn cache_mnemonic(&mut self, mnemonic_bytes: Vec<u8>) -> Result<()> {
-        let mnemonic_str = String::from_utf8(mnemonic_bytes)?;
+    fn decrypt_passphrase(&self, key: Key) -> Result<Zeroizing<Vec<u8>>> {
+        match &self.store.enc_passphrase {
+            Some(enc_pair) => self.store.crypto.decrypt_enc_pair(key, enc_pair),
+            None => Ok(Zeroizing::new(vec![])),
+        }
+    }
 
-        let _mnemonic = Mnemonic::from_phrase(&mnemonic_str, Language::English)
-            .map_err(transform_mnemonic_error)?;
+    fn cache_mnemonic(
+        &mut self,
+        mnemonic_bytes: Zeroizing<Vec<u8>>,
+        passphrase_bytes: Zeroizing<Vec<u8>>,
+    ) -> Result<()> {
+        let mnemonic_str = std::str::from_utf8(&mnemonic_bytes)?;
+        let passphrase = Zeroizing::new(std::str::from_utf8(&passphrase_bytes)?.to_owned());
+
+        let language = self.store.language.unwrap_or_default();
+        let (phrase, entropy) = language.parse_mnemonic(mnemonic_str)?;
+        let seed = Zeroizing::new(bip39_seed(&phrase, &passphrase));
 
         self.cache = Some(Cache {
-            mnemonic: mnemonic_str,
-            keys: HashMap::new(),
+            mnemonic: Some(MnemonicSecret {
+                phrase,
+                entropy,
+                language,
+                passphrase,
+            }),
+            seed,
+            roots: HashMap::new(),
+            account_keys: HashMap::new(),
         });
 
         Ok(())
@@ -94,20 +317,53 @@ impl HdKeystore {
 
     pub(crate) fn mnemonic(&self) -> Result<String> {
         let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+        let mnemonic = cache
+            .mnemonic
+            .as_ref()
+            .ok_or(Error::KeystoreHasNoMnemonic)?;
 
-        Ok(cache.mnemonic.to_string())
+        let words = mnemonic.phrase.split(' ').collect::<Vec<&str>>();
+        Ok(words.join(mnemonic.language.word_separator()))
     }
 
-    pub(crate) fn find_private_key(&self, symbol: &str, address: &str) -> Result<TypedPrivateKey> {
+    /// Split the seed into SLIP-39 share mnemonics encrypted by the SLIP-39 passphrase. The seed
+    /// is the master secret of the shares, it is the BIP-39 seed of the mnemonic with the BIP-39
+    /// passphrase, so a SLIP-39 wallet recovers the same BIP-32 and SLIP-10 keys from the shares.
+    /// The 64 bytes seed takes 59 words in a share
+    pub(crate) fn export_shares(
+        &self,
+        group_threshold: u8,
+        groups: &[(u8, u8)],
+        passphrase: &str,
+    ) -> Result<Vec<Vec<String>>> {
         let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
 
+        generate_shares(
+            group_threshold,
+            groups,
+            &cache.seed,
+            passphrase,
+            DEFAULT_ITERATION_EXPONENT,
+            false,
+        )
+    }
+
+    pub(crate) fn find_private_key(
+        &mut self,
+        symbol: &str,
+        address: &str,
+    ) -> Result<TypedPrivateKey> {
+        tcx_ensure!(!self.is_locked(), Error::KeystoreLocked);
+
         let account = self
             .account(symbol, address)
             .ok_or(Error::AccountNotFound)?;
+        let (curve, derivation_path) = (account.curve, account.derivation_path.to_owned());
 
-        let root = TypedDeterministicPrivateKey::from_mnemonic(account.curve, &cache.mnemonic)?;
-
-        Ok(root.derive(&account.derivation_path)?.private_key())
+        self.cache
+            .as_mut()
+            .unwrap()
+            .private_key(curve, &derivation_path)
     }
 
     pub(crate) fn find_deterministic_public_key(
@@ -128,45 +384,132 @@ impl HdKeystore {
         main_address: &str,
         relative_path: &str,
     ) -> Result<TypedPrivateKey> {
-        let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+        tcx_ensure!(!self.is_locked(), Error::KeystoreLocked);
 
-        if !cache.keys.contains_key(main_address) {
-            let account = self
-                .account(symbol, main_address)
-                .ok_or(Error::AccountNotFound)?;
+        let account = self
+            .account(symbol, main_address)
+            .ok_or(Error::AccountNotFound)?;
+        let curve = account.curve;
+        let account_path = get_account_path(&account.derivation_path)?;
 
-            let esk = TypedDeterministicPrivateKey::from_mnemonic(account.curve, &cache.mnemonic)?;
+        let esk = self
+            .cache
+            .as_mut()
+            .unwrap()
+            .account_key(curve, &account_path)?;
 
-            let k = esk.derive(&get_account_path(&account.derivation_path)?)?;
+        Ok(esk.derive(relative_path)?.private_key())
+    }
 
-            self.cache
-                .as_mut()
-                .unwrap()
-                .keys
-                .insert(main_address.to_owned(), k);
-        }
+    /// The BIP-32 fingerprint of the secp256k1 master key, it identifies the key origin in PSBT
+    pub(crate) fn master_fingerprint(&mut self) -> Result<Vec<u8>> {
+        let cache = self.cache.as_mut().ok_or(Error::KeystoreLocked)?;
 
-        let esk = &self.cache.as_ref().unwrap().keys[main_address];
+        let root = cache.root(CurveType::SECP256k1)?;
+        let pub_key = root.deterministic_public_key().public_key().to_bytes();
 
-        Ok(esk.derive(relative_path)?.private_key())
+        Ok(hash160::Hash::hash(&pub_key)[..4].to_vec())
     }
 
     pub fn new(password: &str, meta: Metadata) -> HdKeystore {
-        let mnemonic = generate_mnemonic();
+        Self::new_with_kdf(password, meta, TypedKdfParams::default()).unwrap()
+    }
+
+    pub fn new_with_kdf(password: &str, meta: Metadata, kdf: TypedKdfParams) -> Result<HdKeystore> {
+        Self::new_with_passphrase(password, "", meta, kdf)
+    }
+
+    pub fn new_with_passphrase(
+        password: &str,
+        passphrase: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        Self::new_in_language(
+            MnemonicLanguage::English,
+            12,
+            password,
+            passphrase,
+            meta,
+            kdf,
+        )
+    }
 
-        Self::from_mnemonic(&mnemonic, password, meta).unwrap()
+    pub fn new_in_language(
+        language: MnemonicLanguage,
+        word_count: usize,
+        password: &str,
+        passphrase: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        let mnemonic = Zeroizing::new(language.generate_mnemonic(word_count)?);
+
+        Self::from_mnemonic_in_language(&mnemonic, language, passphrase, password, meta, kdf)
     }
 
     pub fn from_mnemonic(mnemonic: &str, password: &str, meta: Metadata) -> Result<HdKeystore> {
-        let mnemonic: &str = &mnemonic.split_whitespace().collect::<Vec<&str>>().join(" ");
+        Self::from_mnemonic_with_kdf(mnemonic, password, meta, TypedKdfParams::default())
+    }
+
+    pub fn from_mnemonic_with_kdf(
+        mnemonic: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        Self::from_mnemonic_with_passphrase(mnemonic, "", password, meta, kdf)
+    }
 
-        let key_hash = key_hash_from_mnemonic(mnemonic)?;
+    pub fn from_mnemonic_with_passphrase(
+        mnemonic: &str,
+        passphrase: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        Self::from_mnemonic_in_language(
+            mnemonic,
+            MnemonicLanguage::English,
+            passphrase,
+            password,
+            meta,
+            kdf,
+        )
+    }
 
-        let crypto: Crypto<Pbkdf2Params> = Crypto::new(password, mnemonic.as_bytes());
+    /// The passphrase is encrypted along with the mnemonic only if it is not empty, the language
+    /// is kept only if it is not english
+    pub fn from_mnemonic_in_language(
+        mnemonic: &str,
+        language: MnemonicLanguage,
+        passphrase: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        let mnemonic: &str = &Zeroizing::new(normalize_mnemonic(mnemonic));
+        let passphrase: &str = &Zeroizing::new(passphrase.nfkd().collect::<String>());
+
+        let key_hash = key_hash_from_mnemonic_in_language(mnemonic, language, passphrase)?;
+
+        let crypto = Crypto::new_with_kdf(password, mnemonic.as_bytes(), kdf)?;
+        let enc_passphrase = if passphrase.is_empty() {
+            None
+        } else {
+            Some(crypto.derive_enc_pair(password, passphrase.as_bytes())?)
+        };
         Ok(HdKeystore {
             store: Store {
                 key_hash,
                 crypto,
+                enc_passphrase,
+                language: if language == MnemonicLanguage::English {
+                    None
+                } else {
+                    Some(language)
+                },
+                slip39: false,
                 id: Uuid::new_v4().to_hyphenated().to_string(),
                 version: Self::VERSION,
                 active_accounts: vec![],
@@ -177,20 +520,54 @@ impl HdKeystore {
         })
     }
 
-    pub(crate) fn derive_coin<A: Address>(&mut self, coin_info: &CoinInfo) -> Result<Account> {
-        let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+    /// Recover the keystore from a quorum of SLIP-39 share mnemonics, the master secret is the seed
+    /// of the keys. The keystore has no mnemonic, so it exports no mnemonic and derives no sr25519
+    /// keys
+    pub fn from_shares(
+        shares: &[String],
+        passphrase: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<HdKeystore> {
+        let master_secret = Zeroizing::new(combine_shares(shares, passphrase)?);
+
+        let crypto = Crypto::new_with_kdf(password, &master_secret, kdf)?;
+        Ok(HdKeystore {
+            store: Store {
+                key_hash: key_hash_from_seed(&master_secret),
+                crypto,
+                enc_passphrase: None,
+                language: None,
+                slip39: true,
+                id: Uuid::new_v4().to_hyphenated().to_string(),
+                version: Self::VERSION,
+                active_accounts: vec![],
+                meta,
+            },
 
-        let root = TypedDeterministicPrivateKey::from_mnemonic(coin_info.curve, &cache.mnemonic)?;
+            cache: None,
+        })
+    }
 
-        let private_key = root.derive(&coin_info.derivation_path)?.private_key();
+    pub(crate) fn derive_coin<A: Address>(&mut self, coin_info: &CoinInfo) -> Result<Account> {
+        let cache = self.cache.as_mut().ok_or(Error::KeystoreLocked)?;
+
+        let private_key = cache.private_key(coin_info.curve, &coin_info.derivation_path)?;
         let public_key = private_key.public_key();
 
         let address = A::from_public_key(&public_key, coin_info)?;
         // todo: ext_pub_key
         let ext_pub_key = match coin_info.curve {
-            CurveType::SubSr25519 | CurveType::BLS | CurveType::ED25519 => "".to_owned(),
-            _ => root
-                .derive(&get_account_path(&coin_info.derivation_path)?)?
+            CurveType::SubSr25519
+            | CurveType::BLS
+            | CurveType::ED25519
+            | CurveType::ED25519Blake2bNano => "".to_owned(),
+            _ => cache
+                .account_key(
+                    coin_info.curve,
+                    &get_account_path(&coin_info.derivation_path)?,
+                )?
                 .deterministic_public_key()
                 .to_hex(),
         };
@@ -349,7 +726,7 @@ mod tests {
             .crypto
             .decrypt(Key::Password(TEST_PASSWORD.to_owned()))
             .unwrap();
-        let decrypted_mnemonic = String::from_utf8(decrypted_bytes).unwrap();
+        let decrypted_mnemonic = String::from_utf8(decrypted_bytes.to_vec()).unwrap();
         assert_eq!(decrypted_mnemonic, TEST_MNEMONIC);
         assert_eq!(keystore.store.active_accounts.len(), 0);
 
@@ -362,6 +739,242 @@ mod tests {
         assert_eq!(format!("{}", wrong_password_err), "password_incorrect");
     }
 
+    #[test]
+    pub fn from_mnemonic_with_passphrase() {
+        let keystore = HdKeystore::from_mnemonic_with_passphrase(
+            TEST_MNEMONIC,
+            "TREZOR",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        assert_ne!(
+            keystore.store.key_hash,
+            key_hash_from_mnemonic(TEST_MNEMONIC).unwrap()
+        );
+        assert_eq!(
+            keystore.store.key_hash,
+            key_hash_from_mnemonic_with_passphrase(TEST_MNEMONIC, "TREZOR").unwrap()
+        );
+
+        let json = serde_json::to_string(&keystore.store).unwrap();
+        let mut keystore = HdKeystore::from_store(serde_json::from_str(&json).unwrap());
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(keystore.mnemonic().unwrap(), TEST_MNEMONIC);
+
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        let acc = keystore.derive_coin::<MockAddress>(&coin_info).unwrap();
+        let private_key = keystore.find_private_key("BITCOIN", &acc.address).unwrap();
+        assert_eq!(
+            hex::encode(private_key.to_bytes()),
+            "c4db8f29890ee84a179e42ec2b586b2db8d1883d337fc91070d079ca231e25f7"
+        );
+        let private_key = keystore
+            .find_private_key_by_path("BITCOIN", &acc.address, "0/0")
+            .unwrap();
+        assert_eq!(
+            hex::encode(private_key.to_bytes()),
+            "c4db8f29890ee84a179e42ec2b586b2db8d1883d337fc91070d079ca231e25f7"
+        );
+    }
+
+    #[test]
+    pub fn from_mnemonic_in_language() {
+        // the same entropy as TEST_MNEMONIC, the words are NFC normalized
+        let vectors = vec![
+            (
+                MnemonicLanguage::Japanese,
+                "せおう　せんとう　げんそう　おかわり　はっぴょう　てきとう　かんこう　るりがわら　きぬごし　すいとう　るりがわら　ひくい",
+                "0c13bd5b447bf2253821a72c2363af46736b4725bda332c241a0abd6d7153a9b",
+            ),
+            (
+                MnemonicLanguage::ChineseSimplified,
+                "景 沿 兵 门 燥 芳 段 晒 团 染 晒 爬",
+                "85cfc65da9184761a096b91e7da925165ce61e5046f78fee9945a7a3bb5b990a",
+            ),
+            (
+                MnemonicLanguage::Korean,
+                "숙소 시점 발음 기업 주먹 원래 담요 확장 딸아이 소지품 확장 지능",
+                "f7c5f202d2c265425aaf2f445af834439056ebd2772460a25af6fcb997610790",
+            ),
+            (
+                MnemonicLanguage::Czech,
+                "napadat nocleh klacek doplnit sotva pokrok geolog zmar hoch motorka zmar strach",
+                "d824534eb45d29b0cd7a7ff1e63468d7ef94844f474ee9c21d6e2f5863f55e9b",
+            ),
+        ];
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+
+        for (language, mnemonic, private_key) in vectors {
+            let keystore = HdKeystore::from_mnemonic_in_language(
+                mnemonic,
+                language,
+                "",
+                TEST_PASSWORD,
+                Metadata::default(),
+                TypedKdfParams::default(),
+            )
+            .unwrap();
+            assert_eq!(keystore.store.language, Some(language));
+
+            let json = serde_json::to_string(&keystore.store).unwrap();
+            let mut keystore = HdKeystore::from_store(serde_json::from_str(&json).unwrap());
+            keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+            assert_eq!(
+                keystore.mnemonic().unwrap().nfc().collect::<String>(),
+                mnemonic
+            );
+
+            let acc = keystore.derive_coin::<MockAddress>(&coin_info).unwrap();
+            let pk = keystore.find_private_key("BITCOIN", &acc.address).unwrap();
+            assert_eq!(hex::encode(pk.to_bytes()), private_key);
+        }
+
+        let mut keystore = HdKeystore::new_in_language(
+            MnemonicLanguage::Japanese,
+            24,
+            TEST_PASSWORD,
+            "",
+            Metadata::default(),
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(keystore.mnemonic().unwrap().split('\u{3000}').count(), 24);
+
+        let ret = HdKeystore::new_in_language(
+            MnemonicLanguage::English,
+            13,
+            TEST_PASSWORD,
+            "",
+            Metadata::default(),
+            TypedKdfParams::default(),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "mnemonic_length_invalid");
+
+        let mut keystore = HdKeystore::new_in_language(
+            MnemonicLanguage::Czech,
+            15,
+            TEST_PASSWORD,
+            "",
+            Metadata::default(),
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(keystore.mnemonic().unwrap().split(' ').count(), 15);
+
+        let ret = HdKeystore::from_mnemonic_in_language(
+            TEST_MNEMONIC,
+            MnemonicLanguage::Czech,
+            "",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "mnemonic_word_invalid");
+
+        let ret = HdKeystore::from_mnemonic_in_language(
+            "napadat nocleh klacek doplnit sotva pokrok geolog zmar hoch motorka strach zmar",
+            MnemonicLanguage::Czech,
+            "",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        );
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "mnemonic_checksum_invalid"
+        );
+    }
+
+    #[test]
+    pub fn export_shares() {
+        let mut keystore = HdKeystore::from_mnemonic_with_passphrase(
+            TEST_MNEMONIC,
+            "TREZOR",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        let ret = keystore.export_shares(2, &[(2, 3)], "slip39_passphrase");
+        assert_eq!(format!("{}", ret.err().unwrap()), "keystore_locked");
+
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        let groups = keystore
+            .export_shares(1, &[(2, 3)], "slip39_passphrase")
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].len(), 3);
+        assert_eq!(groups[0][0].split(' ').count(), 59);
+
+        // the master secret of the shares is the seed of the mnemonic with the BIP-39 passphrase
+        let shares = vec![groups[0][2].clone(), groups[0][0].clone()];
+        let master_secret = combine_shares(&shares, "slip39_passphrase").unwrap();
+        assert_eq!(master_secret, *keystore.cache.as_ref().unwrap().seed);
+
+        let ret = HdKeystore::from_shares(
+            &shares[..1],
+            "slip39_passphrase",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "insufficient_shares");
+
+        let recovered = HdKeystore::from_shares(
+            &shares,
+            "slip39_passphrase",
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        assert_eq!(recovered.store.key_hash, keystore.store.key_hash);
+        assert!(recovered.store.slip39);
+
+        let json = serde_json::to_string(&recovered.store).unwrap();
+        let mut recovered = HdKeystore::from_store(serde_json::from_str(&json).unwrap());
+        recovered.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        assert_eq!(
+            recovered.derive_coin::<MockAddress>(&coin_info).unwrap(),
+            keystore.derive_coin::<MockAddress>(&coin_info).unwrap()
+        );
+        assert_eq!(
+            format!("{}", recovered.mnemonic().err().unwrap()),
+            "keystore_has_no_mnemonic"
+        );
+
+        // the shares of the recovered keystore keep the same master secret
+        let groups = recovered.export_shares(1, &[(1, 1)], "").unwrap();
+        assert_eq!(
+            combine_shares(&groups[0], "").unwrap(),
+            *keystore.cache.as_ref().unwrap().seed
+        );
+    }
+
     //    #[test]
     //    pub fn generate_seed() {
     //        let mnemonic = Mnemonic::from_phrase(
@@ -413,4 +1026,96 @@ mod tests {
         );
         assert_eq!(keystore.store.active_accounts.len(), 1);
     }
+
+    #[test]
+    pub fn master_fingerprint() {
+        let mut keystore =
+            HdKeystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        assert!(keystore.master_fingerprint().is_err());
+
+        let _ = keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(
+            hex::encode(keystore.master_fingerprint().unwrap()),
+            "1468dba9"
+        );
+    }
+
+    #[test]
+    pub fn cache_derived_keys() {
+        let mut keystore =
+            HdKeystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        let _ = keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        let acc = keystore.derive_coin::<MockAddress>(&coin_info).unwrap();
+
+        let private_key = keystore.find_private_key("BITCOIN", &acc.address).unwrap();
+        assert_eq!(
+            hex::encode(private_key.to_bytes()),
+            "c4db8f29890ee84a179e42ec2b586b2db8d1883d337fc91070d079ca231e25f7"
+        );
+        let private_key = keystore
+            .find_private_key_by_path("BITCOIN", &acc.address, "0/1")
+            .unwrap();
+        assert_eq!(
+            hex::encode(private_key.public_key().to_bytes()),
+            "024fb7df3961e08f01025e434ea19708a4317d2fe59775cddd38df6e8a2d30697d"
+        );
+
+        assert_eq!(
+            hex::encode(keystore.master_fingerprint().unwrap()),
+            "1468dba9"
+        );
+
+        let coin_info = CoinInfo {
+            coin: "TEZOS".to_string(),
+            derivation_path: "m/44'/1729'/0'/0'".to_string(),
+            curve: CurveType::ED25519,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        let acc = keystore.derive_coin::<MockAddress>(&coin_info).unwrap();
+        let private_key = keystore.find_private_key("TEZOS", &acc.address).unwrap();
+        let root = TypedDeterministicPrivateKey::from_bip39_seed(
+            CurveType::ED25519,
+            &keystore.cache.as_ref().unwrap().seed,
+        )
+        .unwrap();
+        assert_eq!(
+            private_key.to_bytes(),
+            root.derive("m/44'/1729'/0'/0'")
+                .unwrap()
+                .private_key()
+                .to_bytes()
+        );
+
+        let cache = keystore.cache.as_ref().unwrap();
+        assert_eq!(cache.roots.len(), 2);
+        assert_eq!(cache.account_keys.len(), 2);
+        assert!(cache
+            .account_keys
+            .contains_key(&(CurveType::SECP256k1, "m/44'/0'/0'".to_string())));
+        assert!(cache
+            .account_keys
+            .contains_key(&(CurveType::ED25519, "m/44'/1729'/0'".to_string())));
+
+        keystore.lock();
+        assert!(keystore.cache.is_none());
+        assert_eq!(
+            format!(
+                "{}",
+                keystore
+                    .find_private_key("BITCOIN", &acc.address)
+                    .err()
+                    .unwrap()
+            ),
+            "keystore_locked"
+        );
+    }
 }
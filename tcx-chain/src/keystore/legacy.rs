@@ -0,0 +1,89 @@
+use super::Error;
+use super::Result;
+
+use serde::Deserialize;
+use tcx_crypto::{Crypto, EncPair, Key, TypedKdfParams};
+
+/// The secret stored in a legacy keystore
+#[derive(Debug, Clone, PartialEq)]
+pub enum LegacySecret {
+    Mnemonic(String),
+    /// Hex of the raw key, or the WIF which imToken v2 stored for bitcoin wallets
+    PrivateKey(String),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LegacyMetadata {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    password_hint: String,
+}
+
+/// Keystore files written by imToken v2 and other Web3 Secret Storage (V3) wallets
+///
+/// NOTE: Only the secret and the meta are migrated, accounts must be derived again
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyKeystore {
+    pub version: i64,
+    #[serde(alias = "Crypto")]
+    crypto: Crypto<TypedKdfParams>,
+    enc_mnemonic: Option<EncPair>,
+    #[serde(default, rename = "imTokenMeta")]
+    meta: Option<LegacyMetadata>,
+}
+
+impl LegacyKeystore {
+    pub const V3_VERSION: i64 = 3i64;
+    pub const V44_VERSION: i64 = 44i64;
+    pub const IDENTITY_VERSION: i64 = 10000i64;
+
+    pub fn from_json(json: &str) -> Result<LegacyKeystore> {
+        let keystore: LegacyKeystore = serde_json::from_str(json)?;
+
+        match keystore.version {
+            Self::V3_VERSION => {}
+            Self::V44_VERSION | Self::IDENTITY_VERSION if keystore.enc_mnemonic.is_some() => {}
+            _ => return Err(Error::InvalidVersion.into()),
+        }
+        // the file comes from another wallet, the KDF costs must be checked before deriving
+        keystore.crypto.validate_import()?;
+        Ok(keystore)
+    }
+
+    pub fn name(&self) -> String {
+        self.meta
+            .as_ref()
+            .map(|meta| meta.name.to_owned())
+            .unwrap_or_default()
+    }
+
+    pub fn password_hint(&self) -> String {
+        self.meta
+            .as_ref()
+            .map(|meta| meta.password_hint.to_owned())
+            .unwrap_or_default()
+    }
+
+    /// The mnemonic is preferred when the keystore holds both of the mnemonic and the key
+    pub fn decrypt(&self, password: &str) -> Result<LegacySecret> {
+        let key = Key::Password(password.to_owned());
+        if let Some(enc_mnemonic) = &self.enc_mnemonic {
+            let mnemonic = self.crypto.decrypt_enc_pair(key, enc_mnemonic)?;
+            return Ok(LegacySecret::Mnemonic(String::from_utf8(
+                mnemonic.to_vec(),
+            )?));
+        }
+
+        let private_key = self.crypto.decrypt(key)?;
+        if private_key.len() == 32 {
+            Ok(LegacySecret::PrivateKey(hex::encode(&*private_key)))
+        } else {
+            Ok(LegacySecret::PrivateKey(String::from_utf8(
+                private_key.to_vec(),
+            )?))
+        }
+    }
+}
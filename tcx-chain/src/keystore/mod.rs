@@ -3,20 +3,34 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 mod guard;
 mod hd;
+mod legacy;
 mod private;
+mod watch;
 
 use serde::{Deserialize, Serialize};
 
 use tcx_constants::{CoinInfo, CurveType};
 
 pub use self::{
-    guard::KeystoreGuard, hd::key_hash_from_mnemonic, hd::HdKeystore,
-    private::key_hash_from_private_key, private::PrivateKeystore,
+    guard::KeystoreGuard, hd::key_hash_from_mnemonic, hd::key_hash_from_mnemonic_in_language,
+    hd::key_hash_from_mnemonic_with_passphrase, hd::HdKeystore, hd::MnemonicLanguage,
+    legacy::LegacyKeystore, legacy::LegacySecret, private::key_hash_from_private_key,
+    private::PrivateKeystore, watch::key_hash_from_watch_key, watch::WatchKeystore,
 };
 
-use crate::signer::ChainSigner;
-use tcx_crypto::{Crypto, Pbkdf2Params};
-use tcx_primitive::{TypedDeterministicPublicKey, TypedPrivateKey, TypedPublicKey};
+use crate::signer::{self_check_signature, ChainSigner};
+use tcx_crypto::{Crypto, EncPair, Key, TypedKdfParams};
+use tcx_primitive::{
+    get_account_path, Derive, TypedDeterministicPublicKey, TypedPrivateKey, TypedPublicKey,
+};
+use zeroize::Zeroizing;
+
+/// The BIP-44 gap limit, the discovery stops after this number of consecutive unused addresses
+pub const DEFAULT_GAP_LIMIT: u32 = 20;
+/// The most addresses derived in one call, also the largest gap limit of the discovery
+pub const MAX_DERIVED_ADDRESSES: u32 = 1000;
+/// Indexes from 2^31 are hardened, which the extended public key can not derive
+const HARDENED_INDEX: u32 = 1 << 31;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -24,7 +38,17 @@ pub(crate) struct Store {
     pub id: String,
     pub version: i64,
     pub key_hash: String,
-    pub crypto: Crypto<Pbkdf2Params>,
+    pub crypto: Crypto<TypedKdfParams>,
+    /// The BIP-39 passphrase of a HD keystore, it is encrypted by the same key as the mnemonic
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enc_passphrase: Option<EncPair>,
+    /// The word list of the mnemonic of a HD keystore, it is english if not present
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<MnemonicLanguage>,
+    /// A HD keystore recovered from SLIP-39 shares encrypts the master secret in place of the
+    /// mnemonic
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub slip39: bool,
     pub active_accounts: Vec<Account>,
 
     #[serde(rename = "imTokenMeta")]
@@ -51,6 +75,22 @@ pub enum Error {
     InvalidVersion,
     #[fail(display = "pkstore_can_not_add_other_curve_account")]
     PkstoreCannotAddOtherCurveAccount,
+    #[fail(display = "unsupported_language")]
+    UnsupportedLanguage,
+    #[fail(display = "keystore_has_no_mnemonic")]
+    KeystoreHasNoMnemonic,
+    #[fail(display = "pkstore_can_not_export_shares")]
+    PkstoreCannotExportShares,
+    #[fail(display = "watch_key_invalid")]
+    WatchKeyInvalid,
+    #[fail(display = "watch_only_keystore_has_no_private_key")]
+    WatchOnlyKeystoreHasNoPrivateKey,
+    #[fail(display = "invalid_gap_limit")]
+    InvalidGapLimit,
+    #[fail(display = "invalid_address_count")]
+    InvalidAddressCount,
+    #[fail(display = "signature_self_check_failed")]
+    SignatureSelfCheckFailed,
 }
 
 fn transform_mnemonic_error(err: failure::Error) -> Error {
@@ -77,6 +117,15 @@ pub struct Account {
     pub public_key: Option<String>,
 }
 
+/// The result of the address discovery on the receive or the change chain of an account
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressDiscovery {
+    /// The addresses having history, in the order of the index
+    pub used_addresses: Vec<Account>,
+    /// The first address after the last used one
+    pub next_address: Account,
+}
+
 /// Chain address interface, for encapsulate derivation
 pub trait Address {
     // Incompatible between the trait `Address:PubKey is not implemented for `&<impl curve::PrivateKey as curve::PrivateKey>::PublicKey`
@@ -97,6 +146,8 @@ pub enum Source {
     Mnemonic,
     NewIdentity,
     RecoveredIdentity,
+    Watch,
+    Slip39,
 }
 
 /// Metadata of keystore, for presenting wallet data
@@ -135,6 +186,7 @@ impl Default for Metadata {
 pub enum Keystore {
     PrivateKey(PrivateKeystore),
     Hd(HdKeystore),
+    Watch(WatchKeystore),
 }
 
 impl Keystore {
@@ -164,6 +216,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.store(),
             Keystore::Hd(ks) => ks.store(),
+            Keystore::Watch(ks) => ks.store(),
         }
     }
 
@@ -171,6 +224,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.store_mut(),
             Keystore::Hd(ks) => ks.store_mut(),
+            Keystore::Watch(ks) => ks.store_mut(),
         }
     }
 
@@ -186,6 +240,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.unlock_by_password(password),
             Keystore::Hd(ks) => ks.unlock_by_password(password),
+            Keystore::Watch(ks) => ks.unlock_by_password(password),
         }
     }
 
@@ -193,6 +248,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.unlock_by_derived_key(derived_key),
             Keystore::Hd(ks) => ks.unlock_by_derived_key(derived_key),
+            Keystore::Watch(ks) => ks.unlock_by_derived_key(derived_key),
         }
     }
 
@@ -201,13 +257,14 @@ impl Keystore {
         self.store()
             .crypto
             .generate_derived_key(password)
-            .map(|arr| hex::encode(arr))
+            .map(|arr| hex::encode(&*arr))
     }
 
     pub fn is_locked(&self) -> bool {
         match self {
             Keystore::PrivateKey(ks) => ks.is_locked(),
             Keystore::Hd(ks) => ks.is_locked(),
+            Keystore::Watch(ks) => ks.is_locked(),
         }
     }
 
@@ -215,6 +272,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(_) => false,
             Keystore::Hd(_) => true,
+            Keystore::Watch(_) => false,
         }
     }
 
@@ -222,6 +280,22 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(pk_store) => pk_store.private_key(),
             Keystore::Hd(hd_store) => hd_store.mnemonic(),
+            Keystore::Watch(watch_store) => watch_store.key_expression(),
+        }
+    }
+
+    /// Split the seed of a HD keystore into SLIP-39 shares encrypted by the SLIP-39 passphrase,
+    /// each group is given as the pair of (member_threshold, member_count)
+    pub fn export_shares(
+        &self,
+        group_threshold: u8,
+        groups: &[(u8, u8)],
+        passphrase: &str,
+    ) -> Result<Vec<Vec<String>>> {
+        match self {
+            Keystore::PrivateKey(_) => Err(Error::PkstoreCannotExportShares.into()),
+            Keystore::Hd(hd_store) => hd_store.export_shares(group_threshold, groups, passphrase),
+            Keystore::Watch(_) => Err(Error::WatchOnlyKeystoreHasNoPrivateKey.into()),
         }
     }
 
@@ -245,8 +319,10 @@ impl Keystore {
                     hd_store.find_private_key(coin, main_address)?
                 };
 
-                Ok(hex::encode(typed_pk.to_bytes()))
+                let bytes = Zeroizing::new(typed_pk.to_bytes());
+                Ok(hex::encode(&*bytes))
             }
+            Keystore::Watch(_) => Err(Error::WatchOnlyKeystoreHasNoPrivateKey.into()),
         }
     }
 
@@ -254,6 +330,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.lock(),
             Keystore::Hd(ks) => ks.lock(),
+            Keystore::Watch(ks) => ks.lock(),
         }
     }
 
@@ -261,6 +338,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.derive_coin::<A>(coin_info),
             Keystore::Hd(ks) => ks.derive_coin::<A>(coin_info),
+            Keystore::Watch(ks) => ks.derive_coin::<A>(coin_info),
         }
     }
 
@@ -268,6 +346,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.find_private_key(address),
             Keystore::Hd(ks) => ks.find_private_key(symbol, address),
+            Keystore::Watch(_) => Err(Error::WatchOnlyKeystoreHasNoPrivateKey.into()),
         }
     }
 
@@ -280,6 +359,7 @@ impl Keystore {
         match self {
             Keystore::Hd(ks) => ks.find_private_key_by_path(symbol, address, path),
             Keystore::PrivateKey(ks) => ks.find_private_key(address),
+            Keystore::Watch(_) => Err(Error::WatchOnlyKeystoreHasNoPrivateKey.into()),
         }
     }
 
@@ -299,14 +379,111 @@ impl Keystore {
     ) -> Result<TypedDeterministicPublicKey> {
         match self {
             Keystore::Hd(ks) => ks.find_deterministic_public_key(symbol, address),
+            Keystore::Watch(ks) => ks.find_deterministic_public_key(symbol, address),
             _ => Err(Error::CannotDeriveKey.into()),
         }
     }
 
+    pub fn master_fingerprint(&mut self) -> Result<Vec<u8>> {
+        match self {
+            Keystore::Hd(ks) => ks.master_fingerprint(),
+            Keystore::Watch(ks) => ks.master_fingerprint(),
+            _ => Err(Error::CannotDeriveKey.into()),
+        }
+    }
+
+    /// Derive the addresses `{change}/{index}` for the indexes in `start..start + count` under the
+    /// account of `address`. Only the extended public key of the account is used, so the keystore
+    /// can be locked, but the curve must be secp256k1. At most `MAX_DERIVED_ADDRESSES` are derived
+    pub fn derive_addresses<A: Address>(
+        &self,
+        symbol: &str,
+        address: &str,
+        change: u32,
+        start: u32,
+        count: u32,
+    ) -> Result<Vec<Account>> {
+        let account = self
+            .account(symbol, address)
+            .ok_or(Error::AccountNotFound)?;
+        tcx_ensure!(
+            account.curve == CurveType::SECP256k1 && !account.ext_pub_key.is_empty(),
+            Error::CannotDeriveKey
+        );
+        tcx_ensure!(count <= MAX_DERIVED_ADDRESSES, Error::InvalidAddressCount);
+        tcx_ensure!(
+            change < HARDENED_INDEX && start.saturating_add(count) <= HARDENED_INDEX,
+            Error::CannotDeriveKey
+        );
+        let end = start + count;
+
+        let account_path = get_account_path(&account.derivation_path)?;
+        let change_key =
+            TypedDeterministicPublicKey::from_hex(account.curve, &account.ext_pub_key)?
+                .derive(&change.to_string())?;
+        (start..end)
+            .map(|index| {
+                let public_key = change_key.derive(&index.to_string())?.public_key();
+                let coin_info = CoinInfo {
+                    coin: account.coin.to_string(),
+                    derivation_path: format!("{}/{}/{}", account_path, change, index),
+                    curve: account.curve,
+                    network: account.network.to_string(),
+                    seg_wit: account.seg_wit.to_string(),
+                };
+                Ok(Account {
+                    address: A::from_public_key(&public_key, &coin_info)?,
+                    derivation_path: coin_info.derivation_path,
+                    public_key: Some(hex::encode(public_key.to_bytes())),
+                    ..account.clone()
+                })
+            })
+            .collect()
+    }
+
+    /// BIP-44 address discovery on the chain `change` of the account, the addresses are scanned
+    /// from index 0 until `gap_limit` consecutive addresses have no history
+    pub fn discover_addresses<A: Address, F: FnMut(&str) -> Result<bool>>(
+        &self,
+        symbol: &str,
+        address: &str,
+        change: u32,
+        gap_limit: u32,
+        mut has_history: F,
+    ) -> Result<AddressDiscovery> {
+        tcx_ensure!(
+            gap_limit > 0 && gap_limit <= MAX_DERIVED_ADDRESSES,
+            Error::InvalidGapLimit
+        );
+
+        let mut used_addresses = vec![];
+        let mut next_index = 0u32;
+        let mut index = 0u32;
+        while index - next_index < gap_limit {
+            let count = gap_limit - (index - next_index);
+            for account in self.derive_addresses::<A>(symbol, address, change, index, count)? {
+                if has_history(&account.address)? {
+                    used_addresses.push(account);
+                    next_index = index + 1;
+                }
+                index += 1;
+            }
+        }
+
+        let next_address = self
+            .derive_addresses::<A>(symbol, address, change, next_index, 1)?
+            .remove(0);
+        Ok(AddressDiscovery {
+            used_addresses,
+            next_address,
+        })
+    }
+
     pub fn account(&self, symbol: &str, address: &str) -> Option<&Account> {
         match self {
             Keystore::PrivateKey(ks) => ks.account(symbol, address),
             Keystore::Hd(ks) => ks.account(symbol, address),
+            Keystore::Watch(ks) => ks.account(symbol, address),
         }
     }
 
@@ -314,6 +491,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.store().active_accounts.as_slice(),
             Keystore::Hd(ks) => ks.store().active_accounts.as_slice(),
+            Keystore::Watch(ks) => ks.store().active_accounts.as_slice(),
         }
     }
 
@@ -321,7 +499,40 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => ks.verify_password(password),
             Keystore::Hd(ks) => ks.verify_password(password),
+            Keystore::Watch(ks) => ks.verify_password(password),
+        }
+    }
+
+    /// Re-encrypt the secret by the new password with a fresh salt and iv, the KDF is kept
+    /// if `kdf` is none. The id and the accounts are not changed
+    pub fn change_password(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+        kdf: Option<TypedKdfParams>,
+    ) -> Result<()> {
+        let store = self.store_mut();
+        let secret = store
+            .crypto
+            .decrypt(Key::Password(old_password.to_owned()))?;
+        let passphrase = match &store.enc_passphrase {
+            Some(enc_pair) => Some(
+                store
+                    .crypto
+                    .decrypt_enc_pair(Key::Password(old_password.to_owned()), enc_pair)?,
+            ),
+            None => None,
+        };
+        let kdf = kdf.unwrap_or_else(|| store.crypto.kdfparams().clone());
+        store.crypto = Crypto::new_with_kdf(new_password, &secret, kdf)?;
+        if let Some(passphrase) = passphrase {
+            store.enc_passphrase = Some(store.crypto.derive_enc_pair(new_password, &passphrase)?);
         }
+        Ok(())
+    }
+
+    pub fn set_password_hint(&mut self, password_hint: &str) {
+        self.store_mut().meta.password_hint = password_hint.to_owned();
     }
 
     pub fn from_json(json: &str) -> Result<Keystore> {
@@ -332,6 +543,7 @@ impl Keystore {
             PrivateKeystore::VERSION => {
                 Ok(Keystore::PrivateKey(PrivateKeystore::from_store(store)))
             }
+            WatchKeystore::VERSION => Ok(Keystore::Watch(WatchKeystore::from_store(store))),
 
             _ => Err(Error::InvalidVersion.into()),
         }
@@ -341,6 +553,7 @@ impl Keystore {
         match self {
             Keystore::PrivateKey(ks) => serde_json::to_string(ks.store()).unwrap(),
             Keystore::Hd(ks) => serde_json::to_string(ks.store()).unwrap(),
+            Keystore::Watch(ks) => serde_json::to_string(ks.store()).unwrap(),
         }
     }
 }
@@ -358,7 +571,9 @@ impl ChainSigner for Keystore {
         } else {
             self.find_private_key(symbol, address)?
         };
-        private_key.sign_recoverable(data)
+        let signature = private_key.sign_recoverable(data)?;
+        self_check_signature(&private_key.public_key(), data, &signature)?;
+        Ok(signature)
     }
 
     fn sign_hash(
@@ -374,20 +589,27 @@ impl ChainSigner for Keystore {
             self.find_private_key(symbol, address)?
         };
 
-        private_key.sign(data)
+        let signature = private_key.sign(data)?;
+        self_check_signature(&private_key.public_key(), data, &signature)?;
+        Ok(signature)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::keystore::Keystore::{Hd, PrivateKey};
-    use crate::{ChainSigner, HdKeystore, Keystore, Metadata, PrivateKeystore, Source};
+    use crate::{
+        ChainSigner, HdKeystore, Keystore, LegacyKeystore, LegacySecret, Metadata, PrivateKeystore,
+        Source,
+    };
     use serde_json::Value;
     use std::str::FromStr;
 
     use crate::keystore::metadata_default_source;
-    use tcx_constants::{TEST_MNEMONIC, TEST_PASSWORD};
-    use tcx_primitive::{Ss58Codec, ToHex};
+    use crate::{Address, Result};
+    use tcx_constants::{CoinInfo, CurveType, TEST_MNEMONIC, TEST_PASSWORD};
+    use tcx_crypto::{SCryptParams, TypedKdfParams};
+    use tcx_primitive::{Ss58Codec, ToHex, TypedPublicKey};
 
     static HD_KEYSTORE_JSON: &'static str = r#"
         {
@@ -508,6 +730,255 @@ mod tests {
         assert!(ret.is_err());
     }
 
+    #[test]
+    fn test_legacy_keystore() {
+        let keystore = LegacyKeystore::from_json(OLD_KEYSTORE_JSON).unwrap();
+        assert_eq!("BTC", keystore.name());
+        assert_eq!(
+            format!("{}", keystore.decrypt(TEST_PASSWORD).unwrap_err()),
+            "password_incorrect"
+        );
+        assert_eq!(
+            keystore.decrypt("imtoken1").unwrap(),
+            LegacySecret::Mnemonic(
+                "inject kidney empty canal shadow pact comfort wife crush horse wife sketch"
+                    .to_owned()
+            )
+        );
+
+        let v3_json = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {
+                    "iv": "6087dab2f9fdbbfaddc31a909735c1e6"
+                },
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+            "version": 3
+        }"#;
+        let keystore = LegacyKeystore::from_json(v3_json).unwrap();
+        assert_eq!("", keystore.name());
+        assert_eq!(
+            keystore.decrypt("testpassword").unwrap(),
+            LegacySecret::PrivateKey(
+                "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d".to_owned()
+            )
+        );
+
+        let v3_scrypt_json = r#"{
+            "Crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {
+                    "iv": "799f757ee52b7c95aa76967fa908676c"
+                },
+                "ciphertext": "d5c053f4893fbbaa0d58cc87d5b82abdbac55599a46fe6eb8e355487e5c4799039a4a4ae7f365db3d573946f3acb51a2cfb2aafe",
+                "kdf": "scrypt",
+                "kdfparams": {
+                    "dklen": 32,
+                    "n": 1024,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "e3fa7f40fecac7f6c61326dfb6aba4697c2daba9ecd41f017e996ae15aa18a51"
+                },
+                "mac": "a10b412993ec783e854cb339b1f4165a013d41267adb561ed9ab47c209dea3ab"
+            },
+            "version": 3
+        }"#;
+        let keystore = LegacyKeystore::from_json(v3_scrypt_json).unwrap();
+        assert_eq!(
+            keystore.decrypt(TEST_PASSWORD).unwrap(),
+            LegacySecret::PrivateKey(
+                "L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB".to_owned()
+            )
+        );
+
+        let ret = LegacyKeystore::from_json(HD_KEYSTORE_JSON);
+        assert_eq!(format!("{}", ret.unwrap_err()), "invalid_version");
+    }
+
+    #[test]
+    fn test_malformed_legacy_keystore() {
+        let v3_json = r#"{
+            "crypto": {
+                "cipher": "aes-128-ctr",
+                "cipherparams": {
+                    "iv": "6087dab2f9fdbbfaddc31a909735c1e6"
+                },
+                "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                "kdf": "pbkdf2",
+                "kdfparams": {
+                    "c": 262144,
+                    "dklen": 32,
+                    "prf": "hmac-sha256",
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },
+                "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+            },
+            "version": 3
+        }"#;
+        assert!(LegacyKeystore::from_json(v3_json).is_ok());
+
+        let scrypt_params = r#""kdfparams": {
+                    "dklen": 32,
+                    "n": 1073741824,
+                    "p": 1,
+                    "r": 8,
+                    "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                },"#;
+        let pbkdf2_params_start = v3_json.find(r#""kdfparams""#).unwrap();
+        let pbkdf2_params_end = v3_json.find(r#""mac""#).unwrap();
+        let pbkdf2_params = &v3_json[pbkdf2_params_start..pbkdf2_params_end];
+        let huge_scrypt = v3_json
+            .replace(pbkdf2_params, scrypt_params)
+            .replace(r#""kdf": "pbkdf2""#, r#""kdf": "scrypt""#);
+
+        let cases = vec![
+            (huge_scrypt.clone(), "kdf_params_invalid"),
+            (
+                huge_scrypt.replace("1073741824", "1024"),
+                "password_incorrect",
+            ),
+            (
+                v3_json.replace(r#""kdf": "pbkdf2""#, r#""kdf": "scrypt""#),
+                "kdf_params_invalid",
+            ),
+            (
+                v3_json.replace(r#""c": 262144"#, r#""c": 0"#),
+                "kdf_params_invalid",
+            ),
+            (
+                v3_json.replace(r#""c": 262144"#, r#""c": 4294967295"#),
+                "kdf_params_invalid",
+            ),
+            (
+                v3_json.replace("ae3cd4e7013836a3", "not_hex_salt____"),
+                "kdf_params_invalid",
+            ),
+            (
+                v3_json.replace("6087dab2f9fdbbfaddc31a909735c1e6", "6087dab2"),
+                "invalid_key_iv_length",
+            ),
+            (
+                v3_json.replace("5318b4d5bcd28de6", "not_hex_ciphertx"),
+                "invalid_ciphertext",
+            ),
+        ];
+        for (json, err) in cases {
+            let ret = LegacyKeystore::from_json(&json).and_then(|ks| ks.decrypt("testpassword"));
+            assert_eq!(format!("{}", ret.unwrap_err()), err);
+        }
+    }
+
+    #[test]
+    fn test_scrypt_keystore() {
+        let kdf = TypedKdfParams::SCrypt(SCryptParams::new(1024, 8, 1));
+        let hd_store = HdKeystore::from_mnemonic_with_kdf(
+            TEST_MNEMONIC,
+            TEST_PASSWORD,
+            Metadata::default(),
+            kdf.clone(),
+        )
+        .unwrap();
+        let pk_store = PrivateKeystore::from_private_key_with_kdf(
+            "a392604efc2fad9c0b3da43b5f698a2e3f270f170d859912be0d54742275c5f6",
+            TEST_PASSWORD,
+            Metadata::default(),
+            kdf,
+        )
+        .unwrap();
+
+        for keystore in vec![Hd(hd_store), PrivateKey(pk_store)] {
+            let json = Value::from_str(&keystore.to_json()).unwrap();
+            assert_eq!(json["crypto"]["kdf"], "scrypt");
+            assert_eq!(json["crypto"]["kdfparams"]["n"], 1024);
+
+            let mut keystore = Keystore::from_json(&keystore.to_json()).unwrap();
+            assert!(keystore.verify_password(TEST_PASSWORD));
+            assert!(!keystore.verify_password("WRONG PASSWORD"));
+            keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+            assert!(keystore.export().is_ok());
+        }
+
+        let ret = HdKeystore::from_mnemonic_with_kdf(
+            TEST_MNEMONIC,
+            TEST_PASSWORD,
+            Metadata::default(),
+            TypedKdfParams::SCrypt(SCryptParams::new(1000, 8, 1)),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "kdf_params_invalid");
+    }
+
+    #[test]
+    fn test_change_password() {
+        let mut keystore = Keystore::from_json(HD_KEYSTORE_JSON).unwrap();
+        let ret = keystore.change_password("WRONG PASSWORD", "new_password", None);
+        assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
+
+        keystore
+            .change_password(TEST_PASSWORD, "new_password", None)
+            .unwrap();
+        keystore.set_password_hint("new hint");
+        assert!(!keystore.verify_password(TEST_PASSWORD));
+        assert!(keystore.verify_password("new_password"));
+
+        let json = Value::from_str(&keystore.to_json()).unwrap();
+        let origin = Value::from_str(HD_KEYSTORE_JSON).unwrap();
+        assert_eq!(json["id"], origin["id"]);
+        assert_eq!(json["activeAccounts"], origin["activeAccounts"]);
+        assert_eq!(json["crypto"]["kdf"], "pbkdf2");
+        assert_eq!(json["crypto"]["kdfparams"]["c"], 10240);
+        assert_ne!(
+            json["crypto"]["kdfparams"]["salt"],
+            origin["crypto"]["kdfparams"]["salt"]
+        );
+        assert_ne!(
+            json["crypto"]["cipherparams"]["iv"],
+            origin["crypto"]["cipherparams"]["iv"]
+        );
+        assert_eq!(json["imTokenMeta"]["passwordHint"], "new hint");
+
+        let kdf = TypedKdfParams::SCrypt(SCryptParams::new(1024, 8, 1));
+        keystore
+            .change_password("new_password", TEST_PASSWORD, Some(kdf))
+            .unwrap();
+        let mut keystore = Keystore::from_json(&keystore.to_json()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(
+            "inject kidney empty canal shadow pact comfort wife crush horse wife sketch",
+            keystore.export().unwrap()
+        );
+
+        // the passphrase is encrypted by the new password too
+        let mut keystore = Keystore::Hd(
+            HdKeystore::from_mnemonic_with_passphrase(
+                TEST_MNEMONIC,
+                "TREZOR",
+                TEST_PASSWORD,
+                Metadata::default(),
+                TypedKdfParams::default(),
+            )
+            .unwrap(),
+        );
+        keystore
+            .change_password(TEST_PASSWORD, "new_password", None)
+            .unwrap();
+        let mut keystore = Keystore::from_json(&keystore.to_json()).unwrap();
+        keystore.unlock_by_password("new_password").unwrap();
+        assert_eq!(
+            hex::encode(keystore.master_fingerprint().unwrap()),
+            "112ea845"
+        );
+    }
+
     #[test]
     fn test_sign_hash() {
         let msg = hex::decode("645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76")
@@ -713,4 +1184,147 @@ mod tests {
     fn test_default_source() {
         assert_eq!(Source::Mnemonic, metadata_default_source());
     }
+
+    struct PublicKeyAddress {}
+    impl Address for PublicKeyAddress {
+        fn from_public_key(public_key: &TypedPublicKey, _coin: &CoinInfo) -> Result<String> {
+            Ok(hex::encode(public_key.to_bytes()))
+        }
+
+        fn is_valid(_address: &str, _coin: &CoinInfo) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn test_derive_addresses() {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        let account = keystore
+            .derive_coin::<PublicKeyAddress>(&coin_info)
+            .unwrap();
+        keystore.lock();
+
+        let accounts = keystore
+            .derive_addresses::<PublicKeyAddress>("BITCOIN", &account.address, 0, 0, 2)
+            .unwrap();
+        assert_eq!(accounts[0], account);
+        assert_eq!(
+            accounts[1].address,
+            "024fb7df3961e08f01025e434ea19708a4317d2fe59775cddd38df6e8a2d30697d"
+        );
+        assert_eq!(accounts[1].derivation_path, "m/44'/0'/0'/0/1");
+
+        let accounts = keystore
+            .derive_addresses::<PublicKeyAddress>("BITCOIN", &account.address, 1, 0, 1)
+            .unwrap();
+        assert_eq!(
+            accounts[0].address,
+            "0352470ace48f25b01b9c341e3b0e033fc32a203fb7a81a0453f97d94eca819a35"
+        );
+        assert_eq!(accounts[0].derivation_path, "m/44'/0'/0'/1/0");
+
+        let ret = keystore.derive_addresses::<PublicKeyAddress>("BITCOIN", "unknown", 0, 0, 1);
+        assert_eq!(format!("{}", ret.err().unwrap()), "account_not_found");
+        let ret = keystore.derive_addresses::<PublicKeyAddress>(
+            "BITCOIN",
+            &account.address,
+            0,
+            std::u32::MAX,
+            2,
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "can_not_derive_key");
+        let ret = keystore.derive_addresses::<PublicKeyAddress>(
+            "BITCOIN",
+            &account.address,
+            0,
+            HARDENED_INDEX - 1,
+            2,
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "can_not_derive_key");
+
+        let accounts = keystore
+            .derive_addresses::<PublicKeyAddress>(
+                "BITCOIN",
+                &account.address,
+                0,
+                0,
+                MAX_DERIVED_ADDRESSES,
+            )
+            .unwrap();
+        assert_eq!(accounts.len(), MAX_DERIVED_ADDRESSES as usize);
+        let ret = keystore.derive_addresses::<PublicKeyAddress>(
+            "BITCOIN",
+            &account.address,
+            0,
+            0,
+            MAX_DERIVED_ADDRESSES + 1,
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_address_count");
+    }
+
+    #[test]
+    fn test_discover_addresses() {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        let coin_info = CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/44'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        };
+        let account = keystore
+            .derive_coin::<PublicKeyAddress>(&coin_info)
+            .unwrap();
+
+        let mut scanned = 0;
+        let discovery = keystore
+            .discover_addresses::<PublicKeyAddress, _>("BITCOIN", &account.address, 0, 3, |x| {
+                scanned += 1;
+                Ok(x == "024fb7df3961e08f01025e434ea19708a4317d2fe59775cddd38df6e8a2d30697d")
+            })
+            .unwrap();
+        assert_eq!(scanned, 5);
+        assert_eq!(discovery.used_addresses.len(), 1);
+        assert_eq!(
+            discovery.used_addresses[0].derivation_path,
+            "m/44'/0'/0'/0/1"
+        );
+        assert_eq!(discovery.next_address.derivation_path, "m/44'/0'/0'/0/2");
+
+        let discovery = keystore
+            .discover_addresses::<PublicKeyAddress, _>("BITCOIN", &account.address, 0, 20, |_| {
+                Ok(false)
+            })
+            .unwrap();
+        assert!(discovery.used_addresses.is_empty());
+        assert_eq!(discovery.next_address, account);
+
+        let ret = keystore.discover_addresses::<PublicKeyAddress, _>(
+            "BITCOIN",
+            &account.address,
+            0,
+            0,
+            |_| Ok(false),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_gap_limit");
+        let ret = keystore.discover_addresses::<PublicKeyAddress, _>(
+            "BITCOIN",
+            &account.address,
+            0,
+            MAX_DERIVED_ADDRESSES + 1,
+            |_| Ok(false),
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_gap_limit");
+    }
 }
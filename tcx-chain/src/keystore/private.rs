@@ -1,7 +1,7 @@
 use super::Account;
 use super::{Address, Metadata};
 use tcx_constants::CoinInfo;
-use tcx_crypto::{Crypto, Key, Pbkdf2Params};
+use tcx_crypto::{Crypto, Key, TypedKdfParams};
 
 use super::Error;
 use super::Result;
@@ -10,6 +10,7 @@ use crate::keystore::Store;
 use tcx_crypto::hash::dsha256;
 use tcx_primitive::TypedPrivateKey;
 use uuid::Uuid;
+use zeroize::Zeroizing;
 
 pub fn key_hash_from_private_key(data: &[u8]) -> String {
     hex::encode(dsha256(data)[..20].to_vec())
@@ -18,7 +19,7 @@ pub fn key_hash_from_private_key(data: &[u8]) -> String {
 pub struct PrivateKeystore {
     store: Store,
 
-    private_key: Option<Vec<u8>>,
+    private_key: Option<Zeroizing<Vec<u8>>>,
 }
 
 impl PrivateKeystore {
@@ -112,24 +113,37 @@ impl PrivateKeystore {
     }
 
     pub fn from_private_key(private_key: &str, password: &str, meta: Metadata) -> PrivateKeystore {
-        let key_data: Vec<u8> = hex::decode(private_key).expect("hex can't decode");
+        Self::from_private_key_with_kdf(private_key, password, meta, TypedKdfParams::default())
+            .expect("default kdf params")
+    }
+
+    pub fn from_private_key_with_kdf(
+        private_key: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<PrivateKeystore> {
+        let key_data = Zeroizing::new(hex::decode(private_key).expect("hex can't decode"));
         let key_hash = key_hash_from_private_key(&key_data);
         //        let pk_bytes = hex::decode(private_key).expect("valid private_key");
-        let crypto: Crypto<Pbkdf2Params> = Crypto::new(password, &key_data);
+        let crypto = Crypto::new_with_kdf(password, &key_data, kdf)?;
 
         let store = Store {
             key_hash,
             crypto,
+            enc_passphrase: None,
+            language: None,
+            slip39: false,
             meta,
             id: Uuid::new_v4().to_hyphenated().to_string(),
             version: PrivateKeystore::VERSION,
             active_accounts: vec![],
         };
 
-        PrivateKeystore {
+        Ok(PrivateKeystore {
             store,
             private_key: None,
-        }
+        })
     }
 
     pub(crate) fn private_key_to_account<A: Address>(
@@ -156,11 +170,10 @@ impl PrivateKeystore {
 
     pub(crate) fn private_key(&self) -> Result<String> {
         tcx_ensure!(self.private_key.is_some(), Error::KeystoreLocked);
-        let vec = self.private_key.as_ref().unwrap().to_vec();
-        Ok(hex::encode(&vec))
+        Ok(hex::encode(self.private_key.as_ref().unwrap().as_slice()))
     }
 
-    fn decrypt_private_key(&self, key: Key) -> Result<Vec<u8>> {
+    fn decrypt_private_key(&self, key: Key) -> Result<Zeroizing<Vec<u8>>> {
         self.store.crypto.decrypt(key)
     }
 }
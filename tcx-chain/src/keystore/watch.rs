@@ -0,0 +1,372 @@
+use super::Account;
+use super::{Address, Metadata};
+use tcx_constants::{CoinInfo, CurveType};
+use tcx_crypto::{Crypto, Key, TypedKdfParams};
+
+use super::Error;
+use super::Result;
+use crate::keystore::Store;
+
+use tcx_crypto::hash::dsha256;
+use tcx_primitive::{
+    get_account_path, Bip32DeterministicPublicKey, Derive, DeterministicPublicKey, Ss58Codec,
+    ToHex, TypedDeterministicPublicKey, TypedPublicKey,
+};
+use uuid::Uuid;
+
+/// The origin of the key in a key expression, `[1468dba9/49'/1'/0']`
+struct KeyOrigin {
+    fingerprint: Vec<u8>,
+    path: String,
+}
+
+enum WatchKey {
+    Extended(Bip32DeterministicPublicKey),
+    Public(Vec<u8>),
+}
+
+struct Cache {
+    key_expression: String,
+    origin: Option<KeyOrigin>,
+    key: WatchKey,
+}
+
+/// A key expression of output descriptors(BIP-380): an optional key origin followed by an
+/// extended public key of any version(xpub, ypub, zpub, tpub...) or a hex encoded public key
+fn parse_watch_key(key_expression: &str) -> Result<Cache> {
+    let key_expression = key_expression.trim();
+    let (origin, key) = if key_expression.starts_with('[') {
+        let end = key_expression.find(']').ok_or(Error::WatchKeyInvalid)?;
+        let mut parts = key_expression[1..end].split('/');
+        let fingerprint =
+            hex::decode(parts.next().unwrap_or_default()).map_err(|_| Error::WatchKeyInvalid)?;
+        tcx_ensure!(fingerprint.len() == 4, Error::WatchKeyInvalid);
+
+        let path = std::iter::once("m")
+            .chain(parts)
+            .collect::<Vec<&str>>()
+            .join("/")
+            .replace('h', "'");
+        (
+            Some(KeyOrigin { fingerprint, path }),
+            &key_expression[end + 1..],
+        )
+    } else {
+        (None, key_expression)
+    };
+
+    let key = match hex::decode(key) {
+        Ok(public_key) => WatchKey::Public(public_key),
+        Err(_) => {
+            let (epk, _) = Bip32DeterministicPublicKey::from_ss58check_with_version(key)
+                .map_err(|_| Error::WatchKeyInvalid)?;
+            WatchKey::Extended(epk)
+        }
+    };
+    Ok(Cache {
+        key_expression: key_expression.to_owned(),
+        origin,
+        key,
+    })
+}
+
+/// Extended keys of any version are the same key, so the hash is taken from the key data
+pub fn key_hash_from_watch_key(key_expression: &str) -> Result<String> {
+    let data = match parse_watch_key(key_expression)?.key {
+        WatchKey::Extended(epk) => hex::decode(epk.to_hex())?,
+        WatchKey::Public(public_key) => public_key,
+    };
+    Ok(hex::encode(dsha256(&data)[..20].to_vec()))
+}
+
+/// Keystore of a public key or an extended public key which derives addresses but never signs,
+/// the key is encrypted as well since it reveals every address of the wallet
+pub struct WatchKeystore {
+    store: Store,
+
+    cache: Option<Cache>,
+}
+
+impl WatchKeystore {
+    pub const VERSION: i64 = 11002i64;
+
+    pub(crate) fn store(&self) -> &Store {
+        &self.store
+    }
+
+    pub(crate) fn store_mut(&mut self) -> &mut Store {
+        &mut self.store
+    }
+
+    pub(crate) fn from_store(store: Store) -> Self {
+        WatchKeystore { store, cache: None }
+    }
+
+    pub(crate) fn unlock_by_password(&mut self, password: &str) -> Result<()> {
+        self.unlock(Key::Password(password.to_owned()))
+    }
+
+    pub(crate) fn unlock_by_derived_key(&mut self, derived_key: &str) -> Result<()> {
+        self.unlock(Key::DerivedKey(derived_key.to_owned()))
+    }
+
+    fn unlock(&mut self, key: Key) -> Result<()> {
+        let decrypted = self.store.crypto.decrypt(key)?;
+        self.cache = Some(parse_watch_key(std::str::from_utf8(&decrypted)?)?);
+        Ok(())
+    }
+
+    pub(crate) fn lock(&mut self) {
+        self.cache = None;
+    }
+
+    pub(crate) fn is_locked(&self) -> bool {
+        self.cache.is_none()
+    }
+
+    pub(crate) fn key_expression(&self) -> Result<String> {
+        let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+        Ok(cache.key_expression.to_owned())
+    }
+
+    /// The extended public key derives the addresses under its account, the path of the coin
+    /// must be under the origin of the key if it is known. The public key is the only address
+    pub(crate) fn derive_coin<A: Address>(&mut self, coin_info: &CoinInfo) -> Result<Account> {
+        let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+
+        let (public_key, ext_pub_key) = match &cache.key {
+            WatchKey::Extended(epk) => {
+                tcx_ensure!(
+                    coin_info.curve == CurveType::SECP256k1,
+                    Error::CannotDeriveKey
+                );
+                let account_path = get_account_path(&coin_info.derivation_path)?;
+                if let Some(origin) = &cache.origin {
+                    tcx_ensure!(origin.path == account_path, Error::CannotDeriveKey);
+                }
+                let relative_path = coin_info
+                    .derivation_path
+                    .get(account_path.len() + 1..)
+                    .ok_or(Error::CannotDeriveKey)?;
+                (
+                    TypedPublicKey::Secp256k1(epk.derive(relative_path)?.public_key()),
+                    epk.to_hex(),
+                )
+            }
+            WatchKey::Public(public_key) => (
+                TypedPublicKey::from_slice(coin_info.curve, public_key)?,
+                "".to_owned(),
+            ),
+        };
+
+        let address = A::from_public_key(&public_key, coin_info)?;
+        let account = Account {
+            address,
+            derivation_path: coin_info.derivation_path.to_string(),
+            curve: coin_info.curve,
+            coin: coin_info.coin.to_string(),
+            network: coin_info.network.to_string(),
+            ext_pub_key,
+            seg_wit: coin_info.seg_wit.to_string(),
+            public_key: Some(hex::encode(public_key.to_bytes())),
+        };
+
+        if self
+            .store
+            .active_accounts
+            .iter()
+            .all(|x| x.address != account.address || x.coin != account.coin)
+        {
+            self.store.active_accounts.push(account.clone());
+        }
+        Ok(account)
+    }
+
+    pub(crate) fn account(&self, symbol: &str, address: &str) -> Option<&Account> {
+        self.store
+            .active_accounts
+            .iter()
+            .find(|acc| acc.address == address && acc.coin == symbol)
+    }
+
+    pub(crate) fn find_deterministic_public_key(
+        &self,
+        symbol: &str,
+        address: &str,
+    ) -> Result<TypedDeterministicPublicKey> {
+        let account = self
+            .account(symbol, address)
+            .ok_or(Error::AccountNotFound)?;
+        tcx_ensure!(!account.ext_pub_key.is_empty(), Error::CannotDeriveKey);
+
+        TypedDeterministicPublicKey::from_hex(account.curve, &account.ext_pub_key)
+    }
+
+    /// Only the key with origin knows the fingerprint of the master key
+    pub(crate) fn master_fingerprint(&self) -> Result<Vec<u8>> {
+        let cache = self.cache.as_ref().ok_or(Error::KeystoreLocked)?;
+        cache
+            .origin
+            .as_ref()
+            .map(|origin| origin.fingerprint.clone())
+            .ok_or_else(|| Error::CannotDeriveKey.into())
+    }
+
+    pub(crate) fn verify_password(&self, password: &str) -> bool {
+        self.store.crypto.verify_password(password)
+    }
+
+    pub fn from_key_expression(
+        key_expression: &str,
+        password: &str,
+        meta: Metadata,
+        kdf: TypedKdfParams,
+    ) -> Result<WatchKeystore> {
+        let key_hash = key_hash_from_watch_key(key_expression)?;
+        let crypto = Crypto::new_with_kdf(password, key_expression.trim().as_bytes(), kdf)?;
+
+        let store = Store {
+            key_hash,
+            crypto,
+            enc_passphrase: None,
+            language: None,
+            slip39: false,
+            meta,
+            id: Uuid::new_v4().to_hyphenated().to_string(),
+            version: WatchKeystore::VERSION,
+            active_accounts: vec![],
+        };
+
+        Ok(WatchKeystore { store, cache: None })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Source;
+    use tcx_constants::TEST_PASSWORD;
+
+    struct MockAddress {}
+    impl Address for MockAddress {
+        fn from_public_key(public_key: &TypedPublicKey, _coin: &CoinInfo) -> Result<String> {
+            Ok(hex::encode(public_key.to_bytes()))
+        }
+
+        fn is_valid(_address: &str, _coin: &CoinInfo) -> bool {
+            true
+        }
+    }
+
+    fn coin_info(path: &str) -> CoinInfo {
+        CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: path.to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "NONE".to_string(),
+        }
+    }
+
+    fn watch_keystore(key_expression: &str) -> WatchKeystore {
+        let mut meta = Metadata::default();
+        meta.source = Source::Watch;
+        let mut keystore = WatchKeystore::from_key_expression(
+            key_expression,
+            TEST_PASSWORD,
+            meta,
+            TypedKdfParams::default(),
+        )
+        .unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        keystore
+    }
+
+    #[test]
+    pub fn derive_from_extended_public_key() {
+        let mut keystore = watch_keystore("xpub6CqzLtyKdJN53jPY13W6GdyB8ZGWuFZuBPU4Xh9DXm6Q1cULVLtsyfXSjx4G77rNdCRBgi83LByaWxjtDaZfLAKT6vFUq3EhPtNwTpJigx8");
+        let acc = keystore
+            .derive_coin::<MockAddress>(&coin_info("m/44'/0'/0'/0/1"))
+            .unwrap();
+        assert_eq!(
+            acc.address,
+            "024fb7df3961e08f01025e434ea19708a4317d2fe59775cddd38df6e8a2d30697d"
+        );
+        assert!(keystore.master_fingerprint().is_err());
+
+        // zpub of m/84'/0'/0' with the origin
+        let mut keystore = watch_keystore("[1468dba9/84h/0h/0h]zpub6qytVKvhi1obMLUuCvgvVYCnHQbwh9W8NWZJcu9LjYSr8UbKjjCbKEhBQWBdRrfXrtBbFETgvNo6sjKQrCNGouiMNKhmrYrcmm5UL17MzUV");
+        let acc = keystore
+            .derive_coin::<MockAddress>(&coin_info("m/84'/0'/0'/1/0"))
+            .unwrap();
+        assert_eq!(
+            acc.address,
+            "02785275f3ff700adf9ce40e9f937e4c45b7e9d8564432fd3b7971ca9a00a72d0c"
+        );
+        assert_eq!(
+            hex::encode(keystore.master_fingerprint().unwrap()),
+            "1468dba9"
+        );
+        assert_eq!(keystore.store.active_accounts.len(), 1);
+        let dpk = keystore
+            .find_deterministic_public_key("BITCOIN", &acc.address)
+            .unwrap();
+        assert_eq!(dpk.to_hex(), acc.ext_pub_key);
+
+        let ret = keystore.derive_coin::<MockAddress>(&coin_info("m/44'/0'/0'/0/0"));
+        assert_eq!(format!("{}", ret.err().unwrap()), "can_not_derive_key");
+
+        keystore.lock();
+        let ret = keystore.derive_coin::<MockAddress>(&coin_info("m/84'/0'/0'/0/0"));
+        assert_eq!(format!("{}", ret.err().unwrap()), "keystore_locked");
+    }
+
+    #[test]
+    pub fn derive_from_public_key() {
+        let mut keystore =
+            watch_keystore("0280c98b8ea7cab630defb0c09a4295c2193cdee016c1d5b9b0cb18572b9c370fe");
+        let acc = keystore
+            .derive_coin::<MockAddress>(&coin_info("m/44'/60'/0'/0/0"))
+            .unwrap();
+        assert_eq!(
+            acc.address,
+            "0280c98b8ea7cab630defb0c09a4295c2193cdee016c1d5b9b0cb18572b9c370fe"
+        );
+        assert_eq!(acc.ext_pub_key, "");
+        assert!(keystore
+            .find_deterministic_public_key("BITCOIN", &acc.address)
+            .is_err());
+
+        let json = serde_json::to_string(keystore.store()).unwrap();
+        let mut keystore = crate::Keystore::from_json(&json).unwrap();
+        assert!(!keystore.determinable());
+        assert_eq!(keystore.accounts().len(), 1);
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        assert_eq!(
+            keystore.export().unwrap(),
+            "0280c98b8ea7cab630defb0c09a4295c2193cdee016c1d5b9b0cb18572b9c370fe"
+        );
+        let ret = keystore.find_private_key("BITCOIN", &acc.address);
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "watch_only_keystore_has_no_private_key"
+        );
+    }
+
+    #[test]
+    pub fn key_hash_ignores_the_version() {
+        // the same key encoded as xpub and zpub
+        assert_eq!(
+            key_hash_from_watch_key("xpub6CKMszasQeidek6fYD7g5N1mwUK3ouX8YHWs47MZyXh62GxsEQsU57NuN6GTS3Mh3bwykHGa14617A6HQoYFDSM9deJvgjDeEJxBYsfJ1bs").unwrap(),
+            key_hash_from_watch_key("[1468dba9/84'/0'/0']zpub6qytVKvhi1obMLUuCvgvVYCnHQbwh9W8NWZJcu9LjYSr8UbKjjCbKEhBQWBdRrfXrtBbFETgvNo6sjKQrCNGouiMNKhmrYrcmm5UL17MzUV").unwrap()
+        );
+        assert_eq!(
+            format!("{}", key_hash_from_watch_key("zpub-invalid").err().unwrap()),
+            "watch_key_invalid"
+        );
+        assert_eq!(
+            format!("{}", key_hash_from_watch_key("[1468/84'/0'/0']xpub6CKMszasQeidek6fYD7g5N1mwUK3ouX8YHWs47MZyXh62GxsEQsU57NuN6GTS3Mh3bwykHGa14617A6HQoYFDSM9deJvgjDeEJxBYsfJ1bs").err().unwrap()),
+            "watch_key_invalid"
+        );
+    }
+}
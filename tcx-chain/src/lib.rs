@@ -23,10 +23,17 @@ mod keystore;
 mod signer;
 
 pub use keystore::{
-    key_hash_from_mnemonic, key_hash_from_private_key, Account, Address, HdKeystore, Keystore,
-    KeystoreGuard, Metadata, PrivateKeystore, Source,
+    key_hash_from_mnemonic, key_hash_from_mnemonic_in_language,
+    key_hash_from_mnemonic_with_passphrase, key_hash_from_private_key, key_hash_from_watch_key,
+    Account, Address, AddressDiscovery, HdKeystore, Keystore, KeystoreGuard, LegacyKeystore,
+    LegacySecret, Metadata, MnemonicLanguage, PrivateKeystore, Source, WatchKeystore,
+    DEFAULT_GAP_LIMIT,
 };
 
-pub use signer::{ChainSigner, MessageSigner, TransactionSigner};
+pub use signer::{
+    is_signature_self_check_enabled, self_check_signature, self_check_signature_by,
+    set_signature_self_check, ChainSigner, MessageSigner, TransactionDecoder, TransactionSigner,
+    TxRecipient, TxSummary,
+};
 
 pub type Result<T> = result::Result<T, failure::Error>;
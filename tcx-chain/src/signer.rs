@@ -1,9 +1,72 @@
+use crate::keystore::Error;
 use crate::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tcx_constants::CoinInfo;
+use tcx_primitive::TypedPublicKey;
+
+/// Verify every signature with the public key before returning it, it costs a verification
+/// per signature but catches the faulty signing caused by the broken hardware or memory
+static SIGNATURE_SELF_CHECK: AtomicBool = AtomicBool::new(false);
+
+pub fn set_signature_self_check(enabled: bool) {
+    SIGNATURE_SELF_CHECK.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_signature_self_check_enabled() -> bool {
+    SIGNATURE_SELF_CHECK.load(Ordering::SeqCst)
+}
+
+/// Fail with `signature_self_check_failed` if the self check is enabled and the signature is
+/// not valid for the public key, the signers which sign by the keys directly should call it too
+pub fn self_check_signature(
+    public_key: &TypedPublicKey,
+    data: &[u8],
+    signature: &[u8],
+) -> Result<()> {
+    self_check_signature_by(|| public_key.verify(data, signature))
+}
+
+/// Same as `self_check_signature` but for the signatures the typed keys can't verify,
+/// such as the BIP-340 ones, the verification only runs if the self check is enabled
+pub fn self_check_signature_by<F: FnOnce() -> bool>(verify: F) -> Result<()> {
+    if is_signature_self_check_enabled() && !verify() {
+        return Err(Error::SignatureSelfCheckFailed.into());
+    }
+    Ok(())
+}
 
 pub trait TransactionSigner<Input, Output> {
     fn sign_transaction(&mut self, symbol: &str, address: &str, tx: &Input) -> Result<Output>;
 }
 
+/// A payment of the transaction, the amount is in the smallest unit of the chain or the token
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TxRecipient {
+    pub address: String,
+    pub amount: String,
+    /// The output returns to the wallet itself, such as the change of a bitcoin transaction
+    pub is_change: bool,
+}
+
+/// The readable summary of an unsigned transaction, which is shown to the user before signing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TxSummary {
+    pub senders: Vec<String>,
+    pub recipients: Vec<TxRecipient>,
+    /// The max fee the sender pays, empty if it is decided by the chain
+    pub fee: String,
+    /// The token contract, the amounts of the recipients are in the token if it is set
+    pub contract: String,
+    /// The called method of the contract, such as the ABI selector
+    pub method: String,
+    /// Hex of the attached data, such as the contract call data or the OP_RETURN data
+    pub data: String,
+}
+
+pub trait TransactionDecoder {
+    fn decode_transaction(&self, coin: &CoinInfo) -> Result<TxSummary>;
+}
+
 //pub trait Message: Sized {}
 //pub trait SignedMessage: Sized {}
 pub trait MessageSigner<Input, Output> {
@@ -1,4 +1,6 @@
 use crate::hash::blake2b_160;
+use crate::hex_to_bytes;
+use crate::transaction::Script;
 use bech32::{FromBase32, ToBase32};
 use tcx_chain::{Address, Result};
 use tcx_constants::CoinInfo;
@@ -9,8 +11,43 @@ static TYPE_FULL_DATA: u8 = 2u8;
 static TYPE_FULL_TYPE: u8 = 4u8;
 static TYPE_SHORT: u8 = 1u8;
 
+// the default SECP256K1/blake160 lock
+static SECP256K1_BLAKE160_CODE_HASH: &str =
+    "0x9bd7e06f3ecf4be0f2fcd2188b23f1b9fcc88e5d4b65a8637b17723bbda3cce8";
+
 pub struct CkbAddress();
 
+impl CkbAddress {
+    /// The short address for the default lock, otherwise the full address
+    pub fn from_script(script: &Script, coin: &CoinInfo) -> Result<String> {
+        let prefix = match coin.network.as_str() {
+            "TESTNET" => "ckt",
+            _ => "ckb",
+        };
+
+        let args = hex_to_bytes(&script.args)?;
+        let mut buf = vec![];
+        if script.code_hash == SECP256K1_BLAKE160_CODE_HASH
+            && script.hash_type == "type"
+            && args.len() == 20
+        {
+            buf.extend(vec![TYPE_SHORT, 0x00]);
+        } else {
+            let address_type = match script.hash_type.as_str() {
+                "data" => TYPE_FULL_DATA,
+                _ => TYPE_FULL_TYPE,
+            };
+            // check the hash type
+            script.serialize_hash_type()?;
+            buf.push(address_type);
+            buf.extend(hex_to_bytes(&script.code_hash)?);
+        }
+        buf.extend(args);
+
+        Ok(bech32::encode(prefix, buf.to_base32())?)
+    }
+}
+
 impl Address for CkbAddress {
     fn from_public_key(public_key: &TypedPublicKey, coin: &CoinInfo) -> Result<String> {
         let prefix = match coin.network.as_str() {
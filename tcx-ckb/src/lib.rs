@@ -10,7 +10,10 @@ use failure::Fail;
 pub use address::CkbAddress;
 pub use serializer::Serializer;
 use tcx_chain::Result;
-pub use transaction::{CachedCell, CellInput, CkbTxInput, CkbTxOutput, OutPoint, Script, Witness};
+pub use transaction::{
+    CachedCell, CellInput, CkbMessageInput, CkbMessageOutput, CkbTxInput, CkbTxOutput, OutPoint,
+    Script, Witness,
+};
 
 #[derive(Fail, Debug, PartialEq)]
 pub enum Error {
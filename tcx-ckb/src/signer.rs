@@ -1,11 +1,17 @@
-use tcx_chain::{Keystore, Result, TransactionSigner};
+use tcx_chain::{
+    Keystore, MessageSigner, Result, TransactionDecoder, TransactionSigner, TxSummary,
+};
 
-use crate::hash::new_blake2b;
+use crate::address::CkbAddress;
+use crate::hash::{blake2b_256, new_blake2b};
 use crate::serializer::Serializer;
-use crate::transaction::{CachedCell, CkbTxInput, CkbTxOutput, OutPoint, Witness};
+use crate::transaction::{
+    CachedCell, CkbMessageInput, CkbMessageOutput, CkbTxInput, CkbTxOutput, OutPoint, Witness,
+};
 use crate::{hex_to_bytes, Error};
 use std::collections::HashMap;
 use tcx_chain::ChainSigner;
+use tcx_constants::CoinInfo;
 
 use lazy_static::lazy_static;
 
@@ -134,6 +140,33 @@ impl<'a> CkbTxSigner<'a> {
     }
 }
 
+fn find_input_cells(tx: &CkbTxInput) -> Result<Vec<&CachedCell>> {
+    let find_cache_cell = |x: &OutPoint| -> Result<&CachedCell> {
+        for y in tx.cached_cells.iter() {
+            if y.out_point.is_some() {
+                let point = y.out_point.as_ref().unwrap();
+                if point.index == x.index && point.tx_hash == x.tx_hash {
+                    return Ok(y);
+                }
+            }
+        }
+
+        Err(Error::CellInputNotCached.into())
+    };
+
+    let mut input_cells: Vec<&CachedCell> = vec![];
+
+    for x in tx.inputs.iter() {
+        if x.previous_output.is_none() {
+            return Err(Error::InvalidOutputPoint.into());
+        }
+
+        input_cells.push(find_cache_cell(x.previous_output.as_ref().unwrap())?);
+    }
+
+    Ok(input_cells)
+}
+
 impl TransactionSigner<CkbTxInput, CkbTxOutput> for Keystore {
     fn sign_transaction(
         &mut self,
@@ -145,28 +178,7 @@ impl TransactionSigner<CkbTxInput, CkbTxOutput> for Keystore {
             return Err(Error::RequiredWitness.into());
         }
 
-        let find_cache_cell = |x: &OutPoint| -> Result<&CachedCell> {
-            for y in tx.cached_cells.iter() {
-                if y.out_point.is_some() {
-                    let point = y.out_point.as_ref().unwrap();
-                    if point.index == x.index && point.tx_hash == x.tx_hash {
-                        return Ok(y);
-                    }
-                }
-            }
-
-            Err(Error::CellInputNotCached.into())
-        };
-
-        let mut input_cells: Vec<&CachedCell> = vec![];
-
-        for x in tx.inputs.iter() {
-            if x.previous_output.is_none() {
-                return Err(Error::InvalidOutputPoint.into());
-            }
-
-            input_cells.push(find_cache_cell(x.previous_output.as_ref().unwrap())?);
-        }
+        let input_cells = find_input_cells(tx)?;
 
         if tx.witnesses.len() < input_cells.len() || input_cells.len() == 0 {
             return Err(Error::InvalidInputCells.into());
@@ -190,11 +202,52 @@ impl TransactionSigner<CkbTxInput, CkbTxOutput> for Keystore {
     }
 }
 
+impl TransactionDecoder for CkbTxInput {
+    /// The outputs are not part of the input, only the senders are decoded
+    fn decode_transaction(&self, coin: &CoinInfo) -> Result<TxSummary> {
+        let mut summary = TxSummary::default();
+        for cell in find_input_cells(self)? {
+            if let Some(lock) = cell.lock.as_ref() {
+                let address = CkbAddress::from_script(lock, coin)?;
+                if !summary.senders.contains(&address) {
+                    summary.senders.push(address);
+                }
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+const MESSAGE_PREFIX: &[u8] = b"Nervos Message:";
+
+impl MessageSigner<CkbMessageInput, CkbMessageOutput> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &CkbMessageInput,
+    ) -> Result<CkbMessageOutput> {
+        let data = if message.is_hex {
+            hex_to_bytes(&message.message)?
+        } else {
+            message.message.as_bytes().to_vec()
+        };
+        let hash = blake2b_256([MESSAGE_PREFIX, &data].concat());
+        let signature = self.sign_recoverable_hash(&hash, symbol, address, None)?;
+        Ok(CkbMessageOutput {
+            signature: format!("0x{}", hex::encode(signature)),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::address::CkbAddress;
-    use crate::transaction::{CachedCell, CellInput, CkbTxInput, OutPoint, Script, Witness};
-    use tcx_chain::{Keystore, Metadata, TransactionSigner};
+    use crate::transaction::{
+        CachedCell, CellInput, CkbMessageInput, CkbTxInput, OutPoint, Script, Witness,
+    };
+    use tcx_chain::{Keystore, MessageSigner, Metadata, TransactionDecoder, TransactionSigner};
     use tcx_constants::{CoinInfo, CurveType};
 
     #[test]
@@ -316,6 +369,10 @@ mod tests {
             "ckt1qyqtr684u76tu7r8efkd24hw8922xfvhnazskzdzy6"
         );
 
+        let summary = tx_input.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.senders, vec![account.address.clone()]);
+        assert!(summary.recipients.is_empty());
+
         let tx_output = ks
             .sign_transaction("NERVOS", &account.address, &tx_input)
             .unwrap();
@@ -480,4 +537,38 @@ mod tests {
             assert_eq!(format!("{}", ret.err().unwrap()), err);
         }
     }
+
+    #[test]
+    fn test_sign_message() {
+        let mut ks = Keystore::from_private_key(
+            "dcec27d0d975b0378471183a03f7071dea8532aaf968be796719ecd20af6988f",
+            "Password",
+            Metadata::default(),
+        );
+        ks.unlock_by_password("Password").unwrap();
+
+        let coin_info = CoinInfo {
+            coin: "NERVOS".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "TESTNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let account = ks.derive_coin::<CkbAddress>(&coin_info).unwrap().clone();
+
+        let inputs = vec![
+            CkbMessageInput {
+                message: "hello world".to_string(),
+                is_hex: false,
+            },
+            CkbMessageInput {
+                message: "0x68656c6c6f20776f726c64".to_string(),
+                is_hex: true,
+            },
+        ];
+        for input in inputs {
+            let output = ks.sign_message("NERVOS", &account.address, &input).unwrap();
+            assert_eq!(output.signature, "0x9a3b864f02e626bd01e7a6caa4670e57accfc147f747bfb7586b4c47af57bb6b3b79d250b5759192ae61c881a0837df0923acea3052ccb39159408727f9547e701");
+        }
+    }
 }
@@ -59,3 +59,20 @@ pub struct CkbTxOutput {
     #[prost(string, repeated, tag = "2")]
     pub witnesses: ::std::vec::Vec<std::string::String>,
 }
+/// FUNCTION: sign_message(SignParam{input: CkbMessageInput}): CkbMessageOutput
+///
+/// Sign the personal message like Neuron, the hash to sign is the
+/// ckb blake2b of `Nervos Message:` followed by the message
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CkbMessageInput {
+    #[prost(string, tag = "1")]
+    pub message: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_hex: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CkbMessageOutput {
+    /// hex string of the recoverable signature
+    #[prost(string, tag = "1")]
+    pub signature: std::string::String,
+}
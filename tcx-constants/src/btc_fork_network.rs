@@ -79,6 +79,17 @@ lazy_static! {
             xpub_prefix: [0x04, 0x35, 0x87, 0xCF],
             xprv_prefix: [0x04, 0x35, 0x83, 0x94],
         });
+        networks.push(BtcForkNetwork {
+            coin: "LITECOIN",
+            network: "TESTNET",
+            seg_wit: "SEGWIT",
+            hrp: "tltc",
+            p2pkh_prefix: 0x6f,
+            p2sh_prefix: 0x3a,
+            private_prefix: 0xef,
+            xpub_prefix: [0x04, 0x35, 0x87, 0xCF],
+            xprv_prefix: [0x04, 0x35, 0x83, 0x94],
+        });
         networks.push(BtcForkNetwork {
             coin: "BITCOIN",
             network: "MAINNET",
@@ -98,6 +109,18 @@ lazy_static! {
             p2pkh_prefix: 0x0,
             p2sh_prefix: 0x05,
             private_prefix: 0x80,
+            // ypub/yprv
+            xpub_prefix: [0x04, 0x9D, 0x7C, 0xB2],
+            xprv_prefix: [0x04, 0x9D, 0x78, 0x78],
+        });
+        networks.push(BtcForkNetwork {
+            coin: "BITCOIN",
+            network: "MAINNET",
+            seg_wit: "P2TR",
+            hrp: "bc",
+            p2pkh_prefix: 0x0,
+            p2sh_prefix: 0x05,
+            private_prefix: 0x80,
             xpub_prefix: [0x04, 0x88, 0xB2, 0x1E],
             xprv_prefix: [0x04, 0x88, 0xAD, 0xE4],
         });
@@ -109,8 +132,9 @@ lazy_static! {
             p2pkh_prefix: 0x0,
             p2sh_prefix: 0x05,
             private_prefix: 0x80,
-            xpub_prefix: [0x04, 0x88, 0xB2, 0x1E],
-            xprv_prefix: [0x04, 0x88, 0xAD, 0xE4],
+            // zpub/zprv
+            xpub_prefix: [0x04, 0xB2, 0x47, 0x46],
+            xprv_prefix: [0x04, 0xB2, 0x43, 0x0C],
         });
         networks.push(BtcForkNetwork {
             coin: "BITCOIN",
@@ -131,9 +155,33 @@ lazy_static! {
             p2pkh_prefix: 0x6f,
             p2sh_prefix: 0xc4,
             private_prefix: 0xef,
+            // upub/uprv
+            xpub_prefix: [0x04, 0x4A, 0x52, 0x62],
+            xprv_prefix: [0x04, 0x4A, 0x4E, 0x28],
+        });
+        networks.push(BtcForkNetwork {
+            coin: "BITCOIN",
+            network: "TESTNET",
+            seg_wit: "P2TR",
+            hrp: "tb",
+            p2pkh_prefix: 0x6f,
+            p2sh_prefix: 0xc4,
+            private_prefix: 0xef,
             xpub_prefix: [0x04, 0x35, 0x87, 0xCF],
             xprv_prefix: [0x04, 0x35, 0x83, 0x94],
         });
+        networks.push(BtcForkNetwork {
+            coin: "BITCOIN",
+            network: "TESTNET",
+            seg_wit: "SEGWIT",
+            hrp: "tb",
+            p2pkh_prefix: 0x6f,
+            p2sh_prefix: 0xc4,
+            private_prefix: 0xef,
+            // vpub/vprv
+            xpub_prefix: [0x04, 0x5F, 0x1C, 0xF6],
+            xprv_prefix: [0x04, 0x5F, 0x18, 0xBC],
+        });
         //Definition of BitcoinCash networks https://github.com/bitpay/bitcore/blob/master/packages/bitcore-lib-cash/lib/networks.js#L168
         networks.push(BtcForkNetwork {
             coin: "BITCOINCASH",
@@ -170,6 +218,22 @@ lazy_static! {
             pub_version: [0x04, 0x35, 0x87, 0xCF],
             prv_version: [0x04, 0x35, 0x83, 0x94],
         });
+        versions.push(HdVersion {
+            pub_version: [0x04, 0x9D, 0x7C, 0xB2],
+            prv_version: [0x04, 0x9D, 0x78, 0x78],
+        });
+        versions.push(HdVersion {
+            pub_version: [0x04, 0x4A, 0x52, 0x62],
+            prv_version: [0x04, 0x4A, 0x4E, 0x28],
+        });
+        versions.push(HdVersion {
+            pub_version: [0x04, 0xB2, 0x47, 0x46],
+            prv_version: [0x04, 0xB2, 0x43, 0x0C],
+        });
+        versions.push(HdVersion {
+            pub_version: [0x04, 0x5F, 0x1C, 0xF6],
+            prv_version: [0x04, 0x5F, 0x18, 0xBC],
+        });
         RwLock::new(versions)
     };
 }
@@ -177,6 +241,7 @@ lazy_static! {
 // LTC address prefix: https://bitcoin.stackexchange.com/questions/62781/litecoin-constants-and-prefixes
 // hrp: https://github.com/satoshilabs/slips/blob/master/slip-0173.md
 // BTC https://en.bitcoin.it/wiki/List_of_address_prefixes
+// ypub/zpub: https://github.com/satoshilabs/slips/blob/master/slip-0132.md
 
 pub fn network_from_coin(coin_info: &CoinInfo) -> Option<BtcForkNetwork> {
     network_from_param(&coin_info.coin, &coin_info.network, &coin_info.seg_wit)
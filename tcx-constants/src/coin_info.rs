@@ -47,6 +47,34 @@ lazy_static! {
             network: "TESTNET".to_string(),
             seg_wit: "P2WPKH".to_string(),
         });
+        coin_infos.push(CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/84'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+        });
+        coin_infos.push(CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/84'/1'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+        });
+        coin_infos.push(CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/86'/0'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "P2TR".to_string(),
+        });
+        coin_infos.push(CoinInfo {
+            coin: "BITCOIN".to_string(),
+            derivation_path: "m/86'/1'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "TESTNET".to_string(),
+            seg_wit: "P2TR".to_string(),
+        });
         coin_infos.push(CoinInfo {
             coin: "BITCOINCASH".to_string(),
             derivation_path: "m/44'/145'/0'/0/0".to_string(),
@@ -89,6 +117,20 @@ lazy_static! {
             network: "TESTNET".to_string(),
             seg_wit: "P2WPKH".to_string(),
         });
+        coin_infos.push(CoinInfo {
+            coin: "LITECOIN".to_string(),
+            derivation_path: "m/84'/2'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+        });
+        coin_infos.push(CoinInfo {
+            coin: "LITECOIN".to_string(),
+            derivation_path: "m/84'/1'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "TESTNET".to_string(),
+            seg_wit: "SEGWIT".to_string(),
+        });
         coin_infos.push(CoinInfo {
             coin: "TRON".to_string(),
             derivation_path: "m/44'/195'/0'/0/0".to_string(),
@@ -96,6 +138,13 @@ lazy_static! {
             network: "".to_string(),
             seg_wit: "".to_string(),
         });
+        coin_infos.push(CoinInfo {
+            coin: "ETHEREUM".to_string(),
+            derivation_path: "m/44'/60'/0'/0/0".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "".to_string(),
+            seg_wit: "".to_string(),
+        });
         coin_infos.push(CoinInfo {
             coin: "NERVOS".to_string(),
             derivation_path: "m/44'/309'/0'/0/0".to_string(),
@@ -131,6 +180,13 @@ lazy_static! {
             network: "MAINNET".to_string(),
             seg_wit: "".to_string(),
         });
+        coin_infos.push(CoinInfo {
+            coin: "NANO".to_string(),
+            derivation_path: "m/44'/165'/0'".to_string(),
+            curve: CurveType::ED25519Blake2bNano,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        });
         coin_infos.push(CoinInfo {
             coin: "FILECOIN".to_string(),
             derivation_path: "m/44'/461'/0'/0/0".to_string(),
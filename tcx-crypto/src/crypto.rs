@@ -5,9 +5,17 @@ use crate::Result;
 use bitcoin_hashes::hex::{FromHex, ToHex};
 use serde::{Deserialize, Serialize};
 use std::env;
+use zeroize::Zeroizing;
 
 const CREDENTIAL_LEN: usize = 64usize;
 
+/// The max KDF costs accepted from the keystores of other wallets, the larger ones could exhaust
+/// the memory or hang the app
+const MAX_IMPORT_PBKDF2_ROUNDS: u32 = 10_000_000;
+const MAX_IMPORT_SCRYPT_N: u32 = 1 << 20;
+const MAX_IMPORT_SCRYPT_R: u32 = 8;
+const MAX_IMPORT_SCRYPT_P: u32 = 16;
+
 pub type Credential = [u8; CREDENTIAL_LEN];
 
 fn default_kdf_rounds() -> u32 {
@@ -38,8 +46,10 @@ struct CipherParams {
 }
 
 pub trait KdfParams: Default {
-    fn kdf_key() -> String;
+    fn kdf_key(&self) -> String;
     fn validate(&self) -> Result<()>;
+    /// `validate` with the cost limits of the keystores made by other wallets
+    fn validate_import(&self) -> Result<()>;
     fn generate_derived_key(&self, password: &[u8], out: &mut [u8]);
     fn set_salt(&mut self, salt: &str);
 }
@@ -64,8 +74,19 @@ impl Default for Pbkdf2Params {
     }
 }
 
+impl Pbkdf2Params {
+    /// 0 means the default rounds
+    pub fn new(c: u32) -> Pbkdf2Params {
+        let default = Pbkdf2Params::default();
+        Pbkdf2Params {
+            c: if c > 0 { c } else { default.c },
+            ..default
+        }
+    }
+}
+
 impl KdfParams for Pbkdf2Params {
-    fn kdf_key() -> String {
+    fn kdf_key(&self) -> String {
         "pbkdf2".to_owned()
     }
 
@@ -77,6 +98,18 @@ impl KdfParams for Pbkdf2Params {
         }
     }
 
+    fn validate_import(&self) -> Result<()> {
+        self.validate()?;
+        // only hmac-sha256 is implemented
+        if self.prf != "hmac-sha256"
+            || self.c > MAX_IMPORT_PBKDF2_ROUNDS
+            || Vec::<u8>::from_hex(&self.salt).is_err()
+        {
+            return Err(Error::KdfParamsInvalid.into());
+        }
+        Ok(())
+    }
+
     fn generate_derived_key(&self, password: &[u8], out: &mut [u8]) {
         let salt_bytes: Vec<u8> = FromHex::from_hex(&self.salt).unwrap();
         pbkdf2::pbkdf2::<hmac::Hmac<sha2::Sha256>>(password, &salt_bytes, self.c as usize, out);
@@ -109,19 +142,53 @@ impl Default for SCryptParams {
     }
 }
 
+impl SCryptParams {
+    /// 0 means the default cost param
+    pub fn new(n: u32, r: u32, p: u32) -> SCryptParams {
+        let default = SCryptParams::default();
+        let or_default = |value: u32, default: u32| if value > 0 { value } else { default };
+        SCryptParams {
+            n: or_default(n, default.n),
+            r: or_default(r, default.r),
+            p: or_default(p, default.p),
+            ..default
+        }
+    }
+}
+
 impl KdfParams for SCryptParams {
-    fn kdf_key() -> String {
+    fn kdf_key(&self) -> String {
         "scrypt".to_owned()
     }
 
     fn validate(&self) -> Result<()> {
-        if self.dklen == 0 || self.n == 0 || self.salt.is_empty() || self.p == 0 || self.r == 0 {
+        // n must be a power of 2, and the memory r * p must fit the scrypt limits
+        if self.dklen == 0
+            || self.n < 2
+            || !self.n.is_power_of_two()
+            || self.salt.is_empty()
+            || self.p == 0
+            || self.r == 0
+            || (self.r as u64) * (self.p as u64) >= (1 << 30)
+        {
             Err(Error::KdfParamsInvalid.into())
         } else {
             Ok(())
         }
     }
 
+    fn validate_import(&self) -> Result<()> {
+        self.validate()?;
+        if self.n > MAX_IMPORT_SCRYPT_N
+            || self.r > MAX_IMPORT_SCRYPT_R
+            || self.p > MAX_IMPORT_SCRYPT_P
+            || Vec::<u8>::from_hex(&self.salt).is_err()
+        {
+            return Err(Error::KdfParamsInvalid.into());
+        }
+        Ok(())
+    }
+
     fn generate_derived_key(&self, password: &[u8], out: &mut [u8]) {
         let salt_bytes: Vec<u8> = FromHex::from_hex(&self.salt).unwrap();
         let log_n = (self.n as f64).log2().round();
@@ -136,17 +203,68 @@ impl KdfParams for SCryptParams {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// The KDF chosen at runtime, the variant is told apart by its params when loading the keystore
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum TypedKdfParams {
+    Pbkdf2(Pbkdf2Params),
+    SCrypt(SCryptParams),
+}
+
+impl Default for TypedKdfParams {
+    fn default() -> Self {
+        TypedKdfParams::Pbkdf2(Pbkdf2Params::default())
+    }
+}
+
+impl KdfParams for TypedKdfParams {
+    fn kdf_key(&self) -> String {
+        match self {
+            TypedKdfParams::Pbkdf2(params) => params.kdf_key(),
+            TypedKdfParams::SCrypt(params) => params.kdf_key(),
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        match self {
+            TypedKdfParams::Pbkdf2(params) => params.validate(),
+            TypedKdfParams::SCrypt(params) => params.validate(),
+        }
+    }
+
+    fn validate_import(&self) -> Result<()> {
+        match self {
+            TypedKdfParams::Pbkdf2(params) => params.validate_import(),
+            TypedKdfParams::SCrypt(params) => params.validate_import(),
+        }
+    }
+
+    fn generate_derived_key(&self, password: &[u8], out: &mut [u8]) {
+        match self {
+            TypedKdfParams::Pbkdf2(params) => params.generate_derived_key(password, out),
+            TypedKdfParams::SCrypt(params) => params.generate_derived_key(password, out),
+        }
+    }
+
+    fn set_salt(&mut self, salt: &str) {
+        match self {
+            TypedKdfParams::Pbkdf2(params) => params.set_salt(salt),
+            TypedKdfParams::SCrypt(params) => params.set_salt(salt),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 struct CacheDerivedKey {
     hashed_key: String,
-    derived_key: Vec<u8>,
+    derived_key: Zeroizing<Vec<u8>>,
 }
 
 impl CacheDerivedKey {
     pub fn new(key: &str, derived_key: &[u8]) -> CacheDerivedKey {
         CacheDerivedKey {
             hashed_key: Self::hash(key),
-            derived_key: derived_key.to_vec(),
+            derived_key: Zeroizing::new(derived_key.to_vec()),
         }
     }
 
@@ -154,7 +272,7 @@ impl CacheDerivedKey {
         hex_dsha256(key)
     }
 
-    pub fn get_derived_key(&self, key: &str) -> Result<Vec<u8>> {
+    pub fn get_derived_key(&self, key: &str) -> Result<Zeroizing<Vec<u8>>> {
         if self.hashed_key == Self::hash(key) {
             Ok(self.derived_key.clone())
         } else {
@@ -181,15 +299,21 @@ where
     T: KdfParams,
 {
     pub fn new(password: &str, origin: &[u8]) -> Crypto<T> {
-        let mut param = T::default();
+        Self::new_with_kdf(password, origin, T::default()).expect("default kdf params")
+    }
+
+    /// Encrypt with the given KDF params, the salt is generated
+    pub fn new_with_kdf(password: &str, origin: &[u8], kdfparams: T) -> Result<Crypto<T>> {
+        let mut param = kdfparams;
         param.set_salt(&numberic_util::random_iv(32).to_hex());
+        param.validate()?;
         let iv = numberic_util::random_iv(16);
 
         let mut crypto = Crypto {
             cipher: "aes-128-ctr".to_owned(),
             cipherparams: CipherParams { iv: iv.to_hex() },
             ciphertext: String::from(""),
-            kdf: T::kdf_key(),
+            kdf: param.kdf_key(),
             kdfparams: param,
             mac: String::from(""),
             cached_derived_key: None,
@@ -203,24 +327,53 @@ where
         crypto.ciphertext = ciphertext.to_hex();
         let mac = Self::generate_mac(&derived_key, &ciphertext);
         crypto.mac = mac.to_hex();
-        crypto
+        Ok(crypto)
+    }
+
+    pub fn kdf(&self) -> &str {
+        &self.kdf
+    }
+
+    pub fn kdfparams(&self) -> &T {
+        &self.kdfparams
+    }
+
+    /// Check the crypto of a keystore made by another wallet before deriving the key from it,
+    /// the `kdf` must name the parsed params and the hex fields must be valid
+    pub fn validate_import(&self) -> Result<()> {
+        if self.kdf != self.kdfparams.kdf_key() {
+            return Err(Error::KdfParamsInvalid.into());
+        }
+        self.kdfparams.validate_import()?;
+
+        if self.cipher != "aes-128-ctr"
+            || Vec::<u8>::from_hex(&self.ciphertext).is_err()
+            || Vec::<u8>::from_hex(&self.mac).is_err()
+        {
+            return Err(Error::InvalidCiphertext.into());
+        }
+        match Vec::<u8>::from_hex(&self.cipherparams.iv) {
+            Ok(iv) if iv.len() == 16 => Ok(()),
+            _ => Err(Error::InvalidKeyIvLength.into()),
+        }
     }
 
-    pub fn generate_derived_key(&self, key: &str) -> Result<Vec<u8>> {
+    /// The derived key and the decrypted secret are wiped from the memory when they are dropped
+    pub fn generate_derived_key(&self, key: &str) -> Result<Zeroizing<Vec<u8>>> {
         if let Some(ckd) = &self.cached_derived_key {
             ckd.get_derived_key(key)
         } else {
-            let mut derived_key: Credential = [0u8; CREDENTIAL_LEN];
+            let mut derived_key = Zeroizing::new(vec![0u8; CREDENTIAL_LEN]);
             self.kdfparams
                 .generate_derived_key(key.as_bytes(), &mut derived_key);
             if &self.mac != "" && !self.verify_derived_key(&derived_key) {
                 return Err(Error::PasswordIncorrect.into());
             }
-            Ok(derived_key.to_vec())
+            Ok(derived_key)
         }
     }
 
-    pub fn decrypt(&self, key: Key) -> Result<Vec<u8>> {
+    pub fn decrypt(&self, key: Key) -> Result<Zeroizing<Vec<u8>>> {
         let encrypted: Vec<u8> = FromHex::from_hex(&self.ciphertext).expect("ciphertext");
         let iv: Vec<u8> = FromHex::from_hex(&self.cipherparams.iv).expect("iv");
         self.decrypt_data(key, &encrypted, &iv)
@@ -245,9 +398,11 @@ where
         })
     }
 
-    pub fn decrypt_enc_pair(&self, key: Key, enc_pair: &EncPair) -> Result<Vec<u8>> {
-        let encrypted: Vec<u8> = FromHex::from_hex(&enc_pair.enc_str).unwrap();
-        let iv: Vec<u8> = FromHex::from_hex(&enc_pair.nonce).unwrap();
+    pub fn decrypt_enc_pair(&self, key: Key, enc_pair: &EncPair) -> Result<Zeroizing<Vec<u8>>> {
+        let encrypted: Vec<u8> =
+            FromHex::from_hex(&enc_pair.enc_str).map_err(|_| Error::InvalidCiphertext)?;
+        let iv: Vec<u8> =
+            FromHex::from_hex(&enc_pair.nonce).map_err(|_| Error::InvalidKeyIvLength)?;
         self.decrypt_data(key, &encrypted, &iv)
     }
 
@@ -268,8 +423,8 @@ where
         super::aes::ctr::encrypt_nopadding(origin, key, &iv)
     }
 
-    fn decrypt_data(&self, key: Key, encrypted: &[u8], iv: &[u8]) -> Result<Vec<u8>> {
-        let derived_key: Vec<u8> = match key {
+    fn decrypt_data(&self, key: Key, encrypted: &[u8], iv: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let derived_key: Zeroizing<Vec<u8>> = match key {
             Key::Password(password) => {
                 let dk = self.generate_derived_key(&password)?;
                 if !self.verify_derived_key(&dk) {
@@ -282,7 +437,7 @@ where
                 if !(cfg!(feature = "cache_dk")) {
                     return Err(Error::CachedDkFeatureNotSupport.into());
                 } else {
-                    let dk = hex::decode(dk)?;
+                    let dk = Zeroizing::new(hex::decode(dk)?);
                     if !self.verify_derived_key(&dk) {
                         return Err(Error::DerivedKeyNotMatched.into());
                     } else {
@@ -293,7 +448,9 @@ where
         };
 
         let key = &derived_key[0..16];
-        super::aes::ctr::decrypt_nopadding(encrypted, key, &iv)
+        Ok(Zeroizing::new(super::aes::ctr::decrypt_nopadding(
+            encrypted, key, &iv,
+        )?))
     }
 
     pub fn verify_derived_key(&self, dk: &[u8]) -> bool {
@@ -353,7 +510,10 @@ mod tests {
         let cipher_bytes = crypto
             .decrypt(Key::Password(TEST_PASSWORD.to_owned()))
             .expect("cipher bytes");
-        assert_eq!("TokenCoreX", String::from_utf8(cipher_bytes).unwrap());
+        assert_eq!(
+            "TokenCoreX",
+            String::from_utf8(cipher_bytes.to_vec()).unwrap()
+        );
 
         let ret = crypto.decrypt(Key::Password("WrongPassword".to_owned()));
         assert!(ret.is_err());
@@ -364,6 +524,59 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn new_crypto_with_kdf() {
+        let crypto: Crypto<TypedKdfParams> = Crypto::new_with_kdf(
+            TEST_PASSWORD,
+            "TokenCoreX".as_bytes(),
+            TypedKdfParams::SCrypt(SCryptParams::new(1024, 8, 1)),
+        )
+        .unwrap();
+        assert_eq!("scrypt", crypto.kdf());
+        assert_ne!("", crypto.mac);
+
+        let json = serde_json::to_string(&crypto).unwrap();
+        let crypto: Crypto<TypedKdfParams> = serde_json::from_str(&json).unwrap();
+        match &crypto.kdfparams {
+            TypedKdfParams::SCrypt(params) => assert_eq!(params.n, 1024),
+            _ => panic!("should load the scrypt params"),
+        }
+        let decrypted = crypto
+            .decrypt(Key::Password(TEST_PASSWORD.to_owned()))
+            .unwrap();
+        assert_eq!("TokenCoreX", String::from_utf8(decrypted.to_vec()).unwrap());
+        assert!(!crypto.verify_password("WrongPassword"));
+
+        let crypto: Crypto<TypedKdfParams> = Crypto::new_with_kdf(
+            TEST_PASSWORD,
+            "TokenCoreX".as_bytes(),
+            TypedKdfParams::Pbkdf2(Pbkdf2Params::new(1024)),
+        )
+        .unwrap();
+        let json = serde_json::to_string(&crypto).unwrap();
+        let crypto: Crypto<TypedKdfParams> = serde_json::from_str(&json).unwrap();
+        assert_eq!("pbkdf2", crypto.kdf());
+        match &crypto.kdfparams {
+            TypedKdfParams::Pbkdf2(params) => assert_eq!(params.c, 1024),
+            _ => panic!("should load the pbkdf2 params"),
+        }
+
+        assert_eq!(SCryptParams::new(0, 0, 0), SCryptParams::default());
+        let invalid_params = vec![
+            SCryptParams::new(1000, 8, 1),
+            SCryptParams::new(1, 8, 1),
+            SCryptParams::new(1024, 1 << 15, 1 << 15),
+        ];
+        for params in invalid_params {
+            let ret: Result<Crypto<TypedKdfParams>> = Crypto::new_with_kdf(
+                TEST_PASSWORD,
+                "TokenCoreX".as_bytes(),
+                TypedKdfParams::SCrypt(params),
+            );
+            assert_eq!(format!("{}", ret.err().unwrap()), "kdf_params_invalid");
+        }
+    }
+
     #[test]
     pub fn enc_pair_test() {
         let crypto: Crypto<Pbkdf2Params> = Crypto::new(TEST_PASSWORD, "TokenCoreX".as_bytes());
@@ -377,7 +590,7 @@ mod tests {
         let decrypted_bytes = crypto
             .decrypt_enc_pair(Key::Password(TEST_PASSWORD.to_owned()), &enc_pair)
             .unwrap();
-        let decrypted = String::from_utf8(decrypted_bytes).unwrap();
+        let decrypted = String::from_utf8(decrypted_bytes.to_vec()).unwrap();
 
         assert_eq!("TokenCoreX", decrypted);
 
@@ -475,7 +688,7 @@ mod tests {
         let result = crypto
             .decrypt(Key::Password("Insecure Pa55w0rd".to_owned()))
             .unwrap();
-        let wif = String::from_utf8(result).unwrap();
+        let wif = String::from_utf8(result.to_vec()).unwrap();
         assert_eq!("L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB", wif)
     }
 
@@ -515,6 +728,6 @@ mod tests {
         assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
 
         let ret = cdk.get_derived_key("12345678").unwrap();
-        assert_eq!(hex::encode(ret), "01010101");
+        assert_eq!(hex::encode(&*ret), "01010101");
     }
 }
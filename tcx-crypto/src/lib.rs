@@ -4,7 +4,7 @@ pub mod hash;
 pub mod numberic_util;
 
 use core::result;
-pub use crypto::{Crypto, EncPair, Key, Pbkdf2Params};
+pub use crypto::{Crypto, EncPair, KdfParams, Key, Pbkdf2Params, SCryptParams, TypedKdfParams};
 use parking_lot::RwLock;
 
 #[macro_use]
@@ -0,0 +1,146 @@
+use crate::{keccak, Error};
+
+use tcx_chain::tcx_ensure;
+use tcx_chain::Address;
+use tcx_chain::Result;
+use tcx_constants::CoinInfo;
+use tcx_primitive::TypedPublicKey;
+
+pub struct EthAddress();
+
+impl Address for EthAddress {
+    fn from_public_key(public_key: &TypedPublicKey, _coin: &CoinInfo) -> Result<String> {
+        let pk = public_key.as_secp256k1()?;
+        let bytes = pk.to_uncompressed();
+
+        let hash = keccak(&bytes[1..]);
+        Ok(EthAddress::to_checksum(&hash[12..32]))
+    }
+
+    fn is_valid(address: &str, _coin: &CoinInfo) -> bool {
+        let hex_addr = strip_hex_prefix(address);
+        if hex_addr.len() != 40 || hex::decode(hex_addr).is_err() {
+            return false;
+        }
+
+        // all lower or all upper case addresses carry no checksum
+        if hex_addr.to_lowercase() == hex_addr || hex_addr.to_uppercase() == hex_addr {
+            return true;
+        }
+
+        EthAddress::to_checksum(&hex::decode(hex_addr).expect("eth_address")) == address
+    }
+}
+
+impl EthAddress {
+    /// EIP-55 mixed-case checksum encoding
+    /// ref: https://eips.ethereum.org/EIPS/eip-55
+    pub fn to_checksum(address: &[u8]) -> String {
+        let lower = hex::encode(address);
+        let hash = keccak(lower.as_bytes());
+
+        let checksummed: String = lower
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                let nibble = if i % 2 == 0 {
+                    hash[i / 2] >> 4
+                } else {
+                    hash[i / 2] & 0x0f
+                };
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+        format!("0x{}", checksummed)
+    }
+
+    /// Decode the 20 bytes address, an empty address means contract creation
+    pub fn decode(address: &str) -> Result<Vec<u8>> {
+        let hex_addr = strip_hex_prefix(address);
+        if hex_addr.is_empty() {
+            return Ok(vec![]);
+        }
+        let bytes = hex::decode(hex_addr).map_err(|_| Error::InvalidAddress)?;
+        tcx_ensure!(bytes.len() == 20, Error::InvalidAddress);
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn strip_hex_prefix(value: &str) -> &str {
+    if value.starts_with("0x") || value.starts_with("0X") {
+        &value[2..]
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EthAddress;
+    use tcx_chain::Address;
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::CurveType;
+    use tcx_primitive::TypedPublicKey;
+
+    #[test]
+    fn eth_address() {
+        let bytes = hex::decode("04DAAC763B1B3492720E404C53D323BAF29391996F7DD5FA27EF0D12F7D50D694700684A32AD97FF4C09BF9CF0B9D0AC7F0091D9C6CB8BE9BB6A1106DA557285D8").unwrap();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+
+        assert_eq!(
+            EthAddress::from_public_key(
+                &TypedPublicKey::from_slice(CurveType::SECP256k1, &bytes).unwrap(),
+                &coin_info
+            )
+            .unwrap(),
+            "0x547b45770EE4401494c9157e8263E7a133cbD88d"
+        );
+    }
+
+    #[test]
+    fn eth_address_checksum() {
+        let addresses = vec![
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+            "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+            "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+        ];
+        for addr in addresses {
+            let bytes = hex::decode(&addr[2..]).unwrap();
+            assert_eq!(EthAddress::to_checksum(&bytes), addr);
+        }
+    }
+
+    #[test]
+    fn eth_address_validation() {
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        assert!(EthAddress::is_valid(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            &coin_info
+        ));
+        assert!(EthAddress::is_valid(
+            "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed",
+            &coin_info
+        ));
+        assert!(EthAddress::is_valid(
+            "0x5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+            &coin_info
+        ));
+        assert!(!EthAddress::is_valid(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD",
+            &coin_info
+        ));
+        assert!(!EthAddress::is_valid(
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA",
+            &coin_info
+        ));
+        assert!(!EthAddress::is_valid(
+            "THfuSDVRvSsjNDPFdGjMU19Ha4Kf7acotq",
+            &coin_info
+        ));
+    }
+}
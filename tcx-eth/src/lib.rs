@@ -0,0 +1,32 @@
+pub mod address;
+pub mod rlp;
+pub mod signer;
+pub mod transaction;
+
+pub use crate::address::EthAddress;
+pub use crate::transaction::{AccessList, EthTxInput, EthTxOutput};
+
+#[macro_use]
+extern crate failure;
+
+use digest::Digest;
+
+pub fn keccak(bytes: &[u8]) -> Vec<u8> {
+    let mut keccak = sha3::Keccak256::new();
+    keccak.input(bytes);
+    keccak.result().to_vec()
+}
+
+#[derive(Fail, Debug, PartialEq)]
+pub enum Error {
+    #[fail(display = "invalid_address")]
+    InvalidAddress,
+    #[fail(display = "invalid_number")]
+    InvalidNumber,
+    #[fail(display = "invalid_hex")]
+    InvalidHex,
+    #[fail(display = "unsupported_tx_type")]
+    UnsupportedTxType,
+    #[fail(display = "chain_id_required")]
+    ChainIdRequired,
+}
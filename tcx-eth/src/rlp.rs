@@ -0,0 +1,84 @@
+//! A minimal RLP encoder, only covers what transaction serialization needs.
+//! ref: https://eth.wiki/fundamentals/rlp
+
+/// Strip the leading zero bytes, RLP encodes integers in their shortest big-endian form
+pub fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let first_non_zero = bytes.iter().position(|x| *x != 0).unwrap_or(bytes.len());
+    &bytes[first_non_zero..]
+}
+
+pub fn encode_u64(value: u64) -> Vec<u8> {
+    encode_bytes(trim_leading_zeros(&value.to_be_bytes()))
+}
+
+pub fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        bytes.to_vec()
+    } else {
+        [encode_length(bytes.len(), 0x80), bytes.to_vec()].concat()
+    }
+}
+
+/// Every item should already be RLP encoded
+pub fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    [encode_length(payload.len(), 0xc0), payload].concat()
+}
+
+fn encode_length(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        vec![offset + len as u8]
+    } else {
+        let len_bytes = trim_leading_zeros(&(len as u64).to_be_bytes()).to_vec();
+        [vec![offset + 55 + len_bytes.len() as u8], len_bytes].concat()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_bytes_test() {
+        assert_eq!(encode_bytes(&[]), vec![0x80]);
+        assert_eq!(encode_bytes(&[0x00]), vec![0x00]);
+        assert_eq!(encode_bytes(&[0x7f]), vec![0x7f]);
+        assert_eq!(encode_bytes(&[0x80]), vec![0x81, 0x80]);
+        assert_eq!(
+            encode_bytes("dog".as_bytes()),
+            hex::decode("83646f67").unwrap()
+        );
+
+        let long = "Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        assert_eq!(
+            hex::encode(encode_bytes(long.as_bytes()))[..4].to_string(),
+            "b838"
+        );
+    }
+
+    #[test]
+    fn encode_u64_test() {
+        assert_eq!(encode_u64(0), vec![0x80]);
+        assert_eq!(encode_u64(15), vec![0x0f]);
+        assert_eq!(encode_u64(1024), vec![0x82, 0x04, 0x00]);
+    }
+
+    #[test]
+    fn encode_list_test() {
+        assert_eq!(encode_list(&[]), vec![0xc0]);
+        let cat_dog = encode_list(&[
+            encode_bytes("cat".as_bytes()),
+            encode_bytes("dog".as_bytes()),
+        ]);
+        assert_eq!(hex::encode(cat_dog), "c88363617483646f67");
+
+        // the set theoretical representation of three
+        let empty = encode_list(&[]);
+        let one = encode_list(&[empty.clone()]);
+        let two = encode_list(&[empty.clone(), one.clone()]);
+        assert_eq!(
+            hex::encode(encode_list(&[empty, one, two])),
+            "c7c0c1c0c3c0c1c0"
+        );
+    }
+}
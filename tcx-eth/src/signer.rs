@@ -0,0 +1,482 @@
+use crate::address::{strip_hex_prefix, EthAddress};
+use crate::rlp::{encode_bytes, encode_list, encode_u64, trim_leading_zeros};
+use crate::transaction::{EthTxInput, EthTxOutput};
+use crate::{keccak, Error};
+
+use num_bigint::BigUint;
+use num_traits::Num;
+use tcx_chain::tcx_ensure;
+use tcx_chain::{
+    ChainSigner, Keystore, Result, TransactionDecoder, TransactionSigner as TraitTransactionSigner,
+    TxRecipient, TxSummary,
+};
+use tcx_constants::CoinInfo;
+
+const LEGACY_TX_TYPE: u32 = 0;
+const ACCESS_LIST_TX_TYPE: u32 = 1;
+const DYNAMIC_FEE_TX_TYPE: u32 = 2;
+/// The selector of ERC-20 `transfer(address,uint256)`
+const ERC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+impl TraitTransactionSigner<EthTxInput, EthTxOutput> for Keystore {
+    fn sign_transaction(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        tx: &EthTxInput,
+    ) -> Result<EthTxOutput> {
+        let hash = keccak(&tx.unsigned_payload()?);
+        let sign_result = self.sign_recoverable_hash(&hash, symbol, address, None)?;
+        let signed_tx = tx.signed_payload(&sign_result)?;
+
+        Ok(EthTxOutput {
+            signature: format!("0x{}", hex::encode(&signed_tx)),
+            tx_hash: format!("0x{}", hex::encode(keccak(&signed_tx))),
+        })
+    }
+}
+
+impl TransactionDecoder for EthTxInput {
+    fn decode_transaction(&self, _coin: &CoinInfo) -> Result<TxSummary> {
+        let to = EthAddress::decode(&self.to)?;
+        let to = if to.is_empty() {
+            "".to_string()
+        } else {
+            EthAddress::to_checksum(&to)
+        };
+        let value = BigUint::from_bytes_be(&parse_number(&self.value)?);
+        let data = parse_hex(&self.data)?;
+        let gas_price = if self.tx_type == DYNAMIC_FEE_TX_TYPE {
+            &self.max_fee_per_gas
+        } else {
+            &self.gas_price
+        };
+        let fee = BigUint::from_bytes_be(&parse_number(&self.gas_limit)?)
+            * BigUint::from_bytes_be(&parse_number(gas_price)?);
+
+        let mut summary = TxSummary {
+            fee: fee.to_string(),
+            data: hex::encode(&data),
+            ..TxSummary::default()
+        };
+        if data.len() >= 4 && !to.is_empty() {
+            summary.method = format!("0x{}", hex::encode(&data[..4]));
+        }
+        // the token payment is only shown if no ether is paid along with it
+        if data.len() == 68 && data[..4] == ERC20_TRANSFER_SELECTOR && value == BigUint::from(0u32)
+        {
+            summary.contract = to;
+            summary.recipients.push(TxRecipient {
+                address: EthAddress::to_checksum(&data[16..36]),
+                amount: BigUint::from_bytes_be(&data[36..68]).to_string(),
+                is_change: false,
+            });
+        } else {
+            summary.recipients.push(TxRecipient {
+                address: to,
+                amount: value.to_string(),
+                is_change: false,
+            });
+        }
+        Ok(summary)
+    }
+}
+
+impl EthTxInput {
+    /// The payload to be hashed and signed, typed transactions are prefixed by their EIP-2718 type
+    pub fn unsigned_payload(&self) -> Result<Vec<u8>> {
+        let mut fields = self.rlp_fields()?;
+        match self.tx_type {
+            LEGACY_TX_TYPE => {
+                // EIP-155 replay protection appends (chainId, 0, 0)
+                if !self.chain_id.is_empty() {
+                    fields.push(encode_bytes(&parse_number(&self.chain_id)?));
+                    fields.push(encode_u64(0));
+                    fields.push(encode_u64(0));
+                }
+                Ok(encode_list(&fields))
+            }
+            tx_type => Ok([vec![tx_type as u8], encode_list(&fields)].concat()),
+        }
+    }
+
+    /// Serialize the transaction with the 65 bytes recoverable signature `r || s || recovery_id`
+    pub fn signed_payload(&self, signature: &[u8]) -> Result<Vec<u8>> {
+        tcx_ensure!(signature.len() == 65, format_err!("invalid_signature"));
+        let recovery_id = u64::from(signature[64]);
+        let mut fields = self.rlp_fields()?;
+
+        match self.tx_type {
+            LEGACY_TX_TYPE => {
+                let v = if self.chain_id.is_empty() {
+                    recovery_id + 27
+                } else {
+                    recovery_id + parse_u64(&self.chain_id)? * 2 + 35
+                };
+                fields.push(encode_u64(v));
+            }
+            _ => fields.push(encode_u64(recovery_id)),
+        }
+        fields.push(encode_bytes(trim_leading_zeros(&signature[0..32])));
+        fields.push(encode_bytes(trim_leading_zeros(&signature[32..64])));
+
+        match self.tx_type {
+            LEGACY_TX_TYPE => Ok(encode_list(&fields)),
+            tx_type => Ok([vec![tx_type as u8], encode_list(&fields)].concat()),
+        }
+    }
+
+    fn rlp_fields(&self) -> Result<Vec<Vec<u8>>> {
+        let nonce = encode_bytes(&parse_number(&self.nonce)?);
+        let gas_limit = encode_bytes(&parse_number(&self.gas_limit)?);
+        let to = encode_bytes(&EthAddress::decode(&self.to)?);
+        let value = encode_bytes(&parse_number(&self.value)?);
+        let data = encode_bytes(&parse_hex(&self.data)?);
+
+        match self.tx_type {
+            LEGACY_TX_TYPE => {
+                let gas_price = encode_bytes(&parse_number(&self.gas_price)?);
+                Ok(vec![nonce, gas_price, gas_limit, to, value, data])
+            }
+            ACCESS_LIST_TX_TYPE => {
+                tcx_ensure!(!self.chain_id.is_empty(), Error::ChainIdRequired);
+                let chain_id = encode_bytes(&parse_number(&self.chain_id)?);
+                let gas_price = encode_bytes(&parse_number(&self.gas_price)?);
+                let access_list = self.encode_access_list()?;
+                Ok(vec![
+                    chain_id,
+                    nonce,
+                    gas_price,
+                    gas_limit,
+                    to,
+                    value,
+                    data,
+                    access_list,
+                ])
+            }
+            DYNAMIC_FEE_TX_TYPE => {
+                tcx_ensure!(!self.chain_id.is_empty(), Error::ChainIdRequired);
+                let chain_id = encode_bytes(&parse_number(&self.chain_id)?);
+                let max_priority_fee_per_gas =
+                    encode_bytes(&parse_number(&self.max_priority_fee_per_gas)?);
+                let max_fee_per_gas = encode_bytes(&parse_number(&self.max_fee_per_gas)?);
+                let access_list = self.encode_access_list()?;
+                Ok(vec![
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas_limit,
+                    to,
+                    value,
+                    data,
+                    access_list,
+                ])
+            }
+            _ => Err(Error::UnsupportedTxType.into()),
+        }
+    }
+
+    fn encode_access_list(&self) -> Result<Vec<u8>> {
+        let mut items = vec![];
+        for item in &self.access_list {
+            let address = EthAddress::decode(&item.address)?;
+            tcx_ensure!(!address.is_empty(), Error::InvalidAddress);
+
+            let mut storage_keys = vec![];
+            for key in &item.storage_keys {
+                let key_bytes = parse_hex(key)?;
+                tcx_ensure!(key_bytes.len() == 32, Error::InvalidHex);
+                storage_keys.push(encode_bytes(&key_bytes));
+            }
+            items.push(encode_list(&[
+                encode_bytes(&address),
+                encode_list(&storage_keys),
+            ]));
+        }
+        Ok(encode_list(&items))
+    }
+}
+
+/// Parse a decimal or `0x` prefixed hex string into its shortest big-endian bytes
+fn parse_number(value: &str) -> Result<Vec<u8>> {
+    let number = if value.is_empty() {
+        BigUint::from(0u32)
+    } else if value.starts_with("0x") || value.starts_with("0X") {
+        BigUint::from_str_radix(&value[2..], 16).map_err(|_| Error::InvalidNumber)?
+    } else {
+        BigUint::from_str_radix(value, 10).map_err(|_| Error::InvalidNumber)?
+    };
+    Ok(trim_leading_zeros(&number.to_bytes_be()).to_vec())
+}
+
+fn parse_u64(value: &str) -> Result<u64> {
+    let bytes = parse_number(value)?;
+    tcx_ensure!(bytes.len() <= 8, Error::InvalidNumber);
+    let mut buf = [0u8; 8];
+    buf[8 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(u64::from_be_bytes(buf))
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(strip_hex_prefix(value)).map_err(|_| Error::InvalidHex.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::EthAddress;
+    use crate::transaction::AccessList;
+
+    use tcx_chain::{HdKeystore, Keystore, KeystoreGuard, Metadata, PrivateKeystore};
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::{TEST_MNEMONIC, TEST_PASSWORD};
+
+    fn eip155_tx() -> EthTxInput {
+        EthTxInput {
+            nonce: "9".to_string(),
+            gas_price: "20000000000".to_string(),
+            gas_limit: "21000".to_string(),
+            to: "0x3535353535353535353535353535353535353535".to_string(),
+            value: "1000000000000000000".to_string(),
+            data: "".to_string(),
+            chain_id: "1".to_string(),
+            tx_type: 0,
+            max_fee_per_gas: "".to_string(),
+            max_priority_fee_per_gas: "".to_string(),
+            access_list: vec![],
+        }
+    }
+
+    fn pk_keystore() -> Keystore {
+        let pk_store = PrivateKeystore::from_private_key(
+            "4646464646464646464646464646464646464646464646464646464646464646",
+            TEST_PASSWORD,
+            Metadata::default(),
+        );
+        Keystore::PrivateKey(pk_store)
+    }
+
+    #[test]
+    fn parse_number_test() {
+        assert_eq!(parse_number("").unwrap(), Vec::<u8>::new());
+        assert_eq!(parse_number("0").unwrap(), Vec::<u8>::new());
+        assert_eq!(parse_number("0x0").unwrap(), Vec::<u8>::new());
+        assert_eq!(parse_number("1024").unwrap(), vec![0x04, 0x00]);
+        assert_eq!(parse_number("0x0400").unwrap(), vec![0x04, 0x00]);
+        assert!(parse_number("0xzz").is_err());
+        assert!(parse_number("1.5").is_err());
+        assert_eq!(parse_u64("0x01").unwrap(), 1);
+        assert!(parse_u64("0x010000000000000000").is_err());
+    }
+
+    #[test]
+    fn eip155_signing_payload() {
+        // https://eips.ethereum.org/EIPS/eip-155#example
+        let tx = eip155_tx();
+        assert_eq!(
+            hex::encode(tx.unsigned_payload().unwrap()),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+        assert_eq!(
+            hex::encode(keccak(&tx.unsigned_payload().unwrap())),
+            "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+        );
+    }
+
+    #[test]
+    fn sign_eip155_transaction() {
+        let mut keystore = pk_keystore();
+        let mut guard = KeystoreGuard::unlock_by_password(&mut keystore, TEST_PASSWORD).unwrap();
+        let ks = guard.keystore_mut();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let account = ks.derive_coin::<EthAddress>(&coin_info).unwrap().clone();
+        assert_eq!(
+            account.address,
+            "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+        );
+
+        let signed = ks
+            .sign_transaction("ETHEREUM", &account.address, &eip155_tx())
+            .unwrap();
+        assert_eq!(signed.signature, "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83");
+        assert_eq!(
+            signed.tx_hash,
+            "0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+        );
+    }
+
+    #[test]
+    fn sign_legacy_transaction() {
+        let mut keystore = pk_keystore();
+        let mut guard = KeystoreGuard::unlock_by_password(&mut keystore, TEST_PASSWORD).unwrap();
+        let ks = guard.keystore_mut();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let account = ks.derive_coin::<EthAddress>(&coin_info).unwrap().clone();
+
+        let tx = EthTxInput {
+            chain_id: "".to_string(),
+            ..eip155_tx()
+        };
+        let signed = ks
+            .sign_transaction("ETHEREUM", &account.address, &tx)
+            .unwrap();
+        assert_eq!(signed.signature, "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000801ba08383adc8b8ae116f918fb44ca7ff9dfd8012596a5c130c6246a2cc717ba41cdaa053ddfacf5bd4aa7e46d1575acf52636ea659b91f29e2fb91c75567a279738f38");
+    }
+
+    #[test]
+    fn sign_eip2930_transaction() {
+        let mut keystore = pk_keystore();
+        let mut guard = KeystoreGuard::unlock_by_password(&mut keystore, TEST_PASSWORD).unwrap();
+        let ks = guard.keystore_mut();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let account = ks.derive_coin::<EthAddress>(&coin_info).unwrap().clone();
+
+        let tx = EthTxInput {
+            tx_type: 1,
+            access_list: vec![AccessList {
+                address: "0x3535353535353535353535353535353535353535".to_string(),
+                storage_keys: vec![
+                    "0x0000000000000000000000000000000000000000000000000000000000000001"
+                        .to_string(),
+                ],
+            }],
+            ..eip155_tx()
+        };
+        let signed = ks
+            .sign_transaction("ETHEREUM", &account.address, &tx)
+            .unwrap();
+        assert_eq!(signed.signature, "0x01f8a701098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080f838f7943535353535353535353535353535353535353535e1a0000000000000000000000000000000000000000000000000000000000000000180a0381f5272732aaecaa36e57234061be5c675ad65be3a7a8900421c6fe9060965fa01dc3c104e6b5f480132d393594bf20f1375e716a60a449b3bf855cd51f61b870");
+        assert_eq!(
+            signed.tx_hash,
+            "0xe4eae69c2439a5b9b8f32eb7f0a31e965c0dca62947e2df71990fc6c32937249"
+        );
+    }
+
+    #[test]
+    fn sign_eip1559_transaction() {
+        let mut keystore = Keystore::Hd(
+            HdKeystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap(),
+        );
+        let mut guard = KeystoreGuard::unlock_by_password(&mut keystore, TEST_PASSWORD).unwrap();
+        let ks = guard.keystore_mut();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let account = ks.derive_coin::<EthAddress>(&coin_info).unwrap().clone();
+        assert_eq!(
+            account.address,
+            "0x6031564e7b2F5cc33737807b2E58DaFF870B590b"
+        );
+
+        let tx = EthTxInput {
+            nonce: "0x08".to_string(),
+            gas_price: "".to_string(),
+            gas_limit: "0x5208".to_string(),
+            to: "0xe0B3c8E0D3Ce5b80cAF6a5E0db6F6A83E0C8B5e9".to_string(),
+            value: "0x2386f26fc10000".to_string(),
+            data: "".to_string(),
+            chain_id: "0x01".to_string(),
+            tx_type: 2,
+            max_fee_per_gas: "0x0b2d05e000".to_string(),
+            max_priority_fee_per_gas: "0x3b9aca00".to_string(),
+            access_list: vec![],
+        };
+        let signed = ks
+            .sign_transaction("ETHEREUM", &account.address, &tx)
+            .unwrap();
+        assert_eq!(signed.signature, "0x02f8720108843b9aca00850b2d05e00082520894e0b3c8e0d3ce5b80caf6a5e0db6f6a83e0c8b5e9872386f26fc1000080c001a0940c3262d8c710e6f5f26350f69d017f8ebd72044f5e6319f2a53e39819e75a4a03f44f021654170dc8c9563e5e902d3696942951201f19c115663a45d48fc61d0");
+        assert_eq!(
+            signed.tx_hash,
+            "0x45e235e2a83841048c774789a6b8197f0a2d2dc2f69c19e63b06916024e66e14"
+        );
+    }
+
+    #[test]
+    fn sign_invalid_transaction() {
+        let mut keystore = pk_keystore();
+        let mut guard = KeystoreGuard::unlock_by_password(&mut keystore, TEST_PASSWORD).unwrap();
+        let ks = guard.keystore_mut();
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let account = ks.derive_coin::<EthAddress>(&coin_info).unwrap().clone();
+
+        let tx = EthTxInput {
+            tx_type: 3,
+            ..eip155_tx()
+        };
+        let ret = ks.sign_transaction("ETHEREUM", &account.address, &tx);
+        assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_tx_type");
+
+        let tx = EthTxInput {
+            tx_type: 2,
+            chain_id: "".to_string(),
+            ..eip155_tx()
+        };
+        let ret = ks.sign_transaction("ETHEREUM", &account.address, &tx);
+        assert_eq!(format!("{}", ret.err().unwrap()), "chain_id_required");
+
+        let tx = EthTxInput {
+            to: "0x353535".to_string(),
+            ..eip155_tx()
+        };
+        let ret = ks.sign_transaction("ETHEREUM", &account.address, &tx);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_address");
+    }
+
+    #[test]
+    fn decode_transaction() {
+        let coin_info = coin_info_from_param("ETHEREUM", "", "", "").unwrap();
+        let summary = eip155_tx().decode_transaction(&coin_info).unwrap();
+        assert_eq!(
+            summary.recipients,
+            vec![TxRecipient {
+                address: "0x3535353535353535353535353535353535353535".to_string(),
+                amount: "1000000000000000000".to_string(),
+                is_change: false,
+            }]
+        );
+        assert_eq!(summary.fee, "420000000000000");
+        assert_eq!(summary.contract, "");
+
+        let tx = EthTxInput {
+            to: "0x2222222222222222222222222222222222222222".to_string(),
+            value: "0".to_string(),
+            data: "0xa9059cbb0000000000000000000000001111111111111111111111111111111111111111000000000000000000000000000000000000000000000000000000003b9aca00".to_string(),
+            tx_type: 2,
+            max_fee_per_gas: "30000000000".to_string(),
+            max_priority_fee_per_gas: "1000000000".to_string(),
+            gas_limit: "60000".to_string(),
+            ..eip155_tx()
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(
+            summary.recipients,
+            vec![TxRecipient {
+                address: "0x1111111111111111111111111111111111111111".to_string(),
+                amount: "1000000000".to_string(),
+                is_change: false,
+            }]
+        );
+        assert_eq!(summary.fee, "1800000000000000");
+        assert_eq!(
+            summary.contract,
+            "0x2222222222222222222222222222222222222222"
+        );
+        assert_eq!(summary.method, "0xa9059cbb");
+
+        // the ether paid along with the call is shown instead
+        let tx = EthTxInput {
+            value: "1".to_string(),
+            ..tx
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(
+            summary.recipients,
+            vec![TxRecipient {
+                address: "0x2222222222222222222222222222222222222222".to_string(),
+                amount: "1".to_string(),
+                is_change: false,
+            }]
+        );
+        assert_eq!(summary.contract, "");
+        assert_eq!(summary.method, "0xa9059cbb");
+    }
+}
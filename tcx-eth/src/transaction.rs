@@ -0,0 +1,51 @@
+/// This file only contains ethereum related messages.
+// ref:
+// - https://eips.ethereum.org/EIPS/eip-155
+// - https://eips.ethereum.org/EIPS/eip-2930
+// - https://eips.ethereum.org/EIPS/eip-1559
+
+/// FUNCTION: sign_tx(SignParam{input: EthTxInput}): EthTxOutput
+///
+/// All the numeric fields accept decimal strings or `0x` prefixed hex strings.
+/// `txType` follows EIP-2718: 0 is legacy, 1 is EIP-2930 and 2 is EIP-1559.
+/// Legacy transactions with an empty `chainId` are signed without EIP-155 replay protection.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AccessList {
+    #[prost(string, tag = "1")]
+    pub address: std::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub storage_keys: ::std::vec::Vec<std::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EthTxInput {
+    #[prost(string, tag = "1")]
+    pub nonce: std::string::String,
+    #[prost(string, tag = "2")]
+    pub gas_price: std::string::String,
+    #[prost(string, tag = "3")]
+    pub gas_limit: std::string::String,
+    #[prost(string, tag = "4")]
+    pub to: std::string::String,
+    #[prost(string, tag = "5")]
+    pub value: std::string::String,
+    #[prost(string, tag = "6")]
+    pub data: std::string::String,
+    #[prost(string, tag = "7")]
+    pub chain_id: std::string::String,
+    #[prost(uint32, tag = "8")]
+    pub tx_type: u32,
+    #[prost(string, tag = "9")]
+    pub max_fee_per_gas: std::string::String,
+    #[prost(string, tag = "10")]
+    pub max_priority_fee_per_gas: std::string::String,
+    #[prost(message, repeated, tag = "11")]
+    pub access_list: ::std::vec::Vec<AccessList>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EthTxOutput {
+    /// hex string of the signed raw transaction
+    #[prost(string, tag = "1")]
+    pub signature: std::string::String,
+    #[prost(string, tag = "2")]
+    pub tx_hash: std::string::String,
+}
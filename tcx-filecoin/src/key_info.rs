@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use tcx_chain::Result;
 use tcx_constants::CurveType;
+use zeroize::Zeroize;
 
 use super::Error;
 
@@ -41,6 +42,13 @@ impl KeyInfo {
     }
 }
 
+/// The encoded private key is wiped when the key info is dropped
+impl Drop for KeyInfo {
+    fn drop(&mut self) {
+        self.private_key.zeroize();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::KeyInfo;
@@ -8,7 +8,7 @@ mod utils;
 
 pub use crate::address::FilecoinAddress;
 pub use crate::key_info::KeyInfo;
-pub use crate::transaction::{SignedMessage, UnsignedMessage};
+pub use crate::transaction::{FilecoinMessageInput, Signature, SignedMessage, UnsignedMessage};
 #[macro_use]
 extern crate failure;
 
@@ -1,4 +1,4 @@
-use crate::transaction::{Signature, SignedMessage, UnsignedMessage};
+use crate::transaction::{FilecoinMessageInput, Signature, SignedMessage, UnsignedMessage};
 use crate::utils::{digest, HashSize};
 use crate::Error;
 use forest_address::Address;
@@ -9,8 +9,11 @@ use forest_vm::Serialized;
 use num_bigint_chainsafe::BigInt;
 use std::convert::TryFrom;
 use std::str::FromStr;
-use tcx_chain::{ChainSigner, Keystore, Result, TransactionSigner};
-use tcx_constants::CurveType;
+use tcx_chain::{
+    ChainSigner, Keystore, MessageSigner, Result, TransactionDecoder, TransactionSigner,
+    TxRecipient, TxSummary,
+};
+use tcx_constants::{CoinInfo, CurveType};
 
 impl TryFrom<&UnsignedMessage> for ForestUnsignedMessage {
     type Error = crate::Error;
@@ -48,6 +51,34 @@ impl TryFrom<&UnsignedMessage> for ForestUnsignedMessage {
     }
 }
 
+impl TransactionDecoder for UnsignedMessage {
+    fn decode_transaction(&self, _coin: &CoinInfo) -> Result<TxSummary> {
+        // the same validation as signing
+        ForestUnsignedMessage::try_from(self)?;
+        let gas_fee_cap = BigInt::from_str(&self.gas_fee_cap).map_err(|_| Error::InvalidNumber)?;
+        let fee = BigInt::from(self.gas_limit) * gas_fee_cap;
+        let params = base64::decode(&self.params).map_err(|_| Error::InvalidParam)?;
+
+        Ok(TxSummary {
+            senders: vec![self.from.clone()],
+            recipients: vec![TxRecipient {
+                address: self.to.clone(),
+                amount: self.value.clone(),
+                is_change: false,
+            }],
+            fee: fee.to_string(),
+            // method 0 is the plain send
+            method: if self.method == 0 {
+                "".to_string()
+            } else {
+                self.method.to_string()
+            },
+            data: hex::encode(params),
+            ..TxSummary::default()
+        })
+    }
+}
+
 impl TransactionSigner<UnsignedMessage, SignedMessage> for Keystore {
     fn sign_transaction(
         &mut self,
@@ -104,10 +135,66 @@ impl TransactionSigner<UnsignedMessage, SignedMessage> for Keystore {
     }
 }
 
+const MESSAGE_PREFIX: &[u8] = b"\x19Filecoin Signed Message:\n";
+
+/// The envelope of FRC-0102, the length is the decimal string of the message length
+fn message_envelope(message: &[u8]) -> Vec<u8> {
+    let mut envelope = MESSAGE_PREFIX.to_vec();
+    envelope.extend(message.len().to_string().as_bytes());
+    envelope.extend(message);
+    envelope
+}
+
+impl MessageSigner<FilecoinMessageInput, Signature> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &FilecoinMessageInput,
+    ) -> Result<Signature> {
+        let data = if message.is_hex {
+            let value = if message.message.starts_with("0x") {
+                &message.message[2..]
+            } else {
+                &message.message
+            };
+            hex::decode(value).map_err(|_| Error::InvalidParam)?
+        } else {
+            message.message.as_bytes().to_vec()
+        };
+        let envelope = message_envelope(&data);
+
+        let account = self.account(symbol, address);
+        if account.is_none() {
+            return Err(Error::CannotFoundAccount.into());
+        }
+
+        let (signature_type, signature) = match account.unwrap().curve {
+            CurveType::SECP256k1 => (
+                1,
+                self.sign_recoverable_hash(
+                    &digest(&envelope, HashSize::Default),
+                    symbol,
+                    address,
+                    None,
+                )?,
+            ),
+            CurveType::BLS => (2, self.sign_hash(&envelope, symbol, address, None)?),
+            _ => return Err(Error::InvalidCurveType.into()),
+        };
+
+        Ok(Signature {
+            r#type: signature_type,
+            data: base64::encode(&signature),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{FilecoinAddress, KeyInfo, UnsignedMessage};
-    use tcx_chain::{Keystore, Metadata, TransactionSigner};
+    use super::message_envelope;
+    use crate::{FilecoinAddress, FilecoinMessageInput, KeyInfo, UnsignedMessage};
+    use tcx_chain::{Keystore, MessageSigner, Metadata, TransactionDecoder, TransactionSigner};
     use tcx_constants::{CoinInfo, CurveType};
 
     #[test]
@@ -205,4 +292,125 @@ mod tests {
         );
         assert_eq!(signature.data, "tNRsgNdWO6UdY9IOh5tvzcL1Dwi7gljLt22aITKUgtF363lrP2gHxOX9oNGhnFD6BoM4/Y/HMzETlYF0r4+1aHZo1F8fV3XDwxwwz1HKxoDIreXBtPAjTiqBGlTiMwPX");
     }
+
+    #[test]
+    fn test_sign_message() {
+        assert_eq!(
+            message_envelope(b"hello world"),
+            b"\x19Filecoin Signed Message:\n11hello world".to_vec()
+        );
+
+        let key_info =
+            KeyInfo::from_lotus(
+                &hex::decode("7b2254797065223a22736563703235366b31222c22507269766174654b6579223a222f5059574777574e577a58614d5675437a613958502b314b4a695a4474696f4c76777863754268783041553d227d").unwrap()).unwrap();
+        let private_key = key_info.decode_private_key().unwrap();
+        let mut ks =
+            Keystore::from_private_key(&hex::encode(private_key), "Password", Metadata::default());
+        ks.unlock_by_password("Password").unwrap();
+
+        let coin_info = CoinInfo {
+            coin: "FILECOIN".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let account = ks
+            .derive_coin::<FilecoinAddress>(&coin_info)
+            .unwrap()
+            .clone();
+
+        let inputs = vec![
+            FilecoinMessageInput {
+                message: "hello world".to_string(),
+                is_hex: false,
+            },
+            FilecoinMessageInput {
+                message: "0x68656c6c6f20776f726c64".to_string(),
+                is_hex: true,
+            },
+        ];
+        for input in inputs {
+            let signature = ks
+                .sign_message("FILECOIN", &account.address, &input)
+                .unwrap();
+            assert_eq!(signature.r#type, 1);
+            assert_eq!(signature.data, "PnV3B6oUj54+ujbdET1KqS/qa8ZjIhqKQjAzkJ2FSflJU5pAUrYjsnIkXg+6K4u0pfO78FyA58zdW/7gwrXsgAE=");
+        }
+    }
+
+    #[test]
+    fn test_sign_message_bls() {
+        let key_info =
+            KeyInfo::from_lotus(
+                &hex::decode("7b2254797065223a22626c73222c22507269766174654b6579223a2269376b4f2b7a78633651532b7637597967636d555968374d55595352657336616e6967694c684b463830383d227d").unwrap()).unwrap();
+        let private_key = key_info.decode_private_key().unwrap();
+        let mut ks =
+            Keystore::from_private_key(&hex::encode(private_key), "Password", Metadata::default());
+        ks.unlock_by_password("Password").unwrap();
+
+        let coin_info = CoinInfo {
+            coin: "FILECOIN".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::BLS,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let account = ks
+            .derive_coin::<FilecoinAddress>(&coin_info)
+            .unwrap()
+            .clone();
+
+        let input = FilecoinMessageInput {
+            message: "hello world".to_string(),
+            is_hex: false,
+        };
+        let signature = ks
+            .sign_message("FILECOIN", &account.address, &input)
+            .unwrap();
+        assert_eq!(signature.r#type, 2);
+        assert_eq!(base64::decode(&signature.data).unwrap().len(), 96);
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let unsigned_message = UnsignedMessage {
+            to: "f1zlkjwo5pnm6petm4u4luj6gb6e64eecrw4t4stq".to_string(),
+            from: "f12i3bop43tprlnymx2c75u6uvlq7iur2rcd7qsey".to_string(),
+            nonce: 1,
+            value: "10000000000000000".to_string(),
+            gas_limit: 491585,
+            gas_fee_cap: "151367".to_string(),
+            gas_premium: "150313".to_string(),
+            method: 0,
+            params: "".to_string(),
+        };
+        let coin_info = CoinInfo {
+            coin: "FILECOIN".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+
+        let summary = unsigned_message.decode_transaction(&coin_info).unwrap();
+        assert_eq!(
+            summary.senders,
+            vec!["f12i3bop43tprlnymx2c75u6uvlq7iur2rcd7qsey"]
+        );
+        assert_eq!(
+            summary.recipients[0].address,
+            "f1zlkjwo5pnm6petm4u4luj6gb6e64eecrw4t4stq"
+        );
+        assert_eq!(summary.recipients[0].amount, "10000000000000000");
+        assert_eq!(summary.fee, "74409746695");
+        assert_eq!(summary.method, "");
+
+        let unsigned_message = UnsignedMessage {
+            to: "f1zlkjwo5pnm6petm4u4luj6gb6e64eecrw4t4st".to_string(),
+            ..unsigned_message
+        };
+        let ret = unsigned_message.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_address");
+    }
 }
@@ -35,3 +35,14 @@ pub struct SignedMessage {
     #[prost(message, optional, tag = "3")]
     pub signature: ::std::option::Option<Signature>,
 }
+/// FUNCTION: sign_message(SignParam{input: FilecoinMessageInput}): Signature
+///
+/// Sign the message in the envelope of FRC-0102,
+/// `0x19 Filecoin Signed Message:\n <length> <message>`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FilecoinMessageInput {
+    #[prost(string, tag = "1")]
+    pub message: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_hex: bool,
+}
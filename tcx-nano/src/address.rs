@@ -0,0 +1,215 @@
+//! The account address is the public key and its blake2b-40 checksum in the Nano base32
+//! ref: https://docs.nano.org/integration-guides/the-basics/#account-public-address
+
+use crate::Error;
+use blake2b_simd::Params;
+use tcx_chain::{tcx_ensure, Address, Result};
+use tcx_constants::CoinInfo;
+use tcx_primitive::TypedPublicKey;
+
+const ALPHABET: &[u8; 32] = b"13456789abcdefghijkmnopqrstuwxyz";
+
+const PREFIX: &str = "nano_";
+const LEGACY_PREFIX: &str = "xrb_";
+
+/// The 256 bits key is left padded by 4 zero bits to be 52 characters
+const PUBLIC_KEY_PADDING_BITS: u32 = 4;
+const ENCODED_PUBLIC_KEY_LEN: usize = 52;
+const ENCODED_CHECKSUM_LEN: usize = 8;
+
+fn encode_base32(data: &[u8], padding_bits: u32) -> String {
+    let mut ret = String::new();
+    let mut buffer: u32 = 0;
+    let mut bits = padding_bits;
+    for byte in data {
+        buffer = (buffer << 8) | *byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            ret.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        ret.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    ret
+}
+
+/// The padding bits must be zero and the bits must fill the bytes
+fn decode_base32(encoded: &[u8], padding_bits: u32) -> Option<Vec<u8>> {
+    let mut ret = vec![];
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut padding_bits = padding_bits;
+    for c in encoded {
+        let value = ALPHABET.iter().position(|x| x == c)? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if padding_bits > 0 && bits >= padding_bits {
+            bits -= padding_bits;
+            if buffer >> bits != 0 {
+                return None;
+            }
+            padding_bits = 0;
+        }
+        if padding_bits == 0 && bits >= 8 {
+            bits -= 8;
+            ret.push((buffer >> bits) as u8);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        return None;
+    }
+    Some(ret)
+}
+
+/// The blake2b-40 of the public key in the reversed order
+fn checksum(public_key: &[u8]) -> Vec<u8> {
+    let mut hash = Params::new()
+        .hash_length(5)
+        .hash(public_key)
+        .as_bytes()
+        .to_vec();
+    hash.reverse();
+    hash
+}
+
+pub struct NanoAddress();
+
+impl NanoAddress {
+    pub fn from_slice(public_key: &[u8]) -> Result<String> {
+        tcx_ensure!(public_key.len() == 32, Error::InvalidAddress);
+        Ok(format!(
+            "{}{}{}",
+            PREFIX,
+            encode_base32(public_key, PUBLIC_KEY_PADDING_BITS),
+            encode_base32(&checksum(public_key), 0)
+        ))
+    }
+
+    /// The public key of the address, the legacy `xrb_` prefix is accepted too
+    pub fn decode(address: &str) -> Result<Vec<u8>> {
+        let encoded = if address.starts_with(PREFIX) {
+            &address.as_bytes()[PREFIX.len()..]
+        } else if address.starts_with(LEGACY_PREFIX) {
+            &address.as_bytes()[LEGACY_PREFIX.len()..]
+        } else {
+            return Err(Error::InvalidAddress.into());
+        };
+        tcx_ensure!(
+            encoded.len() == ENCODED_PUBLIC_KEY_LEN + ENCODED_CHECKSUM_LEN,
+            Error::InvalidAddress
+        );
+
+        let public_key = decode_base32(&encoded[..ENCODED_PUBLIC_KEY_LEN], PUBLIC_KEY_PADDING_BITS)
+            .ok_or(Error::InvalidAddress)?;
+        let address_checksum =
+            decode_base32(&encoded[ENCODED_PUBLIC_KEY_LEN..], 0).ok_or(Error::InvalidAddress)?;
+        tcx_ensure!(
+            address_checksum == checksum(&public_key),
+            Error::InvalidAddress
+        );
+        Ok(public_key)
+    }
+}
+
+impl Address for NanoAddress {
+    fn from_public_key(public_key: &TypedPublicKey, _coin: &CoinInfo) -> Result<String> {
+        NanoAddress::from_slice(&public_key.to_bytes())
+    }
+
+    fn is_valid(address: &str, _coin: &CoinInfo) -> bool {
+        NanoAddress::decode(address).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::address::NanoAddress;
+    use tcx_chain::Address;
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::CurveType;
+    use tcx_primitive::TypedPublicKey;
+
+    #[test]
+    fn from_public_key() {
+        let coin_info = coin_info_from_param("NANO", "MAINNET", "", "").unwrap();
+        assert_eq!(coin_info.curve, CurveType::ED25519Blake2bNano);
+
+        let public_key = TypedPublicKey::from_slice(
+            CurveType::ED25519Blake2bNano,
+            &hex::decode("c008b814a7d269a1fa3c6528b19201a24d797912db9996ff02a1ff356e45552b")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            NanoAddress::from_public_key(&public_key, &coin_info).unwrap(),
+            "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+        );
+
+        let public_key = TypedPublicKey::from_slice(
+            CurveType::ED25519Blake2bNano,
+            &hex::decode("5b65b0e8173ee0802c2c3e6c9080d1a16b06de1176c938a924f58670904e82c4")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            NanoAddress::from_public_key(&public_key, &coin_info).unwrap(),
+            "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+        );
+    }
+
+    #[test]
+    fn decode() {
+        assert_eq!(
+            hex::encode(
+                NanoAddress::decode(
+                    "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+                )
+                .unwrap()
+            ),
+            "c008b814a7d269a1fa3c6528b19201a24d797912db9996ff02a1ff356e45552b"
+        );
+        assert_eq!(
+            hex::encode(
+                NanoAddress::decode(
+                    "xrb_3igf8hd4sjshoibbbkeitmgkp1o6ug4xads43j6e4gqkj5xk5o83j8ja9php"
+                )
+                .unwrap()
+            ),
+            "c1cd33d62cc72fac1294c990d4dd2b02a4db85d42f220c48c13af288fb21d4c1"
+        );
+    }
+
+    #[test]
+    fn is_valid() {
+        let coin_info = coin_info_from_param("NANO", "MAINNET", "", "").unwrap();
+        let addresses = vec![
+            "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7",
+            "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d",
+            "xrb_3igf8hd4sjshoibbbkeitmgkp1o6ug4xads43j6e4gqkj5xk5o83j8ja9php",
+        ];
+        for address in addresses {
+            assert!(NanoAddress::is_valid(address, &coin_info));
+        }
+
+        let invalid_addresses = vec![
+            "",
+            "nano_",
+            // wrong checksum
+            "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b8",
+            // not zero padding
+            "nano_5i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7",
+            // not in the alphabet
+            "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3bl",
+            "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b",
+            "xno_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7",
+            "tz1dLEU3WfzCrDq2bvoEz4cfLP5wg4S7xNo9",
+        ];
+        for address in invalid_addresses {
+            assert!(!NanoAddress::is_valid(address, &coin_info));
+        }
+    }
+}
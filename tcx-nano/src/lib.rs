@@ -0,0 +1,22 @@
+mod address;
+mod signer;
+mod transaction;
+
+pub use crate::address::NanoAddress;
+pub use crate::signer::block_hash;
+pub use crate::transaction::{NanoTxInput, NanoTxOutput};
+
+#[macro_use]
+extern crate failure;
+
+#[derive(Fail, Debug, PartialEq)]
+pub enum Error {
+    #[fail(display = "invalid_address")]
+    InvalidAddress,
+
+    #[fail(display = "invalid_block_hash")]
+    InvalidBlockHash,
+
+    #[fail(display = "invalid_balance")]
+    InvalidBalance,
+}
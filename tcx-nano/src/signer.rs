@@ -0,0 +1,266 @@
+//! The state block is signed by its hash, the work of the block is not part of the hash so it
+//! could be generated after signing
+//! ref: https://docs.nano.org/integration-guides/the-basics/#block-format
+
+use crate::address::NanoAddress;
+use crate::transaction::{NanoTxInput, NanoTxOutput};
+use crate::Error;
+use blake2b_simd::Params;
+use tcx_chain::{
+    tcx_ensure, ChainSigner, Keystore, Result, TransactionDecoder,
+    TransactionSigner as TraitTransactionSigner, TxRecipient, TxSummary,
+};
+use tcx_constants::CoinInfo;
+
+/// The type of the state block, which is the last byte of the 32 bytes preamble
+const STATE_BLOCK_PREAMBLE: u8 = 6;
+
+/// The 32 bytes hash, the previous of the open block is "0"
+fn parse_hash(value: &str) -> Result<Vec<u8>> {
+    if value == "0" {
+        return Ok(vec![0u8; 32]);
+    }
+    let hash = hex::decode(value).map_err(|_| Error::InvalidBlockHash)?;
+    tcx_ensure!(hash.len() == 32, Error::InvalidBlockHash);
+    Ok(hash)
+}
+
+/// The link is the public key of the destination if it is an address
+fn parse_link(value: &str) -> Result<Vec<u8>> {
+    if value.contains('_') {
+        NanoAddress::decode(value)
+    } else {
+        parse_hash(value)
+    }
+}
+
+/// The 128 bits balance in raw
+fn parse_balance(value: &str) -> Result<u128> {
+    Ok(value.parse::<u128>().map_err(|_| Error::InvalidBalance)?)
+}
+
+/// blake2b-256 of preamble || account || previous || representative || balance || link
+pub fn block_hash(account: &str, block: &NanoTxInput) -> Result<Vec<u8>> {
+    let mut preamble = [0u8; 32];
+    preamble[31] = STATE_BLOCK_PREAMBLE;
+
+    let hash = Params::new()
+        .hash_length(32)
+        .to_state()
+        .update(&preamble)
+        .update(&NanoAddress::decode(account)?)
+        .update(&parse_hash(&block.previous)?)
+        .update(&NanoAddress::decode(&block.representative)?)
+        .update(&parse_balance(&block.balance)?.to_be_bytes())
+        .update(&parse_link(&block.link)?)
+        .finalize();
+    Ok(hash.as_bytes().to_vec())
+}
+
+impl TraitTransactionSigner<NanoTxInput, NanoTxOutput> for Keystore {
+    fn sign_transaction(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        tx: &NanoTxInput,
+    ) -> Result<NanoTxOutput> {
+        let hash = block_hash(address, tx)?;
+        let signature = self.sign_hash(&hash, symbol, address, None)?;
+        Ok(NanoTxOutput {
+            hash: hex::encode(&hash),
+            signature: hex::encode(&signature),
+        })
+    }
+}
+
+impl TransactionDecoder for NanoTxInput {
+    /// The block has the new balance rather than the amount, so the amount of a send is empty,
+    /// the link of a receive is the data. Nano has no fee
+    fn decode_transaction(&self, _coin: &CoinInfo) -> Result<TxSummary> {
+        parse_hash(&self.previous)?;
+        NanoAddress::decode(&self.representative)?;
+        parse_balance(&self.balance)?;
+        let link = parse_link(&self.link)?;
+
+        let mut summary = TxSummary::default();
+        if self.link.contains('_') {
+            summary.recipients.push(TxRecipient {
+                address: NanoAddress::from_slice(&link)?,
+                amount: "".to_string(),
+                is_change: false,
+            });
+        } else {
+            summary.data = hex::encode(&link);
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::address::NanoAddress;
+    use crate::signer::block_hash;
+    use crate::transaction::NanoTxInput;
+    use tcx_chain::{Keystore, Metadata, TransactionDecoder, TransactionSigner};
+    use tcx_constants::coin_info::coin_info_from_param;
+    use tcx_constants::{CurveType, TEST_MNEMONIC, TEST_PASSWORD};
+    use tcx_primitive::TypedPublicKey;
+
+    #[test]
+    fn hash_state_block() {
+        let block = NanoTxInput {
+            previous: "0".to_string(),
+            representative: "xrb_3p1asma84n8k84joneka776q4egm5wwru3suho9wjsfyuem8j95b3c78nw8j"
+                .to_string(),
+            balance: "1".to_string(),
+            link: "1EF0AD02257987B48030CC8D38511D3B2511672F33AF115AD09E18A86A8355A8".to_string(),
+        };
+        let hash = block_hash(
+            "xrb_3igf8hd4sjshoibbbkeitmgkp1o6ug4xads43j6e4gqkj5xk5o83j8ja9php",
+            &block,
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "fc5a7fb777110a858052468d448b2df22b648943c097c0608d1e2341007438b0"
+        );
+    }
+
+    #[test]
+    fn hash_invalid_block() {
+        let account = "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7";
+        let block = NanoTxInput {
+            previous: "0".to_string(),
+            representative: account.to_string(),
+            balance: "1".to_string(),
+            link: account.to_string(),
+        };
+        assert!(block_hash(account, &block).is_ok());
+
+        let cases = vec![
+            (
+                NanoTxInput {
+                    previous: "00".to_string(),
+                    ..block.clone()
+                },
+                "invalid_block_hash",
+            ),
+            (
+                NanoTxInput {
+                    balance: "-1".to_string(),
+                    ..block.clone()
+                },
+                "invalid_balance",
+            ),
+            (
+                NanoTxInput {
+                    balance: "340282366920938463463374607431768211456".to_string(),
+                    ..block.clone()
+                },
+                "invalid_balance",
+            ),
+            (
+                NanoTxInput {
+                    representative: "".to_string(),
+                    ..block.clone()
+                },
+                "invalid_address",
+            ),
+            (
+                NanoTxInput {
+                    link: "nano_1".to_string(),
+                    ..block.clone()
+                },
+                "invalid_address",
+            ),
+        ];
+        for (block, err) in cases {
+            assert_eq!(
+                format!("{}", block_hash(account, &block).err().unwrap()),
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn sign_state_block() {
+        let mut keystore =
+            Keystore::from_mnemonic(TEST_MNEMONIC, TEST_PASSWORD, Metadata::default()).unwrap();
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+
+        let coin_info = coin_info_from_param("NANO", "MAINNET", "", "").unwrap();
+        let account = keystore.derive_coin::<NanoAddress>(&coin_info).unwrap();
+        assert_eq!(
+            account.address,
+            "nano_337o1g55munwh9f3piswk6bg8ka9ik5ytxk1m6cagmxf65je95wcamgyyso7"
+        );
+
+        let block = NanoTxInput {
+            previous: "991cf190094c00f0b68e2e5f75f6bee95a2e0bd93ceaa4a6734db9f19b728948"
+                .to_string(),
+            representative: "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+                .to_string(),
+            balance: "1000000000000000000000000000000".to_string(),
+            link: "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d".to_string(),
+        };
+        let output = keystore
+            .sign_transaction("NANO", &account.address, &block)
+            .unwrap();
+        assert_eq!(
+            output.hash,
+            "1e6d8dc5a03205db5dfe7cebfa1caddf1864d596a6e241a400b28a7c85188cc2"
+        );
+        assert_eq!(output.signature, "4bbca1a670a0d4514f932b34511fc8c7d445db62d637343cf7b19f059e8ef170357f3f1a5e4930c0c660711fdfdb2a9ff3ec192e55477e59a407790597224b0d");
+
+        let public_key = TypedPublicKey::from_slice(
+            CurveType::ED25519Blake2bNano,
+            &NanoAddress::decode(&account.address).unwrap(),
+        )
+        .unwrap();
+        assert!(public_key.verify(
+            &hex::decode(&output.hash).unwrap(),
+            &hex::decode(&output.signature).unwrap()
+        ));
+    }
+
+    #[test]
+    fn decode_state_block() {
+        let coin_info = coin_info_from_param("NANO", "MAINNET", "", "").unwrap();
+        let block = NanoTxInput {
+            previous: "991cf190094c00f0b68e2e5f75f6bee95a2e0bd93ceaa4a6734db9f19b728948"
+                .to_string(),
+            representative: "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+                .to_string(),
+            balance: "1000000000000000000000000000000".to_string(),
+            link: "xrb_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d".to_string(),
+        };
+        let summary = block.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.recipients.len(), 1);
+        assert_eq!(
+            summary.recipients[0].address,
+            "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+        );
+        assert_eq!(summary.recipients[0].amount, "");
+        assert_eq!(summary.fee, "");
+
+        let block = NanoTxInput {
+            link: "1EF0AD02257987B48030CC8D38511D3B2511672F33AF115AD09E18A86A8355A8".to_string(),
+            ..block
+        };
+        let summary = block.decode_transaction(&coin_info).unwrap();
+        assert!(summary.recipients.is_empty());
+        assert_eq!(
+            summary.data,
+            "1ef0ad02257987b48030cc8d38511d3b2511672f33af115ad09e18a86a8355a8"
+        );
+
+        let block = NanoTxInput {
+            balance: "-1".to_string(),
+            ..block
+        };
+        assert_eq!(
+            format!("{}", block.decode_transaction(&coin_info).err().unwrap()),
+            "invalid_balance"
+        );
+    }
+}
@@ -0,0 +1,23 @@
+/// FUNCTION: sign_tx(SignParam{input: NanoTxInput}): NanoTxOutput
+///
+/// The state block of the account, the previous is "0" for the open block, the balance is the
+/// new balance in raw, the link is the destination address of a send or the hash of the block to
+/// receive
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NanoTxInput {
+    #[prost(string, tag = "1")]
+    pub previous: std::string::String,
+    #[prost(string, tag = "2")]
+    pub representative: std::string::String,
+    #[prost(string, tag = "3")]
+    pub balance: std::string::String,
+    #[prost(string, tag = "4")]
+    pub link: std::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NanoTxOutput {
+    #[prost(string, tag = "1")]
+    pub hash: std::string::String,
+    #[prost(string, tag = "2")]
+    pub signature: std::string::String,
+}
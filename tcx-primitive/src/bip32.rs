@@ -3,7 +3,9 @@ use super::Result;
 use crate::constant::SECP256K1_ENGINE;
 use crate::ecc::{DeterministicPrivateKey, DeterministicPublicKey, KeyError};
 
-use crate::{Derive, FromHex, Secp256k1PrivateKey, Secp256k1PublicKey, Ss58Codec, ToHex};
+use crate::{
+    Derive, FromHex, PrivateKey, Secp256k1PrivateKey, Secp256k1PublicKey, Ss58Codec, ToHex,
+};
 use bitcoin::util::key::PublicKey;
 
 use bitcoin::util::base58;
@@ -16,8 +18,17 @@ use byteorder::BigEndian;
 use byteorder::ByteOrder;
 
 use bip39::{Language, Mnemonic};
-
-pub struct Bip32DeterministicPrivateKey(ExtendedPrivKey);
+use zeroize::Zeroizing;
+
+/// The secret key is kept zeroizing in `Secp256k1PrivateKey`, the extended key of the bitcoin crate
+/// is re-created from it for each use
+pub struct Bip32DeterministicPrivateKey {
+    private_key: Secp256k1PrivateKey,
+    depth: u8,
+    parent_fingerprint: Fingerprint,
+    child_number: ChildNumber,
+    chain_code: ChainCode,
+}
 
 pub struct Bip32DeterministicPublicKey(ExtendedPubKey);
 
@@ -38,20 +49,47 @@ impl Bip32DeterministicPrivateKey {
     /// Construct a new master key from a seed value
     pub fn from_seed(seed: &[u8]) -> Result<Self> {
         let epk = ExtendedPrivKey::new_master(Network::Bitcoin, seed)?;
-        Ok(Bip32DeterministicPrivateKey(epk))
+        Ok(Bip32DeterministicPrivateKey::from(epk))
     }
 
     pub fn from_mnemonic(mnemonic: &str) -> Result<Self> {
         let mn = Mnemonic::from_phrase(mnemonic, Language::English)?;
         let seed = bip39::Seed::new(&mn, "");
         let epk = ExtendedPrivKey::new_master(Network::Bitcoin, seed.as_ref())?;
-        Ok(Bip32DeterministicPrivateKey(epk))
+        Ok(Bip32DeterministicPrivateKey::from(epk))
+    }
+
+    fn extended_key(&self) -> ExtendedPrivKey {
+        ExtendedPrivKey {
+            network: Network::Bitcoin,
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            private_key: bitcoin::PrivateKey {
+                compressed: true,
+                network: Network::Bitcoin,
+                key: self.private_key.secret_key(),
+            },
+            chain_code: self.chain_code,
+        }
+    }
+}
+
+impl From<ExtendedPrivKey> for Bip32DeterministicPrivateKey {
+    fn from(epk: ExtendedPrivKey) -> Self {
+        Bip32DeterministicPrivateKey {
+            private_key: Secp256k1PrivateKey::from_secret_key(&epk.private_key.key, true),
+            depth: epk.depth,
+            parent_fingerprint: epk.parent_fingerprint,
+            child_number: epk.child_number,
+            chain_code: epk.chain_code,
+        }
     }
 }
 
 impl Derive for Bip32DeterministicPrivateKey {
     fn derive(&self, path: &str) -> Result<Self> {
-        let extended_key = self.0.clone();
+        let extended_key = self.extended_key();
 
         let mut parts = path.split('/').peekable();
         if *parts.peek().unwrap() == "m" {
@@ -63,7 +101,7 @@ impl Derive for Bip32DeterministicPrivateKey {
             .collect::<std::result::Result<Vec<ChildNumber>, Bip32Error>>()?;
         let child_key = extended_key.derive_priv(&SECP256K1_ENGINE, &children_nums)?;
 
-        Ok(Bip32DeterministicPrivateKey(child_key))
+        Ok(Bip32DeterministicPrivateKey::from(child_key))
     }
 }
 
@@ -91,7 +129,7 @@ impl DeterministicPrivateKey for Bip32DeterministicPrivateKey {
 
     fn from_seed(seed: &[u8]) -> Result<Self> {
         let esk = ExtendedPrivKey::new_master(Network::Bitcoin, seed)?;
-        Ok(Bip32DeterministicPrivateKey(esk))
+        Ok(Bip32DeterministicPrivateKey::from(esk))
     }
 
     fn from_mnemonic(mnemonic: &str) -> Result<Self> {
@@ -99,15 +137,15 @@ impl DeterministicPrivateKey for Bip32DeterministicPrivateKey {
         let seed = bip39::Seed::new(&mn, "");
         let esk = ExtendedPrivKey::new_master(Network::Bitcoin, seed.as_bytes())?;
 
-        Ok(Bip32DeterministicPrivateKey(esk))
+        Ok(Bip32DeterministicPrivateKey::from(esk))
     }
 
     fn private_key(&self) -> Self::PrivateKey {
-        Secp256k1PrivateKey::from(self.0.private_key.clone())
+        self.private_key.clone()
     }
 
     fn deterministic_public_key(&self) -> Self::DeterministicPublicKey {
-        let pk = ExtendedPubKey::from_private(&SECP256K1_ENGINE, &self.0);
+        let pk = ExtendedPubKey::from_private(&SECP256K1_ENGINE, &self.extended_key());
         Bip32DeterministicPublicKey(pk)
     }
 }
@@ -128,7 +166,7 @@ impl ToString for Bip32DeterministicPublicKey {
 
 impl ToString for Bip32DeterministicPrivateKey {
     fn to_string(&self) -> String {
-        self.0.to_string()
+        self.extended_key().to_string()
     }
 }
 
@@ -212,7 +250,7 @@ impl Ss58Codec for Bip32DeterministicPublicKey {
 
 impl Ss58Codec for Bip32DeterministicPrivateKey {
     fn from_ss58check_with_version(s: &str) -> Result<(Self, Vec<u8>)> {
-        let data = base58::from_check(s)?;
+        let data = Zeroizing::new(base58::from_check(s)?);
 
         if data.len() != 78 {
             return Err(InvalidLength(data.len()).into());
@@ -221,38 +259,32 @@ impl Ss58Codec for Bip32DeterministicPrivateKey {
         let cn_int: u32 = BigEndian::read_u32(&data[9..13]);
         let child_number: ChildNumber = ChildNumber::from(cn_int);
 
-        let network = Network::Bitcoin;
-        let epk = ExtendedPrivKey {
-            network,
+        let key = secp256k1::SecretKey::from_slice(&data[46..78])
+            .map_err(|e| base58::Error::Other(e.to_string()))?;
+        let esk = Bip32DeterministicPrivateKey {
+            private_key: Secp256k1PrivateKey::from_secret_key(&key, true),
             depth: data[4],
             parent_fingerprint: Fingerprint::from(&data[5..9]),
             child_number,
             chain_code: ChainCode::from(&data[13..45]),
-            private_key: bitcoin::PrivateKey {
-                compressed: true,
-                network,
-                key: secp256k1::SecretKey::from_slice(&data[46..78])
-                    .map_err(|e| base58::Error::Other(e.to_string()))?,
-            },
         };
         let mut network = [0; 4];
         network.copy_from_slice(&data[0..4]);
-        Ok((Bip32DeterministicPrivateKey(epk), network.to_vec()))
+        Ok((esk, network.to_vec()))
     }
 
     fn to_ss58check_with_version(&self, version: &[u8]) -> String {
-        let mut ret = [0; 78];
-        let extended_key = &self.0;
+        let mut ret = Zeroizing::new([0; 78]);
 
         ret[0..4].copy_from_slice(&version[..]);
-        ret[4] = extended_key.depth as u8;
-        ret[5..9].copy_from_slice(&extended_key.parent_fingerprint[..]);
+        ret[4] = self.depth as u8;
+        ret[5..9].copy_from_slice(&self.parent_fingerprint[..]);
 
-        BigEndian::write_u32(&mut ret[9..13], u32::from(extended_key.child_number));
+        BigEndian::write_u32(&mut ret[9..13], u32::from(self.child_number));
 
-        ret[13..45].copy_from_slice(&extended_key.chain_code[..]);
+        ret[13..45].copy_from_slice(&self.chain_code[..]);
         ret[45] = 0;
-        ret[46..78].copy_from_slice(&extended_key.private_key[..]);
+        ret[46..78].copy_from_slice(&Zeroizing::new(self.private_key.to_bytes()));
         base58::check_encode_slice(&ret[..])
     }
 }
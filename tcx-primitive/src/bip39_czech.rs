@@ -0,0 +1,156 @@
+//! BIP-39 mnemonic in the czech word list, which tiny-bip39 does not ship. The errors are the
+//! `bip39::ErrorKind` of tiny-bip39, so the callers handle both the same way
+
+use super::Result;
+
+use bip39::{ErrorKind, MnemonicType};
+use bitcoin_hashes::{sha256, Hash};
+use hmac::Hmac;
+use lazy_static::lazy_static;
+use pbkdf2::pbkdf2;
+use rand::{thread_rng, RngCore};
+use sha2::Sha512;
+use std::collections::HashMap;
+
+const BITS_PER_WORD: usize = 11;
+const SEED_ITERATION_COUNT: u32 = 2048;
+const SEED_LENGTH_BYTES: usize = 64;
+
+lazy_static! {
+    static ref WORDS: Vec<&'static str> =
+        include_str!("bip39_czech_wordlist.txt").lines().collect();
+    static ref WORD_INDEXES: HashMap<&'static str, usize> = WORDS
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| (*word, idx))
+        .collect();
+}
+
+/// Recover the entropy from the czech words separated by whitespaces, the checksum is verified
+pub fn czech_mnemonic_to_entropy(phrase: &str) -> Result<Vec<u8>> {
+    let indexes = phrase
+        .split_whitespace()
+        .map(|word| {
+            WORD_INDEXES
+                .get(word)
+                .copied()
+                .ok_or(ErrorKind::InvalidWord)
+        })
+        .collect::<std::result::Result<Vec<usize>, ErrorKind>>()?;
+    let mnemonic_type = MnemonicType::for_word_count(indexes.len())?;
+
+    let mut bits = vec![false; indexes.len() * BITS_PER_WORD];
+    for (i, idx) in indexes.iter().enumerate() {
+        for j in 0..BITS_PER_WORD {
+            bits[i * BITS_PER_WORD + j] = idx & (1 << (BITS_PER_WORD - 1 - j)) != 0;
+        }
+    }
+
+    let entropy = bits[..mnemonic_type.entropy_bits()]
+        .chunks(8)
+        .map(|byte| byte.iter().fold(0u8, |acc, bit| (acc << 1) | *bit as u8))
+        .collect::<Vec<u8>>();
+    if czech_mnemonic_from_entropy(&entropy)? != indexes_to_phrase(&indexes) {
+        return Err(ErrorKind::InvalidChecksum.into());
+    }
+
+    Ok(entropy)
+}
+
+/// The entropy is 16, 20, 24, 28 or 32 bytes, the words are separated by a single space
+pub fn czech_mnemonic_from_entropy(entropy: &[u8]) -> Result<String> {
+    let mnemonic_type = MnemonicType::for_key_size(entropy.len() * 8)?;
+    let checksum = sha256::Hash::hash(entropy);
+
+    let mut bits = entropy
+        .iter()
+        .flat_map(|byte| (0..8).rev().map(move |i| byte & (1 << i) != 0))
+        .collect::<Vec<bool>>();
+    bits.extend(
+        (0..mnemonic_type.checksum_bits() as usize)
+            .map(|i| checksum[i / 8] & (0x80 >> (i % 8)) != 0),
+    );
+
+    let indexes = bits
+        .chunks(BITS_PER_WORD)
+        .map(|word| {
+            word.iter()
+                .fold(0usize, |acc, bit| (acc << 1) | *bit as usize)
+        })
+        .collect::<Vec<usize>>();
+    Ok(indexes_to_phrase(&indexes))
+}
+
+/// The word count is one of 12, 15, 18, 21 and 24
+pub fn generate_czech_mnemonic(word_count: usize) -> Result<String> {
+    let mnemonic_type = MnemonicType::for_word_count(word_count)?;
+    let mut entropy = vec![0u8; mnemonic_type.entropy_bits() / 8];
+    thread_rng().fill_bytes(&mut entropy);
+    czech_mnemonic_from_entropy(&entropy)
+}
+
+/// The BIP-39 seed of any word list, both the phrase and the passphrase should be NFKD normalized
+pub fn bip39_seed(phrase: &str, passphrase: &str) -> Vec<u8> {
+    let salt = format!("mnemonic{}", passphrase);
+    let mut seed = vec![0u8; SEED_LENGTH_BYTES];
+    pbkdf2::<Hmac<Sha512>>(
+        phrase.as_bytes(),
+        salt.as_bytes(),
+        SEED_ITERATION_COUNT,
+        &mut seed,
+    );
+    seed
+}
+
+fn indexes_to_phrase(indexes: &[usize]) -> String {
+    indexes
+        .iter()
+        .map(|idx| WORDS[*idx])
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{czech_mnemonic_from_entropy, czech_mnemonic_to_entropy, generate_czech_mnemonic};
+
+    #[test]
+    fn czech_mnemonic_entropy() {
+        let vectors = vec![
+            (
+                "00000000000000000000000000000000",
+                "abdikace abdikace abdikace abdikace abdikace abdikace abdikace abdikace abdikace abdikace abdikace agrese",
+            ),
+            (
+                "9e885d952ad362caeb4efe34a8e91bd2",
+                "pokoj jogurt malovat kroupa holub malvice rachot uznat hnout kasa karamel potupa",
+            ),
+            (
+                "000102030405060708090a0b0c0d0e0f10111213",
+                "abdikace bidlo obvykle bidlo kopnout bachor doma doprovod bojovat lobista kachna dobytek amputace olizovat kongres",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zvyk zavolat",
+            ),
+        ];
+        for (entropy, mnemonic) in vectors {
+            let entropy = hex::decode(entropy).unwrap();
+            assert_eq!(czech_mnemonic_from_entropy(&entropy).unwrap(), mnemonic);
+            assert_eq!(czech_mnemonic_to_entropy(mnemonic).unwrap(), entropy);
+        }
+
+        let mnemonic = generate_czech_mnemonic(18).unwrap();
+        assert_eq!(czech_mnemonic_to_entropy(&mnemonic).unwrap().len(), 24);
+
+        let ret = czech_mnemonic_to_entropy(
+            "pokoj jogurt malovat kroupa holub malvice rachot uznat hnout kasa potupa karamel",
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid checksum");
+        let ret = czech_mnemonic_to_entropy(
+            "pokoj jogurt malovat kroupa holub malvice rachot uznat hnout kasa karamel inject",
+        );
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid word in phrase");
+        assert!(czech_mnemonic_from_entropy(&[0; 15]).is_err());
+    }
+}
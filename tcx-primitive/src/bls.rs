@@ -1,6 +1,6 @@
 use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPublicKey};
 use crate::Result;
-use bls_signatures::{PrivateKey, PublicKey, Serialize};
+use bls_signatures::{hash, verify, PrivateKey, PublicKey, Serialize, Signature};
 
 #[derive(Clone)]
 pub struct BLSPublicKey(PublicKey);
@@ -52,6 +52,13 @@ impl TraitPublicKey for BLSPublicKey {
     fn to_bytes(&self) -> Vec<u8> {
         self.0.as_bytes()
     }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match Signature::from_bytes(signature) {
+            Ok(signature) => verify(&signature, &[hash(data)], &[self.0]),
+            Err(_) => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -70,4 +77,19 @@ mod tests {
         assert_eq!(hex::encode(private_key.public_key().to_bytes()),
                    "b2be11dc8e54ee74dbc07569fd74fe03b5f52ad71cd49a8579b6c6387891f5a20ad980ec2747618c1b9ad35846a68a3e");
     }
+
+    #[test]
+    fn test_bls_verify() {
+        let private_key = BLSPrivateKey::from_slice(
+            &hex::decode("0ef71710671a9f1cfc4bd441c017c9b6db68491929facc68ab072a9676e9e23c")
+                .unwrap(),
+        )
+        .unwrap();
+        let public_key = private_key.public_key();
+
+        let signature = private_key.sign(b"imToken").unwrap();
+        assert!(public_key.verify(b"imToken", &signature));
+        assert!(!public_key.verify(b"TokenCoreX", &signature));
+        assert!(!public_key.verify(b"imToken", &signature[1..]));
+    }
 }
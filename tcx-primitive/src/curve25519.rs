@@ -0,0 +1,196 @@
+//! The X25519 keys for the key agreement, they could not sign
+//! ref:
+//! - https://tools.ietf.org/html/rfc7748
+//!
+//! The key is converted from the ed25519 key of the account
+
+use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPublicKey};
+use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use crate::{FromHex, Result, ToHex};
+
+use bitcoin_hashes::{sha512, Hash};
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use zeroize::Zeroize;
+
+/// The scalar multiplication of RFC 7748, the scalar is clamped
+fn x25519(scalar: &[u8; 32], point: &MontgomeryPoint) -> [u8; 32] {
+    let mut clamped = *scalar;
+    clamped[0] &= 248;
+    clamped[31] &= 127;
+    clamped[31] |= 64;
+    let ret = (point * &Scalar::from_bits(clamped)).to_bytes();
+    clamped.zeroize();
+    ret
+}
+
+pub struct Curve25519PrivateKey([u8; 32]);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve25519PublicKey([u8; 32]);
+
+impl Clone for Curve25519PrivateKey {
+    fn clone(&self) -> Self {
+        Curve25519PrivateKey(self.0)
+    }
+}
+
+impl Drop for Curve25519PrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Curve25519PrivateKey {
+    /// The lower half of sha-512 of the ed25519 seed, which is the scalar of the ed25519 key
+    pub fn from_ed25519(private_key: &Ed25519PrivateKey) -> Self {
+        let mut seed = private_key.to_bytes();
+        let hash = sha512::Hash::hash(&seed);
+        seed.zeroize();
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&hash[..32]);
+        Curve25519PrivateKey(key)
+    }
+
+    /// The shared secret with the public key of the other party
+    pub fn diffie_hellman(&self, public_key: &Curve25519PublicKey) -> Result<[u8; 32]> {
+        let shared = x25519(&self.0, &MontgomeryPoint(public_key.0));
+        // the public key of the small order makes the shared secret zero
+        if shared == [0u8; 32] {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+        Ok(shared)
+    }
+}
+
+impl TraitPrivateKey for Curve25519PrivateKey {
+    type PublicKey = Curve25519PublicKey;
+
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidPrivateKey.into());
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(data);
+        Ok(Curve25519PrivateKey(key))
+    }
+
+    fn public_key(&self) -> Self::PublicKey {
+        Curve25519PublicKey(x25519(&self.0, &X25519_BASEPOINT))
+    }
+
+    fn sign(&self, _: &[u8]) -> Result<Vec<u8>> {
+        Err(KeyError::NotImplement.into())
+    }
+
+    fn sign_recoverable(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.sign(data)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl Curve25519PublicKey {
+    /// The montgomery form of the ed25519 public key
+    pub fn from_ed25519(public_key: &Ed25519PublicKey) -> Result<Self> {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&public_key.to_bytes());
+        let point = CompressedEdwardsY(key)
+            .decompress()
+            .ok_or(KeyError::InvalidPublicKey)?;
+        Ok(Curve25519PublicKey(point.to_montgomery().to_bytes()))
+    }
+}
+
+impl TraitPublicKey for Curve25519PublicKey {
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(data);
+        Ok(Curve25519PublicKey(key))
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    fn verify(&self, _data: &[u8], _signature: &[u8]) -> bool {
+        false
+    }
+}
+
+impl ToHex for Curve25519PublicKey {
+    fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl FromHex for Curve25519PublicKey {
+    fn from_hex(hex: &str) -> Result<Self> {
+        let bytes = hex::decode(hex)?;
+        Curve25519PublicKey::from_slice(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Curve25519PrivateKey, Curve25519PublicKey};
+    use crate::ed25519::Ed25519PrivateKey;
+    use crate::{PrivateKey, PublicKey, ToHex};
+
+    #[test]
+    fn diffie_hellman() {
+        // RFC 7748 6.1
+        let alice = Curve25519PrivateKey::from_slice(
+            &hex::decode("77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a")
+                .unwrap(),
+        )
+        .unwrap();
+        let bob = Curve25519PrivateKey::from_slice(
+            &hex::decode("5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            alice.public_key().to_hex(),
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+        );
+        assert_eq!(
+            bob.public_key().to_hex(),
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"
+        );
+
+        let shared = alice.diffie_hellman(&bob.public_key()).unwrap();
+        assert_eq!(
+            hex::encode(shared),
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+        );
+        assert_eq!(bob.diffie_hellman(&alice.public_key()).unwrap(), shared);
+
+        let zero = Curve25519PublicKey::from_slice(&[0u8; 32]).unwrap();
+        assert!(alice.diffie_hellman(&zero).is_err());
+        assert!(alice.sign(&[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn from_ed25519() {
+        let ed25519_key = Ed25519PrivateKey::from_slice(
+            &hex::decode("5740dedadb610333de66ef2db2d91fd648fcbe419dff766f921ae97d536f94ce")
+                .unwrap(),
+        )
+        .unwrap();
+        let private_key = Curve25519PrivateKey::from_ed25519(&ed25519_key);
+        let public_key = Curve25519PublicKey::from_ed25519(&ed25519_key.public_key()).unwrap();
+        assert_eq!(private_key.public_key(), public_key);
+        assert_eq!(
+            public_key.to_hex(),
+            "7bb600b33ad4a2f72e6bcfcfb0b6a7b31a4d6e36a056cb11a1765df99e750340"
+        );
+    }
+}
@@ -6,10 +6,20 @@ use crate::{
 
 use crate::bls::{BLSPrivateKey, BLSPublicKey};
 use crate::bls_derive::BLSDeterministicPrivateKey;
+use crate::curve25519::{Curve25519PrivateKey, Curve25519PublicKey};
 use crate::ecc::TypedDeterministicPrivateKey::{Bip32Ed25519, Bip32Sepc256k1, SubSr25519};
 use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use crate::ed25519_bip32::{Ed25519DeterministicPrivateKey, Ed25519DeterministicPublicKey};
+use crate::ed25519_blake2b::{
+    Ed25519Blake2bDeterministicPrivateKey, Ed25519Blake2bDeterministicPublicKey,
+    Ed25519Blake2bPrivateKey, Ed25519Blake2bPublicKey,
+};
+use crate::nist256p1::{
+    Nist256p1DeterministicPrivateKey, Nist256p1DeterministicPublicKey, Nist256p1PrivateKey,
+    Nist256p1PublicKey,
+};
 use crate::sr25519::{Sr25519PrivateKey, Sr25519PublicKey};
+use bip39::{Language, Mnemonic, Seed};
 use sp_core::Pair;
 use tcx_constants::CurveType;
 
@@ -62,6 +72,10 @@ pub trait PublicKey: Sized {
     fn from_slice(data: &[u8]) -> Result<Self>;
 
     fn to_bytes(&self) -> Vec<u8>;
+
+    /// Check the signature of the data in the same format as the private key signs,
+    /// a malformed signature is invalid
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool;
 }
 
 pub trait PrivateKey: Sized {
@@ -106,6 +120,9 @@ pub enum TypedPrivateKey {
     Sr25519(Sr25519PrivateKey),
     Ed25519(Ed25519PrivateKey),
     BLS(BLSPrivateKey),
+    Nist256p1(Nist256p1PrivateKey),
+    Ed25519Blake2bNano(Ed25519Blake2bPrivateKey),
+    Curve25519(Curve25519PrivateKey),
 }
 
 impl TypedPrivateKey {
@@ -115,6 +132,9 @@ impl TypedPrivateKey {
             TypedPrivateKey::Sr25519(_) => CurveType::SubSr25519,
             TypedPrivateKey::Ed25519(_) => CurveType::ED25519,
             TypedPrivateKey::BLS(_) => CurveType::BLS,
+            TypedPrivateKey::Nist256p1(_) => CurveType::NIST256p1,
+            TypedPrivateKey::Ed25519Blake2bNano(_) => CurveType::ED25519Blake2bNano,
+            TypedPrivateKey::Curve25519(_) => CurveType::Curve25519,
         }
     }
 
@@ -130,6 +150,15 @@ impl TypedPrivateKey {
                 data,
             )?)),
             CurveType::BLS => Ok(TypedPrivateKey::BLS(BLSPrivateKey::from_slice(data)?)),
+            CurveType::NIST256p1 => Ok(TypedPrivateKey::Nist256p1(
+                Nist256p1PrivateKey::from_slice(data)?,
+            )),
+            CurveType::ED25519Blake2bNano => Ok(TypedPrivateKey::Ed25519Blake2bNano(
+                Ed25519Blake2bPrivateKey::from_slice(data)?,
+            )),
+            CurveType::Curve25519 => Ok(TypedPrivateKey::Curve25519(
+                Curve25519PrivateKey::from_slice(data)?,
+            )),
             _ => Err(KeyError::InvalidCurveType.into()),
         }
     }
@@ -147,6 +176,9 @@ impl TypedPrivateKey {
             TypedPrivateKey::Sr25519(sk) => sk.to_bytes(),
             TypedPrivateKey::Ed25519(sk) => sk.to_bytes(),
             TypedPrivateKey::BLS(sk) => sk.to_bytes(),
+            TypedPrivateKey::Nist256p1(sk) => sk.to_bytes(),
+            TypedPrivateKey::Ed25519Blake2bNano(sk) => sk.to_bytes(),
+            TypedPrivateKey::Curve25519(sk) => sk.to_bytes(),
         }
     }
 
@@ -156,6 +188,11 @@ impl TypedPrivateKey {
             TypedPrivateKey::Sr25519(sk) => TypedPublicKey::Sr25519(sk.public_key()),
             TypedPrivateKey::Ed25519(sk) => TypedPublicKey::Ed25519(sk.public_key()),
             TypedPrivateKey::BLS(sk) => TypedPublicKey::BLS(sk.public_key()),
+            TypedPrivateKey::Nist256p1(sk) => TypedPublicKey::Nist256p1(sk.public_key()),
+            TypedPrivateKey::Ed25519Blake2bNano(sk) => {
+                TypedPublicKey::Ed25519Blake2bNano(sk.public_key())
+            }
+            TypedPrivateKey::Curve25519(sk) => TypedPublicKey::Curve25519(sk.public_key()),
         }
     }
 
@@ -165,6 +202,9 @@ impl TypedPrivateKey {
             TypedPrivateKey::Sr25519(sk) => sk.sign(data),
             TypedPrivateKey::Ed25519(sk) => sk.sign(data),
             TypedPrivateKey::BLS(sk) => sk.sign(data),
+            TypedPrivateKey::Nist256p1(sk) => sk.sign(data),
+            TypedPrivateKey::Ed25519Blake2bNano(sk) => sk.sign(data),
+            TypedPrivateKey::Curve25519(sk) => sk.sign(data),
         }
     }
 
@@ -174,6 +214,9 @@ impl TypedPrivateKey {
             TypedPrivateKey::Sr25519(sk) => sk.sign_recoverable(data),
             TypedPrivateKey::Ed25519(sk) => sk.sign_recoverable(data),
             TypedPrivateKey::BLS(sk) => sk.sign_recoverable(data),
+            TypedPrivateKey::Nist256p1(sk) => sk.sign_recoverable(data),
+            TypedPrivateKey::Ed25519Blake2bNano(sk) => sk.sign_recoverable(data),
+            TypedPrivateKey::Curve25519(sk) => sk.sign_recoverable(data),
         }
     }
 }
@@ -183,6 +226,9 @@ pub enum TypedPublicKey {
     Sr25519(Sr25519PublicKey),
     Ed25519(Ed25519PublicKey),
     BLS(BLSPublicKey),
+    Nist256p1(Nist256p1PublicKey),
+    Ed25519Blake2bNano(Ed25519Blake2bPublicKey),
+    Curve25519(Curve25519PublicKey),
 }
 
 impl TypedPublicKey {
@@ -192,6 +238,9 @@ impl TypedPublicKey {
             TypedPublicKey::Sr25519(_) => CurveType::SubSr25519,
             TypedPublicKey::Ed25519(_) => CurveType::ED25519,
             TypedPublicKey::BLS(_) => CurveType::BLS,
+            TypedPublicKey::Nist256p1(_) => CurveType::NIST256p1,
+            TypedPublicKey::Ed25519Blake2bNano(_) => CurveType::ED25519Blake2bNano,
+            TypedPublicKey::Curve25519(_) => CurveType::Curve25519,
         }
     }
 
@@ -205,6 +254,15 @@ impl TypedPublicKey {
             }
             CurveType::ED25519 => Ok(TypedPublicKey::Ed25519(Ed25519PublicKey::from_slice(data)?)),
             CurveType::BLS => Ok(TypedPublicKey::BLS(BLSPublicKey::from_slice(data)?)),
+            CurveType::NIST256p1 => Ok(TypedPublicKey::Nist256p1(Nist256p1PublicKey::from_slice(
+                data,
+            )?)),
+            CurveType::ED25519Blake2bNano => Ok(TypedPublicKey::Ed25519Blake2bNano(
+                Ed25519Blake2bPublicKey::from_slice(data)?,
+            )),
+            CurveType::Curve25519 => Ok(TypedPublicKey::Curve25519(
+                Curve25519PublicKey::from_slice(data)?,
+            )),
 
             _ => Err(KeyError::InvalidCurveType.into()),
         }
@@ -216,6 +274,21 @@ impl TypedPublicKey {
             TypedPublicKey::Sr25519(pk) => pk.to_bytes(),
             TypedPublicKey::Ed25519(pk) => pk.to_bytes(),
             TypedPublicKey::BLS(pk) => pk.to_bytes(),
+            TypedPublicKey::Nist256p1(pk) => pk.to_bytes(),
+            TypedPublicKey::Ed25519Blake2bNano(pk) => pk.to_bytes(),
+            TypedPublicKey::Curve25519(pk) => pk.to_bytes(),
+        }
+    }
+
+    pub fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match self {
+            TypedPublicKey::Secp256k1(pk) => pk.verify(data, signature),
+            TypedPublicKey::Sr25519(pk) => pk.verify(data, signature),
+            TypedPublicKey::Ed25519(pk) => pk.verify(data, signature),
+            TypedPublicKey::BLS(pk) => pk.verify(data, signature),
+            TypedPublicKey::Nist256p1(pk) => pk.verify(data, signature),
+            TypedPublicKey::Ed25519Blake2bNano(pk) => pk.verify(data, signature),
+            TypedPublicKey::Curve25519(pk) => pk.verify(data, signature),
         }
     }
 
@@ -231,6 +304,8 @@ pub enum TypedDeterministicPublicKey {
     Bip32Sepc256k1(Bip32DeterministicPublicKey),
     SubSr25519(Sr25519PublicKey), //    SubstrateSr25519()
     Bip32Ed25519(Ed25519DeterministicPublicKey),
+    Bip32Nist256p1(Nist256p1DeterministicPublicKey),
+    Bip32Ed25519Blake2bNano(Ed25519Blake2bDeterministicPublicKey),
 }
 
 impl TypedDeterministicPublicKey {
@@ -239,6 +314,10 @@ impl TypedDeterministicPublicKey {
             TypedDeterministicPublicKey::Bip32Sepc256k1(_) => CurveType::SECP256k1,
             TypedDeterministicPublicKey::SubSr25519(_) => CurveType::SubSr25519,
             TypedDeterministicPublicKey::Bip32Ed25519(_) => CurveType::ED25519,
+            TypedDeterministicPublicKey::Bip32Nist256p1(_) => CurveType::NIST256p1,
+            TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(_) => {
+                CurveType::ED25519Blake2bNano
+            }
         }
     }
 
@@ -253,6 +332,12 @@ impl TypedDeterministicPublicKey {
             TypedDeterministicPublicKey::Bip32Ed25519(epk) => {
                 TypedPublicKey::Ed25519(epk.public_key())
             }
+            TypedDeterministicPublicKey::Bip32Nist256p1(epk) => {
+                TypedPublicKey::Nist256p1(epk.public_key())
+            }
+            TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(epk) => {
+                TypedPublicKey::Ed25519Blake2bNano(epk.public_key())
+            }
         }
     }
 }
@@ -263,6 +348,8 @@ impl ToString for TypedDeterministicPublicKey {
             TypedDeterministicPublicKey::Bip32Sepc256k1(epk) => epk.to_string(),
             TypedDeterministicPublicKey::SubSr25519(epk) => epk.to_string(),
             TypedDeterministicPublicKey::Bip32Ed25519(epk) => epk.to_string(),
+            TypedDeterministicPublicKey::Bip32Nist256p1(epk) => epk.to_string(),
+            TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(epk) => epk.to_string(),
         }
     }
 }
@@ -279,6 +366,12 @@ impl Derive for TypedDeterministicPublicKey {
             TypedDeterministicPublicKey::Bip32Ed25519(epk) => {
                 Ok(TypedDeterministicPublicKey::Bip32Ed25519(epk.derive(path)?))
             }
+            TypedDeterministicPublicKey::Bip32Nist256p1(epk) => Ok(
+                TypedDeterministicPublicKey::Bip32Nist256p1(epk.derive(path)?),
+            ),
+            TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(epk) => Ok(
+                TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(epk.derive(path)?),
+            ),
         }
     }
 }
@@ -288,6 +381,8 @@ pub enum TypedDeterministicPrivateKey {
     SubSr25519(Sr25519PrivateKey),
     Bip32Ed25519(Ed25519DeterministicPrivateKey),
     BLS(BLSDeterministicPrivateKey),
+    Bip32Nist256p1(Nist256p1DeterministicPrivateKey),
+    Bip32Ed25519Blake2bNano(Ed25519Blake2bDeterministicPrivateKey),
 }
 
 impl TypedDeterministicPrivateKey {
@@ -297,24 +392,82 @@ impl TypedDeterministicPrivateKey {
             TypedDeterministicPrivateKey::SubSr25519(_) => CurveType::SubSr25519,
             TypedDeterministicPrivateKey::Bip32Ed25519(_) => CurveType::ED25519,
             TypedDeterministicPrivateKey::BLS(_) => CurveType::BLS,
+            TypedDeterministicPrivateKey::Bip32Nist256p1(_) => CurveType::NIST256p1,
+            TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(_) => {
+                CurveType::ED25519Blake2bNano
+            }
         }
     }
 
     pub fn from_mnemonic(
         curve_type: CurveType,
         mnemonic: &str,
+    ) -> Result<TypedDeterministicPrivateKey> {
+        Self::from_mnemonic_with_passphrase(curve_type, mnemonic, "")
+    }
+
+    /// The passphrase is the optional "25th word" of BIP-39, sr25519 takes it as the password of
+    /// the mini secret key like substrate does
+    pub fn from_mnemonic_with_passphrase(
+        curve_type: CurveType,
+        mnemonic: &str,
+        passphrase: &str,
+    ) -> Result<TypedDeterministicPrivateKey> {
+        Self::from_mnemonic_in_language(curve_type, mnemonic, Language::English, passphrase)
+    }
+
+    /// The mnemonic phrase in the word list of the language
+    pub fn from_mnemonic_in_language(
+        curve_type: CurveType,
+        mnemonic: &str,
+        language: Language,
+        passphrase: &str,
+    ) -> Result<TypedDeterministicPrivateKey> {
+        let mn = Mnemonic::from_phrase(mnemonic, language)?;
+        Self::from_bip39_mnemonic(curve_type, &mn, passphrase)
+    }
+
+    /// The mnemonic could be in any language of the BIP-39 word lists
+    pub fn from_bip39_mnemonic(
+        curve_type: CurveType,
+        mnemonic: &Mnemonic,
+        passphrase: &str,
+    ) -> Result<TypedDeterministicPrivateKey> {
+        if curve_type == CurveType::SubSr25519 {
+            return Ok(SubSr25519(Sr25519PrivateKey::from_entropy(
+                mnemonic.entropy(),
+                passphrase,
+            )));
+        }
+
+        let seed = Seed::new(mnemonic, passphrase);
+        Self::from_bip39_seed(curve_type, seed.as_bytes())
+    }
+
+    /// The master key of the curve from the BIP-39 seed, sr25519 is not derived from the seed so
+    /// it must be taken from the mnemonic
+    pub fn from_bip39_seed(
+        curve_type: CurveType,
+        seed: &[u8],
     ) -> Result<TypedDeterministicPrivateKey> {
         match curve_type {
             CurveType::SECP256k1 => Ok(TypedDeterministicPrivateKey::Bip32Sepc256k1(
-                Bip32DeterministicPrivateKey::from_mnemonic(mnemonic)?,
+                Bip32DeterministicPrivateKey::from_seed(seed)?,
             )),
-            CurveType::SubSr25519 => Ok(SubSr25519(Sr25519PrivateKey::from_mnemonic(mnemonic)?)),
-            CurveType::ED25519 => Ok(Bip32Ed25519(Ed25519DeterministicPrivateKey::from_mnemonic(
-                mnemonic,
+            CurveType::ED25519 => Ok(Bip32Ed25519(Ed25519DeterministicPrivateKey::from_seed(
+                seed,
             )?)),
             CurveType::BLS => Ok(TypedDeterministicPrivateKey::BLS(
-                BLSDeterministicPrivateKey::from_mnemonic(mnemonic)?,
+                BLSDeterministicPrivateKey::from_seed(seed)?,
             )),
+            CurveType::NIST256p1 => Ok(TypedDeterministicPrivateKey::Bip32Nist256p1(
+                Nist256p1DeterministicPrivateKey::from_seed(seed)?,
+            )),
+            CurveType::ED25519Blake2bNano => {
+                Ok(TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(
+                    Ed25519Blake2bDeterministicPrivateKey::from_seed(seed)?,
+                ))
+            }
             _ => Err(KeyError::InvalidCurveType.into()),
         }
     }
@@ -331,6 +484,12 @@ impl TypedDeterministicPrivateKey {
                 TypedPrivateKey::Ed25519(dsk.private_key())
             }
             TypedDeterministicPrivateKey::BLS(dsk) => TypedPrivateKey::BLS(dsk.private_key()),
+            TypedDeterministicPrivateKey::Bip32Nist256p1(dsk) => {
+                TypedPrivateKey::Nist256p1(dsk.private_key())
+            }
+            TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(dsk) => {
+                TypedPrivateKey::Ed25519Blake2bNano(dsk.private_key())
+            }
         }
     }
 
@@ -346,6 +505,12 @@ impl TypedDeterministicPrivateKey {
                 TypedDeterministicPublicKey::Bip32Ed25519(sk.deterministic_public_key())
             }
             TypedDeterministicPrivateKey::BLS(_) => panic!("not support"),
+            TypedDeterministicPrivateKey::Bip32Nist256p1(sk) => {
+                TypedDeterministicPublicKey::Bip32Nist256p1(sk.deterministic_public_key())
+            }
+            TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(sk) => {
+                TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(sk.deterministic_public_key())
+            }
         }
     }
 }
@@ -357,6 +522,8 @@ impl ToString for TypedDeterministicPrivateKey {
             TypedDeterministicPrivateKey::SubSr25519(sk) => hex::encode(sk.0.to_raw_vec()),
             TypedDeterministicPrivateKey::Bip32Ed25519(sk) => sk.to_string(),
             TypedDeterministicPrivateKey::BLS(sk) => hex::encode(sk.0.to_string()),
+            TypedDeterministicPrivateKey::Bip32Nist256p1(sk) => sk.to_string(),
+            TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(sk) => sk.to_string(),
         }
     }
 }
@@ -373,6 +540,14 @@ impl TypedDeterministicPublicKey {
             CurveType::ED25519 => Ok(TypedDeterministicPublicKey::Bip32Ed25519(
                 Ed25519DeterministicPublicKey::from_hex(hex)?,
             )),
+            CurveType::NIST256p1 => Ok(TypedDeterministicPublicKey::Bip32Nist256p1(
+                Nist256p1DeterministicPublicKey::from_hex(hex)?,
+            )),
+            CurveType::ED25519Blake2bNano => {
+                Ok(TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(
+                    Ed25519Blake2bDeterministicPublicKey::from_hex(hex)?,
+                ))
+            }
             _ => Err(KeyError::InvalidCurveType.into()),
         }
     }
@@ -384,6 +559,8 @@ impl ToHex for TypedDeterministicPublicKey {
             TypedDeterministicPublicKey::Bip32Sepc256k1(epk) => epk.to_hex(),
             TypedDeterministicPublicKey::SubSr25519(epk) => epk.to_hex(),
             TypedDeterministicPublicKey::Bip32Ed25519(epk) => epk.to_hex(),
+            TypedDeterministicPublicKey::Bip32Nist256p1(epk) => epk.to_hex(),
+            TypedDeterministicPublicKey::Bip32Ed25519Blake2bNano(epk) => epk.to_hex(),
         }
     }
 }
@@ -403,14 +580,23 @@ impl Derive for TypedDeterministicPrivateKey {
             TypedDeterministicPrivateKey::BLS(dsk) => {
                 Ok(TypedDeterministicPrivateKey::BLS(dsk.derive(path)?))
             }
+            TypedDeterministicPrivateKey::Bip32Nist256p1(dsk) => Ok(
+                TypedDeterministicPrivateKey::Bip32Nist256p1(dsk.derive(path)?),
+            ),
+            TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(dsk) => Ok(
+                TypedDeterministicPrivateKey::Bip32Ed25519Blake2bNano(dsk.derive(path)?),
+            ),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{PrivateKey, PublicKey, TypedDeterministicPrivateKey, TypedPrivateKey};
-    use crate::{Derive, TypedPublicKey};
+    use super::{
+        PrivateKey, PublicKey, TypedDeterministicPrivateKey, TypedDeterministicPublicKey,
+        TypedPrivateKey,
+    };
+    use crate::{Derive, ToHex, TypedPublicKey};
     use bip39::{Language, Mnemonic, Seed};
 
     use tcx_constants::{CurveType, TEST_MNEMONIC};
@@ -474,6 +660,160 @@ mod tests {
         assert_eq!(dsk.to_string(), "xprv9yrdwPSRnvomqFK4u1y5uW2SaXS2Vnr3pAYTjJjbyRZR8p9BwoadRsCxtgUFdAKeRPbwvGRcCSYMV69nNK4N2kadevJ6L5iQVy1SwGKDTHQ");
     }
 
+    #[test]
+    fn typed_deterministic_private_key_with_passphrase() {
+        let root = TypedDeterministicPrivateKey::from_mnemonic_with_passphrase(
+            CurveType::SECP256k1,
+            &TEST_MNEMONIC,
+            "TREZOR",
+        )
+        .unwrap();
+        let dpk = root
+            .derive("m/44'/0'/0'")
+            .unwrap()
+            .deterministic_public_key();
+        assert_eq!(dpk.to_string(), "xpub6CK1PT6hjgSBTdU9EEkkss6Sz6PbHk4oo6EQ3MzRHkpxJVbacB7gqAyRwHEg7i7gYuQbucxFrdUiacbSBYDoMyGiwUTu2TS6U7YNBHx2udK");
+
+        let root = TypedDeterministicPrivateKey::from_mnemonic_with_passphrase(
+            CurveType::SECP256k1,
+            &TEST_MNEMONIC,
+            "",
+        )
+        .unwrap();
+        let dpk = root
+            .derive("m/44'/0'/0'")
+            .unwrap()
+            .deterministic_public_key();
+        assert_eq!(dpk.to_string(), "xpub6CqzLtyKdJN53jPY13W6GdyB8ZGWuFZuBPU4Xh9DXm6Q1cULVLtsyfXSjx4G77rNdCRBgi83LByaWxjtDaZfLAKT6vFUq3EhPtNwTpJigx8");
+
+        let sr25519_key = |passphrase: &str| {
+            TypedDeterministicPrivateKey::from_mnemonic_with_passphrase(
+                CurveType::SubSr25519,
+                &TEST_MNEMONIC,
+                passphrase,
+            )
+            .unwrap()
+            .private_key()
+            .public_key()
+            .to_bytes()
+        };
+        assert_ne!(sr25519_key("TREZOR"), sr25519_key(""));
+        assert_eq!(
+            hex::encode(sr25519_key("")),
+            "fc581c897af481b10cf846d88754f1d115e486e5b7bcc39c0588c01b0a9b7a11"
+        );
+    }
+
+    #[test]
+    fn typed_deterministic_private_key_of_nist256p1() {
+        let root =
+            TypedDeterministicPrivateKey::from_mnemonic(CurveType::NIST256p1, &TEST_MNEMONIC)
+                .unwrap();
+        assert_eq!(root.curve_type(), CurveType::NIST256p1);
+
+        let dsk = root.derive("m/44'/1729'/0'").unwrap();
+        let dpk = dsk.deterministic_public_key();
+        assert_eq!(dpk.curve_type(), CurveType::NIST256p1);
+        assert_eq!(
+            dpk.derive("0/0").unwrap().public_key().to_bytes(),
+            dsk.derive("0/0")
+                .unwrap()
+                .private_key()
+                .public_key()
+                .to_bytes()
+        );
+
+        let restored =
+            TypedDeterministicPublicKey::from_hex(CurveType::NIST256p1, &dpk.to_hex()).unwrap();
+        assert_eq!(restored.to_hex(), dpk.to_hex());
+
+        let sk = dsk.private_key();
+        assert_eq!(sk.curve_type(), CurveType::NIST256p1);
+        assert_eq!(
+            TypedPrivateKey::from_slice(CurveType::NIST256p1, &sk.to_bytes())
+                .unwrap()
+                .public_key()
+                .to_bytes(),
+            sk.public_key().to_bytes()
+        );
+    }
+
+    #[test]
+    fn typed_deterministic_private_key_in_language() {
+        // the same entropy as TEST_MNEMONIC in the simplified chinese word list
+        let mnemonic = "景 沿 兵 门 燥 芳 段 晒 团 染 晒 爬";
+        let public_key = |passphrase: &str| {
+            TypedDeterministicPrivateKey::from_mnemonic_in_language(
+                CurveType::NIST256p1,
+                mnemonic,
+                Language::ChineseSimplified,
+                passphrase,
+            )
+            .unwrap()
+            .derive("m/44'/1729'/0'")
+            .unwrap()
+            .deterministic_public_key()
+            .to_hex()
+        };
+
+        let mn = Mnemonic::from_phrase(mnemonic, Language::ChineseSimplified).unwrap();
+        let expected = |passphrase: &str| {
+            TypedDeterministicPrivateKey::from_bip39_seed(
+                CurveType::NIST256p1,
+                Seed::new(&mn, passphrase).as_bytes(),
+            )
+            .unwrap()
+            .derive("m/44'/1729'/0'")
+            .unwrap()
+            .deterministic_public_key()
+            .to_hex()
+        };
+        assert_eq!(public_key("TREZOR"), expected("TREZOR"));
+        assert_eq!(public_key(""), expected(""));
+        assert_ne!(public_key("TREZOR"), public_key(""));
+
+        let english =
+            TypedDeterministicPrivateKey::from_mnemonic(CurveType::NIST256p1, &TEST_MNEMONIC)
+                .unwrap()
+                .derive("m/44'/1729'/0'")
+                .unwrap()
+                .deterministic_public_key()
+                .to_hex();
+        assert_ne!(public_key(""), english);
+        assert!(
+            TypedDeterministicPrivateKey::from_mnemonic(CurveType::NIST256p1, mnemonic).is_err()
+        );
+    }
+
+    #[test]
+    fn typed_deterministic_private_key_of_ed25519_blake2b_nano() {
+        let root = TypedDeterministicPrivateKey::from_mnemonic(
+            CurveType::ED25519Blake2bNano,
+            &TEST_MNEMONIC,
+        )
+        .unwrap();
+        assert_eq!(root.curve_type(), CurveType::ED25519Blake2bNano);
+
+        let dsk = root.derive("m/44'/165'/0'").unwrap();
+        assert_eq!(
+            dsk.to_string(),
+            "7b03249bdab43d3b6566590fb682255d23448d83683c0194def82dd8eebf8a14"
+        );
+        let dpk = dsk.deterministic_public_key();
+        assert_eq!(dpk.curve_type(), CurveType::ED25519Blake2bNano);
+        assert_eq!(
+            dpk.to_hex(),
+            "84b5038639ee9c79da1b433c9112e349078487ed76409914874fad20e2c38f8a"
+        );
+        assert!(dpk.derive("0").is_err());
+        assert!(root.derive("m/44'/165'/0").is_err());
+
+        let sk = dsk.private_key();
+        assert_eq!(sk.curve_type(), CurveType::ED25519Blake2bNano);
+        let signature = sk.sign(&[1u8; 32]).unwrap();
+        assert!(sk.public_key().verify(&[1u8; 32], &signature));
+    }
+
     #[test]
     fn test_typed_public_key() {
         let pub_key = hex::decode(PUB_KEY_HEX).unwrap();
@@ -489,4 +829,23 @@ mod tests {
         );
         assert_eq!(pk.curve_type(), CurveType::SECP256k1);
     }
+
+    #[test]
+    fn test_typed_public_key_verify() {
+        for curve in &[
+            CurveType::SECP256k1,
+            CurveType::ED25519,
+            CurveType::NIST256p1,
+        ] {
+            let sk = TypedPrivateKey::from_slice(*curve, &default_private_key()).unwrap();
+            let pk = sk.public_key();
+            let signature = sk.sign(&default_private_key()).unwrap();
+            assert!(pk.verify(&default_private_key(), &signature));
+            assert!(!pk.verify(&[0u8; 32], &signature));
+        }
+
+        let sk = TypedPrivateKey::from_slice(CurveType::SECP256k1, &default_private_key()).unwrap();
+        let signature = sk.sign_recoverable(&default_private_key()).unwrap();
+        assert!(sk.public_key().verify(&default_private_key(), &signature));
+    }
 }
@@ -1,6 +1,6 @@
 use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPublicKey};
 use crate::{FromHex, Result, ToHex};
-use sp_core::ed25519::{Pair, Public};
+use sp_core::ed25519::{Pair, Public, Signature};
 use sp_core::{Pair as TraitPair, Public as TraitPublic};
 
 #[derive(Clone)]
@@ -63,6 +63,13 @@ impl TraitPublicKey for Ed25519PublicKey {
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_vec()
     }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != 64 {
+            return false;
+        }
+        Pair::verify(&Signature::from_slice(signature), data, &self.0)
+    }
 }
 
 impl ToHex for Ed25519PublicKey {
@@ -126,7 +133,12 @@ mod test {
         let sign_result = sk.sign(&genericHash.as_bytes()).unwrap();
         //        println!("sign result ： {}", hex::encode(sign_result));
         let expected_val = "eaab7f4066217b072b79609a9f76cdfadd93f8dde41763887e131c02324f18c8e41b1009e334baf87f9d2e917bf4c0e73165622e5522409a0c5817234a48cc02";
-        assert_eq!(hex::encode(sign_result), expected_val);
+        assert_eq!(hex::encode(&sign_result), expected_val);
+
+        let public_key = sk.public_key();
+        assert!(public_key.verify(&genericHash.as_bytes(), &sign_result));
+        assert!(!public_key.verify(&msg, &sign_result));
+        assert!(!public_key.verify(&genericHash.as_bytes(), &sign_result[1..]));
     }
 
     #[test]
@@ -0,0 +1,320 @@
+//! The ed25519 keys of Nano, the signature is ed25519 with blake2b-512 in place of sha-512
+//! ref:
+//! - https://docs.nano.org/integration-guides/the-basics/#account-private-key
+//! - https://github.com/nanocurrency/nano-node/blob/develop/nano/crypto/ed25519-donna/ed25519-hash-custom.h
+//!
+//! The private key is the index key of the legacy 32 bytes Nano seed, or the SLIP-10 ed25519 key
+//! of the BIP-44 path `m/44'/165'/index'`
+
+use crate::ecc::{
+    DeterministicPrivateKey, DeterministicPublicKey, KeyError, PrivateKey as TraitPrivateKey,
+    PublicKey as TraitPublicKey,
+};
+use crate::ed25519_bip32::Ed25519DeterministicPrivateKey;
+use crate::{Derive, FromHex, Result, ToHex};
+
+use blake2b_simd::Params;
+use curve25519_dalek::constants::ED25519_BASEPOINT_TABLE;
+use curve25519_dalek::edwards::{CompressedEdwardsY, EdwardsPoint};
+use curve25519_dalek::scalar::Scalar;
+use zeroize::Zeroize;
+
+fn blake2b_512(parts: &[&[u8]]) -> [u8; 64] {
+    let mut state = Params::new().hash_length(64).to_state();
+    for part in parts {
+        state.update(part);
+    }
+    let mut hash = [0u8; 64];
+    hash.copy_from_slice(state.finalize().as_bytes());
+    hash
+}
+
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&blake2b_512(parts))
+}
+
+pub struct Ed25519Blake2bPrivateKey([u8; 32]);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ed25519Blake2bPublicKey([u8; 32]);
+
+impl Clone for Ed25519Blake2bPrivateKey {
+    fn clone(&self) -> Self {
+        Ed25519Blake2bPrivateKey(self.0)
+    }
+}
+
+impl Drop for Ed25519Blake2bPrivateKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Ed25519Blake2bPrivateKey {
+    /// The key of the index in the legacy Nano seed, `blake2b-256(seed || index)`
+    pub fn from_seed_index(seed: &[u8], index: u32) -> Result<Self> {
+        if seed.len() != 32 {
+            return Err(KeyError::InvalidPrivateKey.into());
+        }
+        let hash = Params::new()
+            .hash_length(32)
+            .to_state()
+            .update(seed)
+            .update(&index.to_be_bytes())
+            .finalize();
+        Self::from_slice(hash.as_bytes())
+    }
+
+    /// The clamped scalar and the nonce prefix, which are the halves of blake2b-512 of the key
+    fn expand(&self) -> (Scalar, [u8; 32]) {
+        let mut hash = blake2b_512(&[&self.0]);
+        hash[0] &= 248;
+        hash[31] &= 127;
+        hash[31] |= 64;
+
+        let mut lower = [0u8; 32];
+        lower.copy_from_slice(&hash[..32]);
+        let mut prefix = [0u8; 32];
+        prefix.copy_from_slice(&hash[32..]);
+        // the point is of the prime order, so the scalar could be reduced
+        let scalar = Scalar::from_bytes_mod_order(lower);
+        lower.zeroize();
+        hash.zeroize();
+        (scalar, prefix)
+    }
+}
+
+impl TraitPrivateKey for Ed25519Blake2bPrivateKey {
+    type PublicKey = Ed25519Blake2bPublicKey;
+
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidEd25519Key.into());
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(data);
+        Ok(Ed25519Blake2bPrivateKey(key))
+    }
+
+    fn public_key(&self) -> Self::PublicKey {
+        let (scalar, mut prefix) = self.expand();
+        prefix.zeroize();
+        Ed25519Blake2bPublicKey((&ED25519_BASEPOINT_TABLE * &scalar).compress().to_bytes())
+    }
+
+    /// `R || S`, the data is signed as the message, Nano signs the block hash
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (scalar, mut prefix) = self.expand();
+        let public_key = (&ED25519_BASEPOINT_TABLE * &scalar).compress();
+
+        let r = hash_to_scalar(&[&prefix, data]);
+        prefix.zeroize();
+        let big_r = (&ED25519_BASEPOINT_TABLE * &r).compress();
+        let k = hash_to_scalar(&[big_r.as_bytes(), public_key.as_bytes(), data]);
+        let s = k * scalar + r;
+
+        let mut signature = big_r.to_bytes().to_vec();
+        signature.extend_from_slice(s.as_bytes());
+        Ok(signature)
+    }
+
+    fn sign_recoverable(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.sign(data)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+}
+
+impl Ed25519Blake2bPublicKey {
+    fn point(&self) -> Option<EdwardsPoint> {
+        CompressedEdwardsY(self.0).decompress()
+    }
+}
+
+impl TraitPublicKey for Ed25519Blake2bPublicKey {
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+        let mut key = [0u8; 32];
+        key.copy_from_slice(data);
+        let public_key = Ed25519Blake2bPublicKey(key);
+        if public_key.point().is_none() {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+        Ok(public_key)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_vec()
+    }
+
+    /// S * B = R + k * A
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != 64 {
+            return false;
+        }
+        let point = match self.point() {
+            Some(point) => point,
+            None => return false,
+        };
+        let mut s = [0u8; 32];
+        s.copy_from_slice(&signature[32..]);
+        let s = match Scalar::from_canonical_bytes(s) {
+            Some(s) => s,
+            None => return false,
+        };
+
+        let k = hash_to_scalar(&[&signature[..32], &self.0, data]);
+        let big_r = EdwardsPoint::vartime_double_scalar_mul_basepoint(&k, &(-point), &s);
+        big_r.compress().as_bytes() == &signature[..32]
+    }
+}
+
+impl ToHex for Ed25519Blake2bPublicKey {
+    fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+}
+
+impl FromHex for Ed25519Blake2bPublicKey {
+    fn from_hex(hex: &str) -> Result<Self> {
+        let bytes = hex::decode(hex)?;
+        Ed25519Blake2bPublicKey::from_slice(&bytes)
+    }
+}
+
+/// The SLIP-10 ed25519 derivation, only the key is taken as the blake2b one
+pub struct Ed25519Blake2bDeterministicPrivateKey(Ed25519DeterministicPrivateKey);
+
+pub struct Ed25519Blake2bDeterministicPublicKey(Ed25519Blake2bPublicKey);
+
+impl Derive for Ed25519Blake2bDeterministicPrivateKey {
+    fn derive(&self, path: &str) -> Result<Self> {
+        Ok(Ed25519Blake2bDeterministicPrivateKey(self.0.derive(path)?))
+    }
+}
+
+impl Derive for Ed25519Blake2bDeterministicPublicKey {
+    fn derive(&self, _path: &str) -> Result<Self> {
+        Err(KeyError::UnsupportEd25519PubkeyDerivation.into())
+    }
+}
+
+impl DeterministicPrivateKey for Ed25519Blake2bDeterministicPrivateKey {
+    type DeterministicPublicKey = Ed25519Blake2bDeterministicPublicKey;
+    type PrivateKey = Ed25519Blake2bPrivateKey;
+
+    fn from_seed(seed: &[u8]) -> Result<Self> {
+        Ok(Ed25519Blake2bDeterministicPrivateKey(
+            Ed25519DeterministicPrivateKey::from_seed(seed)?,
+        ))
+    }
+
+    fn from_mnemonic(mnemonic: &str) -> Result<Self> {
+        Ok(Ed25519Blake2bDeterministicPrivateKey(
+            Ed25519DeterministicPrivateKey::from_mnemonic(mnemonic)?,
+        ))
+    }
+
+    fn private_key(&self) -> Self::PrivateKey {
+        Ed25519Blake2bPrivateKey::from_slice(&self.0.private_key().to_bytes())
+            .expect("the ed25519 key is 32 bytes")
+    }
+
+    fn deterministic_public_key(&self) -> Self::DeterministicPublicKey {
+        Ed25519Blake2bDeterministicPublicKey(self.private_key().public_key())
+    }
+}
+
+impl DeterministicPublicKey for Ed25519Blake2bDeterministicPublicKey {
+    type PublicKey = Ed25519Blake2bPublicKey;
+
+    fn public_key(&self) -> Self::PublicKey {
+        self.0.clone()
+    }
+}
+
+impl ToString for Ed25519Blake2bDeterministicPrivateKey {
+    fn to_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+impl ToString for Ed25519Blake2bDeterministicPublicKey {
+    fn to_string(&self) -> String {
+        self.0.to_hex()
+    }
+}
+
+impl ToHex for Ed25519Blake2bDeterministicPublicKey {
+    fn to_hex(&self) -> String {
+        self.0.to_hex()
+    }
+}
+
+impl FromHex for Ed25519Blake2bDeterministicPublicKey {
+    fn from_hex(hex: &str) -> Result<Self> {
+        Ok(Ed25519Blake2bDeterministicPublicKey(
+            Ed25519Blake2bPublicKey::from_hex(hex)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ed25519Blake2bDeterministicPrivateKey, Ed25519Blake2bPrivateKey};
+    use crate::{Derive, DeterministicPrivateKey, PrivateKey, PublicKey, ToHex};
+    use bip39::{Language, Mnemonic, Seed};
+
+    #[test]
+    fn from_seed_index() {
+        let sk = Ed25519Blake2bPrivateKey::from_seed_index(&[0u8; 32], 0).unwrap();
+        assert_eq!(
+            hex::encode(sk.to_bytes()),
+            "9f0e444c69f77a49bd0be89db92c38fe713e0963165cca12faf5712d7657120f"
+        );
+        assert_eq!(
+            sk.public_key().to_hex(),
+            "c008b814a7d269a1fa3c6528b19201a24d797912db9996ff02a1ff356e45552b"
+        );
+
+        assert!(Ed25519Blake2bPrivateKey::from_seed_index(&[0u8; 31], 0).is_err());
+    }
+
+    #[test]
+    fn derive_bip44() {
+        let mnemonic = Mnemonic::from_phrase("edge defense waste choose enrich upon flee junk siren film clown finish luggage leader kid quick brick print evidence swap drill paddle truly occur", Language::English).unwrap();
+        let seed = Seed::new(&mnemonic, "some password");
+        let dsk = Ed25519Blake2bDeterministicPrivateKey::from_seed(seed.as_bytes())
+            .unwrap()
+            .derive("m/44'/165'/0'")
+            .unwrap();
+        assert_eq!(
+            hex::encode(dsk.private_key().to_bytes()),
+            "3be4fc2ef3f3b7374e6fc4fb6e7bb153f8a2998b3b3dab50853eabe128024143"
+        );
+        assert_eq!(
+            dsk.deterministic_public_key().to_hex(),
+            "5b65b0e8173ee0802c2c3e6c9080d1a16b06de1176c938a924f58670904e82c4"
+        );
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let sk = Ed25519Blake2bPrivateKey::from_seed_index(&[0u8; 32], 0).unwrap();
+        let msg = [1u8; 32];
+        let signature = sk.sign(&msg).unwrap();
+        assert_eq!(hex::encode(&signature), "9453c4fb6f3fe4a12e482f33cc91f14d0edb62584fb8897bfeacfb5a0d6cd660cbf794c6f51d200a2f3b1c9897e5584085530c51913e46b5f66b65b0f2eda109");
+
+        let pk = sk.public_key();
+        assert!(pk.verify(&msg, &signature));
+        assert!(!pk.verify(&[2u8; 32], &signature));
+        assert!(!pk.verify(&msg, &signature[1..]));
+        let mut tampered = signature.clone();
+        tampered[63] ^= 0x80;
+        assert!(!pk.verify(&msg, &tampered));
+    }
+}
@@ -0,0 +1,298 @@
+//! The authenticated encryption to the public key of an account, only the holder of the private
+//! key could decrypt it
+//! ref:
+//! - https://libsodium.gitbook.io/doc/public-key_cryptography/sealed_boxes
+//! - https://cr.yp.to/highspeed/naclcrypto-20090310.pdf
+//!
+//! The ed25519 and curve25519 keys use the sealed box of libsodium, the ciphertext is
+//! `ephemeral public key || XSalsa20Poly1305(plaintext)`. The key of the box is HSalsa20 of the
+//! X25519 shared secret and the nonce is blake2b-192 of `ephemeral public key || public key`.
+//! The ed25519 keys are converted to the X25519 keys.
+//!
+//! The secp256k1 keys use the same construction with the ECDH shared x coordinate and the
+//! compressed public keys, which is an ECIES variant of the sealed box
+
+use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPublicKey};
+use crate::{
+    Curve25519PrivateKey, Curve25519PublicKey, Result, Secp256k1PrivateKey, Secp256k1PublicKey,
+    TypedPrivateKey, TypedPublicKey,
+};
+
+use blake2b_simd::Params;
+use rand::{thread_rng, RngCore};
+use salsa20::{hsalsa20, Key};
+use xsalsa20poly1305::aead::{generic_array::GenericArray, Aead, NewAead};
+use xsalsa20poly1305::XSalsa20Poly1305;
+use zeroize::Zeroize;
+
+const NONCE_LENGTH: usize = 24;
+const TAG_LENGTH: usize = 16;
+const X25519_PUBLIC_KEY_LENGTH: usize = 32;
+const SECP256K1_PUBLIC_KEY_LENGTH: usize = 33;
+
+#[derive(Fail, Debug, PartialEq)]
+pub enum EncryptionError {
+    #[fail(display = "invalid_ciphertext")]
+    InvalidCiphertext,
+    #[fail(display = "encrypt_failed")]
+    EncryptFailed,
+}
+
+fn box_nonce(ephemeral_public_key: &[u8], public_key: &[u8]) -> [u8; NONCE_LENGTH] {
+    let hash = Params::new()
+        .hash_length(NONCE_LENGTH)
+        .to_state()
+        .update(ephemeral_public_key)
+        .update(public_key)
+        .finalize();
+    let mut nonce = [0u8; NONCE_LENGTH];
+    nonce.copy_from_slice(hash.as_bytes());
+    nonce
+}
+
+/// The key of the box is HSalsa20 of the shared secret with the zero input like
+/// `crypto_box_beforenm` does
+fn box_cipher(mut shared: [u8; 32]) -> XSalsa20Poly1305 {
+    let mut key = hsalsa20(Key::from_slice(&shared), &Default::default());
+    shared.zeroize();
+    let cipher = XSalsa20Poly1305::new(GenericArray::from_slice(&key));
+    key.as_mut_slice().zeroize();
+    cipher
+}
+
+fn seal(
+    ephemeral_public_key: &[u8],
+    public_key: &[u8],
+    shared: [u8; 32],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = box_nonce(ephemeral_public_key, public_key);
+    let encrypted = box_cipher(shared)
+        .encrypt(GenericArray::from_slice(&nonce), plaintext)
+        .map_err(|_| EncryptionError::EncryptFailed)?;
+    Ok([ephemeral_public_key, &encrypted].concat())
+}
+
+fn open(
+    ephemeral_public_key: &[u8],
+    public_key: &[u8],
+    shared: [u8; 32],
+    encrypted: &[u8],
+) -> Result<Vec<u8>> {
+    let nonce = box_nonce(ephemeral_public_key, public_key);
+    Ok(box_cipher(shared)
+        .decrypt(GenericArray::from_slice(&nonce), encrypted)
+        .map_err(|_| EncryptionError::InvalidCiphertext)?)
+}
+
+/// The X25519 key of the ed25519 or curve25519 public key
+pub fn x25519_public_key(public_key: &TypedPublicKey) -> Result<Curve25519PublicKey> {
+    match public_key {
+        TypedPublicKey::Ed25519(pk) => Curve25519PublicKey::from_ed25519(pk),
+        TypedPublicKey::Curve25519(pk) => Ok(pk.clone()),
+        _ => Err(KeyError::InvalidCurveType.into()),
+    }
+}
+
+/// The X25519 key of the ed25519 or curve25519 private key
+pub fn x25519_private_key(private_key: &TypedPrivateKey) -> Result<Curve25519PrivateKey> {
+    match private_key {
+        TypedPrivateKey::Ed25519(sk) => Ok(Curve25519PrivateKey::from_ed25519(sk)),
+        TypedPrivateKey::Curve25519(sk) => Ok(sk.clone()),
+        _ => Err(KeyError::InvalidCurveType.into()),
+    }
+}
+
+/// Encrypt to the ed25519, curve25519 or secp256k1 public key with a random ephemeral key
+pub fn encrypt_to_public_key(public_key: &TypedPublicKey, plaintext: &[u8]) -> Result<Vec<u8>> {
+    let mut ephemeral = [0u8; 32];
+    thread_rng().fill_bytes(&mut ephemeral);
+    let ret = encrypt_with_ephemeral_key(public_key, &ephemeral, plaintext);
+    ephemeral.zeroize();
+    ret
+}
+
+fn encrypt_with_ephemeral_key(
+    public_key: &TypedPublicKey,
+    ephemeral: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>> {
+    match public_key {
+        TypedPublicKey::Secp256k1(pk) => {
+            let ephemeral_key = Secp256k1PrivateKey::from_slice(ephemeral)?;
+            let shared = ephemeral_key.diffie_hellman(pk)?;
+            seal(
+                &ephemeral_key.public_key().to_compressed(),
+                &pk.to_compressed(),
+                shared,
+                plaintext,
+            )
+        }
+        _ => {
+            let pk = x25519_public_key(public_key)?;
+            let ephemeral_key = Curve25519PrivateKey::from_slice(ephemeral)?;
+            let shared = ephemeral_key.diffie_hellman(&pk)?;
+            seal(
+                &ephemeral_key.public_key().to_bytes(),
+                &pk.to_bytes(),
+                shared,
+                plaintext,
+            )
+        }
+    }
+}
+
+/// Decrypt the ciphertext of `encrypt_to_public_key` with the private key
+pub fn decrypt_with_private_key(
+    private_key: &TypedPrivateKey,
+    ciphertext: &[u8],
+) -> Result<Vec<u8>> {
+    match private_key {
+        TypedPrivateKey::Secp256k1(sk) => {
+            if ciphertext.len() < SECP256K1_PUBLIC_KEY_LENGTH + TAG_LENGTH {
+                return Err(EncryptionError::InvalidCiphertext.into());
+            }
+            let (ephemeral_public_key, encrypted) =
+                ciphertext.split_at(SECP256K1_PUBLIC_KEY_LENGTH);
+            let ephemeral_key = Secp256k1PublicKey::from_slice(ephemeral_public_key)
+                .map_err(|_| EncryptionError::InvalidCiphertext)?;
+            let shared = sk.diffie_hellman(&ephemeral_key)?;
+            open(
+                ephemeral_public_key,
+                &sk.public_key().to_compressed(),
+                shared,
+                encrypted,
+            )
+        }
+        _ => {
+            let sk = x25519_private_key(private_key)?;
+            if ciphertext.len() < X25519_PUBLIC_KEY_LENGTH + TAG_LENGTH {
+                return Err(EncryptionError::InvalidCiphertext.into());
+            }
+            let (ephemeral_public_key, encrypted) = ciphertext.split_at(X25519_PUBLIC_KEY_LENGTH);
+            let ephemeral_key = Curve25519PublicKey::from_slice(ephemeral_public_key)?;
+            let shared = sk
+                .diffie_hellman(&ephemeral_key)
+                .map_err(|_| EncryptionError::InvalidCiphertext)?;
+            open(
+                ephemeral_public_key,
+                &sk.public_key().to_bytes(),
+                shared,
+                encrypted,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decrypt_with_private_key, encrypt_to_public_key, encrypt_with_ephemeral_key,
+        x25519_private_key, x25519_public_key,
+    };
+    use crate::{PrivateKey, TypedPrivateKey};
+    use tcx_constants::CurveType;
+
+    const ALICE: &str = "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a";
+    const BOB: &str = "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb";
+
+    fn private_key(curve: CurveType, hex: &str) -> TypedPrivateKey {
+        TypedPrivateKey::from_slice(curve, &hex::decode(hex).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn open_sealed_box_of_libsodium() {
+        // crypto_box_seal of libsodium 1.0.18 to the public key of BOB
+        let bob = private_key(CurveType::Curve25519, BOB);
+        assert_eq!(
+            hex::encode(bob.public_key().to_bytes()),
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"
+        );
+        let ciphertext = hex::decode("84433a19ddb9ca85a8bd5a5e1d8ee8d8627313f75021da2c9b03cadf61796f29c324938c6118aa26f59f6edf0e39fe9ccae0a28050c17e0621b8f6a21fea1f4171").unwrap();
+        assert_eq!(
+            decrypt_with_private_key(&bob, &ciphertext).unwrap(),
+            b"hello, token core"
+        );
+    }
+
+    #[test]
+    fn sealed_box_of_curve25519() {
+        let bob = private_key(CurveType::Curve25519, BOB);
+        let ciphertext = encrypt_with_ephemeral_key(
+            &bob.public_key(),
+            &hex::decode(ALICE).unwrap(),
+            b"hello, token core",
+        )
+        .unwrap();
+        assert_eq!(hex::encode(&ciphertext), "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6ad67d19861a6ea66a971c41b5d93f13aacf62c0aa87d42325bf0d2e14cd1846390a");
+        assert_eq!(
+            decrypt_with_private_key(&bob, &ciphertext).unwrap(),
+            b"hello, token core"
+        );
+
+        let alice = private_key(CurveType::Curve25519, ALICE);
+        assert_eq!(
+            format!(
+                "{}",
+                decrypt_with_private_key(&alice, &ciphertext).err().unwrap()
+            ),
+            "invalid_ciphertext"
+        );
+        let mut tampered = ciphertext.clone();
+        tampered[40] ^= 1;
+        assert!(decrypt_with_private_key(&bob, &tampered).is_err());
+        assert!(decrypt_with_private_key(&bob, &ciphertext[..47]).is_err());
+    }
+
+    #[test]
+    fn sealed_box_of_ed25519() {
+        let sk = private_key(
+            CurveType::ED25519,
+            "5740dedadb610333de66ef2db2d91fd648fcbe419dff766f921ae97d536f94ce",
+        );
+        let x25519_key = x25519_private_key(&sk).unwrap();
+        assert_eq!(
+            x25519_key.public_key(),
+            x25519_public_key(&sk.public_key()).unwrap()
+        );
+
+        let ciphertext = encrypt_to_public_key(&sk.public_key(), b"backup share").unwrap();
+        assert_eq!(ciphertext.len(), 32 + 16 + 12);
+        assert_eq!(
+            decrypt_with_private_key(&sk, &ciphertext).unwrap(),
+            b"backup share"
+        );
+        // the converted key is the same key of the box
+        assert_eq!(
+            decrypt_with_private_key(&TypedPrivateKey::Curve25519(x25519_key), &ciphertext)
+                .unwrap(),
+            b"backup share"
+        );
+    }
+
+    #[test]
+    fn ecies_of_secp256k1() {
+        let sk = private_key(CurveType::SECP256k1, &hex::encode([2u8; 32]));
+        let ciphertext =
+            encrypt_with_ephemeral_key(&sk.public_key(), &[1u8; 32], b"hello, token core").unwrap();
+        assert_eq!(hex::encode(&ciphertext), "031b84c5567b126440995d3ed5aaba0565d71e1834604819ff9c17f5e9d5dd078f2ae565069dae3a5c134ee03b11afcf3c60f6db2c5353020b6a914134b2a3bcb3c4");
+        assert_eq!(
+            decrypt_with_private_key(&sk, &ciphertext).unwrap(),
+            b"hello, token core"
+        );
+
+        let ciphertext = encrypt_to_public_key(&sk.public_key(), b"").unwrap();
+        assert_eq!(decrypt_with_private_key(&sk, &ciphertext).unwrap(), b"");
+
+        let other = private_key(CurveType::SECP256k1, &hex::encode([3u8; 32]));
+        assert!(decrypt_with_private_key(&other, &ciphertext).is_err());
+        assert!(decrypt_with_private_key(&sk, &ciphertext[1..]).is_err());
+    }
+
+    #[test]
+    fn unsupported_curve() {
+        let sk = private_key(CurveType::NIST256p1, &hex::encode([1u8; 32]));
+        assert!(encrypt_to_public_key(&sk.public_key(), b"data").is_err());
+        assert!(decrypt_with_private_key(&sk, &[0u8; 64]).is_err());
+    }
+}
@@ -2,15 +2,21 @@
 extern crate failure;
 
 mod bip32;
+mod bip39_czech;
 mod bls;
 mod bls_derive;
 mod constant;
+mod curve25519;
 mod derive;
 mod ecc;
 mod ed25519;
 mod ed25519_bip32;
+mod ed25519_blake2b;
+mod encryption;
+mod nist256p1;
 mod rand;
 mod secp256k1;
+mod slip39;
 mod sr25519;
 mod subkey;
 
@@ -19,6 +25,10 @@ use core::result;
 pub type Result<T> = result::Result<T, failure::Error>;
 
 pub use crate::bip32::{Bip32DeterministicPrivateKey, Bip32DeterministicPublicKey};
+pub use crate::bip39_czech::{
+    bip39_seed, czech_mnemonic_from_entropy, czech_mnemonic_to_entropy, generate_czech_mnemonic,
+};
+pub use crate::curve25519::{Curve25519PrivateKey, Curve25519PublicKey};
 pub use crate::derive::{get_account_path, Derive, DeriveJunction, DerivePath};
 pub use crate::ecc::{
     DeterministicPrivateKey, DeterministicPublicKey, PrivateKey, PublicKey,
@@ -26,9 +36,26 @@ pub use crate::ecc::{
     TypedPrivateKeyDisplay, TypedPublicKey,
 };
 pub use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
-pub use crate::rand::generate_mnemonic;
+pub use crate::ed25519_blake2b::{
+    Ed25519Blake2bDeterministicPrivateKey, Ed25519Blake2bDeterministicPublicKey,
+    Ed25519Blake2bPrivateKey, Ed25519Blake2bPublicKey,
+};
+pub use crate::encryption::{
+    decrypt_with_private_key, encrypt_to_public_key, x25519_private_key, x25519_public_key,
+    EncryptionError,
+};
+pub use crate::nist256p1::{
+    Nist256p1DeterministicPrivateKey, Nist256p1DeterministicPublicKey, Nist256p1PrivateKey,
+    Nist256p1PublicKey,
+};
+pub use crate::rand::{generate_mnemonic, generate_mnemonic_with};
 pub use crate::secp256k1::{
-    private_key_without_version, verify_private_key, Secp256k1PrivateKey, Secp256k1PublicKey,
+    private_key_without_version, tagged_hash, verify_private_key, Secp256k1PrivateKey,
+    Secp256k1PublicKey,
+};
+pub use crate::slip39::{
+    combine_shares, generate_shares, Slip39Error, DEFAULT_ITERATION_EXPONENT,
+    MAX_ITERATION_EXPONENT,
 };
 pub use crate::sr25519::{Sr25519PrivateKey, Sr25519PublicKey};
 
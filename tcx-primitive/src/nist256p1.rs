@@ -0,0 +1,671 @@
+//! The NIST P-256 (secp256r1) keys and the SLIP-10 derivation, the curve arithmetic and the ECDSA
+//! with the RFC 6979 nonce are of the `p256` crate
+//! ref:
+//! - https://github.com/satoshilabs/slips/blob/master/slip-0010.md
+//! - https://tools.ietf.org/html/rfc6979
+
+use crate::ecc::{
+    DeterministicPrivateKey, DeterministicPublicKey, KeyError, PrivateKey as TraitPrivateKey,
+    PublicKey as TraitPublicKey,
+};
+use crate::{Derive, DeriveJunction, DerivePath, FromHex, Result, ToHex};
+
+use bip39::{Language, Mnemonic, Seed};
+use bitcoin_hashes::hmac::{Hmac, HmacEngine};
+use bitcoin_hashes::{hash160, sha512, Hash, HashEngine};
+use byteorder::{BigEndian, ByteOrder};
+use p256::ecdsa::signature::{DigestSigner, DigestVerifier};
+use p256::ecdsa::{Signature, SigningKey, VerifyingKey};
+use p256::elliptic_curve::ff::PrimeField;
+use p256::elliptic_curve::ops::Invert;
+use p256::elliptic_curve::sec1::FromEncodedPoint;
+use p256::elliptic_curve::FromDigest;
+use p256::{
+    AffinePoint, EncodedPoint, FieldBytes, NonZeroScalar, ProjectivePoint, PublicKey, Scalar,
+    SecretKey,
+};
+use sha2::digest::generic_array::GenericArray;
+use sha2::digest::{BlockInput, FixedOutput, Reset, Update};
+use sha2::Sha256;
+use std::convert::TryFrom;
+use std::str::FromStr;
+use zeroize::Zeroize;
+
+const MASTER_SECRET: &[u8] = b"Nist256p1 seed";
+
+const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// The ECDSA of the `p256` crate takes the digest of the message, this one gives the 32 bytes
+/// hash which is already computed. A default instance is plain SHA-256, so the HMAC-SHA256 of the
+/// RFC 6979 nonce in the crate is not changed
+#[derive(Clone, Default)]
+struct Prehash {
+    hash: Option<FieldBytes>,
+    sha256: Sha256,
+}
+
+impl Prehash {
+    fn new(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidMessage.into());
+        }
+        Ok(Prehash {
+            hash: Some(FieldBytes::clone_from_slice(data)),
+            sha256: Sha256::default(),
+        })
+    }
+}
+
+impl Update for Prehash {
+    fn update(&mut self, data: impl AsRef<[u8]>) {
+        self.sha256.update(data);
+    }
+}
+
+impl BlockInput for Prehash {
+    type BlockSize = <Sha256 as BlockInput>::BlockSize;
+}
+
+impl FixedOutput for Prehash {
+    type OutputSize = <Sha256 as FixedOutput>::OutputSize;
+
+    fn finalize_into(self, out: &mut GenericArray<u8, Self::OutputSize>) {
+        match self.hash {
+            Some(hash) => out.copy_from_slice(&hash),
+            None => self.sha256.finalize_into(out),
+        }
+    }
+
+    fn finalize_into_reset(&mut self, out: &mut GenericArray<u8, Self::OutputSize>) {
+        std::mem::take(self).finalize_into(out);
+    }
+}
+
+impl Reset for Prehash {
+    fn reset(&mut self) {
+        *self = Prehash::default();
+    }
+}
+
+/// `r || s`, both of them must be in [1, n) since `Signature::r` and `Signature::s` take them as
+/// valid scalars
+fn parse_compact(signature: &[u8]) -> Result<Signature> {
+    let is_scalar =
+        |bytes: &[u8]| NonZeroScalar::from_repr(FieldBytes::clone_from_slice(bytes)).is_some();
+    if signature.len() != 64 || !is_scalar(&signature[..32]) || !is_scalar(&signature[32..]) {
+        return Err(KeyError::InvalidSignature.into());
+    }
+    Signature::try_from(signature).map_err(|_| KeyError::InvalidSignature.into())
+}
+
+/// The s is normalized to the lower half of the order like secp256k1, n - s < s when s is high
+fn normalize_s(signature: &Signature) -> Result<Signature> {
+    let s = signature.s();
+    let negative_s = -*s;
+    if negative_s.to_repr().as_slice() < s.to_repr().as_slice() {
+        Signature::from_scalars(signature.r(), negative_s)
+            .map_err(|_| KeyError::InvalidSignature.into())
+    } else {
+        Ok(signature.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct Nist256p1PrivateKey(SecretKey);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Nist256p1PublicKey(VerifyingKey);
+
+impl Nist256p1PrivateKey {
+    /// The low s signature of the 32 bytes hash and the recovery id of it
+    fn sign_compact(&self, data: &[u8]) -> Result<(Signature, u8)> {
+        let signature: Signature = SigningKey::from(self.0.clone())
+            .try_sign_digest(Prehash::new(data)?)
+            .map_err(|_| KeyError::InvalidSignature)?;
+        let signature = normalize_s(&signature)?;
+
+        let public_key = self.public_key();
+        for recovery_id in 0..2 {
+            let recoverable = [signature.as_ref(), &[recovery_id]].concat();
+            if Nist256p1PublicKey::recover(data, &recoverable).ok() == Some(public_key.clone()) {
+                return Ok((signature, recovery_id));
+            }
+        }
+        Err(KeyError::InvalidSignature.into())
+    }
+}
+
+impl TraitPrivateKey for Nist256p1PrivateKey {
+    type PublicKey = Nist256p1PublicKey;
+
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 32 {
+            return Err(KeyError::InvalidPrivateKey.into());
+        }
+        SecretKey::from_bytes(data)
+            .map(Nist256p1PrivateKey)
+            .map_err(|_| KeyError::InvalidPrivateKey.into())
+    }
+
+    fn public_key(&self) -> Self::PublicKey {
+        Nist256p1PublicKey(VerifyingKey::from(&self.0.public_key()))
+    }
+
+    /// DER encoded like secp256k1
+    fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (signature, _) = self.sign_compact(data)?;
+        Ok(signature.to_asn1().as_bytes().to_vec())
+    }
+
+    /// `r || s || recovery id`
+    fn sign_recoverable(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let (signature, recovery_id) = self.sign_compact(data)?;
+        Ok([signature.as_ref(), &[recovery_id]].concat())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes().to_vec()
+    }
+}
+
+impl Nist256p1PublicKey {
+    pub fn to_compressed(&self) -> Vec<u8> {
+        self.0.to_encoded_point(true).as_bytes().to_vec()
+    }
+
+    pub fn to_uncompressed(&self) -> Vec<u8> {
+        self.0.to_encoded_point(false).as_bytes().to_vec()
+    }
+
+    fn to_projective(&self) -> ProjectivePoint {
+        PublicKey::from(&self.0).to_projective()
+    }
+
+    fn from_projective(point: ProjectivePoint) -> Result<Self> {
+        PublicKey::from_affine(point.to_affine())
+            .map(|public_key| Nist256p1PublicKey(VerifyingKey::from(&public_key)))
+            .map_err(|_| KeyError::InvalidPublicKey.into())
+    }
+
+    /// Recover the public key from the `r || s || recovery id` signature of the 32 bytes hash,
+    /// the recovery id is the parity of the y of the point whose x is r. The x overflowing the
+    /// order, which has a negligible chance, is not supported
+    pub fn recover(data: &[u8], signature: &[u8]) -> Result<Self> {
+        if signature.len() != 65 {
+            return Err(KeyError::InvalidSignature.into());
+        }
+        let recovery_id = signature[64];
+        if recovery_id > 1 {
+            return Err(KeyError::InvalidRecoveryId.into());
+        }
+        let hash = Prehash::new(data)?;
+        let compact = parse_compact(&signature[..64])?;
+
+        let r = compact.r();
+        let point = EncodedPoint::from_bytes([&[0x02 | recovery_id][..], &r.to_repr()].concat())
+            .ok()
+            .and_then(|encoded| AffinePoint::from_encoded_point(&encoded))
+            .ok_or(KeyError::InvalidSignature)?;
+        let r_inv: Scalar = Option::from(r.invert()).ok_or(KeyError::InvalidSignature)?;
+        let e = Scalar::from_digest(hash);
+
+        // Q = r^-1 * (s * R - e * G)
+        let q = ProjectivePoint::from(point) * (*compact.s() * r_inv)
+            + ProjectivePoint::generator() * (-e * r_inv);
+        Self::from_projective(q).map_err(|_| KeyError::InvalidSignature.into())
+    }
+
+    fn verify_compact(&self, data: &[u8], signature: &Signature) -> bool {
+        Prehash::new(data)
+            .map(|hash| self.0.verify_digest(hash, signature).is_ok())
+            .unwrap_or(false)
+    }
+}
+
+impl TraitPublicKey for Nist256p1PublicKey {
+    /// The compressed or uncompressed SEC1 encoding
+    fn from_slice(data: &[u8]) -> Result<Self> {
+        if data.len() != 33 && data.len() != 65 {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+        VerifyingKey::from_sec1_bytes(data)
+            .map(Nist256p1PublicKey)
+            .map_err(|_| KeyError::InvalidPublicKey.into())
+    }
+
+    /// The compressed one
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_compressed()
+    }
+
+    /// The signature is DER encoded like `sign`, or the 64 bytes compact one, or the 65 bytes
+    /// recoverable one like `sign_recoverable` whose recovered key must be this key
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        match signature.len() {
+            65 => Nist256p1PublicKey::recover(data, signature)
+                .map(|pk| pk == *self)
+                .unwrap_or(false),
+            64 => parse_compact(signature)
+                .map(|compact| self.verify_compact(data, &compact))
+                .unwrap_or(false),
+            _ => Signature::from_asn1(signature)
+                .ok()
+                .and_then(|der| parse_compact(der.as_ref()).ok())
+                .map(|compact| self.verify_compact(data, &compact))
+                .unwrap_or(false),
+        }
+    }
+}
+
+impl ToHex for Nist256p1PublicKey {
+    fn to_hex(&self) -> String {
+        hex::encode(self.to_compressed())
+    }
+}
+
+impl FromHex for Nist256p1PublicKey {
+    fn from_hex(hex: &str) -> Result<Self> {
+        Self::from_slice(&hex::decode(hex)?)
+    }
+}
+
+fn hmac_sha512(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut engine = HmacEngine::<sha512::Hash>::new(key);
+    engine.input(data);
+    Hmac::<sha512::Hash>::from_engine(engine)
+        .into_inner()
+        .to_vec()
+}
+
+/// The left 32 bytes of the HMAC as a scalar, it is invalid if it is not less than the order
+fn parse_il(i: &[u8]) -> Option<Scalar> {
+    Scalar::from_repr(FieldBytes::clone_from_slice(&i[..32]))
+}
+
+fn fingerprint(public_key: &Nist256p1PublicKey) -> [u8; 4] {
+    let mut ret = [0u8; 4];
+    ret.copy_from_slice(&hash160::Hash::hash(&public_key.to_compressed())[..4]);
+    ret
+}
+
+fn child_index(junction: &DeriveJunction) -> Result<u32> {
+    match *junction {
+        DeriveJunction::Soft(index) if index < HARDENED_OFFSET => Ok(index),
+        DeriveJunction::Hard(index) if index < HARDENED_OFFSET => Ok(index | HARDENED_OFFSET),
+        _ => Err(KeyError::InvalidChildNumber.into()),
+    }
+}
+
+/// The SLIP-10 extended private key of nist256p1, which is BIP-32 with another curve
+#[derive(Clone)]
+pub struct Nist256p1DeterministicPrivateKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    private_key: Nist256p1PrivateKey,
+}
+
+#[derive(Clone)]
+pub struct Nist256p1DeterministicPublicKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    public_key: Nist256p1PublicKey,
+}
+
+impl Drop for Nist256p1DeterministicPrivateKey {
+    fn drop(&mut self) {
+        self.chain_code.zeroize();
+    }
+}
+
+impl Nist256p1DeterministicPrivateKey {
+    /// The master key of the BIP-39 mnemonic in its own word list with the passphrase
+    pub fn from_bip39_mnemonic(mnemonic: &Mnemonic, passphrase: &str) -> Result<Self> {
+        Self::from_seed(Seed::new(mnemonic, passphrase).as_bytes())
+    }
+
+    fn derive_child(&self, index: u32) -> Result<Self> {
+        let mut data = if index >= HARDENED_OFFSET {
+            [&[0u8][..], &self.private_key.0.to_bytes()].concat()
+        } else {
+            self.private_key.public_key().to_compressed()
+        };
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let parent = self.private_key.0.secret_scalar();
+        loop {
+            let mut i = hmac_sha512(&self.chain_code, &data);
+            let child = parse_il(&i).and_then(|il| NonZeroScalar::new(il + **parent));
+            if let Some(child) = child {
+                let mut chain_code = [0u8; 32];
+                chain_code.copy_from_slice(&i[32..]);
+                i.zeroize();
+                data.zeroize();
+                return Ok(Nist256p1DeterministicPrivateKey {
+                    depth: self.depth.wrapping_add(1),
+                    parent_fingerprint: fingerprint(&self.private_key.public_key()),
+                    child_number: index,
+                    chain_code,
+                    private_key: Nist256p1PrivateKey(SecretKey::new(child)),
+                });
+            }
+            data.zeroize();
+            data = [&[1u8][..], &i[32..], &index.to_be_bytes()].concat();
+            i.zeroize();
+        }
+    }
+}
+
+impl Derive for Nist256p1DeterministicPrivateKey {
+    fn derive(&self, path: &str) -> Result<Self> {
+        let path = DerivePath::from_str(path)?;
+        let mut key = self.clone();
+        for junction in &path {
+            key = key.derive_child(child_index(&junction)?)?;
+        }
+        Ok(key)
+    }
+}
+
+impl DeterministicPrivateKey for Nist256p1DeterministicPrivateKey {
+    type DeterministicPublicKey = Nist256p1DeterministicPublicKey;
+    type PrivateKey = Nist256p1PrivateKey;
+
+    /// The master key is retried with the HMAC of the previous one if it is not a valid key
+    fn from_seed(seed: &[u8]) -> Result<Self> {
+        let mut i = hmac_sha512(MASTER_SECRET, seed);
+        let private_key = loop {
+            if let Ok(secret_key) = SecretKey::from_bytes(&i[..32]) {
+                break Nist256p1PrivateKey(secret_key);
+            }
+            let next = hmac_sha512(MASTER_SECRET, &i);
+            i.zeroize();
+            i = next;
+        };
+
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&i[32..]);
+        i.zeroize();
+        Ok(Nist256p1DeterministicPrivateKey {
+            depth: 0,
+            parent_fingerprint: [0u8; 4],
+            child_number: 0,
+            chain_code,
+            private_key,
+        })
+    }
+
+    /// The English mnemonic without passphrase like the other curves, `from_bip39_mnemonic` takes
+    /// the mnemonic of another language and the passphrase
+    fn from_mnemonic(mnemonic: &str) -> Result<Self> {
+        let mn = Mnemonic::from_phrase(mnemonic, Language::English)?;
+        Self::from_bip39_mnemonic(&mn, "")
+    }
+
+    fn private_key(&self) -> Self::PrivateKey {
+        self.private_key.clone()
+    }
+
+    fn deterministic_public_key(&self) -> Self::DeterministicPublicKey {
+        Nist256p1DeterministicPublicKey {
+            depth: self.depth,
+            parent_fingerprint: self.parent_fingerprint,
+            child_number: self.child_number,
+            chain_code: self.chain_code,
+            public_key: self.private_key.public_key(),
+        }
+    }
+}
+
+impl Nist256p1DeterministicPublicKey {
+    fn derive_child(&self, index: u32) -> Result<Self> {
+        if index >= HARDENED_OFFSET {
+            return Err(KeyError::CannotDeriveFromHardenedKey.into());
+        }
+        let mut data = self.public_key.to_compressed();
+        data.extend_from_slice(&index.to_be_bytes());
+
+        loop {
+            let i = hmac_sha512(&self.chain_code, &data);
+            let child = parse_il(&i).and_then(|il| {
+                Nist256p1PublicKey::from_projective(
+                    ProjectivePoint::generator() * il + self.public_key.to_projective(),
+                )
+                .ok()
+            });
+            if let Some(child) = child {
+                let mut chain_code = [0u8; 32];
+                chain_code.copy_from_slice(&i[32..]);
+                return Ok(Nist256p1DeterministicPublicKey {
+                    depth: self.depth.wrapping_add(1),
+                    parent_fingerprint: fingerprint(&self.public_key),
+                    child_number: index,
+                    chain_code,
+                    public_key: child,
+                });
+            }
+            data = [&[1u8][..], &i[32..], &index.to_be_bytes()].concat();
+        }
+    }
+}
+
+impl Derive for Nist256p1DeterministicPublicKey {
+    fn derive(&self, path: &str) -> Result<Self> {
+        let path = DerivePath::from_str(path)?;
+        let mut key = self.clone();
+        for junction in &path {
+            key = key.derive_child(child_index(&junction)?)?;
+        }
+        Ok(key)
+    }
+}
+
+impl DeterministicPublicKey for Nist256p1DeterministicPublicKey {
+    type PublicKey = Nist256p1PublicKey;
+
+    fn public_key(&self) -> Self::PublicKey {
+        self.public_key.clone()
+    }
+}
+
+impl ToString for Nist256p1DeterministicPrivateKey {
+    fn to_string(&self) -> String {
+        hex::encode(self.private_key.to_bytes())
+    }
+}
+
+impl ToString for Nist256p1DeterministicPublicKey {
+    fn to_string(&self) -> String {
+        self.to_hex()
+    }
+}
+
+/// The same layout as the secp256k1 one, depth || fingerprint || child number || chain code || key
+impl ToHex for Nist256p1DeterministicPublicKey {
+    fn to_hex(&self) -> String {
+        let mut ret = [0; 74];
+        ret[0] = self.depth;
+        ret[1..5].copy_from_slice(&self.parent_fingerprint);
+        BigEndian::write_u32(&mut ret[5..9], self.child_number);
+        ret[9..41].copy_from_slice(&self.chain_code);
+        ret[41..74].copy_from_slice(&self.public_key.to_compressed());
+        hex::encode(ret.to_vec())
+    }
+}
+
+impl FromHex for Nist256p1DeterministicPublicKey {
+    fn from_hex(hex: &str) -> Result<Self> {
+        let data = hex::decode(hex)?;
+        if data.len() != 74 {
+            return Err(KeyError::InvalidPublicKey.into());
+        }
+
+        let mut parent_fingerprint = [0u8; 4];
+        parent_fingerprint.copy_from_slice(&data[1..5]);
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&data[9..41]);
+        Ok(Nist256p1DeterministicPublicKey {
+            depth: data[0],
+            parent_fingerprint,
+            child_number: BigEndian::read_u32(&data[5..9]),
+            chain_code,
+            public_key: Nist256p1PublicKey::from_slice(&data[41..74])?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Nist256p1DeterministicPrivateKey, Nist256p1PrivateKey, Nist256p1PublicKey};
+    use crate::{
+        Derive, DeterministicPrivateKey, DeterministicPublicKey, FromHex, PrivateKey, PublicKey,
+        ToHex,
+    };
+    use bitcoin_hashes::{sha256, Hash};
+
+    #[test]
+    fn test_public_key() {
+        // the private key of RFC 6979 A.2.5
+        let sk = Nist256p1PrivateKey::from_slice(
+            &hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap(),
+        )
+        .unwrap();
+        let pk = sk.public_key();
+        assert_eq!(
+            hex::encode(pk.to_uncompressed()),
+            "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb67903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"
+        );
+        assert_eq!(
+            pk.to_hex(),
+            "0360fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6"
+        );
+        assert_eq!(
+            Nist256p1PublicKey::from_hex(&pk.to_hex()).unwrap(),
+            Nist256p1PublicKey::from_slice(&pk.to_uncompressed()).unwrap()
+        );
+
+        assert!(Nist256p1PrivateKey::from_slice(&[0u8; 32]).is_err());
+        assert!(Nist256p1PublicKey::from_slice(&[5u8; 33]).is_err());
+        let not_on_curve = [&pk.to_uncompressed()[..33], &[0u8; 32]].concat();
+        assert!(Nist256p1PublicKey::from_slice(&not_on_curve).is_err());
+    }
+
+    #[test]
+    fn test_sign() {
+        let sk = Nist256p1PrivateKey::from_slice(
+            &hex::decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap(),
+        )
+        .unwrap();
+        let pk = sk.public_key();
+        // RFC 6979 A.2.5 with SHA-256 and the message "sample", the s is normalized to the low one
+        let hash = sha256::Hash::hash(b"sample").into_inner();
+        let signature = sk.sign_recoverable(&hash).unwrap();
+        assert_eq!(
+            hex::encode(&signature[..32]),
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"
+        );
+        assert_eq!(
+            hex::encode(&signature[32..64]),
+            "0834e36ad29a83bf2bc9385e491d6099c8fdf9d1ed67aa7ea5f51f93782857a9"
+        );
+
+        let der = sk.sign(&hash).unwrap();
+        assert!(pk.verify(&hash, &der));
+        assert!(pk.verify(&hash, &signature));
+        assert!(pk.verify(&hash, &signature[..64]));
+        assert_eq!(Nist256p1PublicKey::recover(&hash, &signature).unwrap(), pk);
+
+        let other_hash = sha256::Hash::hash(b"test").into_inner();
+        assert!(!pk.verify(&other_hash, &der));
+        assert!(!pk.verify(&other_hash, &signature));
+        assert!(!pk.verify(&other_hash, &signature[..64]));
+
+        let ret = sk.sign(&[0, 1, 2, 3]);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_message");
+    }
+
+    #[test]
+    fn test_slip10_derive() {
+        // SLIP-10 test vector 1 for nist256p1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let root = Nist256p1DeterministicPrivateKey::from_seed(&seed).unwrap();
+        assert_eq!(
+            hex::encode(root.chain_code),
+            "beeb672fe4621673f722f38529c07392fecaa61015c80c34f29ce8b41b3cb6ea"
+        );
+        assert_eq!(
+            root.to_string(),
+            "612091aaa12e22dd2abef664f8a01a82cae99ad7441b7ef8110424915c268bc2"
+        );
+
+        let child = root.derive("m/0'/1/2'/2/1000000000").unwrap();
+        assert_eq!(
+            hex::encode(child.chain_code),
+            "b9b7b82d326bb9cb5b5b121066feea4eb93d5241103c9e7a18aad40f1dde8059"
+        );
+        assert_eq!(
+            child.to_string(),
+            "21c4f269ef0a5fd1badf47eeacebeeaa3de22eb8e5b0adcd0f27dd99d34d0119"
+        );
+        assert_eq!(
+            child.private_key().public_key().to_hex(),
+            "02216cd26d31147f72427a453c443ed2cde8a1e53c9cc44e5ddf739725413fe3f4"
+        );
+
+        let xpub = root.derive("m/0'/1/2'").unwrap().deterministic_public_key();
+        let child_pub = xpub.derive("2/1000000000").unwrap();
+        assert_eq!(
+            child_pub.public_key().to_hex(),
+            child.private_key().public_key().to_hex()
+        );
+        assert_eq!(
+            child_pub.to_hex(),
+            child.deterministic_public_key().to_hex()
+        );
+        assert_eq!(
+            super::Nist256p1DeterministicPublicKey::from_hex(&child_pub.to_hex())
+                .unwrap()
+                .to_hex(),
+            child_pub.to_hex()
+        );
+
+        let ret = xpub.derive("0'");
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "cannot_derive_from_hardened_key"
+        );
+    }
+
+    #[test]
+    fn test_slip10_derive_retry() {
+        // the SLIP-10 vectors of the seed retry and the derivation retry for nist256p1
+        let seed = hex::decode("a7305bc8df8d0951f0cb224c0e95d7707cbdf2c6ce7e8d481fec69c7ff5e9446")
+            .unwrap();
+        let root = Nist256p1DeterministicPrivateKey::from_seed(&seed).unwrap();
+        assert_eq!(
+            root.to_string(),
+            "3b8c18469a4634517d6d0b65448f8e6c62091b45540a1743c5846be55d47d88f"
+        );
+
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let root = Nist256p1DeterministicPrivateKey::from_seed(&seed).unwrap();
+        let child = root.derive("m/28578'").unwrap();
+        assert_eq!(
+            hex::encode(child.chain_code),
+            "e94c8ebe30c2250a14713212f6449b20f3329105ea15b652ca5bdfc68f6c65c2"
+        );
+        assert_eq!(
+            child.to_string(),
+            "06f0db126f023755d0b8d86d4591718a5210dd8d024e3e14b6159d63f53aa669"
+        );
+        assert_eq!(
+            child.derive("33941").unwrap().to_string(),
+            "092154eed4af83e078ff9b84322015aefe5769e31270f62c3f66c33888335f3a"
+        );
+    }
+}
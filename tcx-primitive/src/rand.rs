@@ -1,5 +1,12 @@
+use super::Result;
 use bip39::{Language, Mnemonic, MnemonicType};
 
 pub fn generate_mnemonic() -> String {
     Mnemonic::new(MnemonicType::Words12, Language::English).to_string()
 }
+
+/// The word count is one of 12, 15, 18, 21 and 24
+pub fn generate_mnemonic_with(language: Language, word_count: usize) -> Result<String> {
+    let mnemonic_type = MnemonicType::for_word_count(word_count)?;
+    Ok(Mnemonic::new(mnemonic_type, language).into_phrase())
+}
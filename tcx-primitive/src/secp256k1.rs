@@ -1,15 +1,17 @@
-use crate::constant::SECP256K1_ENGINE;
+use crate::constant::{SCHNORRSIG_ENGINE, SECP256K1_ENGINE};
 use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPublicKey};
 
-use bitcoin::Network;
-
 use bitcoin::util::key::{PrivateKey, PublicKey};
 
 use crate::{Result, Ss58Codec};
 use bitcoin::util::base58;
 
-use bitcoin::secp256k1::Message;
+use bitcoin::secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use bitcoin::secp256k1::{Message, Signature};
+use bitcoin_hashes::{sha256, Hash, HashEngine};
+use secp256k1_schnorrsig::schnorrsig;
 use tcx_constants::{network_from_coin, CoinInfo};
+use zeroize::Zeroizing;
 
 #[cfg_attr(tarpaulin, skip)]
 fn transform_secp256k1_error(err: secp256k1::Error) -> KeyError {
@@ -28,8 +30,13 @@ fn transform_secp256k1_error(err: secp256k1::Error) -> KeyError {
 #[derive(Clone)]
 pub struct Secp256k1PublicKey(pub PublicKey);
 
+/// The secret key is kept in a zeroizing array, the `SecretKey` of secp256k1 can not be wiped so
+/// it is re-created from the array for each use
 #[derive(Clone)]
-pub struct Secp256k1PrivateKey(pub PrivateKey);
+pub struct Secp256k1PrivateKey {
+    secret: Zeroizing<[u8; 32]>,
+    compressed: bool,
+}
 
 impl From<PublicKey> for Secp256k1PublicKey {
     fn from(pk: PublicKey) -> Self {
@@ -39,7 +46,7 @@ impl From<PublicKey> for Secp256k1PublicKey {
 
 impl From<PrivateKey> for Secp256k1PrivateKey {
     fn from(sk: PrivateKey) -> Self {
-        Secp256k1PrivateKey(sk)
+        Secp256k1PrivateKey::from_secret_key(&sk.key, sk.compressed)
     }
 }
 
@@ -51,12 +58,132 @@ impl Secp256k1PublicKey {
     pub fn to_uncompressed(&self) -> Vec<u8> {
         self.0.key.serialize_uncompressed().to_vec()
     }
+
+    /// The 32 bytes x coordinate of the key, as used by BIP-340
+    pub fn to_x_only(&self) -> Vec<u8> {
+        self.0.key.serialize()[1..].to_vec()
+    }
+
+    /// The x-only taproot output key committing to no script path
+    /// ref: https://github.com/bitcoin/bips/blob/master/bip-0086.mediawiki
+    pub fn tap_tweak(&self) -> Result<Vec<u8>> {
+        let mut key = self.0.key;
+        if self.0.key.serialize()[0] == 0x03 {
+            key.negate_assign(&SECP256K1_ENGINE);
+        }
+        let tweak = tagged_hash("TapTweak", &self.to_x_only());
+        key.add_exp_assign(&SECP256K1_ENGINE, &tweak)
+            .map_err(transform_secp256k1_error)?;
+        Ok(key.serialize()[1..].to_vec())
+    }
+
+    /// Recover the public key from the `r || s || recovery id` signature of the 32 bytes hash,
+    /// the recovery id could be offset by 27 like Ethereum does
+    pub fn recover(data: &[u8], signature: &[u8]) -> Result<Self> {
+        if signature.len() != 65 {
+            return Err(KeyError::InvalidSignature.into());
+        }
+        let msg = Message::from_slice(data).map_err(transform_secp256k1_error)?;
+        let recovery_id = if signature[64] >= 27 {
+            signature[64] - 27
+        } else {
+            signature[64]
+        };
+        let recovery_id =
+            RecoveryId::from_i32(recovery_id as i32).map_err(transform_secp256k1_error)?;
+        let signature = RecoverableSignature::from_compact(&signature[..64], recovery_id)
+            .map_err(transform_secp256k1_error)?;
+        let key = SECP256K1_ENGINE
+            .recover(&msg, &signature)
+            .map_err(transform_secp256k1_error)?;
+        Ok(Secp256k1PublicKey(PublicKey {
+            compressed: true,
+            key,
+        }))
+    }
+
+    /// BIP-340 verification with the x coordinate of the key, see `sign_schnorr`
+    pub fn verify_schnorr(&self, data: &[u8], signature: &[u8]) -> bool {
+        let verify = || -> std::result::Result<(), secp256k1_schnorrsig::Error> {
+            let msg = secp256k1_schnorrsig::Message::from_slice(data)?;
+            let signature = schnorrsig::Signature::from_slice(signature)?;
+            let key = schnorrsig::PublicKey::from_slice(&self.to_x_only())?;
+            SCHNORRSIG_ENGINE.schnorrsig_verify(&signature, &msg, &key)
+        };
+        verify().is_ok()
+    }
 }
 
 impl Secp256k1PrivateKey {
     pub fn from_wif(wif: &str) -> Result<Self> {
         Secp256k1PrivateKey::from_ss58check(wif)
     }
+
+    pub(crate) fn from_secret_key(key: &secp256k1::SecretKey, compressed: bool) -> Self {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        secret.copy_from_slice(&key[..]);
+        Secp256k1PrivateKey { secret, compressed }
+    }
+
+    /// The array always holds a valid secret key
+    pub(crate) fn secret_key(&self) -> secp256k1::SecretKey {
+        secp256k1::SecretKey::from_slice(&self.secret[..]).expect("secret_key")
+    }
+
+    /// The private key of the taproot output key, see `Secp256k1PublicKey::tap_tweak`
+    pub fn tap_tweak(&self) -> Result<Self> {
+        let pub_key = self.public_key();
+        let mut key = self.even_y_key();
+        let tweak = tagged_hash("TapTweak", &pub_key.to_x_only());
+        key.add_assign(&tweak).map_err(transform_secp256k1_error)?;
+        Ok(Secp256k1PrivateKey::from_secret_key(&key, true))
+    }
+
+    /// BIP-340 schnorr signature of libsecp256k1, the auxiliary random data is fixed to zero
+    /// bytes so the signature is deterministic
+    /// ref: https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki
+    pub fn sign_schnorr(&self, data: &[u8]) -> Result<Vec<u8>> {
+        self.sign_schnorr_with_aux_rand(data, &[0u8; 32])
+    }
+
+    fn sign_schnorr_with_aux_rand(&self, data: &[u8], aux_rand: &[u8; 32]) -> Result<Vec<u8>> {
+        let msg = secp256k1_schnorrsig::Message::from_slice(data)
+            .map_err(|_| KeyError::InvalidMessage)?;
+        let key_pair = schnorrsig::KeyPair::from_seckey_slice(&SCHNORRSIG_ENGINE, &self.secret[..])
+            .map_err(|_| KeyError::InvalidPrivateKey)?;
+        let signature = SCHNORRSIG_ENGINE.schnorrsig_sign_with_aux_rand(&msg, &key_pair, aux_rand);
+        Ok(signature[..].to_vec())
+    }
+
+    /// The x coordinate of the ECDH shared point with the public key of the other party
+    pub fn diffie_hellman(&self, public_key: &Secp256k1PublicKey) -> Result<[u8; 32]> {
+        let mut point = public_key.0.key;
+        point
+            .mul_assign(&SECP256K1_ENGINE, &self.secret[..])
+            .map_err(transform_secp256k1_error)?;
+        let mut shared = [0u8; 32];
+        shared.copy_from_slice(&point.serialize()[1..]);
+        Ok(shared)
+    }
+
+    /// BIP-340 signs with the key whose public key has an even y coordinate
+    fn even_y_key(&self) -> secp256k1::SecretKey {
+        let mut key = self.secret_key();
+        if self.public_key().to_compressed()[0] == 0x03 {
+            key.negate_assign();
+        }
+        key
+    }
+}
+
+/// The BIP-340 tagged hash, sha256(sha256(tag) || sha256(tag) || msg)
+pub fn tagged_hash(tag: &str, msg: &[u8]) -> Vec<u8> {
+    let tag_hash = sha256::Hash::hash(tag.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag_hash[..]);
+    engine.input(&tag_hash[..]);
+    engine.input(msg);
+    sha256::Hash::from_engine(engine).into_inner().to_vec()
 }
 
 impl TraitPrivateKey for Secp256k1PrivateKey {
@@ -64,33 +191,32 @@ impl TraitPrivateKey for Secp256k1PrivateKey {
 
     fn from_slice(data: &[u8]) -> Result<Self> {
         let key = secp256k1::SecretKey::from_slice(data).map_err(transform_secp256k1_error)?;
-        Ok(Secp256k1PrivateKey(PrivateKey {
-            key,
-            compressed: true,
-            network: Network::Bitcoin,
-        }))
+        Ok(Secp256k1PrivateKey::from_secret_key(&key, true))
     }
 
     fn public_key(&self) -> Self::PublicKey {
-        Secp256k1PublicKey(self.0.public_key(&SECP256K1_ENGINE))
+        Secp256k1PublicKey(PublicKey {
+            compressed: self.compressed,
+            key: secp256k1::PublicKey::from_secret_key(&SECP256K1_ENGINE, &self.secret_key()),
+        })
     }
 
     fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
         let msg = Message::from_slice(data).map_err(transform_secp256k1_error)?;
-        let signature = SECP256K1_ENGINE.sign(&msg, &self.0.key);
+        let signature = SECP256K1_ENGINE.sign(&msg, &self.secret_key());
         Ok(signature.serialize_der().to_vec())
     }
 
     fn sign_recoverable(&self, data: &[u8]) -> Result<Vec<u8>> {
         let msg = Message::from_slice(data).map_err(transform_secp256k1_error)?;
-        let signature = SECP256K1_ENGINE.sign_recoverable(&msg, &self.0.key);
+        let signature = SECP256K1_ENGINE.sign_recoverable(&msg, &self.secret_key());
         let (recover_id, sign) = signature.serialize_compact();
         let signed_bytes = [sign[..].to_vec(), vec![(recover_id.to_i32()) as u8]].concat();
         Ok(signed_bytes)
     }
 
     fn to_bytes(&self) -> Vec<u8> {
-        self.0.to_bytes()
+        self.secret.to_vec()
     }
 }
 
@@ -109,11 +235,35 @@ impl TraitPublicKey for Secp256k1PublicKey {
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_bytes()
     }
+
+    /// The signature is DER encoded like `sign`, or the 64 bytes compact one, or the 65 bytes
+    /// recoverable one like `sign_recoverable` whose recovered key must be this key
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        let msg = match Message::from_slice(data) {
+            Ok(msg) => msg,
+            Err(_) => return false,
+        };
+        let signature = match signature.len() {
+            65 => {
+                return Secp256k1PublicKey::recover(data, signature)
+                    .map(|pk| pk.0.key == self.0.key)
+                    .unwrap_or(false)
+            }
+            64 => Signature::from_compact(signature),
+            _ => Signature::from_der(signature),
+        };
+        match signature {
+            Ok(signature) => SECP256K1_ENGINE
+                .verify(&msg, &signature, &self.0.key)
+                .is_ok(),
+            Err(_) => false,
+        }
+    }
 }
 
 impl Ss58Codec for Secp256k1PrivateKey {
     fn from_ss58check_with_version(wif: &str) -> Result<(Self, Vec<u8>)> {
-        let data = base58::from_check(wif)?;
+        let data = Zeroizing::new(base58::from_check(wif)?);
 
         let compressed = match data.len() {
             33 => false,
@@ -123,20 +273,17 @@ impl Ss58Codec for Secp256k1PrivateKey {
             }
         };
 
-        let pk = Secp256k1PrivateKey(PrivateKey {
-            key: secp256k1::SecretKey::from_slice(&data[1..33])?,
-            compressed,
-            network: Network::Bitcoin,
-        });
+        let key = secp256k1::SecretKey::from_slice(&data[1..33])?;
+        let pk = Secp256k1PrivateKey::from_secret_key(&key, compressed);
 
         Ok((pk, vec![data[0]]))
     }
 
     fn to_ss58check_with_version(&self, version: &[u8]) -> String {
-        let mut ret = [0; 34];
+        let mut ret = Zeroizing::new([0; 34]);
         ret[0..1].copy_from_slice(&version[0..]);
-        ret[1..33].copy_from_slice(&self.0.key[..]);
-        if self.0.compressed {
+        ret[1..33].copy_from_slice(&self.secret[..]);
+        if self.compressed {
             ret[33] = 1;
             base58::check_encode_slice(&ret[..]).to_string()
         } else {
@@ -165,9 +312,9 @@ pub fn private_key_without_version(private_key: &str) -> Result<Vec<u8>> {
 #[cfg(test)]
 mod tests {
 
-    use super::{verify_private_key, Secp256k1PrivateKey, Ss58Codec};
+    use super::{verify_private_key, Secp256k1PrivateKey, Secp256k1PublicKey, Ss58Codec};
 
-    use crate::PrivateKey;
+    use crate::{PrivateKey, PublicKey};
 
     use bitcoin_hashes::hex::ToHex;
     use bitcoin_hashes::Hash;
@@ -219,6 +366,181 @@ mod tests {
         )
     }
 
+    #[test]
+    fn secp256k1_verify() {
+        let prv_key =
+            Secp256k1PrivateKey::from_wif("L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB")
+                .unwrap();
+        let pub_key = prv_key.public_key();
+        let hash = bitcoin_hashes::sha256::Hash::hash("TokenCoreX".as_bytes()).into_inner();
+        let other_hash = bitcoin_hashes::sha256::Hash::hash("imToken".as_bytes()).into_inner();
+
+        let der = prv_key.sign(&hash).unwrap();
+        assert!(pub_key.verify(&hash, &der));
+        assert!(!pub_key.verify(&other_hash, &der));
+        assert!(!pub_key.verify(&hash, &der[1..]));
+
+        let recoverable = prv_key.sign_recoverable(&hash).unwrap();
+        assert!(pub_key.verify(&hash, &recoverable));
+        assert!(pub_key.verify(&hash, &recoverable[..64]));
+        assert!(!pub_key.verify(&other_hash, &recoverable));
+        assert!(!pub_key.verify(&other_hash, &recoverable[..64]));
+        assert!(!pub_key.verify(&[0, 1, 2, 3], &recoverable));
+    }
+
+    #[test]
+    fn secp256k1_recover() {
+        let prv_key =
+            Secp256k1PrivateKey::from_wif("L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB")
+                .unwrap();
+        let hash = bitcoin_hashes::sha256::Hash::hash("TokenCoreX".as_bytes()).into_inner();
+        let mut signature = prv_key.sign_recoverable(&hash).unwrap();
+
+        let recovered = Secp256k1PublicKey::recover(&hash, &signature).unwrap();
+        assert_eq!(
+            recovered.to_compressed().to_hex(),
+            "02506bc1dc099358e5137292f4efdd57e400f29ba5132aa5d12b18dac1c1f6aaba"
+        );
+
+        signature[64] += 27;
+        let recovered = Secp256k1PublicKey::recover(&hash, &signature).unwrap();
+        assert_eq!(
+            recovered.to_compressed().to_hex(),
+            "02506bc1dc099358e5137292f4efdd57e400f29ba5132aa5d12b18dac1c1f6aaba"
+        );
+
+        let ret = Secp256k1PublicKey::recover(&hash, &signature[..64]);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_signature");
+        signature[64] = 4;
+        let ret = Secp256k1PublicKey::recover(&hash, &signature);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_recovery_id");
+    }
+
+    #[test]
+    fn secp256k1_sign_schnorr() {
+        // BIP-340 test vector 0
+        let prv_key = Secp256k1PrivateKey::from_slice(
+            &hex::decode("0000000000000000000000000000000000000000000000000000000000000003")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            prv_key.public_key().to_x_only().to_hex(),
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"
+        );
+        let signed_bytes = prv_key.sign_schnorr(&[0u8; 32]).unwrap();
+        assert_eq!(signed_bytes.to_hex(), "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0");
+        assert!(prv_key
+            .public_key()
+            .verify_schnorr(&[0u8; 32], &signed_bytes));
+        assert!(!prv_key
+            .public_key()
+            .verify_schnorr(&[1u8; 32], &signed_bytes));
+
+        let wrong_signed = prv_key.sign_schnorr(&[0, 1, 2, 3]);
+        assert_eq!(
+            format!("{}", wrong_signed.err().unwrap()),
+            "invalid_message"
+        )
+    }
+
+    #[test]
+    fn secp256k1_schnorr_test_vectors() {
+        // the signing cases of test-vectors.csv of BIP-340: secret key, public key, aux_rand,
+        // message and signature
+        let vectors = [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000003",
+                "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            ),
+            (
+                "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+                "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+                "0000000000000000000000000000000000000000000000000000000000000001",
+                "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+                "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            ),
+            (
+                "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+                "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+                "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+                "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+                "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+            ),
+            (
+                "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+                "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+            ),
+        ];
+        for (secret_key, public_key, aux_rand, msg, signature) in vectors.iter() {
+            let prv_key =
+                Secp256k1PrivateKey::from_slice(&hex::decode(secret_key).unwrap()).unwrap();
+            assert_eq!(&prv_key.public_key().to_x_only().to_hex(), public_key);
+
+            let mut aux = [0u8; 32];
+            aux.copy_from_slice(&hex::decode(aux_rand).unwrap());
+            let msg = hex::decode(msg).unwrap();
+            let signed_bytes = prv_key.sign_schnorr_with_aux_rand(&msg, &aux).unwrap();
+            assert_eq!(&signed_bytes.to_hex(), signature);
+            assert!(prv_key.public_key().verify_schnorr(&msg, &signed_bytes));
+        }
+    }
+
+    #[test]
+    fn secp256k1_verify_schnorr() {
+        // BIP-340 test vector 1, the key has the odd y coordinate
+        let pub_key = Secp256k1PublicKey::from_slice(
+            &hex::decode("03dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659")
+                .unwrap(),
+        )
+        .unwrap();
+        let msg = hex::decode("243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89")
+            .unwrap();
+        let mut signature = hex::decode("6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a").unwrap();
+        assert!(pub_key.verify_schnorr(&msg, &signature));
+
+        signature[63] ^= 1;
+        assert!(!pub_key.verify_schnorr(&msg, &signature));
+        assert!(!pub_key.verify_schnorr(&msg[1..], &signature));
+    }
+
+    #[test]
+    fn secp256k1_tap_tweak() {
+        // BIP-86 test vector, m/86'/0'/0'/0/0 of "abandon ... about"
+        let pub_key = Secp256k1PublicKey::from_slice(
+            &hex::decode("03cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            pub_key.tap_tweak().unwrap().to_hex(),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+    }
+
+    #[test]
+    fn secp256k1_diffie_hellman() {
+        let alice = Secp256k1PrivateKey::from_slice(&[1u8; 32]).unwrap();
+        let bob = Secp256k1PrivateKey::from_slice(&[2u8; 32]).unwrap();
+        assert_eq!(
+            hex::encode(bob.public_key().to_compressed()),
+            "024d4b6cd1361032ca9bd2aeb9d900aa4d45d9ead80ac9423374c451a7254d0766"
+        );
+
+        let shared = alice.diffie_hellman(&bob.public_key()).unwrap();
+        assert_eq!(
+            hex::encode(shared),
+            "d0158a38faf6118af133af12d9bfa388eab4a08d1a2088ea6e6ec1269e03567f"
+        );
+        assert_eq!(bob.diffie_hellman(&alice.public_key()).unwrap(), shared);
+    }
+
     #[test]
     fn private_key() {
         let private_key =
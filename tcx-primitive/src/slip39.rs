@@ -0,0 +1,943 @@
+//! SLIP-39: Shamir's Secret-Sharing for Mnemonic Codes
+//!
+//! https://github.com/satoshilabs/slips/blob/master/slip-0039.md
+use super::Result;
+
+use hmac::{Hmac, Mac, NewMac};
+use lazy_static::lazy_static;
+use pbkdf2::pbkdf2;
+use rand::{thread_rng, RngCore};
+use sha2::Sha256;
+use std::collections::{BTreeMap, HashMap};
+
+const RADIX_BITS: usize = 10;
+const ID_LENGTH_BITS: usize = 15;
+const EXTENDABLE_FLAG_LENGTH_BITS: usize = 1;
+const ITERATION_EXP_LENGTH_BITS: usize = 4;
+const METADATA_LENGTH_WORDS: usize = 4 + CHECKSUM_LENGTH_WORDS;
+const CHECKSUM_LENGTH_WORDS: usize = 3;
+const DIGEST_LENGTH_BYTES: usize = 4;
+const CUSTOMIZATION_STRING: &[u8] = b"shamir";
+const EXTENDABLE_CUSTOMIZATION_STRING: &[u8] = b"shamir_extendable";
+const MAX_SHARE_COUNT: u8 = 16;
+const MIN_STRENGTH_BYTES: usize = 16;
+const MIN_MNEMONIC_LENGTH_WORDS: usize = METADATA_LENGTH_WORDS + 13;
+const BASE_ITERATION_COUNT: u32 = 10000;
+const ROUND_COUNT: u8 = 4;
+const SECRET_INDEX: u8 = 255;
+const DIGEST_INDEX: u8 = 254;
+
+/// The exponent used by trezor firmware, the PBKDF2 runs 20000 iterations in total
+pub const DEFAULT_ITERATION_EXPONENT: u8 = 1;
+/// The iteration exponent takes 4 bits of the share
+pub const MAX_ITERATION_EXPONENT: u8 = (1 << ITERATION_EXP_LENGTH_BITS) - 1;
+
+const RS1024_GEN: [u32; 10] = [
+    0xE0_E040,
+    0x1C1_C080,
+    0x383_8100,
+    0x707_0200,
+    0xE0E_0009,
+    0x1C0C_2412,
+    0x3808_6C24,
+    0x3090_FC48,
+    0x21B1_F890,
+    0x3F3_F120,
+];
+
+lazy_static! {
+    static ref WORDS: Vec<&'static str> = include_str!("slip39_wordlist.txt").lines().collect();
+    static ref WORD_INDEXES: HashMap<&'static str, u16> = WORDS
+        .iter()
+        .enumerate()
+        .map(|(idx, word)| (*word, idx as u16))
+        .collect();
+    /// Exp and log tables of GF(256) over the Rijndael polynomial x^8 + x^4 + x^3 + x + 1
+    static ref GF256_TABLES: ([u8; 255], [u8; 256]) = {
+        let mut exp = [0u8; 255];
+        let mut log = [0u8; 256];
+        let mut poly: u16 = 1;
+        for i in 0..255 {
+            exp[i] = poly as u8;
+            log[poly as usize] = i as u8;
+            poly = (poly << 1) ^ poly;
+            if poly & 0x100 != 0 {
+                poly ^= 0x11B;
+            }
+        }
+        (exp, log)
+    };
+}
+
+#[derive(Fail, Debug, PartialEq)]
+pub enum Slip39Error {
+    #[fail(display = "invalid_share_mnemonic")]
+    InvalidShareMnemonic,
+    #[fail(display = "invalid_share_checksum")]
+    InvalidShareChecksum,
+    #[fail(display = "invalid_share_digest")]
+    InvalidShareDigest,
+    #[fail(display = "share_mismatch")]
+    ShareMismatch,
+    #[fail(display = "insufficient_shares")]
+    InsufficientShares,
+    #[fail(display = "invalid_share_threshold")]
+    InvalidThreshold,
+    #[fail(display = "invalid_master_secret_length")]
+    InvalidMasterSecretLength,
+    #[fail(display = "invalid_iteration_exponent")]
+    InvalidIterationExponent,
+    #[fail(display = "invalid_share_passphrase")]
+    InvalidPassphrase,
+    #[fail(display = "invalid_share_group_index")]
+    InvalidGroupIndex,
+}
+
+/// The extendable backups take another customization string of the checksum
+fn customization_string(extendable: bool) -> &'static [u8] {
+    if extendable {
+        EXTENDABLE_CUSTOMIZATION_STRING
+    } else {
+        CUSTOMIZATION_STRING
+    }
+}
+
+/// One share mnemonic decoded
+#[derive(Debug, Clone, PartialEq)]
+struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Vec<u8>,
+}
+
+impl Share {
+    fn from_mnemonic(mnemonic: &str) -> Result<Share> {
+        let indexes = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                WORD_INDEXES
+                    .get(word.to_lowercase().as_str())
+                    .map(|idx| *idx as u32)
+                    .ok_or(Slip39Error::InvalidShareMnemonic)
+            })
+            .collect::<std::result::Result<Vec<u32>, Slip39Error>>()?;
+
+        if indexes.len() < MIN_MNEMONIC_LENGTH_WORDS {
+            return Err(Slip39Error::InvalidShareMnemonic.into());
+        }
+
+        // the secret is an even number of bytes, so the padding never exceeds 8 bits
+        let padding_bits = (RADIX_BITS * (indexes.len() - METADATA_LENGTH_WORDS)) % 16;
+        if padding_bits > 8 {
+            return Err(Slip39Error::InvalidShareMnemonic.into());
+        }
+
+        let metadata = indexes[..4]
+            .iter()
+            .fold(0u64, |acc, idx| (acc << RADIX_BITS) | *idx as u64);
+        let field = |offset: usize, bits: usize| ((metadata >> offset) & ((1 << bits) - 1)) as u8;
+        let extendable = field(20 + ITERATION_EXP_LENGTH_BITS, EXTENDABLE_FLAG_LENGTH_BITS) == 1;
+
+        if rs1024_polymod(customization_string(extendable), &indexes) != 1 {
+            return Err(Slip39Error::InvalidShareChecksum.into());
+        }
+
+        // the exponent never exceeds MAX_ITERATION_EXPONENT as it takes 4 bits
+        let share = Share {
+            identifier: (metadata >> (40 - ID_LENGTH_BITS)) as u16,
+            extendable,
+            iteration_exponent: field(20, ITERATION_EXP_LENGTH_BITS),
+            group_index: field(16, 4),
+            group_threshold: field(12, 4) + 1,
+            group_count: field(8, 4) + 1,
+            member_index: field(4, 4),
+            member_threshold: field(0, 4) + 1,
+            value: words_to_bytes(
+                &indexes[4..indexes.len() - CHECKSUM_LENGTH_WORDS],
+                padding_bits,
+            )?,
+        };
+
+        if share.group_count < share.group_threshold {
+            return Err(Slip39Error::InvalidThreshold.into());
+        }
+        if share.group_index >= share.group_count {
+            return Err(Slip39Error::InvalidGroupIndex.into());
+        }
+        if share.value.len() < MIN_STRENGTH_BYTES {
+            return Err(Slip39Error::InvalidMasterSecretLength.into());
+        }
+        Ok(share)
+    }
+
+    fn to_mnemonic(&self) -> String {
+        let metadata = (self.identifier as u64) << (40 - ID_LENGTH_BITS)
+            | (self.extendable as u64) << (20 + ITERATION_EXP_LENGTH_BITS)
+            | (self.iteration_exponent as u64) << 20
+            | (self.group_index as u64) << 16
+            | (self.group_threshold as u64 - 1) << 12
+            | (self.group_count as u64 - 1) << 8
+            | (self.member_index as u64) << 4
+            | (self.member_threshold as u64 - 1);
+
+        let mut indexes = (0..4)
+            .rev()
+            .map(|i| ((metadata >> (i * RADIX_BITS)) & 0x3FF) as u32)
+            .collect::<Vec<u32>>();
+        indexes.extend(bytes_to_words(&self.value));
+
+        let mut checksum_data = indexes.clone();
+        checksum_data.extend(&[0; CHECKSUM_LENGTH_WORDS]);
+        let checksum = rs1024_polymod(customization_string(self.extendable), &checksum_data) ^ 1;
+        indexes.extend(
+            (0..CHECKSUM_LENGTH_WORDS)
+                .rev()
+                .map(|i| (checksum >> (i * RADIX_BITS)) & 0x3FF),
+        );
+
+        indexes
+            .iter()
+            .map(|idx| WORDS[*idx as usize])
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+}
+
+fn rs1024_polymod(customization_string: &[u8], values: &[u32]) -> u32 {
+    customization_string
+        .iter()
+        .map(|b| *b as u32)
+        .chain(values.iter().cloned())
+        .fold(1u32, |chk, value| {
+            let top = chk >> 20;
+            let mut chk = ((chk & 0xF_FFFF) << 10) ^ value;
+            for (i, gen) in RS1024_GEN.iter().enumerate() {
+                if (top >> i) & 1 == 1 {
+                    chk ^= gen;
+                }
+            }
+            chk
+        })
+}
+
+/// The value is left padded with zero bits to fit into the 10 bits words
+fn bytes_to_words(value: &[u8]) -> Vec<u32> {
+    let padding_bits = (RADIX_BITS - (value.len() * 8) % RADIX_BITS) % RADIX_BITS;
+    let mut words = vec![];
+    let mut acc: u32 = 0;
+    let mut bits = padding_bits;
+    for byte in value {
+        acc = (acc << 8) | *byte as u32;
+        bits += 8;
+        while bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push(acc >> bits);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    words
+}
+
+fn words_to_bytes(words: &[u32], padding_bits: usize) -> Result<Vec<u8>> {
+    let mut value = vec![];
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut padding_bits = padding_bits;
+    for word in words {
+        acc = (acc << RADIX_BITS) | word;
+        bits += RADIX_BITS;
+        if padding_bits > 0 {
+            bits -= padding_bits;
+            if acc >> bits != 0 {
+                return Err(Slip39Error::InvalidShareMnemonic.into());
+            }
+            padding_bits = 0;
+        }
+        while bits >= 8 {
+            bits -= 8;
+            value.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(value)
+}
+
+/// Evaluate the polynomial going through all of the shares at x by Lagrange interpolation
+fn interpolate(shares: &[(u8, Vec<u8>)], x: u8) -> Vec<u8> {
+    if let Some((_, value)) = shares.iter().find(|(share_x, _)| *share_x == x) {
+        return value.clone();
+    }
+
+    let (exp, log) = &*GF256_TABLES;
+    let log_prod: usize = shares
+        .iter()
+        .map(|(share_x, _)| log[(share_x ^ x) as usize] as usize)
+        .sum();
+
+    let mut result = vec![0u8; shares[0].1.len()];
+    for (share_x, value) in shares {
+        let log_denominator: usize = log[(share_x ^ x) as usize] as usize
+            + shares
+                .iter()
+                .filter(|(other_x, _)| other_x != share_x)
+                .map(|(other_x, _)| log[(share_x ^ other_x) as usize] as usize)
+                .sum::<usize>();
+        let log_basis = (log_prod + 255 * shares.len() - log_denominator) % 255;
+
+        for (r, v) in result.iter_mut().zip(value.iter()) {
+            if *v != 0 {
+                *r ^= exp[(log[*v as usize] as usize + log_basis) % 255];
+            }
+        }
+    }
+    result
+}
+
+fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; len];
+    thread_rng().fill_bytes(&mut bytes);
+    bytes
+}
+
+fn create_digest(random: &[u8], secret: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_varkey(random).expect("hmac accepts any key length");
+    mac.update(secret);
+    mac.finalize().into_bytes()[..DIGEST_LENGTH_BYTES].to_vec()
+}
+
+fn split_secret(threshold: u8, share_count: u8, secret: &[u8]) -> Result<Vec<(u8, Vec<u8>)>> {
+    if threshold < 1 || threshold > share_count || share_count > MAX_SHARE_COUNT {
+        return Err(Slip39Error::InvalidThreshold.into());
+    }
+
+    if threshold == 1 {
+        return Ok((0..share_count).map(|i| (i, secret.to_vec())).collect());
+    }
+
+    let random_share_count = threshold - 2;
+    let mut shares = (0..random_share_count)
+        .map(|i| (i, random_bytes(secret.len())))
+        .collect::<Vec<(u8, Vec<u8>)>>();
+
+    let random_part = random_bytes(secret.len() - DIGEST_LENGTH_BYTES);
+    let mut digest = create_digest(&random_part, secret);
+    digest.extend(random_part);
+
+    let mut base_shares = shares.clone();
+    base_shares.push((DIGEST_INDEX, digest));
+    base_shares.push((SECRET_INDEX, secret.to_vec()));
+
+    for i in random_share_count..share_count {
+        shares.push((i, interpolate(&base_shares, i)));
+    }
+    Ok(shares)
+}
+
+fn recover_secret(threshold: u8, shares: &[(u8, Vec<u8>)]) -> Result<Vec<u8>> {
+    if threshold == 1 {
+        return Ok(shares[0].1.clone());
+    }
+
+    let secret = interpolate(shares, SECRET_INDEX);
+    let digest = interpolate(shares, DIGEST_INDEX);
+    if digest[..DIGEST_LENGTH_BYTES] != create_digest(&digest[DIGEST_LENGTH_BYTES..], &secret)[..] {
+        return Err(Slip39Error::InvalidShareDigest.into());
+    }
+    Ok(secret)
+}
+
+/// The four rounds Feistel network, decryption is running the rounds in reverse order. The
+/// identifier is not in the salt of an extendable backup, so its shares can be added later
+fn feistel(
+    secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    encrypt: bool,
+) -> Result<Vec<u8>> {
+    let half = secret.len() / 2;
+    let mut left = secret[..half].to_vec();
+    let mut right = secret[half..].to_vec();
+
+    let salt_prefix = if extendable {
+        vec![]
+    } else {
+        let mut salt_prefix = CUSTOMIZATION_STRING.to_vec();
+        salt_prefix.extend(&identifier.to_be_bytes());
+        salt_prefix
+    };
+    let iterations = 1u32
+        .checked_shl(iteration_exponent as u32)
+        .filter(|_| iteration_exponent <= MAX_ITERATION_EXPONENT)
+        .and_then(|factor| (BASE_ITERATION_COUNT / ROUND_COUNT as u32).checked_mul(factor))
+        .ok_or(Slip39Error::InvalidIterationExponent)?;
+
+    let rounds: Vec<u8> = if encrypt {
+        (0..ROUND_COUNT).collect()
+    } else {
+        (0..ROUND_COUNT).rev().collect()
+    };
+    for i in rounds {
+        let mut password = vec![i];
+        password.extend(passphrase);
+        let mut salt = salt_prefix.clone();
+        salt.extend(&right);
+
+        let mut f = vec![0u8; right.len()];
+        pbkdf2::<Hmac<Sha256>>(&password, &salt, iterations, &mut f);
+
+        let next_right = left.iter().zip(f.iter()).map(|(l, f)| l ^ f).collect();
+        left = right;
+        right = next_right;
+    }
+
+    right.extend(left);
+    Ok(right)
+}
+
+/// Only printable ASCII characters are allowed in the SLIP-39 passphrase
+fn validate_passphrase(passphrase: &str) -> Result<()> {
+    if passphrase.bytes().all(|b| (32..=126).contains(&b)) {
+        Ok(())
+    } else {
+        Err(Slip39Error::InvalidPassphrase.into())
+    }
+}
+
+/// Split the master secret into groups of share mnemonics, any `group_threshold` of the groups
+/// with `member_threshold` of its members recover the secret. Groups are given as the pairs of
+/// (member_threshold, member_count).
+pub fn generate_shares(
+    group_threshold: u8,
+    groups: &[(u8, u8)],
+    master_secret: &[u8],
+    passphrase: &str,
+    iteration_exponent: u8,
+    extendable: bool,
+) -> Result<Vec<Vec<String>>> {
+    if iteration_exponent > MAX_ITERATION_EXPONENT {
+        return Err(Slip39Error::InvalidIterationExponent.into());
+    }
+    validate_passphrase(passphrase)?;
+    validate_groups(group_threshold, groups, master_secret)?;
+
+    let identifier = (thread_rng().next_u32() & ((1 << ID_LENGTH_BITS) - 1)) as u16;
+    let encrypted_secret = feistel(
+        master_secret,
+        passphrase.as_bytes(),
+        iteration_exponent,
+        identifier,
+        extendable,
+        true,
+    )?;
+
+    let template = Share {
+        identifier,
+        extendable,
+        iteration_exponent,
+        group_index: 0,
+        group_threshold,
+        group_count: groups.len() as u8,
+        member_index: 0,
+        member_threshold: 0,
+        value: vec![],
+    };
+    split_into_mnemonics(&template, groups, &encrypted_secret)
+}
+
+/// Recover the master secret from share mnemonics, shares beyond the thresholds are ignored
+pub fn combine_shares(mnemonics: &[String], passphrase: &str) -> Result<Vec<u8>> {
+    validate_passphrase(passphrase)?;
+    let (first, encrypted_secret) = combine_mnemonics(mnemonics)?;
+    feistel(
+        &encrypted_secret,
+        passphrase.as_bytes(),
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+        false,
+    )
+}
+
+fn validate_groups(group_threshold: u8, groups: &[(u8, u8)], secret: &[u8]) -> Result<()> {
+    if secret.len() < MIN_STRENGTH_BYTES || secret.len() % 2 != 0 {
+        return Err(Slip39Error::InvalidMasterSecretLength.into());
+    }
+    if groups.len() > MAX_SHARE_COUNT as usize
+        || group_threshold < 1
+        || group_threshold as usize > groups.len()
+    {
+        return Err(Slip39Error::InvalidThreshold.into());
+    }
+    // a member threshold of 1 makes every member a copy of the group secret
+    if groups.iter().any(|(threshold, count)| {
+        *threshold < 1
+            || threshold > count
+            || *count > MAX_SHARE_COUNT
+            || (*threshold == 1 && *count > 1)
+    }) {
+        return Err(Slip39Error::InvalidThreshold.into());
+    }
+    Ok(())
+}
+
+/// Split the secret into the groups, then every group secret into the member shares
+fn split_into_mnemonics(
+    template: &Share,
+    groups: &[(u8, u8)],
+    secret: &[u8],
+) -> Result<Vec<Vec<String>>> {
+    let group_shares = split_secret(template.group_threshold, groups.len() as u8, secret)?;
+    group_shares
+        .iter()
+        .zip(groups.iter())
+        .map(
+            |((group_index, group_secret), (member_threshold, member_count))| {
+                let member_shares = split_secret(*member_threshold, *member_count, group_secret)?;
+                Ok(member_shares
+                    .into_iter()
+                    .map(|(member_index, value)| {
+                        Share {
+                            group_index: *group_index,
+                            member_index,
+                            member_threshold: *member_threshold,
+                            value,
+                            ..template.clone()
+                        }
+                        .to_mnemonic()
+                    })
+                    .collect())
+            },
+        )
+        .collect()
+}
+
+/// Recover the group secrets from the members, then the secret from the groups. The secret is
+/// returned along with the first share, which carries the parameters of the backup
+fn combine_mnemonics(mnemonics: &[String]) -> Result<(Share, Vec<u8>)> {
+    let shares = mnemonics
+        .iter()
+        .map(String::as_str)
+        .map(Share::from_mnemonic)
+        .collect::<Result<Vec<Share>>>()?;
+
+    let first = shares.first().ok_or(Slip39Error::InsufficientShares)?;
+    if shares.iter().any(|share| {
+        share.identifier != first.identifier
+            || share.extendable != first.extendable
+            || share.iteration_exponent != first.iteration_exponent
+            || share.group_threshold != first.group_threshold
+            || share.group_count != first.group_count
+            || share.value.len() != first.value.len()
+    }) {
+        return Err(Slip39Error::ShareMismatch.into());
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in &shares {
+        let members = groups.entry(share.group_index).or_insert_with(Vec::new);
+        if let Some(member) = members
+            .iter()
+            .find(|member| member.member_index == share.member_index)
+        {
+            if member.value != share.value {
+                return Err(Slip39Error::ShareMismatch.into());
+            }
+            continue;
+        }
+        if members
+            .iter()
+            .any(|member| member.member_threshold != share.member_threshold)
+        {
+            return Err(Slip39Error::ShareMismatch.into());
+        }
+        members.push(share);
+    }
+
+    let group_secrets = groups
+        .iter()
+        .filter(|(_, members)| members.len() >= members[0].member_threshold as usize)
+        .take(first.group_threshold as usize)
+        .map(|(group_index, members)| {
+            let threshold = members[0].member_threshold;
+            let member_shares = members[..threshold as usize]
+                .iter()
+                .map(|member| (member.member_index, member.value.clone()))
+                .collect::<Vec<(u8, Vec<u8>)>>();
+            Ok((*group_index, recover_secret(threshold, &member_shares)?))
+        })
+        .collect::<Result<Vec<(u8, Vec<u8>)>>>()?;
+
+    if group_secrets.len() < first.group_threshold as usize {
+        return Err(Slip39Error::InsufficientShares.into());
+    }
+
+    let secret = recover_secret(first.group_threshold, &group_secrets)?;
+    Ok((first.clone(), secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        combine_shares, feistel, generate_shares, Share, Slip39Error, MAX_ITERATION_EXPONENT, WORDS,
+    };
+
+    fn mnemonics(shares: &[&str]) -> Vec<String> {
+        shares.iter().map(|share| share.to_string()).collect()
+    }
+
+    #[test]
+    fn test_word_list() {
+        assert_eq!(WORDS.len(), 1024);
+        assert_eq!(WORDS[0], "academic");
+        assert_eq!(WORDS[1023], "zero");
+    }
+
+    /// The official test vectors of SLIP-39 which pass the checksum of every share, the passphrase
+    /// is "TREZOR"
+    #[test]
+    fn test_combine_spec_vectors() {
+        let vectors: Vec<(&str, Vec<&str>, std::result::Result<&str, Slip39Error>)> = vec![
+            (
+                "1. Valid mnemonic without sharing (128 bits)",
+                vec![
+                    "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision keyboard",
+                ],
+                Ok("bb54aac4b89dc868ba37d9cc21b2cece"),
+            ),
+            (
+                "2. Mnemonic with invalid checksum (128 bits)",
+                vec![
+                    "duckling enlarge academic academic agency result length solution fridge kidney coal piece deal husband erode duke ajar critical decision kidney",
+                ],
+                Err(Slip39Error::InvalidShareChecksum),
+            ),
+            (
+                "3. Mnemonic with invalid padding (128 bits)",
+                vec![
+                    "duckling enlarge academic academic email result length solution fridge kidney coal piece deal husband erode duke ajar music cargo fitness",
+                ],
+                Err(Slip39Error::InvalidShareMnemonic),
+            ),
+            (
+                "4. Basic sharing 2-of-3 (128 bits)",
+                vec![
+                    "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+                    "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+                ],
+                Ok("b43ceb7e57a0ea8766221624d01b0864"),
+            ),
+            (
+                "5. Basic sharing 2-of-3 (128 bits)",
+                vec![
+                    "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+                ],
+                Err(Slip39Error::InsufficientShares),
+            ),
+            (
+                "6. Mnemonics with different identifiers (128 bits)",
+                vec![
+                    "adequate smoking academic acid debut wine petition glen cluster slow rhyme slow simple epidemic rumor junk tracks treat olympic tolerate",
+                    "adequate stay academic agency agency formal party ting frequent learn upstairs remember smear leaf damage anatomy ladle market hush corner",
+                ],
+                Err(Slip39Error::ShareMismatch),
+            ),
+            (
+                "7. Mnemonics with different iteration exponents (128 bits)",
+                vec![
+                    "peasant leaves academic acid desert exact olympic math alive axle trial tackle drug deny decent smear dominant desert bucket remind",
+                    "peasant leader academic agency cultural blessing percent network envelope medal junk primary human pumps jacket fragment payroll ticket evoke voice",
+                ],
+                Err(Slip39Error::ShareMismatch),
+            ),
+            (
+                "9. Mnemonics with mismatching group counts (128 bits)",
+                vec![
+                    "average senior academic leaf broken teacher expect surface hour capture obesity desire negative dynamic dominant pistol mineral mailman iris aide",
+                    "average senior academic agency curious pants blimp spew clothes slice script dress wrap firm shaft regular slavery negative theater roster",
+                ],
+                Err(Slip39Error::ShareMismatch),
+            ),
+            (
+                "10. Mnemonics with greater group threshold than group counts (128 bits)",
+                vec![
+                    "music husband acrobat acid artist finance center either graduate swimming object bike medical clothes station aspect spider maiden bulb welcome",
+                    "music husband acrobat agency advance hunting bike corner density careful material civil evil tactics remind hawk discuss hobo voice rainbow",
+                    "music husband beard academic black tricycle clock mayor estimate level photo episode exclude ecology papa source amazing salt verify divorce",
+                ],
+                Err(Slip39Error::InvalidThreshold),
+            ),
+            (
+                "12. Mnemonics with mismatching member thresholds (128 bits)",
+                vec![
+                    "hour painting academic academic device formal evoke guitar random modern justice filter withdraw trouble identify mailman insect general cover oven",
+                    "hour painting academic agency artist again daisy capital beaver fiber much enjoy suitable symbolic identify photo editor romp float echo",
+                ],
+                Err(Slip39Error::ShareMismatch),
+            ),
+            (
+                "13. Mnemonics giving an invalid digest (128 bits)",
+                vec![
+                    "guilt walnut academic acid deliver remove equip listen vampire tactics nylon rhythm failure husband fatigue alive blind enemy teaspoon rebound",
+                    "guilt walnut academic agency brave hamster hobo declare herd taste alpha slim criminal mild arcade formal romp branch pink ambition",
+                ],
+                Err(Slip39Error::InvalidShareDigest),
+            ),
+            (
+                "14. Insufficient number of groups (128 bits, case 1)",
+                vec![
+                    "eraser senior beard romp adorn nuclear spill corner cradle style ancient family general leader ambition exchange unusual garlic promise voice",
+                ],
+                Err(Slip39Error::InsufficientShares),
+            ),
+            (
+                "15. Insufficient number of groups (128 bits, case 2)",
+                vec![
+                    "eraser senior ceramic snake clay various huge numb argue hesitate auction category timber browser greatest hanger petition script leaf pickup",
+                    "eraser senior ceramic shaft dynamic become junior wrist silver peasant force math alto coal amazing segment yelp velvet image paces",
+                    "eraser senior ceramic round column hawk trust auction smug shame alive greatest sheriff living perfect corner chest sled fumes adequate",
+                ],
+                Err(Slip39Error::InsufficientShares),
+            ),
+            (
+                "16. Threshold number of groups, but insufficient number of members in one group (128 bits)",
+                vec![
+                    "eraser senior decision shadow artist work morning estate greatest pipeline plan ting petition forget hormone flexible general goat admit surface",
+                    "eraser senior beard romp adorn nuclear spill corner cradle style ancient family general leader ambition exchange unusual garlic promise voice",
+                ],
+                Err(Slip39Error::InsufficientShares),
+            ),
+            (
+                "17. Threshold number of groups and members in each group (128 bits, case 1)",
+                vec![
+                    "eraser senior decision roster beard treat identify grumpy salt index fake aviation theater cubic bike cause research dragon emphasis counter",
+                    "eraser senior ceramic snake clay various huge numb argue hesitate auction category timber browser greatest hanger petition script leaf pickup",
+                    "eraser senior ceramic shaft dynamic become junior wrist silver peasant force math alto coal amazing segment yelp velvet image paces",
+                    "eraser senior decision shadow artist work morning estate greatest pipeline plan ting petition forget hormone flexible general goat admit surface",
+                    "eraser senior ceramic round column hawk trust auction smug shame alive greatest sheriff living perfect corner chest sled fumes adequate",
+                ],
+                Ok("7c3397a292a5941682d7a4ae2d898d11"),
+            ),
+            (
+                "18. Threshold number of groups and members in each group (128 bits, case 2)",
+                vec![
+                    "eraser senior decision smug corner ruin rescue cubic angel tackle skin skunk program roster trash rumor slush angel flea amazing",
+                    "eraser senior beard romp adorn nuclear spill corner cradle style ancient family general leader ambition exchange unusual garlic promise voice",
+                    "eraser senior decision roster beard treat identify grumpy salt index fake aviation theater cubic bike cause research dragon emphasis counter",
+                ],
+                Ok("7c3397a292a5941682d7a4ae2d898d11"),
+            ),
+            (
+                "19. Threshold number of groups and members in each group (128 bits, case 3)",
+                vec![
+                    "eraser senior beard romp adorn nuclear spill corner cradle style ancient family general leader ambition exchange unusual garlic promise voice",
+                    "eraser senior acrobat romp bishop medical gesture pumps secret alive ultimate quarter priest subject class dictate spew material endless market",
+                ],
+                Ok("7c3397a292a5941682d7a4ae2d898d11"),
+            ),
+            (
+                "20. Valid mnemonic without sharing (256 bits)",
+                vec![
+                    "theory painting academic academic armed sweater year military elder discuss acne wildlife boring employer fused large satoshi bundle carbon diagnose anatomy hamster leaves tracks paces beyond phantom capital marvel lips brave detect luck",
+                ],
+                Ok("989baf9dcaad5b10ca33dfd8cc75e42477025dce88ae83e75a230086a0e00e92"),
+            ),
+            (
+                "21. Mnemonic with invalid checksum (256 bits)",
+                vec![
+                    "theory painting academic academic armed sweater year military elder discuss acne wildlife boring employer fused large satoshi bundle carbon diagnose anatomy hamster leaves tracks paces beyond phantom capital marvel lips brave detect lunar",
+                ],
+                Err(Slip39Error::InvalidShareChecksum),
+            ),
+            (
+                "23. Basic sharing 2-of-3 (256 bits)",
+                vec![
+                    "humidity disease academic always aluminum jewelry energy woman receiver strategy amuse duckling lying evidence network walnut tactics forget hairy rebound impulse brother survive clothes stadium mailman rival ocean reward venture always armed unwrap",
+                    "humidity disease academic agency actress jacket gross physics cylinder solution fake mortgage benefit public busy prepare sharp friar change work slow purchase ruler again tricycle involve viral wireless mixture anatomy desert cargo upgrade",
+                ],
+                Ok("c938b319067687e990e05e0da0ecce1278f75ff58d9853f19dcaeed5de104aae"),
+            ),
+            (
+                "24. Basic sharing 2-of-3 (256 bits)",
+                vec![
+                    "humidity disease academic always aluminum jewelry energy woman receiver strategy amuse duckling lying evidence network walnut tactics forget hairy rebound impulse brother survive clothes stadium mailman rival ocean reward venture always armed unwrap",
+                ],
+                Err(Slip39Error::InsufficientShares),
+            ),
+            (
+                "40. Mnemonic with insufficient length",
+                vec![
+                    "junk necklace academic academic acne isolate join hesitate lunar roster dough calcium chemical ladybug amount mobile glasses verify cylinder",
+                ],
+                Err(Slip39Error::InvalidShareMnemonic),
+            ),
+            (
+                "41. Mnemonic with invalid master secret length",
+                vec![
+                    "fraction necklace academic academic award teammate mouse regular testify coding building member verdict purchase blind camera duration email prepare spirit quarter",
+                ],
+                Err(Slip39Error::InvalidShareMnemonic),
+            ),
+            (
+                "43. Valid extendable mnemonic without sharing (128 bits)",
+                vec![
+                    "testify swimming academic academic column loyalty smear include exotic bedroom exotic wrist lobe cover grief golden smart junior estimate learn",
+                ],
+                Ok("1679b4516e0ee5954351d288a838f45e"),
+            ),
+            (
+                "44. Extendable basic sharing 2-of-3 (128 bits)",
+                vec![
+                    "enemy favorite academic acid cowboy phrase havoc level response walnut budget painting inside trash adjust froth kitchen learn tidy punish",
+                    "enemy favorite academic always academic sniff script carpet romp kind promise scatter center unfair training emphasis evening belong fake enforce",
+                ],
+                Ok("48b1a4b80b8c209ad42c33672bdaa428"),
+            ),
+        ];
+
+        for (description, shares, expected) in vectors {
+            let ret = combine_shares(&mnemonics(&shares), "TREZOR");
+            match expected {
+                Ok(secret) => assert_eq!(hex::encode(ret.unwrap()), secret, "{}", description),
+                Err(err) => assert_eq!(
+                    ret.unwrap_err().downcast::<Slip39Error>().unwrap(),
+                    err,
+                    "{}",
+                    description
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_and_combine() {
+        let master_secret =
+            hex::decode("0c94ef4b8e1b4e1a0a0a4b3ea4c1e3b2bd4e5a33a4aef7d0c1a4b5c6d7e8f901")
+                .unwrap();
+        let groups = generate_shares(
+            2,
+            &[(1, 1), (2, 3), (3, 5)],
+            &master_secret,
+            "TREZOR",
+            0,
+            false,
+        )
+        .unwrap();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[1].len(), 3);
+        assert_eq!(groups[2].len(), 5);
+        assert_eq!(groups[2][0].split(' ').count(), 33);
+
+        let shares = vec![
+            groups[0][0].clone(),
+            groups[2][4].clone(),
+            groups[2][0].clone(),
+            groups[2][2].clone(),
+        ];
+        assert_eq!(combine_shares(&shares, "TREZOR").unwrap(), master_secret);
+        assert_ne!(combine_shares(&shares, "").unwrap(), master_secret);
+
+        let shares = vec![
+            groups[1][2].clone(),
+            groups[2][4].clone(),
+            groups[2][1].clone(),
+            groups[1][0].clone(),
+            groups[2][3].clone(),
+        ];
+        assert_eq!(combine_shares(&shares, "TREZOR").unwrap(), master_secret);
+
+        let shares = vec![
+            groups[1][2].clone(),
+            groups[2][4].clone(),
+            groups[2][1].clone(),
+        ];
+        let err = combine_shares(&shares, "TREZOR").unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InsufficientShares
+        );
+
+        let err = generate_shares(1, &[(1, 3)], &master_secret, "", 0, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidThreshold
+        );
+        let err = generate_shares(1, &[(2, 3)], &master_secret[..15], "", 0, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidMasterSecretLength
+        );
+        let err = generate_shares(2, &[(2, 3)], &master_secret, "", 0, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidThreshold
+        );
+        let err = generate_shares(1, &[(4, 3)], &master_secret, "", 0, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidThreshold
+        );
+        let err = generate_shares(1, &[(2, 3)], &master_secret, "", 16, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidIterationExponent
+        );
+    }
+
+    #[test]
+    fn test_extendable_shares() {
+        let master_secret = hex::decode("48b1a4b80b8c209ad42c33672bdaa428").unwrap();
+        let groups = generate_shares(1, &[(2, 3)], &master_secret, "TREZOR", 1, true).unwrap();
+        let shares = vec![groups[0][2].clone(), groups[0][0].clone()];
+        assert_eq!(combine_shares(&shares, "TREZOR").unwrap(), master_secret);
+
+        // the identifier is not in the salt of an extendable backup
+        assert_eq!(
+            feistel(&master_secret, b"TREZOR", 1, 1, true, true).unwrap(),
+            feistel(&master_secret, b"TREZOR", 1, 2, true, true).unwrap()
+        );
+        assert_ne!(
+            feistel(&master_secret, b"TREZOR", 1, 1, false, true).unwrap(),
+            feistel(&master_secret, b"TREZOR", 1, 2, false, true).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_share_metadata() {
+        let share = Share {
+            identifier: 7945,
+            extendable: false,
+            iteration_exponent: 0,
+            group_index: 2,
+            group_threshold: 1,
+            group_count: 2,
+            member_index: 0,
+            member_threshold: 1,
+            value: vec![0; 16],
+        };
+        let mnemonic = share.to_mnemonic();
+        let err = Share::from_mnemonic(&mnemonic).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidGroupIndex
+        );
+
+        let err = feistel(&[0; 16], b"", MAX_ITERATION_EXPONENT + 1, 0, false, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidIterationExponent
+        );
+        let err = feistel(&[0; 16], b"", 31, 0, false, true).unwrap_err();
+        assert_eq!(
+            err.downcast::<Slip39Error>().unwrap(),
+            Slip39Error::InvalidIterationExponent
+        );
+    }
+}
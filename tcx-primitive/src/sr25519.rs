@@ -2,7 +2,7 @@ use crate::ecc::{KeyError, PrivateKey as TraitPrivateKey, PublicKey as TraitPubl
 use crate::{FromHex, Result, ToHex};
 use schnorrkel::SecretKey;
 
-use sp_core::sr25519::{Pair, Public};
+use sp_core::sr25519::{Pair, Public, Signature};
 use sp_core::{Pair as TraitPair, Public as TraitPublic};
 
 //use sp_core::crypto::Ss58Codec;
@@ -69,6 +69,13 @@ impl TraitPublicKey for Sr25519PublicKey {
     fn to_bytes(&self) -> Vec<u8> {
         self.0.to_vec()
     }
+
+    fn verify(&self, data: &[u8], signature: &[u8]) -> bool {
+        if signature.len() != 64 {
+            return false;
+        }
+        Pair::verify(&Signature::from_slice(signature), data, &self.0)
+    }
 }
 
 impl ToHex for Sr25519PublicKey {
@@ -112,6 +119,16 @@ mod tests {
 
     #[test]
     fn test_sign_sr25519() {
-        // use integration test
+        let pk_bytes: Vec<u8> =
+            hex::decode("00ea01b0116da6ca425c477521fd49cc763988ac403ab560f4022936a18a4341016e7df1f5020068c9b150e0722fea65a264d5fbb342d4af4ddf2f1cdbddf1fd")
+                .unwrap();
+        let pk: Sr25519PrivateKey = Sr25519PrivateKey::from_slice(&pk_bytes).unwrap();
+        let public_key = pk.public_key();
+
+        // the signature is randomized, so only the verification is checked
+        let signature = pk.sign(b"imToken").unwrap();
+        assert!(public_key.verify(b"imToken", &signature));
+        assert!(!public_key.verify(b"TokenCoreX", &signature));
+        assert!(!public_key.verify(b"imToken", &signature[..63]));
     }
 }
@@ -31,6 +31,18 @@ impl Derive for Sr25519PublicKey {
     }
 }
 
+impl Sr25519PrivateKey {
+    pub fn from_mnemonic_with_passphrase(mnemonic: &str, passphrase: &str) -> Result<Self> {
+        let pair = Pair::from_phrase(mnemonic, Some(passphrase))
+            .map_err(|_| format_err!("mnemonic_error"))?;
+        Ok(Sr25519PrivateKey(pair.0))
+    }
+
+    pub fn from_entropy(entropy: &[u8], passphrase: &str) -> Self {
+        Sr25519PrivateKey(Pair::from_entropy(entropy, Some(passphrase)).0)
+    }
+}
+
 impl DeterministicPrivateKey for Sr25519PrivateKey {
     type DeterministicPublicKey = Sr25519PublicKey;
     type PrivateKey = Sr25519PrivateKey;
@@ -41,8 +53,7 @@ impl DeterministicPrivateKey for Sr25519PrivateKey {
     }
 
     fn from_mnemonic(mnemonic: &str) -> Result<Self> {
-        let pair = Pair::from_phrase(mnemonic, None).map_err(|_| format_err!("mnemonic_error"))?;
-        Ok(Sr25519PrivateKey(pair.0))
+        Self::from_mnemonic_with_passphrase(mnemonic, "")
     }
 
     fn private_key(&self) -> Self::PrivateKey {
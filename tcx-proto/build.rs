@@ -22,6 +22,10 @@ fn main() {
     env::set_var("OUT_DIR", "../tcx-tron/src");
     prost_build::compile_protos(&["src/tron.proto"], &["src/"]).unwrap();
 
+    // tcx-eth
+    env::set_var("OUT_DIR", "../tcx-eth/src");
+    prost_build::compile_protos(&["src/eth.proto"], &["src/"]).unwrap();
+
     // tcx-btc-fork
     env::set_var("OUT_DIR", "../tcx-btc-fork/src");
     prost_build::compile_protos(&["src/btc_fork.proto"], &["src/"]).unwrap();
@@ -45,4 +49,8 @@ fn main() {
     //tcx-tezos
     env::set_var("OUT_DIR", "../tcx-tezos/src");
     prost_build::compile_protos(&["src/tezos.proto"], &["src/"]).unwrap();
+
+    //tcx-nano
+    env::set_var("OUT_DIR", "../tcx-nano/src");
+    prost_build::compile_protos(&["src/nano.proto"], &["src/"]).unwrap();
 }
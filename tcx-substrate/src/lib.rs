@@ -6,7 +6,8 @@ mod transaction;
 pub use address::SubstrateAddress;
 pub use keystore::{decode_substrate_keystore, encode_substrate_keystore, SubstrateKeystore};
 pub use transaction::{
-    ExportSubstrateKeystoreResult, SubstrateKeystoreParam, SubstrateRawTxIn, SubstrateTxOut,
+    ExportSubstrateKeystoreResult, SubstrateKeystoreParam, SubstrateMessageInput,
+    SubstrateMessageOutput, SubstrateRawTxIn, SubstrateTxOut,
 };
 
 pub(crate) const SIGNATURE_TYPE_SR25519: u8 = 0x01;
@@ -1,9 +1,16 @@
-use crate::transaction::{SubstrateRawTxIn, SubstrateTxOut};
+use crate::transaction::{
+    SubstrateMessageInput, SubstrateMessageOutput, SubstrateRawTxIn, SubstrateTxOut,
+};
 use crate::{PAYLOAD_HASH_THRESHOLD, SIGNATURE_TYPE_SR25519};
 use sp_core::blake2_256;
+use sp_core::crypto::{Ss58AddressFormat, Ss58Codec};
+use sp_core::sr25519::Public;
 
-use tcx_chain::{ChainSigner, Keystore, TransactionSigner as TraitTransactionSigner};
-use tcx_constants::Result;
+use tcx_chain::{
+    ChainSigner, Keystore, MessageSigner, TransactionDecoder,
+    TransactionSigner as TraitTransactionSigner, TxRecipient, TxSummary,
+};
+use tcx_constants::{CoinInfo, Result};
 
 pub(crate) fn hash_unsigned_payload(payload: &[u8]) -> Result<Vec<u8>> {
     if payload.len() > PAYLOAD_HASH_THRESHOLD {
@@ -39,9 +46,119 @@ impl TraitTransactionSigner<SubstrateRawTxIn, SubstrateTxOut> for Keystore {
     }
 }
 
+const BYTES_PREFIX: &[u8] = b"<Bytes>";
+const BYTES_SUFFIX: &[u8] = b"</Bytes>";
+
+/// polkadot.js wraps the raw message so it never is a valid extrinsic payload
+pub(crate) fn wrap_bytes(message: &[u8]) -> Vec<u8> {
+    if message.starts_with(BYTES_PREFIX) && message.ends_with(BYTES_SUFFIX) {
+        message.to_vec()
+    } else {
+        [BYTES_PREFIX, message, BYTES_SUFFIX].concat()
+    }
+}
+
+impl MessageSigner<SubstrateMessageInput, SubstrateMessageOutput> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &SubstrateMessageInput,
+    ) -> Result<SubstrateMessageOutput> {
+        let data = if message.is_hex {
+            let value = if message.message.starts_with("0x") {
+                &message.message[2..]
+            } else {
+                &message.message
+            };
+            hex::decode(value)?
+        } else {
+            message.message.as_bytes().to_vec()
+        };
+
+        let sig = self.sign_recoverable_hash(&wrap_bytes(&data), symbol, address, None)?;
+        let sig_with_type = [vec![SIGNATURE_TYPE_SR25519], sig].concat();
+        Ok(SubstrateMessageOutput {
+            signature: format!("0x{}", hex::encode(sig_with_type)),
+        })
+    }
+}
+
+/// Decodes the SCALE compact integer at the beginning of the data
+fn decode_compact(data: &[u8]) -> Result<u128> {
+    let first = *data
+        .first()
+        .ok_or_else(|| format_err!("invalid_raw_data"))?;
+    let (start, len) = match first & 0b11 {
+        0b00 => return Ok((first >> 2) as u128),
+        0b01 => (0, 2),
+        0b10 => (0, 4),
+        _ => (1, (first >> 2) as usize + 4),
+    };
+    ensure!(len <= 16 && data.len() >= start + len, "invalid_raw_data");
+
+    let mut bytes = [0u8; 16];
+    bytes[..len].copy_from_slice(&data[start..start + len]);
+    let value = u128::from_le_bytes(bytes);
+    if start == 0 {
+        Ok(value >> 2)
+    } else {
+        Ok(value)
+    }
+}
+
+/// The call index of balances.transfer and balances.transfer_keep_alive
+fn is_balances_transfer(coin: &str, call_index: &[u8]) -> bool {
+    match coin {
+        "POLKADOT" => call_index == [0x05, 0x00] || call_index == [0x05, 0x03],
+        "KUSAMA" => call_index == [0x04, 0x00] || call_index == [0x04, 0x03],
+        _ => false,
+    }
+}
+
+impl TransactionDecoder for SubstrateRawTxIn {
+    /// Only the balances transfers are decoded, the fee is decided by the chain
+    fn decode_transaction(&self, coin: &CoinInfo) -> Result<TxSummary> {
+        let raw_data = if self.raw_data.starts_with("0x") {
+            &self.raw_data[2..]
+        } else {
+            &self.raw_data
+        };
+        let payload = hex::decode(raw_data)?;
+        ensure!(payload.len() >= 2, "invalid_raw_data");
+
+        let mut summary = TxSummary {
+            method: format!("0x{}", hex::encode(&payload[..2])),
+            ..TxSummary::default()
+        };
+        if !is_balances_transfer(&coin.coin, &payload[..2]) {
+            return Ok(summary);
+        }
+
+        // MultiAddress::Id or the legacy AccountId of the Indices lookup
+        ensure!(
+            payload.len() >= 35 && (payload[2] == 0x00 || payload[2] == 0xff),
+            "invalid_raw_data"
+        );
+        let mut account = [0u8; 32];
+        account.copy_from_slice(&payload[3..35]);
+        let format = match coin.coin.as_str() {
+            "KUSAMA" => Ss58AddressFormat::KusamaAccount,
+            _ => Ss58AddressFormat::PolkadotAccount,
+        };
+        summary.recipients.push(TxRecipient {
+            address: Public::from_raw(account).to_ss58check_with_version(format),
+            amount: decode_compact(&payload[35..])?.to_string(),
+            is_change: false,
+        });
+        Ok(summary)
+    }
+}
+
 #[cfg(test)]
 mod test_super {
     use super::*;
+    use tcx_constants::CurveType;
 
     #[test]
     fn test_payload_hash() {
@@ -57,6 +174,66 @@ mod test_super {
         }
     }
 
+    #[test]
+    fn test_wrap_bytes() {
+        assert_eq!(wrap_bytes(b"imToken"), b"<Bytes>imToken</Bytes>".to_vec());
+        assert_eq!(
+            wrap_bytes(b"<Bytes>imToken</Bytes>"),
+            b"<Bytes>imToken</Bytes>".to_vec()
+        );
+        assert_eq!(wrap_bytes(b""), b"<Bytes></Bytes>".to_vec());
+    }
+
+    #[test]
+    fn test_decode_compact() {
+        assert_eq!(decode_compact(&[0x04]).unwrap(), 1);
+        assert_eq!(decode_compact(&[0x15, 0x01]).unwrap(), 69);
+        assert_eq!(
+            decode_compact(&[0x02, 0x28, 0x6b, 0xee]).unwrap(),
+            1_000_000_000
+        );
+        assert_eq!(
+            decode_compact(&[0x0b, 0x00, 0x40, 0x7a, 0x10, 0xf3, 0x5a]).unwrap(),
+            100_000_000_000_000
+        );
+        assert!(decode_compact(&[0x02, 0x28]).is_err());
+    }
+
+    #[test]
+    fn test_decode_transaction() {
+        let coin_info = CoinInfo {
+            coin: "POLKADOT".to_string(),
+            derivation_path: "//polkadot//imToken/0".to_string(),
+            curve: CurveType::SubSr25519,
+            network: "".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let tx = SubstrateRawTxIn {
+            raw_data: "0x05030050780547322a1ceba67ea8c552c9bc6c686f8698ac9a8cafab7cd15a1db1985902286bee45010400".to_string(),
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.method, "0x0503");
+        assert_eq!(
+            summary.recipients[0].address,
+            "12pWV6LvG4iAfNpFNTvvkWy3H9H8wtCkjiXupAzo2BCmPViM"
+        );
+        assert_eq!(summary.recipients[0].amount, "1000000000");
+        assert_eq!(summary.fee, "");
+
+        let tx = SubstrateRawTxIn {
+            raw_data: "0x0701450104".to_string(),
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.method, "0x0701");
+        assert!(summary.recipients.is_empty());
+
+        let tx = SubstrateRawTxIn {
+            raw_data: "0x05030050780547322a1ceba67ea8c552".to_string(),
+        };
+        let ret = tx.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_raw_data");
+    }
+
     #[test]
     fn test_sign() {
         // check the integration test
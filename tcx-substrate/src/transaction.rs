@@ -24,3 +24,20 @@ pub struct SubstrateTxOut {
     #[prost(string, tag = "1")]
     pub signature: std::string::String,
 }
+/// FUNCTION: sign_message(SignParam{input: SubstrateMessageInput}): SubstrateMessageOutput
+///
+/// Sign the raw bytes like the `signRaw` of polkadot.js, the message is wrapped
+/// in `<Bytes>` and `</Bytes>` unless it's already wrapped
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubstrateMessageInput {
+    #[prost(string, tag = "1")]
+    pub message: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_hex: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SubstrateMessageOutput {
+    /// hex string of the signature with the sr25519 type prefix
+    #[prost(string, tag = "1")]
+    pub signature: std::string::String,
+}
@@ -1,8 +1,12 @@
-use crate::transaction::{TezosRawTxIn, TezosTxOut};
+use crate::transaction::{TezosMessageInput, TezosMessageOutput, TezosRawTxIn, TezosTxOut};
 use bitcoin::util::base58;
 use blake2b_simd::Params;
-use tcx_chain::{ChainSigner, Keystore, TransactionSigner as TraitTransactionSigner};
-use tcx_constants::Result;
+use failure::format_err;
+use tcx_chain::{
+    tcx_ensure, ChainSigner, Keystore, MessageSigner, TransactionDecoder,
+    TransactionSigner as TraitTransactionSigner, TxRecipient, TxSummary,
+};
+use tcx_constants::{CoinInfo, Result};
 
 impl TraitTransactionSigner<TezosRawTxIn, TezosTxOut> for Keystore {
     fn sign_transaction(
@@ -27,18 +31,367 @@ impl TraitTransactionSigner<TezosRawTxIn, TezosTxOut> for Keystore {
         let sign_result =
             self.sign_recoverable_hash(hash_result.as_bytes(), symbol, address, None)?;
 
-        //tezos ed25519 signature prefix
-        let edsig_prefix: [u8; 5] = [9, 245, 205, 134, 18];
-        let mut edsig_source_data = vec![];
-        edsig_source_data.extend(&edsig_prefix);
-        edsig_source_data.extend(sign_result.as_slice());
-
-        let sign_result_hex = hex::encode(sign_result);
+        let sign_result_hex = hex::encode(&sign_result);
         let tx_out = TezosTxOut {
             signature: sign_result_hex.clone(),
-            edsig: base58::check_encode_slice(edsig_source_data.as_slice()),
+            edsig: edsig(&sign_result),
             sbytes: format!("{}{}", tx.raw_data, sign_result_hex),
         };
         Ok(tx_out)
     }
 }
+
+fn edsig(signature: &[u8]) -> String {
+    //tezos ed25519 signature prefix
+    let edsig_prefix: [u8; 5] = [9, 245, 205, 134, 18];
+    let mut edsig_source_data = vec![];
+    edsig_source_data.extend(&edsig_prefix);
+    edsig_source_data.extend(signature);
+    base58::check_encode_slice(edsig_source_data.as_slice())
+}
+
+const PACKED_WATERMARK: u8 = 0x05;
+const MICHELINE_STRING_TAG: u8 = 0x01;
+
+/// Pack the message as the Micheline string like `PACK` of Michelson
+fn pack_string(message: &str) -> Vec<u8> {
+    let mut packed = vec![PACKED_WATERMARK, MICHELINE_STRING_TAG];
+    packed.extend(&(message.len() as u32).to_be_bytes());
+    packed.extend(message.as_bytes());
+    packed
+}
+
+impl MessageSigner<TezosMessageInput, TezosMessageOutput> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &TezosMessageInput,
+    ) -> Result<TezosMessageOutput> {
+        let payload = if message.is_hex {
+            let value = if message.message.starts_with("0x") {
+                &message.message[2..]
+            } else {
+                &message.message
+            };
+            hex::decode(value)?
+        } else {
+            pack_string(&message.message)
+        };
+        // the payload of the operation starts with 0x03, it must not be signed as a message
+        tcx_ensure!(
+            payload.first() == Some(&PACKED_WATERMARK),
+            format_err!("invalid_message")
+        );
+
+        let mut params = Params::new();
+        params.hash_length(32);
+        let hash_result = params.hash(payload.as_slice());
+        let sign_result =
+            self.sign_recoverable_hash(hash_result.as_bytes(), symbol, address, None)?;
+
+        Ok(TezosMessageOutput {
+            signature: hex::encode(&sign_result),
+            edsig: edsig(&sign_result),
+        })
+    }
+}
+
+const REVEAL_TAG: u8 = 0x6b;
+const TRANSACTION_TAG: u8 = 0x6c;
+const ORIGINATION_TAG: u8 = 0x6d;
+const DELEGATION_TAG: u8 = 0x6e;
+
+/// Reads the binary forged operation, ref: http://tezos.gitlab.io/shell/p2p_api.html
+struct ForgedReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ForgedReader<'a> {
+    fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        tcx_ensure!(
+            self.pos + len <= self.data.len(),
+            format_err!("invalid_raw_data")
+        );
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn byte(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn bool(&mut self) -> Result<bool> {
+        match self.byte()? {
+            0x00 => Ok(false),
+            0xff => Ok(true),
+            _ => Err(format_err!("invalid_raw_data")),
+        }
+    }
+
+    fn sized_bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.bytes(4)?;
+        let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        self.bytes(len)
+    }
+
+    /// zarith natural number in decimal
+    fn zarith(&mut self) -> Result<String> {
+        let mut value: u128 = 0;
+        let mut shift = 0;
+        loop {
+            let b = self.byte()?;
+            tcx_ensure!(shift < 126, format_err!("invalid_raw_data"));
+            value |= ((b & 0x7f) as u128) << shift;
+            shift += 7;
+            if b & 0x80 == 0 {
+                return Ok(value.to_string());
+            }
+        }
+    }
+
+    fn public_key_hash(&mut self) -> Result<String> {
+        let prefix: &[u8] = match self.byte()? {
+            0 => &[6, 161, 159],
+            1 => &[6, 161, 161],
+            2 => &[6, 161, 164],
+            _ => return Err(format_err!("invalid_raw_data")),
+        };
+        Ok(encode_address(prefix, self.bytes(20)?))
+    }
+
+    fn contract_id(&mut self) -> Result<String> {
+        match self.byte()? {
+            0 => self.public_key_hash(),
+            1 => {
+                let hash = self.bytes(20)?;
+                // padding
+                self.byte()?;
+                Ok(encode_address(&[2, 90, 121], hash))
+            }
+            _ => Err(format_err!("invalid_raw_data")),
+        }
+    }
+
+    fn public_key(&mut self) -> Result<()> {
+        let len = match self.byte()? {
+            0 => 32,
+            1 | 2 => 33,
+            _ => return Err(format_err!("invalid_raw_data")),
+        };
+        self.bytes(len)?;
+        Ok(())
+    }
+
+    fn entrypoint(&mut self) -> Result<String> {
+        let entrypoint = match self.byte()? {
+            0 => "default".to_string(),
+            1 => "root".to_string(),
+            2 => "do".to_string(),
+            3 => "set_delegate".to_string(),
+            4 => "remove_delegate".to_string(),
+            0xff => {
+                let len = self.byte()? as usize;
+                String::from_utf8(self.bytes(len)?.to_vec())
+                    .map_err(|_| format_err!("invalid_raw_data"))?
+            }
+            _ => return Err(format_err!("invalid_raw_data")),
+        };
+        Ok(entrypoint)
+    }
+}
+
+fn encode_address(prefix: &[u8], hash: &[u8]) -> String {
+    let mut data = prefix.to_vec();
+    data.extend_from_slice(hash);
+    base58::check_encode_slice(&data)
+}
+
+impl TransactionDecoder for TezosRawTxIn {
+    fn decode_transaction(&self, _coin: &CoinInfo) -> Result<TxSummary> {
+        let raw_data = if self.raw_data.starts_with("0x") {
+            &self.raw_data[2..]
+        } else {
+            &self.raw_data
+        };
+        let raw_data = hex::decode(raw_data)?;
+
+        let mut reader = ForgedReader {
+            data: &raw_data,
+            pos: 0,
+        };
+        // branch
+        reader.bytes(32)?;
+        tcx_ensure!(!reader.is_empty(), format_err!("invalid_raw_data"));
+
+        let mut summary = TxSummary::default();
+        let mut fee: u128 = 0;
+        let mut methods = vec![];
+        while !reader.is_empty() {
+            let tag = reader.byte()?;
+            tcx_ensure!(
+                [REVEAL_TAG, TRANSACTION_TAG, ORIGINATION_TAG, DELEGATION_TAG].contains(&tag),
+                format_err!("unsupported_operation")
+            );
+
+            let source = reader.public_key_hash()?;
+            if !summary.senders.contains(&source) {
+                summary.senders.push(source);
+            }
+            fee += reader
+                .zarith()?
+                .parse::<u128>()
+                .map_err(|_| format_err!("invalid_raw_data"))?;
+            // counter, gas limit and storage limit
+            for _ in 0..3 {
+                reader.zarith()?;
+            }
+
+            match tag {
+                REVEAL_TAG => reader.public_key()?,
+                TRANSACTION_TAG => {
+                    let amount = reader.zarith()?;
+                    let address = reader.contract_id()?;
+                    summary.recipients.push(TxRecipient {
+                        address,
+                        amount,
+                        is_change: false,
+                    });
+                    if reader.bool()? {
+                        methods.push(reader.entrypoint()?);
+                        summary.data = hex::encode(reader.sized_bytes()?);
+                    }
+                }
+                ORIGINATION_TAG => {
+                    reader.zarith()?;
+                    if reader.bool()? {
+                        reader.public_key_hash()?;
+                    }
+                    // code and storage
+                    reader.sized_bytes()?;
+                    reader.sized_bytes()?;
+                    methods.push("origination".to_string());
+                }
+                _ => {
+                    if reader.bool()? {
+                        reader.public_key_hash()?;
+                    }
+                    methods.push("delegation".to_string());
+                }
+            }
+        }
+        summary.fee = fee.to_string();
+        summary.method = methods.join(",");
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pack_string;
+    use crate::address::TezosAddress;
+    use crate::transaction::{TezosMessageInput, TezosRawTxIn};
+    use tcx_chain::{Keystore, MessageSigner, Metadata, TransactionDecoder};
+    use tcx_constants::{CoinInfo, CurveType};
+
+    #[test]
+    fn decode_transaction() {
+        let coin_info = CoinInfo {
+            coin: "TEZOS".to_string(),
+            derivation_path: "m/44'/1729'/0'/0'".to_string(),
+            curve: CurveType::ED25519,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let tx = TezosRawTxIn {
+            raw_data: "d3bdafa2e36f872e24f1ccd68dbdca4356b193823d0a6a54886d7641e532a2a26c00dedf1a2f428e5e85edf105cb3600949f3d0e8837c70cacb4e803e8528102c0843d0000dcdcf88d0cfb769e33b1888d6bdc351ee3277ea700".to_string(),
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(
+            summary.senders,
+            vec!["tz1fxTy89HrgcEkfEgL5iAYeYRM6beqPAruS"]
+        );
+        assert_eq!(
+            summary.recipients[0].address,
+            "tz1fmr4eccz29gvLco7ZhjsnpVqqCq1R58sg"
+        );
+        assert_eq!(summary.recipients[0].amount, "1000000");
+        assert_eq!(summary.fee, "1607");
+        assert_eq!(summary.method, "");
+
+        let tx = TezosRawTxIn {
+            raw_data: "d3bdafa2e36f872e24f1ccd68dbdca4356b193823d0a6a54886d7641e532a2a26c00dedf1a2f428e5e85edf105cb3600949f3d0e8837c70cacb4e803e8528102c0843d0000dcdcf88d0cfb769e33b1888d6bdc351ee3277e".to_string(),
+        };
+        let ret = tx.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_raw_data");
+
+        let tx = TezosRawTxIn {
+            raw_data: "d3bdafa2e36f872e24f1ccd68dbdca4356b193823d0a6a54886d7641e532a2a26b00"
+                .to_string(),
+        };
+        let ret = tx.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_raw_data");
+
+        let tx = TezosRawTxIn {
+            raw_data: "d3bdafa2e36f872e24f1ccd68dbdca4356b193823d0a6a54886d7641e532a2a2ff00"
+                .to_string(),
+        };
+        let ret = tx.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_operation");
+    }
+
+    #[test]
+    fn sign_message() {
+        let mut keystore = Keystore::from_private_key(
+            "2557d034ba7786634d7f692b25d0a5046dd82d7cd297c4772d52fbbc323cb61a",
+            "Password",
+            Metadata::default(),
+        );
+        keystore.unlock_by_password("Password").unwrap();
+        let coin_info = CoinInfo {
+            coin: "TEZOS".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::ED25519,
+            network: "MAINNET".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let account = keystore.derive_coin::<TezosAddress>(&coin_info).unwrap();
+        assert_eq!(account.address, "tz1QSHaKpTFhgHLbqinyYRjxD5sLcbfbzhxy");
+
+        let message = "Tezos Signed Message: imToken 2021-01-01T00:00:00Z hello";
+        let packed = "05010000003854657a6f73205369676e6564204d6573736167653a20696d546f6b656e20323032312d30312d30315430303a30303a30305a2068656c6c6f";
+        assert_eq!(hex::encode(pack_string(message)), packed);
+
+        let inputs = vec![
+            TezosMessageInput {
+                message: message.to_string(),
+                is_hex: false,
+            },
+            TezosMessageInput {
+                message: format!("0x{}", packed),
+                is_hex: true,
+            },
+        ];
+        for input in inputs {
+            let output = keystore
+                .sign_message("TEZOS", "tz1QSHaKpTFhgHLbqinyYRjxD5sLcbfbzhxy", &input)
+                .unwrap();
+            assert_eq!(output.signature, "18b2b47b5b15a7d40cd04e559f2688433581b51bf57609da3f05bdf656d4f52fc3b56bc97b8f97ba824093d627759502eecf85bfb26c0ca231e97dee81587908");
+            assert_eq!(output.edsig, "edsigtb3AyDym8M9kttFw4chUSdNPzzLfAGrRZLmtdLJFKY4PyDE2d2eJ7SPGqM7o4XvC4VycNzGK5LEESXTm4pAVZtMDU5zce1");
+        }
+
+        let input = TezosMessageInput {
+            message: "03d3bdafa2e36f872e24f1ccd68dbdca4356b193823d0a6a54886d7641e532a2a2"
+                .to_string(),
+            is_hex: true,
+        };
+        let ret = keystore.sign_message("TEZOS", "tz1QSHaKpTFhgHLbqinyYRjxD5sLcbfbzhxy", &input);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_message");
+    }
+}
@@ -12,3 +12,21 @@ pub struct TezosTxOut {
     #[prost(string, tag = "3")]
     pub sbytes: std::string::String,
 }
+/// FUNCTION: sign_message(SignParam{input: TezosMessageInput}): TezosMessageOutput
+///
+/// The text message is packed as the Micheline string `0x05 0x01 <length> <bytes>`,
+/// the hex message is signed as the packed expression already
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TezosMessageInput {
+    #[prost(string, tag = "1")]
+    pub message: std::string::String,
+    #[prost(bool, tag = "2")]
+    pub is_hex: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TezosMessageOutput {
+    #[prost(string, tag = "1")]
+    pub signature: std::string::String,
+    #[prost(string, tag = "2")]
+    pub edsig: std::string::String,
+}
@@ -0,0 +1,599 @@
+//! EIP-712 typed structured data hashing, including the TIP-712 address encoding used on TRON.
+//! ref:
+//! - https://eips.ethereum.org/EIPS/eip-712
+//! - https://github.com/tronprotocol/tips/blob/master/tip-712.md
+
+use crate::keccak;
+
+use bitcoin::util::base58;
+use failure::format_err;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{Num, One};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use tcx_chain::{tcx_ensure, Result};
+
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct FieldType {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: HashMap<String, Vec<FieldType>>,
+    pub primary_type: String,
+    pub domain: Value,
+    #[serde(default)]
+    pub message: Value,
+}
+
+impl TypedData {
+    pub fn from_json(json: &str) -> Result<TypedData> {
+        let typed_data: TypedData =
+            serde_json::from_str(json).map_err(|_| format_err!("eip712_invalid_typed_data"))?;
+        tcx_ensure!(
+            typed_data.types.contains_key(DOMAIN_TYPE),
+            format_err!("eip712_missing_domain_type")
+        );
+        tcx_ensure!(
+            typed_data.types.contains_key(&typed_data.primary_type),
+            format_err!("eip712_type_not_found")
+        );
+        for fields in typed_data.types.values() {
+            for field in fields {
+                typed_data.check_type(&field.field_type)?;
+            }
+        }
+        Ok(typed_data)
+    }
+
+    /// A field type is a struct type of the typed data, an atomic type or an array of them
+    fn check_type(&self, field_type: &str) -> Result<()> {
+        if field_type.ends_with(']') {
+            let (item_type, _) = split_array_type(field_type)?;
+            return self.check_type(item_type);
+        }
+
+        if self.types.contains_key(field_type) {
+            return Ok(());
+        }
+
+        match field_type {
+            "string" | "bytes" | "bool" | "address" | "trcToken" => Ok(()),
+            _ if field_type.starts_with("bytes") => type_size(field_type, "bytes", 32).map(|_| ()),
+            _ if field_type.starts_with("uint") => type_size(field_type, "uint", 256).map(|_| ()),
+            _ if field_type.starts_with("int") => type_size(field_type, "int", 256).map(|_| ()),
+            _ => Err(format_err!("eip712_unsupported_type")),
+        }
+    }
+
+    /// The hash to be signed: `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`
+    pub fn sign_hash(&self) -> Result<Vec<u8>> {
+        let mut data = vec![0x19, 0x01];
+        data.extend(self.domain_separator()?);
+        if self.primary_type != DOMAIN_TYPE {
+            data.extend(self.hash_struct(&self.primary_type, &self.message)?);
+        }
+        Ok(keccak(&data))
+    }
+
+    pub fn domain_separator(&self) -> Result<Vec<u8>> {
+        self.hash_struct(DOMAIN_TYPE, &self.domain)
+    }
+
+    /// `typeHash ‖ encodeData(s)` hashed by keccak256
+    pub fn hash_struct(&self, struct_type: &str, data: &Value) -> Result<Vec<u8>> {
+        let mut encoded = self.type_hash(struct_type)?;
+        encoded.extend(self.encode_data(struct_type, data)?);
+        Ok(keccak(&encoded))
+    }
+
+    pub fn type_hash(&self, struct_type: &str) -> Result<Vec<u8>> {
+        Ok(keccak(self.encode_type(struct_type)?.as_bytes()))
+    }
+
+    /// The primary type comes first, followed by all the referenced struct types sorted by name
+    pub fn encode_type(&self, struct_type: &str) -> Result<String> {
+        let mut deps = BTreeSet::new();
+        self.find_dependencies(struct_type, &mut deps)?;
+        deps.remove(struct_type);
+
+        let mut encoded = self.encode_single_type(struct_type)?;
+        for dep in deps {
+            encoded.push_str(&self.encode_single_type(&dep)?);
+        }
+        Ok(encoded)
+    }
+
+    fn encode_single_type(&self, struct_type: &str) -> Result<String> {
+        let fields = self
+            .types
+            .get(struct_type)
+            .ok_or_else(|| format_err!("eip712_type_not_found"))?;
+        let members = fields
+            .iter()
+            .map(|field| format!("{} {}", field.field_type, field.name))
+            .collect::<Vec<String>>()
+            .join(",");
+        Ok(format!("{}({})", struct_type, members))
+    }
+
+    fn find_dependencies(&self, struct_type: &str, deps: &mut BTreeSet<String>) -> Result<()> {
+        let base_type = base_type(struct_type);
+        if deps.contains(base_type) || !self.types.contains_key(base_type) {
+            return Ok(());
+        }
+        deps.insert(base_type.to_string());
+        for field in &self.types[base_type] {
+            self.find_dependencies(&field.field_type, deps)?;
+        }
+        Ok(())
+    }
+
+    fn encode_data(&self, struct_type: &str, data: &Value) -> Result<Vec<u8>> {
+        let fields = self
+            .types
+            .get(struct_type)
+            .ok_or_else(|| format_err!("eip712_type_not_found"))?;
+        let mut encoded = vec![];
+        for field in fields {
+            let value = data
+                .get(&field.name)
+                .ok_or_else(|| format_err!("eip712_missing_field"))?;
+            encoded.extend(self.encode_value(&field.field_type, value)?);
+        }
+        Ok(encoded)
+    }
+
+    fn encode_value(&self, field_type: &str, value: &Value) -> Result<Vec<u8>> {
+        if field_type.ends_with(']') {
+            let (item_type, fixed_len) = split_array_type(field_type)?;
+            let items = value
+                .as_array()
+                .ok_or_else(|| format_err!("eip712_invalid_array"))?;
+            if let Some(len) = fixed_len {
+                tcx_ensure!(items.len() == len, format_err!("eip712_invalid_array"));
+            }
+
+            let mut encoded = vec![];
+            for item in items {
+                encoded.extend(self.encode_value(item_type, item)?);
+            }
+            return Ok(keccak(&encoded));
+        }
+
+        if self.types.contains_key(field_type) {
+            return self.hash_struct(field_type, value);
+        }
+
+        match field_type {
+            "string" => {
+                let text = value
+                    .as_str()
+                    .ok_or_else(|| format_err!("eip712_invalid_string"))?;
+                Ok(keccak(text.as_bytes()))
+            }
+            "bytes" => Ok(keccak(&parse_bytes(value)?)),
+            "bool" => {
+                let flag = match value {
+                    Value::Bool(flag) => *flag,
+                    Value::String(text) if text == "true" || text == "false" => text == "true",
+                    _ => return Err(format_err!("eip712_invalid_bool")),
+                };
+                Ok(pad_left(&[flag as u8]))
+            }
+            "address" => Ok(pad_left(&parse_address(value)?)),
+            // TIP-712 `trcToken` is the token id, encoded like an uint256
+            "trcToken" => encode_uint(value, 256),
+            _ if field_type.starts_with("bytes") => {
+                let size = type_size(field_type, "bytes", 32)?;
+                let bytes = parse_bytes(value)?;
+                tcx_ensure!(bytes.len() <= size, format_err!("eip712_invalid_bytes"));
+                let mut encoded = bytes;
+                encoded.resize(32, 0);
+                Ok(encoded)
+            }
+            _ if field_type.starts_with("uint") => {
+                encode_uint(value, type_size(field_type, "uint", 256)?)
+            }
+            _ if field_type.starts_with("int") => {
+                encode_int(value, type_size(field_type, "int", 256)?)
+            }
+            _ => Err(format_err!("eip712_unsupported_type")),
+        }
+    }
+}
+
+fn base_type(field_type: &str) -> &str {
+    match field_type.find('[') {
+        Some(idx) => &field_type[..idx],
+        None => field_type,
+    }
+}
+
+/// Split `T[]` or `T[N]` into the item type and the fixed length
+fn split_array_type(field_type: &str) -> Result<(&str, Option<usize>)> {
+    let bracket = field_type
+        .rfind('[')
+        .ok_or_else(|| format_err!("eip712_invalid_type"))?;
+    let item_type = &field_type[..bracket];
+    tcx_ensure!(!item_type.is_empty(), format_err!("eip712_invalid_type"));
+
+    let fixed_len = &field_type[bracket + 1..field_type.len() - 1];
+    if fixed_len.is_empty() {
+        return Ok((item_type, None));
+    }
+    let len = fixed_len
+        .parse::<usize>()
+        .map_err(|_| format_err!("eip712_invalid_type"))?;
+    Ok((item_type, Some(len)))
+}
+
+/// Parse the size suffix of `bytesN`, `uintN` or `intN`, e.g. `uint` alone means `uint256`
+fn type_size(field_type: &str, prefix: &str, default: usize) -> Result<usize> {
+    let suffix = &field_type[prefix.len()..];
+    if suffix.is_empty() {
+        return Ok(default);
+    }
+    let size = suffix
+        .parse::<usize>()
+        .map_err(|_| format_err!("eip712_unsupported_type"))?;
+    tcx_ensure!(
+        size > 0 && size <= default,
+        format_err!("eip712_unsupported_type")
+    );
+    Ok(size)
+}
+
+fn pad_left(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = vec![0u8; 32 - bytes.len()];
+    padded.extend_from_slice(bytes);
+    padded
+}
+
+fn strip_hex_prefix(value: &str) -> &str {
+    if value.starts_with("0x") || value.starts_with("0X") {
+        &value[2..]
+    } else {
+        value
+    }
+}
+
+fn parse_bytes(value: &Value) -> Result<Vec<u8>> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| format_err!("eip712_invalid_bytes"))?;
+    hex::decode(strip_hex_prefix(text)).map_err(|_| format_err!("eip712_invalid_bytes"))
+}
+
+/// Accepts TRON base58 addresses, `41` prefixed hex addresses and plain 20 bytes hex addresses
+fn parse_address(value: &Value) -> Result<Vec<u8>> {
+    let text = value
+        .as_str()
+        .ok_or_else(|| format_err!("eip712_invalid_address"))?;
+
+    let bytes = if text.starts_with('T') {
+        base58::from_check(text).map_err(|_| format_err!("eip712_invalid_address"))?
+    } else {
+        hex::decode(strip_hex_prefix(text)).map_err(|_| format_err!("eip712_invalid_address"))?
+    };
+
+    match bytes.len() {
+        20 => Ok(bytes),
+        21 if bytes[0] == 0x41 => Ok(bytes[1..].to_vec()),
+        _ => Err(format_err!("eip712_invalid_address")),
+    }
+}
+
+fn parse_integer(value: &Value) -> Result<BigInt> {
+    match value {
+        Value::Number(number) => {
+            if let Some(n) = number.as_u64() {
+                Ok(BigInt::from(n))
+            } else if let Some(n) = number.as_i64() {
+                Ok(BigInt::from(n))
+            } else {
+                Err(format_err!("eip712_invalid_number"))
+            }
+        }
+        Value::String(text) => {
+            let (negative, digits) = if text.starts_with('-') {
+                (true, &text[1..])
+            } else {
+                (false, text.as_str())
+            };
+            let magnitude = if digits.starts_with("0x") || digits.starts_with("0X") {
+                BigUint::from_str_radix(&digits[2..], 16)
+            } else {
+                BigUint::from_str_radix(digits, 10)
+            }
+            .map_err(|_| format_err!("eip712_invalid_number"))?;
+            let sign = if negative { Sign::Minus } else { Sign::Plus };
+            Ok(BigInt::from_biguint(sign, magnitude))
+        }
+        _ => Err(format_err!("eip712_invalid_number")),
+    }
+}
+
+fn encode_uint(value: &Value, bits: usize) -> Result<Vec<u8>> {
+    let number = parse_integer(value)?;
+    tcx_ensure!(
+        number.sign() != Sign::Minus && number.bits() <= bits,
+        format_err!("eip712_number_overflow")
+    );
+    let (_, bytes) = number.to_bytes_be();
+    Ok(pad_left(&bytes))
+}
+
+/// Signed integers are encoded as 256 bits two's complement
+fn encode_int(value: &Value, bits: usize) -> Result<Vec<u8>> {
+    let number = parse_integer(value)?;
+    let limit = BigInt::one() << (bits - 1);
+    tcx_ensure!(
+        number >= -limit.clone() && number < limit,
+        format_err!("eip712_number_overflow")
+    );
+
+    let bytes = number.to_signed_bytes_be();
+    let fill = if number.sign() == Sign::Minus {
+        0xff
+    } else {
+        0x00
+    };
+    let mut encoded = vec![fill; 32 - bytes.len()];
+    encoded.extend(bytes);
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAIL_TYPED_DATA: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                {"name": "name", "type": "string"},
+                {"name": "version", "type": "string"},
+                {"name": "chainId", "type": "uint256"},
+                {"name": "verifyingContract", "type": "address"}
+            ],
+            "Person": [
+                {"name": "name", "type": "string"},
+                {"name": "wallet", "type": "address"}
+            ],
+            "Mail": [
+                {"name": "from", "type": "Person"},
+                {"name": "to", "type": "Person"},
+                {"name": "contents", "type": "string"}
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+            "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn eip712_mail() {
+        // https://github.com/ethereum/EIPs/blob/master/assets/eip-712/Example.js
+        let typed_data = TypedData::from_json(MAIL_TYPED_DATA).unwrap();
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.type_hash("Mail").unwrap()),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+        );
+        assert_eq!(
+            hex::encode(typed_data.hash_struct("Mail", &typed_data.message).unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed_data.sign_hash().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn eip712_arrays() {
+        let json = r#"{
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallets", "type": "address[]"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person[]"},
+                    {"name": "contents", "type": "string"}
+                ],
+                "Group": [
+                    {"name": "name", "type": "string"},
+                    {"name": "members", "type": "Person[]"}
+                ]
+            },
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "primaryType": "Mail",
+            "message": {
+                "from": {
+                    "name": "Cow",
+                    "wallets": [
+                        "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+                        "0xDeaDbeefdEAdbeefdEadbEEFdeadbeEFdEaDbeeF"
+                    ]
+                },
+                "to": [{
+                    "name": "Bob",
+                    "wallets": [
+                        "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+                        "0xB0BdaBea57B0BDABeA57b0bdABEA57b0BDabEa57",
+                        "0xB0B0b0b0b0b0B000000000000000000000000000"
+                    ]
+                }],
+                "contents": "Hello, Bob!"
+            }
+        }"#;
+        let typed_data = TypedData::from_json(json).unwrap();
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person[] to,string contents)Person(string name,address[] wallets)"
+        );
+        assert_eq!(
+            hex::encode(typed_data.sign_hash().unwrap()),
+            "a85c2e2b118698e88db68a8105b794a8cc7cec074e89ef991cb4f5f533819cc2"
+        );
+    }
+
+    #[test]
+    fn tip712_tron_address() {
+        let json = r#"{
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Permit": [
+                    {"name": "owner", "type": "address"},
+                    {"name": "spender", "type": "address"},
+                    {"name": "value", "type": "uint256"},
+                    {"name": "tokenId", "type": "trcToken"},
+                    {"name": "deadline", "type": "int64"}
+                ]
+            },
+            "primaryType": "Permit",
+            "domain": {
+                "name": "TRC20 Permit",
+                "version": "1",
+                "chainId": "0x2b6653dc",
+                "verifyingContract": "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"
+            },
+            "message": {
+                "owner": "THfuSDVRvSsjNDPFdGjMU19Ha4Kf7acotq",
+                "spender": "41a614f803b6fd780986a42c78ec9c7f77e6ded13c",
+                "value": "1000000000000000000000",
+                "tokenId": 1002000,
+                "deadline": "-1"
+            }
+        }"#;
+        let typed_data = TypedData::from_json(json).unwrap();
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "ba8aca18f2904ccba9b196ff6875f9255b2c872d158db2973ca5f702c89a82fa"
+        );
+        assert_eq!(
+            hex::encode(typed_data.sign_hash().unwrap()),
+            "d680bbfa1d9ea8e7ae6546fded5f9411134872052f67971ead3369dad21ad3f7"
+        );
+
+        // a TRON address is encoded the same as its 20 bytes ethereum form
+        let tron_addr = parse_address(&Value::String(
+            "THfuSDVRvSsjNDPFdGjMU19Ha4Kf7acotq".to_string(),
+        ))
+        .unwrap();
+        let hex_addr = parse_address(&Value::String(
+            "0x547b45770EE4401494c9157e8263E7a133cbD88d".to_string(),
+        ))
+        .unwrap();
+        assert_eq!(tron_addr, hex_addr);
+    }
+
+    #[test]
+    fn encode_numbers() {
+        assert_eq!(
+            hex::encode(encode_int(&Value::String("-1".to_string()), 256).unwrap()),
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"
+        );
+        assert_eq!(
+            hex::encode(encode_int(&serde_json::json!(-128), 8).unwrap()),
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80"
+        );
+        assert!(encode_int(&serde_json::json!(128), 8).is_err());
+        assert_eq!(
+            hex::encode(encode_uint(&Value::String("0xff".to_string()), 8).unwrap()),
+            "00000000000000000000000000000000000000000000000000000000000000ff"
+        );
+        assert!(encode_uint(&serde_json::json!(256), 8).is_err());
+        assert!(encode_uint(&serde_json::json!(-1), 256).is_err());
+        assert!(encode_uint(&serde_json::json!(1.5), 256).is_err());
+    }
+
+    #[test]
+    fn invalid_typed_data() {
+        assert_eq!(
+            format!("{}", TypedData::from_json("{}").err().unwrap()),
+            "eip712_invalid_typed_data"
+        );
+
+        let json =
+            MAIL_TYPED_DATA.replace("\"primaryType\": \"Mail\"", "\"primaryType\": \"Post\"");
+        assert_eq!(
+            format!("{}", TypedData::from_json(&json).err().unwrap()),
+            "eip712_type_not_found"
+        );
+
+        let json = MAIL_TYPED_DATA.replace("\"type\": \"Person\"}", "\"type\": \"Persn\"}");
+        assert_eq!(
+            format!("{}", TypedData::from_json(&json).err().unwrap()),
+            "eip712_unsupported_type"
+        );
+
+        for invalid_type in &["uint256]", "[]", "Person[x]", "uint512[]", "bytes33"] {
+            let json = MAIL_TYPED_DATA.replace(
+                "{\"name\": \"contents\", \"type\": \"string\"}",
+                &format!("{{\"name\": \"contents\", \"type\": \"{}\"}}", invalid_type),
+            );
+            assert!(TypedData::from_json(&json).is_err());
+        }
+
+        let typed_data = TypedData::from_json(MAIL_TYPED_DATA).unwrap();
+        assert_eq!(
+            format!(
+                "{}",
+                typed_data
+                    .encode_value("uint256]", &serde_json::json!([1]))
+                    .err()
+                    .unwrap()
+            ),
+            "eip712_invalid_type"
+        );
+
+        let json = MAIL_TYPED_DATA.replace("\"contents\": \"Hello, Bob!\"", "\"content\": \"\"");
+        let typed_data = TypedData::from_json(&json).unwrap();
+        assert_eq!(
+            format!("{}", typed_data.sign_hash().err().unwrap()),
+            "eip712_missing_field"
+        );
+    }
+}
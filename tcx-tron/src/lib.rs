@@ -1,4 +1,5 @@
 pub mod address;
+pub mod eip712;
 pub mod signer;
 pub mod transaction;
 
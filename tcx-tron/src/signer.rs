@@ -1,8 +1,16 @@
-use crate::transaction::{TronMessageInput, TronMessageOutput, TronTxInput, TronTxOutput};
+use crate::eip712::TypedData;
+use crate::transaction::{
+    TronMessageInput, TronMessageOutput, TronTxInput, TronTxOutput, TronTypedDataInput,
+};
 use tcx_chain::{
-    ChainSigner, Keystore, MessageSigner as TraitMessageSigner, Result,
-    TransactionSigner as TraitTransactionSigner,
+    tcx_ensure, ChainSigner, Keystore, MessageSigner as TraitMessageSigner, Result,
+    TransactionDecoder, TransactionSigner as TraitTransactionSigner, TxRecipient, TxSummary,
 };
+use tcx_constants::CoinInfo;
+
+use bitcoin::util::base58;
+use num_bigint::BigUint;
+use prost::Message;
 
 use bitcoin_hashes::sha256::Hash;
 use bitcoin_hashes::Hash as TraitHash;
@@ -52,14 +60,176 @@ impl TraitTransactionSigner<TronTxInput, TronTxOutput> for Keystore {
         let data = hex::decode(&tx.raw_data)?;
         let hash = Hash::hash(&data);
 
-        let sign_result = self.sign_recoverable_hash(&hash[..], symbol, address, None);
+        let signature = self.sign_recoverable_hash(&hash[..], symbol, address, None)?;
+
+        Ok(TronTxOutput {
+            signatures: vec![hex::encode(signature)],
+        })
+    }
+}
+
+const TRANSFER_CONTRACT: i32 = 1;
+const TRANSFER_ASSET_CONTRACT: i32 = 2;
+const TRIGGER_SMART_CONTRACT: i32 = 31;
+/// The selector of TRC-20 `transfer(address,uint256)`
+const TRC20_TRANSFER_SELECTOR: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+// The parts of the raw transaction which are shown before signing
+// ref: https://github.com/tronprotocol/protocol/blob/master/core/Tron.proto
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct TransactionRaw {
+    #[prost(bytes, tag = "10")]
+    data: Vec<u8>,
+    #[prost(message, repeated, tag = "11")]
+    contract: Vec<Contract>,
+    #[prost(int64, tag = "18")]
+    fee_limit: i64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct Contract {
+    #[prost(int32, tag = "1")]
+    r#type: i32,
+    #[prost(message, optional, tag = "2")]
+    parameter: Option<ContractParameter>,
+}
+
+/// google.protobuf.Any
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct ContractParameter {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes, tag = "2")]
+    value: Vec<u8>,
+}
+
+/// Most of the contracts start with the owner address
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct OwnerContract {
+    #[prost(bytes, tag = "1")]
+    owner_address: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct TransferContract {
+    #[prost(bytes, tag = "1")]
+    owner_address: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    to_address: Vec<u8>,
+    #[prost(int64, tag = "3")]
+    amount: i64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct TransferAssetContract {
+    #[prost(bytes, tag = "1")]
+    asset_name: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    owner_address: Vec<u8>,
+    #[prost(bytes, tag = "3")]
+    to_address: Vec<u8>,
+    #[prost(int64, tag = "4")]
+    amount: i64,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+struct TriggerSmartContract {
+    #[prost(bytes, tag = "1")]
+    owner_address: Vec<u8>,
+    #[prost(bytes, tag = "2")]
+    contract_address: Vec<u8>,
+    #[prost(int64, tag = "3")]
+    call_value: i64,
+    #[prost(bytes, tag = "4")]
+    data: Vec<u8>,
+}
+
+fn encode_address(address: &[u8]) -> String {
+    base58::check_encode_slice(address)
+}
 
-        match sign_result {
-            Ok(r) => Ok(TronTxOutput {
-                signatures: vec![hex::encode(r)],
-            }),
-            Err(_e) => Err(format_err!("{}", "can not format error")),
+impl TransactionDecoder for TronTxInput {
+    fn decode_transaction(&self, _coin: &CoinInfo) -> Result<TxSummary> {
+        let raw = TransactionRaw::decode(hex::decode(&self.raw_data)?.as_slice())
+            .map_err(|_| format_err!("invalid_raw_data"))?;
+        tcx_ensure!(raw.contract.len() == 1, format_err!("invalid_raw_data"));
+        let contract = &raw.contract[0];
+        let parameter = contract
+            .parameter
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_raw_data"))?;
+        let value = parameter.value.as_slice();
+        let invalid_contract = |_| format_err!("invalid_contract");
+
+        let mut summary = TxSummary {
+            fee: if raw.fee_limit > 0 {
+                raw.fee_limit.to_string()
+            } else {
+                "".to_string()
+            },
+            method: parameter
+                .type_url
+                .rsplit('.')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+            data: hex::encode(&raw.data),
+            ..TxSummary::default()
+        };
+        match contract.r#type {
+            TRANSFER_CONTRACT => {
+                let transfer = TransferContract::decode(value).map_err(invalid_contract)?;
+                summary.senders = vec![encode_address(&transfer.owner_address)];
+                summary.recipients = vec![TxRecipient {
+                    address: encode_address(&transfer.to_address),
+                    amount: transfer.amount.to_string(),
+                    is_change: false,
+                }];
+            }
+            TRANSFER_ASSET_CONTRACT => {
+                let transfer = TransferAssetContract::decode(value).map_err(invalid_contract)?;
+                summary.senders = vec![encode_address(&transfer.owner_address)];
+                summary.recipients = vec![TxRecipient {
+                    address: encode_address(&transfer.to_address),
+                    amount: transfer.amount.to_string(),
+                    is_change: false,
+                }];
+                summary.contract = String::from_utf8_lossy(&transfer.asset_name).to_string();
+            }
+            TRIGGER_SMART_CONTRACT => {
+                let trigger = TriggerSmartContract::decode(value).map_err(invalid_contract)?;
+                let data = &trigger.data;
+                summary.senders = vec![encode_address(&trigger.owner_address)];
+                summary.data = hex::encode(data);
+                if data.len() >= 4 {
+                    summary.method = format!("0x{}", hex::encode(&data[..4]));
+                }
+                // the token payment is only shown if no TRX is paid along with it
+                if data.len() == 68
+                    && data[..4] == TRC20_TRANSFER_SELECTOR
+                    && trigger.call_value == 0
+                {
+                    let to_address = [&[0x41], &data[16..36]].concat();
+                    summary.contract = encode_address(&trigger.contract_address);
+                    summary.recipients = vec![TxRecipient {
+                        address: encode_address(&to_address),
+                        amount: BigUint::from_bytes_be(&data[36..68]).to_string(),
+                        is_change: false,
+                    }];
+                } else {
+                    summary.recipients = vec![TxRecipient {
+                        address: encode_address(&trigger.contract_address),
+                        amount: trigger.call_value.to_string(),
+                        is_change: false,
+                    }];
+                }
+            }
+            _ => {
+                let owner = OwnerContract::decode(value).map_err(invalid_contract)?;
+                summary.senders = vec![encode_address(&owner.owner_address)];
+            }
         }
+        Ok(summary)
     }
 }
 
@@ -95,6 +265,22 @@ impl TraitMessageSigner<TronMessageInput, TronMessageOutput> for Keystore {
     }
 }
 
+impl TraitMessageSigner<TronTypedDataInput, TronMessageOutput> for Keystore {
+    fn sign_message(
+        &mut self,
+        symbol: &str,
+        address: &str,
+        message: &TronTypedDataInput,
+    ) -> Result<TronMessageOutput> {
+        let hash = TypedData::from_json(&message.data)?.sign_hash()?;
+        let mut sign_result = self.sign_recoverable_hash(&hash[..], symbol, address, None)?;
+        sign_result[64] = sign_result[64] + 27;
+        Ok(TronMessageOutput {
+            signature: hex::encode(sign_result),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,6 +346,10 @@ mod tests {
 
         assert_eq!(signed_tx.signatures[0], "beac4045c3ea5136b541a3d5ec2a3e5836d94f28a1371440a01258808612bc161b5417e6f5a342451303cda840f7e21bfaba1011fad5f63538cb8cc132a9768800");
 
+        let ret: Result<TronTxOutput> =
+            ks.sign_transaction("TRON", "TXo4VDm8Qc5YBSjPhu8pMaxzTApSvLshWG", &tx);
+        assert_eq!(format!("{}", ret.err().unwrap()), "account_not_found");
+
         Ok(())
     }
 
@@ -179,4 +369,122 @@ mod tests {
         signed[64] = signed[64] + 27;
         assert_eq!("7209610445e867cf2a36ea301bb5d1fbc3da597fd2ce4bb7fa64796fbf0620a4175e9f841cbf60d12c26737797217c0082fdb3caa8e44079e04ec3f93e86bbea1c", hex::encode(&signed))
     }
+
+    #[test]
+    fn sign_typed_data() {
+        // the private key is keccak256("cow") used in the EIP-712 example
+        let mut keystore = Keystore::from_private_key(
+            "c85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4",
+            TEST_PASSWORD,
+            Metadata::default(),
+        );
+        keystore.unlock_by_password(TEST_PASSWORD).unwrap();
+        let coin_info = CoinInfo {
+            coin: "TRON".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let account = keystore.derive_coin::<Address>(&coin_info).unwrap().clone();
+
+        let input = TronTypedDataInput {
+            data: r#"{
+                "types": {
+                    "EIP712Domain": [
+                        {"name": "name", "type": "string"},
+                        {"name": "version", "type": "string"},
+                        {"name": "chainId", "type": "uint256"},
+                        {"name": "verifyingContract", "type": "address"}
+                    ],
+                    "Person": [
+                        {"name": "name", "type": "string"},
+                        {"name": "wallet", "type": "address"}
+                    ],
+                    "Mail": [
+                        {"name": "from", "type": "Person"},
+                        {"name": "to", "type": "Person"},
+                        {"name": "contents", "type": "string"}
+                    ]
+                },
+                "primaryType": "Mail",
+                "domain": {
+                    "name": "Ether Mail",
+                    "version": "1",
+                    "chainId": 1,
+                    "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+                },
+                "message": {
+                    "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                    "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                    "contents": "Hello, Bob!"
+                }
+            }"#
+            .to_string(),
+        };
+        let signed: TronMessageOutput = keystore
+            .sign_message("TRON", &account.address, &input)
+            .unwrap();
+        assert_eq!(signed.signature, "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c");
+
+        let input = TronTypedDataInput {
+            data: "{}".to_string(),
+        };
+        let ret: Result<TronMessageOutput> =
+            keystore.sign_message("TRON", &account.address, &input);
+        assert_eq!(
+            format!("{}", ret.err().unwrap()),
+            "eip712_invalid_typed_data"
+        );
+    }
+
+    #[test]
+    fn decode_transaction() {
+        let coin_info = CoinInfo {
+            coin: "TRON".to_string(),
+            derivation_path: "".to_string(),
+            curve: CurveType::SECP256k1,
+            network: "".to_string(),
+            seg_wit: "".to_string(),
+        };
+        let tx = TronTxInput {
+            raw_data: "0a0202a22208e216e254e43ee10840c8cbe4e3df2d5a67080112630a2d747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e5472616e73666572436f6e747261637412320a15415c68cc82c87446f602f019e5fd797437f5b79cc212154156a6076cd1537fa317c2606e4edfa4acd3e8e92e18a08d06709084e1e3df2d".to_string(),
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.senders, vec!["TJPpiG4r7K5WPydSvV1GDPwPF6DzKiJnFo"]);
+        assert_eq!(
+            summary.recipients,
+            vec![TxRecipient {
+                address: "THsN1TH3sox1emzc8PxeFHC8HiZHhZ1qDX".to_string(),
+                amount: "100000".to_string(),
+                is_change: false,
+            }]
+        );
+        assert_eq!(summary.method, "TransferContract");
+        assert_eq!(summary.fee, "");
+
+        // TRC-20 transfer of USDT
+        let tx = TronTxInput {
+            raw_data: "0a0202a22208e216e254e43ee10840c8cbe4e3df2d5aae01081f12a9010a31747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e54726967676572536d617274436f6e747261637412740a15415c68cc82c87446f602f019e5fd797437f5b79cc2121541a614f803b6fd780986a42c78ec9c7f77e6ded13c2244a9059cbb00000000000000000000000056a6076cd1537fa317c2606e4edfa4acd3e8e92e00000000000000000000000000000000000000000000000000000000000f4240709084e1e3df2d9001c0c39307".to_string(),
+        };
+        let summary = tx.decode_transaction(&coin_info).unwrap();
+        assert_eq!(summary.senders, vec!["TJPpiG4r7K5WPydSvV1GDPwPF6DzKiJnFo"]);
+        assert_eq!(
+            summary.recipients,
+            vec![TxRecipient {
+                address: "THsN1TH3sox1emzc8PxeFHC8HiZHhZ1qDX".to_string(),
+                amount: "1000000".to_string(),
+                is_change: false,
+            }]
+        );
+        assert_eq!(summary.contract, "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t");
+        assert_eq!(summary.method, "0xa9059cbb");
+        assert_eq!(summary.fee, "15000000");
+
+        let tx = TronTxInput {
+            raw_data: "0a0202a2".to_string(),
+        };
+        let ret = tx.decode_transaction(&coin_info);
+        assert_eq!(format!("{}", ret.err().unwrap()), "invalid_raw_data");
+    }
 }
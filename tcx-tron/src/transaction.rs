@@ -32,3 +32,12 @@ pub struct TronMessageOutput {
     #[prost(string, tag = "1")]
     pub signature: std::string::String,
 }
+/// FUNCTION: tron_sign_typed_data(SignParam): TronMessageOutput
+///
+/// Sign the EIP-712/TIP-712 typed structured data, the `data` is the json
+/// with `types`, `primaryType`, `domain` and `message`
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TronTypedDataInput {
+    #[prost(string, tag = "1")]
+    pub data: std::string::String,
+}
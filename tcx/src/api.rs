@@ -33,6 +33,9 @@ pub struct InitTokenCoreXParam {
     pub xpub_common_iv: std::string::String,
     #[prost(bool, tag = "4")]
     pub is_debug: bool,
+    /// verify every signature with the public key before returning it
+    #[prost(bool, tag = "5")]
+    pub signature_self_check: bool,
 }
 ///
 ///// FUNCTION: export_private_key(ExportPrivateKeyParam): KeystoreCommonExportResult
@@ -66,6 +69,23 @@ pub struct WalletKeyParam {
     #[prost(string, tag = "2")]
     pub password: std::string::String,
 }
+/// The key derivation function encrypting the keystore, pbkdf2 is used if it is not set
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KdfParam {
+    /// "pbkdf2" or "scrypt"
+    #[prost(string, tag = "1")]
+    pub kdf: std::string::String,
+    /// iterations of pbkdf2, 0 means the default
+    #[prost(uint32, tag = "2")]
+    pub c: u32,
+    /// cost params of scrypt, n must be a power of 2, 0 means the default
+    #[prost(uint32, tag = "3")]
+    pub n: u32,
+    #[prost(uint32, tag = "4")]
+    pub r: u32,
+    #[prost(uint32, tag = "5")]
+    pub p: u32,
+}
 /// Hd Store
 
 /// FUNCTION: hd_store_create(HdStoreCreateParam): WalletResult
@@ -79,6 +99,17 @@ pub struct HdStoreCreateParam {
     pub password_hint: std::string::String,
     #[prost(string, tag = "3")]
     pub name: std::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub kdf: ::std::option::Option<KdfParam>,
+    /// the optional BIP-39 passphrase
+    #[prost(string, tag = "5")]
+    pub passphrase: std::string::String,
+    /// the word list of the mnemonic, ENGLISH if it is empty
+    #[prost(string, tag = "6")]
+    pub language: std::string::String,
+    /// one of 12, 15, 18, 21 and 24, 12 if it is zero
+    #[prost(uint32, tag = "7")]
+    pub word_count: u32,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct WalletResult {
@@ -110,6 +141,14 @@ pub struct HdStoreImportParam {
     pub password_hint: std::string::String,
     #[prost(bool, tag = "6")]
     pub overwrite: bool,
+    #[prost(message, optional, tag = "7")]
+    pub kdf: ::std::option::Option<KdfParam>,
+    /// the optional BIP-39 passphrase
+    #[prost(string, tag = "8")]
+    pub passphrase: std::string::String,
+    /// the word list of the mnemonic, ENGLISH if it is empty
+    #[prost(string, tag = "9")]
+    pub language: std::string::String,
 }
 /// FUNCTION: hd_store_derive(HdStoreDeriveParam): AccountsResponse
 ///
@@ -168,6 +207,69 @@ pub struct KeystoreCommonExportResult {
     #[prost(string, tag = "3")]
     pub value: std::string::String,
 }
+/// FUNCTION: hd_store_export_shares(HdStoreExportSharesParam): HdStoreExportSharesResult
+///
+/// split the seed of a hd keystore into SLIP-39 share mnemonics, the seed is the master secret of
+/// the shares and is encrypted by the SLIP-39 passphrase
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HdStoreExportSharesParam {
+    #[prost(string, tag = "1")]
+    pub id: std::string::String,
+    #[prost(string, tag = "2")]
+    pub password: std::string::String,
+    #[prost(uint32, tag = "3")]
+    pub group_threshold: u32,
+    #[prost(message, repeated, tag = "4")]
+    pub groups: ::std::vec::Vec<hd_store_export_shares_param::Group>,
+    /// the SLIP-39 passphrase, it is needed with the shares to recover the keystore
+    #[prost(string, tag = "5")]
+    pub passphrase: std::string::String,
+}
+pub mod hd_store_export_shares_param {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Group {
+        #[prost(uint32, tag = "1")]
+        pub member_threshold: u32,
+        #[prost(uint32, tag = "2")]
+        pub member_count: u32,
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HdStoreExportSharesResult {
+    #[prost(string, tag = "1")]
+    pub id: std::string::String,
+    #[prost(message, repeated, tag = "2")]
+    pub groups: ::std::vec::Vec<hd_store_export_shares_result::Group>,
+}
+pub mod hd_store_export_shares_result {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Group {
+        #[prost(string, repeated, tag = "1")]
+        pub shares: ::std::vec::Vec<std::string::String>,
+    }
+}
+/// FUNCTION: hd_store_import_shares(HdStoreImportSharesParam): WalletResult
+///
+/// recover a hd keystore from a quorum of SLIP-39 share mnemonics, the keystore keeps the master
+/// secret and has no mnemonic to export
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HdStoreImportSharesParam {
+    #[prost(string, repeated, tag = "1")]
+    pub shares: ::std::vec::Vec<std::string::String>,
+    #[prost(string, tag = "2")]
+    pub password: std::string::String,
+    #[prost(string, tag = "3")]
+    pub name: std::string::String,
+    #[prost(string, tag = "4")]
+    pub password_hint: std::string::String,
+    #[prost(bool, tag = "5")]
+    pub overwrite: bool,
+    #[prost(message, optional, tag = "6")]
+    pub kdf: ::std::option::Option<KdfParam>,
+    /// the SLIP-39 passphrase of the shares
+    #[prost(string, tag = "7")]
+    pub passphrase: std::string::String,
+}
 /// Private Key Store
 
 /// FUNCTION: private_key_store_import(PrivateKeyStoreImportParam): WalletResult
@@ -187,6 +289,8 @@ pub struct PrivateKeyStoreImportParam {
     pub overwrite: bool,
     #[prost(string, tag = "6")]
     pub encoding: std::string::String,
+    #[prost(message, optional, tag = "7")]
+    pub kdf: ::std::option::Option<KdfParam>,
 }
 /// FUNCTION: private_key_store_export(PrivateKeyStoreExportParam): KeystoreCommonExportResult
 ///
@@ -202,6 +306,41 @@ pub struct PrivateKeyStoreExportParam {
     #[prost(string, tag = "4")]
     pub network: std::string::String,
 }
+/// FUNCTION: legacy_keystore_import(LegacyKeystoreImportParam): WalletResult
+///
+/// migrate an imToken v2 identity/mnemonic keystore or an Ethereum V3 keystore, the keystore is
+/// encrypted by the same password again
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LegacyKeystoreImportParam {
+    #[prost(string, tag = "1")]
+    pub keystore: std::string::String,
+    #[prost(string, tag = "2")]
+    pub password: std::string::String,
+    #[prost(bool, tag = "3")]
+    pub overwrite: bool,
+    #[prost(message, optional, tag = "4")]
+    pub kdf: ::std::option::Option<KdfParam>,
+}
+/// FUNCTION: watch_keystore_import(WatchKeystoreImportParam): WalletResult
+///
+/// create a watch-only keystore which derives the addresses but never signs
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchKeystoreImportParam {
+    /// the extended public key(xpub, ypub, zpub...) or the hex public key, the origin of the
+    /// extended public key may be given as `[fingerprint/path]xpub...`
+    #[prost(string, tag = "1")]
+    pub public_key: std::string::String,
+    #[prost(string, tag = "2")]
+    pub password: std::string::String,
+    #[prost(string, tag = "3")]
+    pub name: std::string::String,
+    #[prost(string, tag = "4")]
+    pub password_hint: std::string::String,
+    #[prost(bool, tag = "5")]
+    pub overwrite: bool,
+    #[prost(message, optional, tag = "6")]
+    pub kdf: ::std::option::Option<KdfParam>,
+}
 /// Keystore Common
 
 // FUNCTION: keystore_common_delete(WalletKeyParam) -> Response
@@ -219,6 +358,12 @@ pub struct KeystoreCommonExistsParam {
     pub value: std::string::String,
     #[prost(string, tag = "3")]
     pub encoding: std::string::String,
+    /// the BIP-39 passphrase of the mnemonic
+    #[prost(string, tag = "4")]
+    pub passphrase: std::string::String,
+    /// the word list of the mnemonic, ENGLISH if it is empty
+    #[prost(string, tag = "5")]
+    pub language: std::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct KeystoreCommonExistsResult {
@@ -235,6 +380,76 @@ pub struct KeystoreCommonAccountsParam {
     #[prost(string, tag = "1")]
     pub id: std::string::String,
 }
+/// FUNCTION: keystore_change_password(KeystoreChangePasswordParam) -> Response
+///
+/// Re-encrypt the keystore by the new password, the id and the accounts are kept
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeystoreChangePasswordParam {
+    #[prost(string, tag = "1")]
+    pub id: std::string::String,
+    #[prost(string, tag = "2")]
+    pub old_password: std::string::String,
+    #[prost(string, tag = "3")]
+    pub new_password: std::string::String,
+    /// the password hint of the keystore is kept if it is empty
+    #[prost(string, tag = "4")]
+    pub password_hint: std::string::String,
+    /// the KDF of the keystore is kept if it is not set
+    #[prost(message, optional, tag = "5")]
+    pub kdf: ::std::option::Option<KdfParam>,
+}
+/// FUNCTION: keystore_common_derive_addresses(KeystoreCommonDeriveAddressesParam): KeystoreCommonDeriveAddressesResult
+///
+/// Derive the addresses `{change}/{index}` of a secp256k1 account from its extended public key,
+/// the password is not required. The BIP-44 gap limit discovery scans from index 0 instead of
+/// the range if `discover` is set
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeystoreCommonDeriveAddressesParam {
+    #[prost(string, tag = "1")]
+    pub id: std::string::String,
+    #[prost(string, tag = "2")]
+    pub chain_type: std::string::String,
+    /// the address of the account
+    #[prost(string, tag = "3")]
+    pub address: std::string::String,
+    /// 0 for the receive addresses, 1 for the change addresses
+    #[prost(uint32, tag = "4")]
+    pub change: u32,
+    #[prost(uint32, tag = "5")]
+    pub start: u32,
+    /// at most 1000
+    #[prost(uint32, tag = "6")]
+    pub count: u32,
+    #[prost(bool, tag = "7")]
+    pub discover: bool,
+    /// 20 if it is zero, at most 1000
+    #[prost(uint32, tag = "8")]
+    pub gap_limit: u32,
+    /// the addresses known to have history
+    #[prost(string, repeated, tag = "9")]
+    pub used_addresses: ::std::vec::Vec<std::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeystoreCommonDeriveAddressesResult {
+    /// the addresses in the range, or the used addresses of the discovery
+    #[prost(message, repeated, tag = "1")]
+    pub addresses: ::std::vec::Vec<keystore_common_derive_addresses_result::DerivedAddress>,
+    /// the first address after the used addresses of the discovery
+    #[prost(message, optional, tag = "2")]
+    pub next_address:
+        ::std::option::Option<keystore_common_derive_addresses_result::DerivedAddress>,
+}
+pub mod keystore_common_derive_addresses_result {
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DerivedAddress {
+        #[prost(string, tag = "1")]
+        pub address: std::string::String,
+        #[prost(string, tag = "2")]
+        pub path: std::string::String,
+        #[prost(string, tag = "3")]
+        pub public_key: std::string::String,
+    }
+}
 /// Sign Transaction
 
 /// FUNCTION: sign_tx(SignParam)
@@ -263,6 +478,132 @@ pub mod sign_param {
         DerivedKey(std::string::String),
     }
 }
+/// FUNCTION: decode_tx(SignParam): DecodeTxResult
+///
+/// Preview what an unsigned transaction does before signing it, takes the same `SignParam` as `sign_tx`,
+/// the key is not required
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecodeTxRecipient {
+    #[prost(string, tag = "1")]
+    pub address: std::string::String,
+    #[prost(string, tag = "2")]
+    pub amount: std::string::String,
+    /// the output returns to the wallet itself, such as the change of a bitcoin transaction
+    #[prost(bool, tag = "3")]
+    pub is_change: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecodeTxResult {
+    #[prost(string, tag = "1")]
+    pub chain_type: std::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub senders: ::std::vec::Vec<std::string::String>,
+    #[prost(message, repeated, tag = "3")]
+    pub recipients: ::std::vec::Vec<DecodeTxRecipient>,
+    /// the max fee in the smallest unit, empty if it is decided by the chain
+    #[prost(string, tag = "4")]
+    pub fee: std::string::String,
+    /// the token contract, the amounts are in the token if it is set
+    #[prost(string, tag = "5")]
+    pub contract: std::string::String,
+    #[prost(string, tag = "6")]
+    pub method: std::string::String,
+    /// hex encoded data attached to the transaction
+    #[prost(string, tag = "7")]
+    pub data: std::string::String,
+}
+/// FUNCTION: verify_signature(VerifySignatureParam): VerifySignatureResult
+///
+/// Verify the signature of the data with the public key, the signature is in the same format as
+/// the signers produce, e.g. DER or `r || s || v` for secp256k1. The data is the signed hash for
+/// secp256k1 and the message for the other curves
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifySignatureParam {
+    /// the curve name like SECP256k1, ED25519, SubSr25519 or BLS
+    #[prost(string, tag = "1")]
+    pub curve: std::string::String,
+    /// the hex encoded public key, it could be empty if the signature is recoverable, then
+    /// the signature is not verified and only the recovered public key is returned
+    #[prost(string, tag = "2")]
+    pub public_key: std::string::String,
+    /// the hex encoded data
+    #[prost(string, tag = "3")]
+    pub data: std::string::String,
+    /// the hex encoded signature
+    #[prost(string, tag = "4")]
+    pub signature: std::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifySignatureResult {
+    #[prost(bool, tag = "1")]
+    pub is_valid: bool,
+    /// the compressed public key recovered from the recoverable secp256k1 signature
+    #[prost(string, tag = "2")]
+    pub recovered_public_key: std::string::String,
+}
+/// FUNCTION: encrypt_to_public_key(EncryptToPublicKeyParam): EncryptToPublicKeyResult
+///
+/// Encrypt the data to the public key of another wallet, only the holder of the private key could
+/// decrypt it by `decrypt_with_account`. ED25519 and Curve25519 keys use the sealed box of
+/// libsodium, SECP256k1 keys use the ECIES variant of it
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptToPublicKeyParam {
+    /// the curve name, ED25519, Curve25519 or SECP256k1
+    #[prost(string, tag = "1")]
+    pub curve: std::string::String,
+    /// the hex encoded public key
+    #[prost(string, tag = "2")]
+    pub public_key: std::string::String,
+    /// the hex encoded plaintext
+    #[prost(string, tag = "3")]
+    pub plaintext: std::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptToPublicKeyResult {
+    /// the hex encoded ciphertext
+    #[prost(string, tag = "1")]
+    pub ciphertext: std::string::String,
+}
+/// FUNCTION: get_encryption_public_key(SignParam): EncryptionPublicKeyResult
+///
+/// The public key of the account that others encrypt to, takes `SignParam` with the
+/// `EncryptionKeyInput` as the input. The key of `SignParam` is not needed since the public key
+/// is taken from the account
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptionKeyInput {
+    /// empty for the key of the account, or Curve25519 for the X25519 key of the ED25519 or
+    /// Curve25519 key of the account
+    #[prost(string, tag = "1")]
+    pub curve: std::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EncryptionPublicKeyResult {
+    /// the curve to pass to `encrypt_to_public_key`
+    #[prost(string, tag = "1")]
+    pub curve: std::string::String,
+    /// the hex encoded public key
+    #[prost(string, tag = "2")]
+    pub public_key: std::string::String,
+}
+/// FUNCTION: decrypt_with_account(SignParam): DecryptWithAccountResult
+///
+/// Decrypt the ciphertext of `encrypt_to_public_key` with the key of the account, takes `SignParam`
+/// with the `DecryptWithAccountInput` as the input
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecryptWithAccountInput {
+    /// the same as the curve of `EncryptionKeyInput`
+    #[prost(string, tag = "1")]
+    pub curve: std::string::String,
+    /// the hex encoded ciphertext
+    #[prost(string, tag = "2")]
+    pub ciphertext: std::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecryptWithAccountResult {
+    /// the hex encoded plaintext
+    #[prost(string, tag = "1")]
+    pub plaintext: std::string::String,
+}
 /// Other
 // TODO: annotate following message usage
 
@@ -24,14 +24,19 @@ pub fn cache_keystore(keystore: Keystore) {
         .insert(keystore.id().to_owned(), keystore);
 }
 
+/// Write to a temporary file then rename it, so the keystore file is never left half written
 pub fn flush_keystore(ks: &Keystore) -> Result<()> {
     let json = ks.to_json();
 
     let file_dir = WALLET_FILE_DIR.read();
     let ks_path = format!("{}/{}.json", file_dir, ks.id());
-    let path = Path::new(&ks_path);
-    let mut file = fs::File::create(path)?;
-    let _ = file.write_all(&json.as_bytes());
+    let tmp_path = format!("{}.tmp", ks_path);
+    {
+        let mut file = fs::File::create(Path::new(&tmp_path))?;
+        file.write_all(&json.as_bytes())?;
+        file.sync_all()?;
+    }
+    fs::rename(Path::new(&tmp_path), Path::new(&ks_path))?;
     Ok(())
 }
 
@@ -1,6 +1,8 @@
+use std::convert::TryFrom;
 use std::fs;
 use std::io::Read;
 use std::path::Path;
+use std::str::FromStr;
 
 use bytes::BytesMut;
 use prost::Message;
@@ -9,24 +11,38 @@ use tcx_primitive::{get_account_path, private_key_without_version, FromHex, Type
 
 use tcx_bch::{BchAddress, BchTransaction};
 use tcx_btc_fork::{
-    BtcForkAddress, BtcForkSegWitTransaction, BtcForkSignedTxOutput, BtcForkTransaction,
-    BtcForkTxInput, WifDisplay,
+    BtcForkAddress, BtcForkMessageInput, BtcForkMessageOutput, BtcForkSegWitTransaction,
+    BtcForkSignedTxOutput, BtcForkTransaction, BtcForkTxInput, PsbtInput, WifDisplay,
 };
-use tcx_chain::{key_hash_from_mnemonic, key_hash_from_private_key, Keystore, KeystoreGuard};
-use tcx_chain::{Account, HdKeystore, Metadata, PrivateKeystore, Source};
-use tcx_ckb::{CkbAddress, CkbTxInput};
+use tcx_chain::{
+    key_hash_from_mnemonic_in_language, key_hash_from_private_key, key_hash_from_watch_key,
+    Keystore, KeystoreGuard,
+};
+use tcx_chain::{
+    Account, HdKeystore, LegacyKeystore, LegacySecret, Metadata, MnemonicLanguage, PrivateKeystore,
+    Source, WatchKeystore, DEFAULT_GAP_LIMIT,
+};
+use tcx_ckb::{CkbAddress, CkbMessageInput, CkbMessageOutput, CkbTxInput};
 use tcx_crypto::{XPUB_COMMON_IV, XPUB_COMMON_KEY_128};
-use tcx_filecoin::{FilecoinAddress, KeyInfo, UnsignedMessage};
+use tcx_eth::{EthAddress, EthTxInput};
+use tcx_filecoin::{FilecoinAddress, FilecoinMessageInput, KeyInfo, Signature, UnsignedMessage};
 use tcx_tron::TrxAddress;
 
+use crate::api::hd_store_export_shares_result;
+use crate::api::keystore_common_derive_addresses_result::DerivedAddress;
 use crate::api::keystore_common_derive_param::Derivation;
 use crate::api::sign_param::Key;
 use crate::api::{
-    AccountResponse, AccountsResponse, DerivedKeyResult, ExportPrivateKeyParam, HdStoreCreateParam,
-    HdStoreImportParam, KeyType, KeystoreCommonAccountsParam, KeystoreCommonDeriveParam,
-    KeystoreCommonExistsParam, KeystoreCommonExistsResult, KeystoreCommonExportResult,
-    PrivateKeyStoreExportParam, PrivateKeyStoreImportParam, PublicKeyParam, PublicKeyResult,
-    Response, WalletKeyParam, WalletResult,
+    AccountResponse, AccountsResponse, DecodeTxRecipient, DecodeTxResult, DecryptWithAccountInput,
+    DecryptWithAccountResult, DerivedKeyResult, EncryptToPublicKeyParam, EncryptToPublicKeyResult,
+    EncryptionKeyInput, EncryptionPublicKeyResult, ExportPrivateKeyParam, HdStoreCreateParam,
+    HdStoreExportSharesParam, HdStoreExportSharesResult, HdStoreImportParam,
+    HdStoreImportSharesParam, KeyType, KeystoreCommonAccountsParam,
+    KeystoreCommonDeriveAddressesParam, KeystoreCommonDeriveAddressesResult,
+    KeystoreCommonDeriveParam, KeystoreCommonExistsParam, KeystoreCommonExistsResult,
+    KeystoreCommonExportResult, LegacyKeystoreImportParam, PrivateKeyStoreExportParam,
+    PrivateKeyStoreImportParam, PublicKeyParam, PublicKeyResult, Response, VerifySignatureParam,
+    VerifySignatureResult, WalletKeyParam, WalletResult, WatchKeystoreImportParam,
 };
 use crate::api::{InitTokenCoreXParam, SignParam};
 use crate::error_handling::Result;
@@ -37,21 +53,29 @@ use crate::IS_DEBUG;
 use base58::ToBase58;
 use tcx_chain::tcx_ensure;
 use tcx_chain::Address;
-use tcx_chain::{MessageSigner, TransactionSigner};
+use tcx_chain::{
+    set_signature_self_check, MessageSigner, TransactionDecoder, TransactionSigner, TxSummary,
+};
 use tcx_constants::coin_info::coin_info_from_param;
-use tcx_constants::CurveType;
+use tcx_constants::{CoinInfo, CurveType};
 use tcx_crypto::aes::cbc::encrypt_pkcs7;
 use tcx_crypto::hash::dsha256;
-use tcx_crypto::KDF_ROUNDS;
-use tcx_primitive::{Bip32DeterministicPublicKey, Ss58Codec};
+use tcx_crypto::{Pbkdf2Params, SCryptParams, TypedKdfParams, KDF_ROUNDS};
+use tcx_nano::{NanoAddress, NanoTxInput};
+use tcx_primitive::{
+    decrypt_with_private_key, x25519_private_key, x25519_public_key, Bip32DeterministicPublicKey,
+    Secp256k1PublicKey, Slip39Error, Ss58Codec, TypedPublicKey,
+};
 use tcx_substrate::{
     decode_substrate_keystore, encode_substrate_keystore, ExportSubstrateKeystoreResult,
-    SubstrateAddress, SubstrateKeystore, SubstrateKeystoreParam, SubstrateRawTxIn,
+    SubstrateAddress, SubstrateKeystore, SubstrateKeystoreParam, SubstrateMessageInput,
+    SubstrateMessageOutput, SubstrateRawTxIn,
 };
 use tcx_tezos::address::TezosAddress;
-use tcx_tezos::transaction::TezosRawTxIn;
+use tcx_tezos::transaction::{TezosMessageInput, TezosMessageOutput, TezosRawTxIn};
 use tcx_tezos::{build_tezos_base58_private_key, pars_tezos_private_key};
-use tcx_tron::transaction::{TronMessageInput, TronTxInput};
+use tcx_tron::transaction::{TronMessageInput, TronMessageOutput, TronTxInput, TronTypedDataInput};
+use zeroize::Zeroizing;
 
 pub(crate) fn encode_message(msg: impl Message) -> Result<Vec<u8>> {
     if *IS_DEBUG.read() {
@@ -73,12 +97,14 @@ fn derive_account<'a, 'b>(keystore: &mut Keystore, derivation: &Derivation) -> R
 
     match derivation.chain_type.as_str() {
         "BITCOINCASH" => keystore.derive_coin::<BchAddress>(&coin_info),
-        "LITECOIN" => keystore.derive_coin::<BtcForkAddress>(&coin_info),
+        "BITCOIN" | "LITECOIN" => keystore.derive_coin::<BtcForkAddress>(&coin_info),
         "TRON" => keystore.derive_coin::<TrxAddress>(&coin_info),
+        "ETHEREUM" => keystore.derive_coin::<EthAddress>(&coin_info),
         "NERVOS" => keystore.derive_coin::<CkbAddress>(&coin_info),
         "POLKADOT" | "KUSAMA" => keystore.derive_coin::<SubstrateAddress>(&coin_info),
         "TEZOS" => keystore.derive_coin::<TezosAddress>(&coin_info),
         "FILECOIN" => keystore.derive_coin::<FilecoinAddress>(&coin_info),
+        "NANO" => keystore.derive_coin::<NanoAddress>(&coin_info),
         _ => Err(format_err!("unsupported_chain")),
     }
 }
@@ -89,10 +115,12 @@ pub fn init_token_core_x(data: &[u8]) -> Result<()> {
         xpub_common_key,
         xpub_common_iv,
         is_debug,
+        signature_self_check,
     } = InitTokenCoreXParam::decode(data).unwrap();
     *WALLET_FILE_DIR.write() = file_dir.to_string();
     *XPUB_COMMON_KEY_128.write() = xpub_common_key.to_string();
     *XPUB_COMMON_IV.write() = xpub_common_iv.to_string();
+    set_signature_self_check(signature_self_check);
 
     if is_debug {
         *IS_DEBUG.write() = is_debug;
@@ -132,6 +160,7 @@ pub(crate) fn scan_keystores() -> Result<()> {
         let version = v["version"].as_i64().expect("version");
         if version == i64::from(HdKeystore::VERSION)
             || version == i64::from(PrivateKeystore::VERSION)
+            || version == i64::from(WatchKeystore::VERSION)
         {
             let keystore = Keystore::from_json(&contents)?;
             cache_keystore(keystore);
@@ -140,6 +169,30 @@ pub(crate) fn scan_keystores() -> Result<()> {
     Ok(())
 }
 
+/// The default params of the KDF are used for the zero fields
+fn kdf_from_param(param: &Option<KdfParam>) -> Result<TypedKdfParams> {
+    let param = match param {
+        Some(param) => param,
+        None => return Ok(TypedKdfParams::default()),
+    };
+
+    match param.kdf.as_str() {
+        "" | "pbkdf2" => Ok(TypedKdfParams::Pbkdf2(Pbkdf2Params::new(param.c))),
+        "scrypt" => Ok(TypedKdfParams::SCrypt(SCryptParams::new(
+            param.n, param.r, param.p,
+        ))),
+        _ => Err(format_err!("unsupported_kdf")),
+    }
+}
+
+fn language_from_param(language: &str) -> Result<MnemonicLanguage> {
+    if language.is_empty() {
+        Ok(MnemonicLanguage::English)
+    } else {
+        MnemonicLanguage::from_str(language)
+    }
+}
+
 pub(crate) fn hd_store_create(data: &[u8]) -> Result<Vec<u8>> {
     let param: HdStoreCreateParam =
         HdStoreCreateParam::decode(data).expect("import wallet from mnemonic");
@@ -149,7 +202,19 @@ pub(crate) fn hd_store_create(data: &[u8]) -> Result<Vec<u8>> {
     meta.password_hint = param.password_hint.to_owned();
     meta.source = Source::Mnemonic;
 
-    let ks = HdKeystore::new(&param.password, meta);
+    let word_count = if param.word_count == 0 {
+        12
+    } else {
+        param.word_count as usize
+    };
+    let ks = HdKeystore::new_in_language(
+        language_from_param(&param.language)?,
+        word_count,
+        &param.password,
+        &param.passphrase,
+        meta,
+        kdf_from_param(&param.kdf)?,
+    )?;
 
     let keystore = Keystore::Hd(ks);
     flush_keystore(&keystore)?;
@@ -173,7 +238,11 @@ pub(crate) fn hd_store_import(data: &[u8]) -> Result<Vec<u8>> {
 
     let mut founded_id: Option<String> = None;
     {
-        let key_hash = key_hash_from_mnemonic(&param.mnemonic)?;
+        let key_hash = key_hash_from_mnemonic_in_language(
+            &param.mnemonic,
+            language_from_param(&param.language)?,
+            &param.passphrase,
+        )?;
         let map = KEYSTORE_MAP.read();
         if let Some(founded) = map
             .values()
@@ -192,7 +261,14 @@ pub(crate) fn hd_store_import(data: &[u8]) -> Result<Vec<u8>> {
     meta.password_hint = param.password_hint.to_owned();
     meta.source = Source::Mnemonic;
 
-    let ks = HdKeystore::from_mnemonic(&param.mnemonic, &param.password, meta)?;
+    let ks = HdKeystore::from_mnemonic_in_language(
+        &param.mnemonic,
+        language_from_param(&param.language)?,
+        &param.passphrase,
+        &param.password,
+        meta,
+        kdf_from_param(&param.kdf)?,
+    )?;
 
     let mut keystore = Keystore::Hd(ks);
 
@@ -239,6 +315,92 @@ pub(crate) fn hd_store_export(data: &[u8]) -> Result<Vec<u8>> {
     encode_message(export_result)
 }
 
+fn share_count_from_param(count: u32) -> Result<u8> {
+    u8::try_from(count).map_err(|_| Slip39Error::InvalidThreshold.into())
+}
+
+pub(crate) fn hd_store_export_shares(data: &[u8]) -> Result<Vec<u8>> {
+    let param: HdStoreExportSharesParam = HdStoreExportSharesParam::decode(data)?;
+    let group_threshold = share_count_from_param(param.group_threshold)?;
+    let groups = param
+        .groups
+        .iter()
+        .map(|group| {
+            Ok((
+                share_count_from_param(group.member_threshold)?,
+                share_count_from_param(group.member_count)?,
+            ))
+        })
+        .collect::<Result<Vec<(u8, u8)>>>()?;
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let guard = KeystoreGuard::unlock_by_password(keystore, &param.password)?;
+
+    let export_result = HdStoreExportSharesResult {
+        id: guard.keystore().id(),
+        groups: guard
+            .keystore()
+            .export_shares(group_threshold, &groups, &param.passphrase)?
+            .into_iter()
+            .map(|shares| hd_store_export_shares_result::Group { shares })
+            .collect(),
+    };
+
+    encode_message(export_result)
+}
+
+pub(crate) fn hd_store_import_shares(data: &[u8]) -> Result<Vec<u8>> {
+    let param: HdStoreImportSharesParam = HdStoreImportSharesParam::decode(data)?;
+
+    let mut meta = Metadata::default();
+    meta.name = param.name.to_owned();
+    meta.password_hint = param.password_hint.to_owned();
+    meta.source = Source::Slip39;
+
+    let mut keystore = Keystore::Hd(HdKeystore::from_shares(
+        &param.shares,
+        &param.passphrase,
+        &param.password,
+        meta,
+        kdf_from_param(&param.kdf)?,
+    )?);
+
+    let founded_id = {
+        let key_hash = keystore.key_hash();
+        let map = KEYSTORE_MAP.read();
+        map.values()
+            .find(|founded| founded.key_hash() == key_hash)
+            .map(|founded| founded.id())
+    };
+
+    if founded_id.is_some() && !param.overwrite {
+        return Err(format_err!("{}", "address_already_exist"));
+    }
+
+    if founded_id.is_some() {
+        keystore.set_id(&founded_id.unwrap());
+    }
+
+    flush_keystore(&keystore)?;
+
+    let meta = keystore.meta();
+    let wallet = WalletResult {
+        id: keystore.id(),
+        name: meta.name.to_owned(),
+        source: "SLIP39".to_owned(),
+        accounts: vec![],
+        created_at: meta.timestamp.clone(),
+    };
+    let ret = encode_message(wallet)?;
+    cache_keystore(keystore);
+    Ok(ret)
+}
+
 fn enc_xpub(xpub: &str, network: &str) -> Result<String> {
     let xpk = Bip32DeterministicPublicKey::from_hex(xpub)?;
     let ext_pub_key: String;
@@ -380,7 +542,12 @@ pub(crate) fn private_key_store_import(data: &[u8]) -> Result<Vec<u8>> {
         source: Source::Private,
         ..Metadata::default()
     };
-    let pk_store = PrivateKeystore::from_private_key(&private_key, &param.password, meta);
+    let pk_store = PrivateKeystore::from_private_key_with_kdf(
+        &private_key,
+        &param.password,
+        meta,
+        kdf_from_param(&param.kdf)?,
+    )?;
 
     let mut keystore = Keystore::PrivateKey(pk_store);
 
@@ -418,7 +585,7 @@ pub(crate) fn private_key_store_export(data: &[u8]) -> Result<Vec<u8>> {
 
     let guard = KeystoreGuard::unlock_by_password(keystore, &param.password)?;
 
-    let pk_hex = guard.keystore().export()?;
+    let pk_hex = Zeroizing::new(guard.keystore().export()?);
 
     // private_key prefix is only about chain type and network
     let coin_info = coin_info_from_param(&param.chain_type, &param.network, "", "")?;
@@ -427,7 +594,7 @@ pub(crate) fn private_key_store_export(data: &[u8]) -> Result<Vec<u8>> {
     } else if param.chain_type.as_str() == "TEZOS" {
         Ok(build_tezos_base58_private_key(pk_hex.as_str())?)
     } else {
-        let bytes = hex::decode(pk_hex.to_string())?;
+        let bytes = Zeroizing::new(hex::decode(pk_hex.as_str())?);
         let typed_pk = TypedPrivateKey::from_slice(CurveType::SECP256k1, &bytes)?;
         typed_pk.fmt(&coin_info)
     }?;
@@ -452,7 +619,7 @@ pub(crate) fn export_private_key(data: &[u8]) -> Result<Vec<u8>> {
 
     let mut guard = KeystoreGuard::unlock_by_password(keystore, &param.password)?;
 
-    let pk_hex = if param.path.is_empty() {
+    let pk_hex = Zeroizing::new(if param.path.is_empty() {
         guard
             .keystore_mut()
             .export_private_key(&param.chain_type, &param.main_address, None)?
@@ -473,20 +640,20 @@ pub(crate) fn export_private_key(data: &[u8]) -> Result<Vec<u8>> {
             &param.main_address,
             Some(relative_path),
         )?
-    };
+    });
 
     // private_key prefix is only about chain type and network
     let coin_info = coin_info_from_param(&param.chain_type, &param.network, "", "")?;
-    let value = if ["TRON", "POLKADOT", "KUSAMA"].contains(&param.chain_type.as_str()) {
+    let value = if ["TRON", "ETHEREUM", "POLKADOT", "KUSAMA"].contains(&param.chain_type.as_str()) {
         Ok(pk_hex.to_string())
     } else if "FILECOIN".contains(&param.chain_type.as_str()) {
         if let Some(account) = guard
             .keystore_mut()
             .account("FILECOIN", &param.main_address)
         {
-            Ok(hex::encode(
-                KeyInfo::from_private_key(account.curve, &hex::decode(pk_hex)?)?.to_json()?,
-            ))
+            let bytes = Zeroizing::new(hex::decode(pk_hex.as_str())?);
+            let key_info = KeyInfo::from_private_key(account.curve, &bytes)?;
+            Ok(hex::encode(&*Zeroizing::new(key_info.to_json()?)))
         } else {
             Err(format_err!("{}", "account_not_found"))
         }
@@ -496,7 +663,7 @@ pub(crate) fn export_private_key(data: &[u8]) -> Result<Vec<u8>> {
         // private_key prefix is only about chain type and network
         let coin_info = coin_info_from_param(&param.chain_type, &param.network, "", "")?;
 
-        let bytes = hex::decode(pk_hex.to_string())?;
+        let bytes = Zeroizing::new(hex::decode(pk_hex.as_str())?);
         let typed_pk = TypedPrivateKey::from_slice(CurveType::SECP256k1, &bytes)?;
         typed_pk.fmt(&coin_info)
     }?;
@@ -529,6 +696,36 @@ pub(crate) fn keystore_common_verify(data: &[u8]) -> Result<Vec<u8>> {
     }
 }
 
+pub(crate) fn keystore_change_password(data: &[u8]) -> Result<Vec<u8>> {
+    let param: KeystoreChangePasswordParam =
+        KeystoreChangePasswordParam::decode(data).expect("keystore_change_password");
+    let kdf = match param.kdf {
+        Some(_) => Some(kdf_from_param(&param.kdf)?),
+        None => None,
+    };
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    // the cached one is replaced only after the file is flushed
+    let mut changed = Keystore::from_json(&keystore.to_json())?;
+    changed.change_password(&param.old_password, &param.new_password, kdf)?;
+    if !param.password_hint.is_empty() {
+        changed.set_password_hint(&param.password_hint);
+    }
+    flush_keystore(&changed)?;
+    *keystore = changed;
+
+    let rsp = Response {
+        is_success: true,
+        error: "".to_owned(),
+    };
+    encode_message(rsp)
+}
+
 pub(crate) fn keystore_common_delete(data: &[u8]) -> Result<Vec<u8>> {
     let param: WalletKeyParam = WalletKeyParam::decode(data).expect("keystore_common_delete");
     let mut map = KEYSTORE_MAP.write();
@@ -561,7 +758,11 @@ pub(crate) fn keystore_common_exists(data: &[u8]) -> Result<Vec<u8>> {
             .split_whitespace()
             .collect::<Vec<&str>>()
             .join(" ");
-        key_hash = key_hash_from_mnemonic(mnemonic)?;
+        key_hash = key_hash_from_mnemonic_in_language(
+            mnemonic,
+            language_from_param(&param.language)?,
+            &param.passphrase,
+        )?;
     } else {
         if param.encoding.eq("TEZOS") {
             key_hash = key_hash_from_tezos_format_pk(&param.value)?;
@@ -619,6 +820,90 @@ pub(crate) fn keystore_common_accounts(data: &[u8]) -> Result<Vec<u8>> {
     encode_message(accounts_rsp)
 }
 
+fn derived_address_from_account(account: Account) -> DerivedAddress {
+    DerivedAddress {
+        address: account.address,
+        path: account.derivation_path,
+        public_key: account.public_key.unwrap_or_default(),
+    }
+}
+
+fn derive_addresses_of<A: Address>(
+    keystore: &Keystore,
+    param: &KeystoreCommonDeriveAddressesParam,
+) -> Result<KeystoreCommonDeriveAddressesResult> {
+    if param.discover {
+        let gap_limit = if param.gap_limit == 0 {
+            DEFAULT_GAP_LIMIT
+        } else {
+            param.gap_limit
+        };
+        let discovery = keystore.discover_addresses::<A, _>(
+            &param.chain_type,
+            &param.address,
+            param.change,
+            gap_limit,
+            |address| Ok(param.used_addresses.iter().any(|x| x == address)),
+        )?;
+        Ok(KeystoreCommonDeriveAddressesResult {
+            addresses: discovery
+                .used_addresses
+                .into_iter()
+                .map(derived_address_from_account)
+                .collect(),
+            next_address: Some(derived_address_from_account(discovery.next_address)),
+        })
+    } else {
+        let accounts = keystore.derive_addresses::<A>(
+            &param.chain_type,
+            &param.address,
+            param.change,
+            param.start,
+            param.count,
+        )?;
+        Ok(KeystoreCommonDeriveAddressesResult {
+            addresses: accounts
+                .into_iter()
+                .map(derived_address_from_account)
+                .collect(),
+            next_address: None,
+        })
+    }
+}
+
+pub(crate) fn keystore_common_derive_addresses(data: &[u8]) -> Result<Vec<u8>> {
+    let param: KeystoreCommonDeriveAddressesParam =
+        KeystoreCommonDeriveAddressesParam::decode(data)?;
+    let map = KEYSTORE_MAP.read();
+    let keystore: &Keystore = match map.get(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let ret = match param.chain_type.as_str() {
+        "BITCOINCASH" => derive_addresses_of::<BchAddress>(keystore, &param),
+        "BITCOIN" | "LITECOIN" => derive_addresses_of::<BtcForkAddress>(keystore, &param),
+        "TRON" => derive_addresses_of::<TrxAddress>(keystore, &param),
+        "ETHEREUM" => derive_addresses_of::<EthAddress>(keystore, &param),
+        "NERVOS" => derive_addresses_of::<CkbAddress>(keystore, &param),
+        "FILECOIN" => derive_addresses_of::<FilecoinAddress>(keystore, &param),
+        _ => Err(format_err!("unsupported_chain")),
+    }?;
+    encode_message(ret)
+}
+
+/// The password or the derived key of the `SignParam` unlocks the keystore until the guard is
+/// dropped
+fn unlock_keystore<'a>(keystore: &'a mut Keystore, key: &Option<Key>) -> Result<KeystoreGuard<'a>> {
+    match key {
+        Some(Key::Password(password)) => KeystoreGuard::unlock_by_password(keystore, password),
+        Some(Key::DerivedKey(derived_key)) => {
+            KeystoreGuard::unlock_by_derived_key(keystore, derived_key)
+        }
+        None => Err(format_err!("password_required")),
+    }
+}
+
 pub(crate) fn sign_tx(data: &[u8]) -> Result<Vec<u8>> {
     let param: SignParam = SignParam::decode(data).expect("SignTxParam");
 
@@ -628,24 +913,235 @@ pub(crate) fn sign_tx(data: &[u8]) -> Result<Vec<u8>> {
         _ => Err(format_err!("{}", "wallet_not_found")),
     }?;
 
-    let mut guard = match param.key.clone().unwrap() {
-        Key::Password(password) => KeystoreGuard::unlock_by_password(keystore, &password)?,
-        Key::DerivedKey(derived_key) => {
-            KeystoreGuard::unlock_by_derived_key(keystore, &derived_key)?
-        }
-    };
+    let mut guard = unlock_keystore(keystore, &param.key)?;
 
     match param.chain_type.as_str() {
-        "BITCOINCASH" | "LITECOIN" => sign_btc_fork_transaction(&param, guard.keystore_mut()),
+        "BITCOIN" | "BITCOINCASH" | "LITECOIN" => {
+            sign_btc_fork_transaction(&param, guard.keystore_mut())
+        }
         "TRON" => sign_tron_tx(&param, guard.keystore_mut()),
+        "ETHEREUM" => sign_eth_tx(&param, guard.keystore_mut()),
         "NERVOS" => sign_nervos_ckb(&param, guard.keystore_mut()),
         "POLKADOT" | "KUSAMA" => sign_substrate_tx_raw(&param, guard.keystore_mut()),
         "FILECOIN" => sign_filecoin_tx(&param, guard.keystore_mut()),
         "TEZOS" => sign_tezos_tx_raw(&param, guard.keystore_mut()),
+        "NANO" => sign_nano_block(&param, guard.keystore_mut()),
+        _ => Err(format_err!("unsupported_chain")),
+    }
+}
+
+pub(crate) fn decode_tx(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data).expect("SignParam");
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut summary = match param.chain_type.as_str() {
+        "BITCOIN" | "BITCOINCASH" | "LITECOIN" => decode_btc_fork_transaction(&param, keystore),
+        "TRON" => decode_transaction::<TronTxInput>(&param, keystore),
+        "ETHEREUM" => decode_transaction::<EthTxInput>(&param, keystore),
+        "NERVOS" => decode_transaction::<CkbTxInput>(&param, keystore),
+        "POLKADOT" | "KUSAMA" => decode_transaction::<SubstrateRawTxIn>(&param, keystore),
+        "FILECOIN" => decode_transaction::<UnsignedMessage>(&param, keystore),
+        "TEZOS" => decode_transaction::<TezosRawTxIn>(&param, keystore),
+        "NANO" => decode_transaction::<NanoTxInput>(&param, keystore),
         _ => Err(format_err!("unsupported_chain")),
+    }?;
+    if summary.senders.is_empty() {
+        summary.senders.push(param.address.to_string());
+    }
+
+    let ret = DecodeTxResult {
+        chain_type: param.chain_type.to_string(),
+        senders: summary.senders,
+        recipients: summary
+            .recipients
+            .into_iter()
+            .map(|x| DecodeTxRecipient {
+                address: x.address,
+                amount: x.amount,
+                is_change: x.is_change,
+            })
+            .collect(),
+        fee: summary.fee,
+        contract: summary.contract,
+        method: summary.method,
+        data: summary.data,
+    };
+    encode_message(ret)
+}
+
+pub(crate) fn verify_signature(data: &[u8]) -> Result<Vec<u8>> {
+    let param: VerifySignatureParam =
+        VerifySignatureParam::decode(data).expect("VerifySignatureParam");
+    let curve = CurveType::from_str(&param.curve)?;
+    let data = hex::decode(&param.data)?;
+    let signature = hex::decode(&param.signature)?;
+
+    let recovered_public_key = if curve == CurveType::SECP256k1 && signature.len() == 65 {
+        Secp256k1PublicKey::recover(&data, &signature)
+            .map(|pk| hex::encode(pk.to_compressed()))
+            .unwrap_or_default()
+    } else {
+        "".to_string()
+    };
+
+    // without the public key nothing is verified, the caller compares the recovered one
+    let is_valid = if param.public_key.is_empty() {
+        tcx_ensure!(
+            !recovered_public_key.is_empty(),
+            format_err!("public_key_required")
+        );
+        false
+    } else {
+        let public_key = TypedPublicKey::from_slice(curve, &hex::decode(&param.public_key)?)?;
+        public_key.verify(&data, &signature)
+    };
+
+    let ret = VerifySignatureResult {
+        is_valid,
+        recovered_public_key,
+    };
+    encode_message(ret)
+}
+
+pub(crate) fn encrypt_to_public_key(data: &[u8]) -> Result<Vec<u8>> {
+    let param: EncryptToPublicKeyParam =
+        EncryptToPublicKeyParam::decode(data).expect("EncryptToPublicKeyParam");
+    let curve = CurveType::from_str(&param.curve)?;
+    let public_key = TypedPublicKey::from_slice(curve, &hex::decode(&param.public_key)?)?;
+    let plaintext = hex::decode(&param.plaintext)?;
+
+    let ciphertext = tcx_primitive::encrypt_to_public_key(&public_key, &plaintext)?;
+    let ret = EncryptToPublicKeyResult {
+        ciphertext: hex::encode(ciphertext),
+    };
+    encode_message(ret)
+}
+
+/// The curve of `EncryptionKeyInput` and `DecryptWithAccountInput`, empty for the key of the
+/// account, or Curve25519 for the X25519 key of the ed25519 or curve25519 key of the account
+fn is_x25519_key(curve: &str) -> Result<bool> {
+    if curve.is_empty() {
+        return Ok(false);
+    }
+    tcx_ensure!(
+        CurveType::from_str(curve)? == CurveType::Curve25519,
+        format_err!("invalid_curve_type")
+    );
+    Ok(true)
+}
+
+fn find_encryption_key(param: &SignParam, curve: &str) -> Result<TypedPrivateKey> {
+    let is_x25519 = is_x25519_key(curve)?;
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut guard = unlock_keystore(keystore, &param.key)?;
+    let private_key = guard
+        .keystore_mut()
+        .find_private_key(&param.chain_type, &param.address)?;
+    if is_x25519 {
+        Ok(TypedPrivateKey::Curve25519(x25519_private_key(
+            &private_key,
+        )?))
+    } else {
+        Ok(private_key)
     }
 }
 
+/// The public key is taken from the account, so the keystore is not unlocked
+pub(crate) fn get_encryption_public_key(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data)?;
+    let input = EncryptionKeyInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_encryption_key_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let is_x25519 = is_x25519_key(&input.curve)?;
+
+    let map = KEYSTORE_MAP.read();
+    let keystore: &Keystore = match map.get(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+    let account = keystore
+        .account(&param.chain_type, &param.address)
+        .ok_or_else(|| format_err!("account_not_found"))?;
+    let public_key = account
+        .public_key
+        .as_ref()
+        .ok_or_else(|| format_err!("account_not_contains_public_key"))?;
+    let public_key = TypedPublicKey::from_slice(account.curve, &hex::decode(public_key)?)?;
+    let public_key = if is_x25519 {
+        TypedPublicKey::Curve25519(x25519_public_key(&public_key)?)
+    } else {
+        public_key
+    };
+
+    let ret = EncryptionPublicKeyResult {
+        curve: public_key.curve_type().as_str().to_string(),
+        public_key: hex::encode(public_key.to_bytes()),
+    };
+    encode_message(ret)
+}
+
+pub(crate) fn decrypt_with_account(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data)?;
+    let input = DecryptWithAccountInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_decrypt_with_account_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let ciphertext = hex::decode(&input.ciphertext)?;
+
+    let private_key = find_encryption_key(&param, &input.curve)?;
+    let plaintext = decrypt_with_private_key(&private_key, &ciphertext)?;
+    let ret = DecryptWithAccountResult {
+        plaintext: hex::encode(plaintext),
+    };
+    encode_message(ret)
+}
+
+/// The network of the account signing the transaction decides how the addresses are rendered
+fn decode_transaction<T: TransactionDecoder + Message + Default>(
+    param: &SignParam,
+    keystore: &Keystore,
+) -> Result<TxSummary> {
+    let input = T::decode(
+        param
+            .input
+            .as_ref()
+            .expect("tx_input")
+            .value
+            .clone()
+            .as_slice(),
+    )?;
+    let coin = match keystore.account(&param.chain_type, &param.address) {
+        Some(account) => CoinInfo {
+            coin: account.coin.to_string(),
+            derivation_path: account.derivation_path.to_string(),
+            curve: account.curve,
+            network: account.network.to_string(),
+            seg_wit: account.seg_wit.to_string(),
+        },
+        None => coin_info_from_param(&param.chain_type, "", "", "")?,
+    };
+    input.decode_transaction(&coin)
+}
+
 pub(crate) fn get_public_key(data: &[u8]) -> Result<Vec<u8>> {
     let param: PublicKeyParam = PublicKeyParam::decode(data).expect("PublicKeyParam");
 
@@ -700,6 +1196,29 @@ pub(crate) fn sign_filecoin_tx(param: &SignParam, keystore: &mut Keystore) -> Re
     encode_message(signed_tx)
 }
 
+/// The legacy signer only handles P2PKH inputs, the segwit one signs every input by its type
+fn has_witness_unspent(input: &BtcForkTxInput) -> bool {
+    input.unspents.iter().any(|x| {
+        BtcForkAddress::from_str(&x.address)
+            .map(|addr| !addr.script_pubkey().is_p2pkh())
+            .unwrap_or(false)
+    })
+}
+
+/// `to` may be left empty when the payments are all in `outputs`
+fn recipients_valid<A: Address>(input: &BtcForkTxInput, coin: &CoinInfo) -> bool {
+    let to_valid = if input.to.is_empty() {
+        !input.outputs.is_empty() || !input.op_return.is_empty()
+    } else {
+        A::is_valid(&input.to, coin)
+    };
+    to_valid
+        && input
+            .outputs
+            .iter()
+            .all(|x| !x.script_pub_key.is_empty() || A::is_valid(&x.address, coin))
+}
+
 pub(crate) fn sign_btc_fork_transaction(
     param: &SignParam,
     keystore: &mut Keystore,
@@ -717,19 +1236,19 @@ pub(crate) fn sign_btc_fork_transaction(
     let coin = coin_info_from_param(&param.chain_type, &input.network, &input.seg_wit, "")?;
 
     let signed_tx: BtcForkSignedTxOutput = if param.chain_type.as_str() == "BITCOINCASH" {
-        if !BchAddress::is_valid(&input.to, &coin) {
+        if !recipients_valid::<BchAddress>(&input, &coin) {
             return Err(format_err!("address_invalid"));
         }
         let tran = BchTransaction::new(input, coin);
         keystore.sign_transaction(&param.chain_type, &param.address, &tran)?
-    } else if input.seg_wit.as_str() != "NONE" {
-        if !BtcForkAddress::is_valid(&input.to, &coin) {
+    } else if input.seg_wit.as_str() != "NONE" || has_witness_unspent(&input) {
+        if !recipients_valid::<BtcForkAddress>(&input, &coin) {
             return Err(format_err!("address_invalid"));
         }
         let tran = BtcForkSegWitTransaction::new(input, coin);
         keystore.sign_transaction(&param.chain_type, &param.address, &tran)?
     } else {
-        if !BtcForkAddress::is_valid(&input.to, &coin) {
+        if !recipients_valid::<BtcForkAddress>(&input, &coin) {
             return Err(format_err!("address_invalid"));
         }
         let tran = BtcForkTransaction::new(input, coin);
@@ -738,6 +1257,33 @@ pub(crate) fn sign_btc_fork_transaction(
     encode_message(signed_tx)
 }
 
+fn decode_btc_fork_transaction(param: &SignParam, keystore: &mut Keystore) -> Result<TxSummary> {
+    let input: BtcForkTxInput = BtcForkTxInput::decode(
+        param
+            .input
+            .as_ref()
+            .expect("tx_input")
+            .value
+            .clone()
+            .as_slice(),
+    )?;
+    let coin = coin_info_from_param(&param.chain_type, &input.network, &input.seg_wit, "")?;
+
+    if param.chain_type.as_str() == "BITCOINCASH" {
+        let tran = BchTransaction::new(input, coin);
+        let change = tran.change_script_pubkey(keystore, &param.chain_type, &param.address)?;
+        tran.decode_transaction(change)
+    } else if input.seg_wit.as_str() != "NONE" || has_witness_unspent(&input) {
+        let tran = BtcForkSegWitTransaction::new(input, coin);
+        let change = tran.change_script_pubkey(keystore, &param.chain_type, &param.address)?;
+        tran.decode_transaction(change)
+    } else {
+        let tran = BtcForkTransaction::new(input, coin);
+        let change = tran.change_script_pubkey(keystore, &param.chain_type, &param.address)?;
+        tran.decode_transaction(change)
+    }
+}
+
 pub(crate) fn sign_nervos_ckb(param: &SignParam, keystore: &mut Keystore) -> Result<Vec<u8>> {
     let input: CkbTxInput = CkbTxInput::decode(
         param
@@ -769,6 +1315,25 @@ pub(crate) fn sign_tron_tx(param: &SignParam, keystore: &mut Keystore) -> Result
     encode_message(signed_tx)
 }
 
+pub(crate) fn sign_eth_tx(param: &SignParam, keystore: &mut Keystore) -> Result<Vec<u8>> {
+    let input: EthTxInput = EthTxInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_tx_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let coin = coin_info_from_param(&param.chain_type, "", "", "")?;
+    // an empty `to` means contract creation
+    if !input.to.is_empty() && !EthAddress::is_valid(&input.to, &coin) {
+        return Err(format_err!("address_invalid"));
+    }
+    let signed_tx = keystore.sign_transaction(&param.chain_type, &param.address, &input)?;
+
+    encode_message(signed_tx)
+}
+
 pub(crate) fn tron_sign_message(data: &[u8]) -> Result<Vec<u8>> {
     let param: SignParam = SignParam::decode(data).expect("SignParam");
 
@@ -800,6 +1365,156 @@ pub(crate) fn tron_sign_message(data: &[u8]) -> Result<Vec<u8>> {
     encode_message(signed_tx)
 }
 
+pub(crate) fn sign_message(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data).expect("SignParam");
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut guard = unlock_keystore(keystore, &param.key)?;
+
+    match param.chain_type.as_str() {
+        "BITCOIN" | "BITCOINCASH" | "LITECOIN" => sign_message_of::<
+            BtcForkMessageInput,
+            BtcForkMessageOutput,
+        >(&param, guard.keystore_mut()),
+        "TRON" => {
+            sign_message_of::<TronMessageInput, TronMessageOutput>(&param, guard.keystore_mut())
+        }
+        "NERVOS" => {
+            sign_message_of::<CkbMessageInput, CkbMessageOutput>(&param, guard.keystore_mut())
+        }
+        "POLKADOT" | "KUSAMA" => sign_message_of::<SubstrateMessageInput, SubstrateMessageOutput>(
+            &param,
+            guard.keystore_mut(),
+        ),
+        "FILECOIN" => {
+            sign_message_of::<FilecoinMessageInput, Signature>(&param, guard.keystore_mut())
+        }
+        "TEZOS" => {
+            sign_message_of::<TezosMessageInput, TezosMessageOutput>(&param, guard.keystore_mut())
+        }
+        _ => Err(format_err!("unsupported_chain")),
+    }
+}
+
+fn sign_message_of<I: Message + Default, O: Message>(
+    param: &SignParam,
+    keystore: &mut Keystore,
+) -> Result<Vec<u8>>
+where
+    Keystore: MessageSigner<I, O>,
+{
+    let input = I::decode(
+        param
+            .input
+            .as_ref()
+            .expect("message_input")
+            .value
+            .clone()
+            .as_slice(),
+    )?;
+    let signed = keystore.sign_message(&param.chain_type, &param.address, &input)?;
+    encode_message(signed)
+}
+
+pub(crate) fn tron_sign_typed_data(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data)?;
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut guard = unlock_keystore(keystore, &param.key)?;
+
+    let input: TronTypedDataInput = TronTypedDataInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_message_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let signed = guard
+        .keystore_mut()
+        .sign_message(&param.chain_type, &param.address, &input)?;
+    encode_message(signed)
+}
+
+pub(crate) fn sign_psbt(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data)?;
+    tcx_ensure!(
+        ["BITCOIN", "LITECOIN"].contains(&param.chain_type.as_str()),
+        format_err!("unsupported_chain")
+    );
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut guard = unlock_keystore(keystore, &param.key)?;
+
+    let input: PsbtInput = PsbtInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_tx_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let signed =
+        guard
+            .keystore_mut()
+            .sign_transaction(&param.chain_type, &param.address, &input)?;
+    encode_message(signed)
+}
+
+pub(crate) fn create_psbt(data: &[u8]) -> Result<Vec<u8>> {
+    let param: SignParam = SignParam::decode(data)?;
+    tcx_ensure!(
+        ["BITCOIN", "LITECOIN"].contains(&param.chain_type.as_str()),
+        format_err!("unsupported_chain")
+    );
+
+    let mut map = KEYSTORE_MAP.write();
+    let keystore: &mut Keystore = match map.get_mut(&param.id) {
+        Some(keystore) => Ok(keystore),
+        _ => Err(format_err!("{}", "wallet_not_found")),
+    }?;
+
+    let mut guard = unlock_keystore(keystore, &param.key)?;
+
+    let input: BtcForkTxInput = BtcForkTxInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_tx_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let coin = coin_info_from_param(&param.chain_type, &input.network, &input.seg_wit, "")?;
+    if !recipients_valid::<BtcForkAddress>(&input, &coin) {
+        return Err(format_err!("address_invalid"));
+    }
+
+    let keystore = guard.keystore_mut();
+    let psbt = if input.seg_wit.as_str() != "NONE" || has_witness_unspent(&input) {
+        let tran = BtcForkSegWitTransaction::new(input, coin);
+        tcx_btc_fork::create_psbt(keystore, &param.chain_type, &param.address, &tran)?
+    } else {
+        let tran = BtcForkTransaction::new(input, coin);
+        tcx_btc_fork::create_psbt(keystore, &param.chain_type, &param.address, &tran)?
+    };
+    encode_message(psbt)
+}
+
 pub(crate) fn get_derived_key(data: &[u8]) -> Result<Vec<u8>> {
     let param: WalletKeyParam = WalletKeyParam::decode(data).unwrap();
     let mut map = KEYSTORE_MAP.write();
@@ -844,11 +1559,97 @@ pub(crate) fn import_substrate_keystore(data: &[u8]) -> Result<Vec<u8>> {
         password_hint: "".to_string(),
         overwrite: param.overwrite,
         encoding: "".to_string(),
+        kdf: None,
     };
     let param_bytes = encode_message(pk_import_param)?;
     private_key_store_import(&param_bytes)
 }
 
+pub(crate) fn import_legacy_keystore(data: &[u8]) -> Result<Vec<u8>> {
+    let param: LegacyKeystoreImportParam = LegacyKeystoreImportParam::decode(data)?;
+    let ks = LegacyKeystore::from_json(&param.keystore)?;
+    match ks.decrypt(&param.password)? {
+        LegacySecret::Mnemonic(mnemonic) => {
+            let hd_import_param = HdStoreImportParam {
+                mnemonic,
+                password: param.password.to_string(),
+                source: "MNEMONIC".to_string(),
+                name: ks.name(),
+                password_hint: ks.password_hint(),
+                overwrite: param.overwrite,
+                kdf: param.kdf,
+                passphrase: "".to_string(),
+                language: "".to_string(),
+            };
+            hd_store_import(&encode_message(hd_import_param)?)
+        }
+        LegacySecret::PrivateKey(private_key) => {
+            let pk_import_param = PrivateKeyStoreImportParam {
+                private_key,
+                password: param.password.to_string(),
+                name: ks.name(),
+                password_hint: ks.password_hint(),
+                overwrite: param.overwrite,
+                encoding: "".to_string(),
+                kdf: param.kdf,
+            };
+            private_key_store_import(&encode_message(pk_import_param)?)
+        }
+    }
+}
+
+pub(crate) fn watch_keystore_import(data: &[u8]) -> Result<Vec<u8>> {
+    let param: WatchKeystoreImportParam = WatchKeystoreImportParam::decode(data)?;
+    let mut founded_id: Option<String> = None;
+    {
+        let key_hash = key_hash_from_watch_key(&param.public_key)?;
+        let map = KEYSTORE_MAP.read();
+        if let Some(founded) = map
+            .values()
+            .find(|keystore| keystore.key_hash() == key_hash)
+        {
+            founded_id = Some(founded.id());
+        }
+    }
+
+    if founded_id.is_some() && !param.overwrite {
+        return Err(format_err!("{}", "address_already_exist"));
+    }
+
+    let meta = Metadata {
+        name: param.name,
+        password_hint: param.password_hint,
+        source: Source::Watch,
+        ..Metadata::default()
+    };
+    let watch_store = WatchKeystore::from_key_expression(
+        &param.public_key,
+        &param.password,
+        meta,
+        kdf_from_param(&param.kdf)?,
+    )?;
+
+    let mut keystore = Keystore::Watch(watch_store);
+
+    if let Some(exist_kid) = founded_id {
+        keystore.set_id(&exist_kid)
+    }
+
+    flush_keystore(&keystore)?;
+
+    let meta = keystore.meta();
+    let wallet = WalletResult {
+        id: keystore.id(),
+        name: meta.name.to_owned(),
+        source: "WATCH".to_owned(),
+        accounts: vec![],
+        created_at: meta.timestamp.clone(),
+    };
+    let ret = encode_message(wallet)?;
+    cache_keystore(keystore);
+    Ok(ret)
+}
+
 pub(crate) fn export_substrate_keystore(data: &[u8]) -> Result<Vec<u8>> {
     let param: ExportPrivateKeyParam = ExportPrivateKeyParam::decode(data.clone())?;
     let meta: Metadata;
@@ -897,6 +1698,8 @@ pub(crate) fn substrate_keystore_exists(data: &[u8]) -> Result<Vec<u8>> {
         r#type: KeyType::PrivateKey as i32,
         value: pk_hex,
         encoding: "".to_string(),
+        passphrase: "".to_string(),
+        language: "".to_string(),
     };
     let exists_param_bytes = encode_message(exists_param)?;
     keystore_common_exists(&exists_param_bytes)
@@ -928,3 +1731,16 @@ pub(crate) fn sign_tezos_tx_raw(param: &SignParam, keystore: &mut Keystore) -> R
     let signed_tx = keystore.sign_transaction(&param.chain_type, &param.address, &input)?;
     encode_message(signed_tx)
 }
+
+pub(crate) fn sign_nano_block(param: &SignParam, keystore: &mut Keystore) -> Result<Vec<u8>> {
+    let input: NanoTxInput = NanoTxInput::decode(
+        param
+            .input
+            .as_ref()
+            .ok_or_else(|| format_err!("invalid_tx_input"))?
+            .value
+            .as_slice(),
+    )?;
+    let signed_block = keystore.sign_transaction(&param.chain_type, &param.address, &input)?;
+    encode_message(signed_block)
+}
@@ -14,17 +14,21 @@ pub mod handler;
 use crate::error_handling::{landingpad, LAST_BACKTRACE, LAST_ERROR};
 #[allow(deprecated)]
 use crate::handler::{
-    encode_message, export_mnemonic, export_private_key, get_derived_key, hd_store_create,
-    hd_store_export, hd_store_import, keystore_common_accounts, keystore_common_delete,
-    keystore_common_derive, keystore_common_exists, keystore_common_verify,
-    private_key_store_export, private_key_store_import, sign_tx, tron_sign_message,
-    unlock_then_crash,
+    create_psbt, decode_tx, decrypt_with_account, encode_message, encrypt_to_public_key,
+    export_mnemonic, export_private_key, get_derived_key, get_encryption_public_key,
+    hd_store_create, hd_store_export, hd_store_export_shares, hd_store_import,
+    hd_store_import_shares, keystore_change_password, keystore_common_accounts,
+    keystore_common_delete, keystore_common_derive, keystore_common_derive_addresses,
+    keystore_common_exists, keystore_common_verify, private_key_store_export,
+    private_key_store_import, sign_message, sign_psbt, sign_tx, tron_sign_message,
+    tron_sign_typed_data, unlock_then_crash, verify_signature,
 };
 
 mod filemanager;
 
 use crate::handler::{
-    export_substrate_keystore, get_public_key, import_substrate_keystore, substrate_keystore_exists,
+    export_substrate_keystore, get_public_key, import_legacy_keystore, import_substrate_keystore,
+    substrate_keystore_exists, watch_keystore_import,
 };
 use parking_lot::RwLock;
 
@@ -69,6 +73,12 @@ pub unsafe extern "C" fn call_tcx_api(hex_str: *const c_char) -> *const c_char {
         "hd_store_create" => landingpad(|| hd_store_create(&action.param.unwrap().value)),
         "hd_store_import" => landingpad(|| hd_store_import(&action.param.unwrap().value)),
         "hd_store_export" => landingpad(|| hd_store_export(&action.param.unwrap().value)),
+        "hd_store_export_shares" => {
+            landingpad(|| hd_store_export_shares(&action.param.unwrap().value))
+        }
+        "hd_store_import_shares" => {
+            landingpad(|| hd_store_import_shares(&action.param.unwrap().value))
+        }
         "export_mnemonic" => landingpad(|| export_mnemonic(&action.param.unwrap().value)),
         "keystore_common_derive" => {
             landingpad(|| keystore_common_derive(&action.param.unwrap().value))
@@ -80,6 +90,12 @@ pub unsafe extern "C" fn call_tcx_api(hex_str: *const c_char) -> *const c_char {
         "private_key_store_export" => {
             landingpad(|| private_key_store_export(&action.param.unwrap().value))
         }
+        "legacy_keystore_import" => {
+            landingpad(|| import_legacy_keystore(&action.param.unwrap().value))
+        }
+        "watch_keystore_import" => {
+            landingpad(|| watch_keystore_import(&action.param.unwrap().value))
+        }
         "export_private_key" => landingpad(|| export_private_key(&action.param.unwrap().value)),
         "keystore_common_verify" => {
             landingpad(|| keystore_common_verify(&action.param.unwrap().value))
@@ -87,17 +103,36 @@ pub unsafe extern "C" fn call_tcx_api(hex_str: *const c_char) -> *const c_char {
         "keystore_common_delete" => {
             landingpad(|| keystore_common_delete(&action.param.unwrap().value))
         }
+        "keystore_change_password" => {
+            landingpad(|| keystore_change_password(&action.param.unwrap().value))
+        }
         "keystore_common_exists" => {
             landingpad(|| keystore_common_exists(&action.param.unwrap().value))
         }
         "keystore_common_accounts" => {
             landingpad(|| keystore_common_accounts(&action.param.unwrap().value))
         }
+        "keystore_common_derive_addresses" => {
+            landingpad(|| keystore_common_derive_addresses(&action.param.unwrap().value))
+        }
 
         "sign_tx" => landingpad(|| sign_tx(&action.param.unwrap().value)),
+        "sign_psbt" => landingpad(|| sign_psbt(&action.param.unwrap().value)),
+        "create_psbt" => landingpad(|| create_psbt(&action.param.unwrap().value)),
+        "decode_tx" => landingpad(|| decode_tx(&action.param.unwrap().value)),
         "get_public_key" => landingpad(|| get_public_key(&action.param.unwrap().value)),
 
+        "sign_message" => landingpad(|| sign_message(&action.param.unwrap().value)),
+        "verify_signature" => landingpad(|| verify_signature(&action.param.unwrap().value)),
+        "encrypt_to_public_key" => {
+            landingpad(|| encrypt_to_public_key(&action.param.unwrap().value))
+        }
+        "get_encryption_public_key" => {
+            landingpad(|| get_encryption_public_key(&action.param.unwrap().value))
+        }
+        "decrypt_with_account" => landingpad(|| decrypt_with_account(&action.param.unwrap().value)),
         "tron_sign_msg" => landingpad(|| tron_sign_message(&action.param.unwrap().value)),
+        "tron_sign_typed_data" => landingpad(|| tron_sign_typed_data(&action.param.unwrap().value)),
 
         "substrate_keystore_exists" => {
             landingpad(|| substrate_keystore_exists(&action.param.unwrap().value))
@@ -163,33 +198,48 @@ mod tests {
 
     use crate::api::keystore_common_derive_param::Derivation;
     use crate::api::{
-        AccountsResponse, DerivedKeyResult, ExportPrivateKeyParam, HdStoreCreateParam,
-        InitTokenCoreXParam, KeyType, KeystoreCommonAccountsParam, KeystoreCommonDeriveParam,
-        KeystoreCommonExistsParam, KeystoreCommonExistsResult, KeystoreCommonExportResult,
-        PrivateKeyStoreExportParam, PrivateKeyStoreImportParam, PublicKeyParam, PublicKeyResult,
-        Response, SignParam, WalletKeyParam,
+        hd_store_export_shares_param, HdStoreExportSharesParam, HdStoreExportSharesResult,
+        HdStoreImportParam, HdStoreImportSharesParam, KdfParam, KeystoreChangePasswordParam,
+        LegacyKeystoreImportParam, WalletResult, WatchKeystoreImportParam,
+    };
+    use crate::api::{
+        AccountsResponse, DecodeTxResult, DecryptWithAccountInput, DecryptWithAccountResult,
+        DerivedKeyResult, EncryptToPublicKeyParam, EncryptToPublicKeyResult, EncryptionKeyInput,
+        EncryptionPublicKeyResult, ExportPrivateKeyParam, HdStoreCreateParam, InitTokenCoreXParam,
+        KeyType, KeystoreCommonAccountsParam, KeystoreCommonDeriveAddressesParam,
+        KeystoreCommonDeriveAddressesResult, KeystoreCommonDeriveParam, KeystoreCommonExistsParam,
+        KeystoreCommonExistsResult, KeystoreCommonExportResult, PrivateKeyStoreExportParam,
+        PrivateKeyStoreImportParam, PublicKeyParam, PublicKeyResult, Response, SignParam,
+        VerifySignatureParam, VerifySignatureResult, WalletKeyParam,
     };
-    use crate::api::{HdStoreImportParam, WalletResult};
     use crate::handler::hd_store_import;
     use crate::handler::{encode_message, private_key_store_import};
     use prost::Message;
     use tcx_chain::Keystore;
-    use tcx_constants::{TEST_MNEMONIC, TEST_PASSWORD};
+    use tcx_constants::{CurveType, TEST_MNEMONIC, TEST_PASSWORD};
+    use tcx_primitive::{x25519_public_key, TypedPublicKey};
 
     use std::fs;
-    use tcx_btc_fork::transaction::BtcForkTxInput;
     use tcx_btc_fork::transaction::Utxo;
+    use tcx_btc_fork::transaction::{
+        BtcForkMessageInput, BtcForkMessageOutput, BtcForkSignedTxOutput, BtcForkTxInput,
+        PsbtInput, PsbtOutput,
+    };
 
     use sp_core::Public as TraitPublic;
     use sp_runtime::traits::Verify;
     use tcx_ckb::{CachedCell, CellInput, CkbTxInput, CkbTxOutput, OutPoint, Script, Witness};
+    use tcx_eth::{EthTxInput, EthTxOutput};
     use tcx_filecoin::{SignedMessage, UnsignedMessage};
+    use tcx_nano::{NanoTxInput, NanoTxOutput};
     use tcx_substrate::{
-        ExportSubstrateKeystoreResult, SubstrateKeystore, SubstrateKeystoreParam, SubstrateRawTxIn,
-        SubstrateTxOut,
+        ExportSubstrateKeystoreResult, SubstrateKeystore, SubstrateKeystoreParam,
+        SubstrateMessageInput, SubstrateMessageOutput, SubstrateRawTxIn, SubstrateTxOut,
     };
     use tcx_tezos::transaction::{TezosRawTxIn, TezosTxOut};
-    use tcx_tron::transaction::{TronMessageInput, TronMessageOutput, TronTxInput, TronTxOutput};
+    use tcx_tron::transaction::{
+        TronMessageInput, TronMessageOutput, TronTxInput, TronTxOutput, TronTypedDataInput,
+    };
 
     static OTHER_MNEMONIC: &'static str =
         "calm release clay imitate top extend close draw quiz refuse shuffle injury";
@@ -214,6 +264,7 @@ mod tests {
             xpub_common_key: "B888D25EC8C12BD5043777B1AC49F872".to_string(),
             xpub_common_iv: "9C0C30889CBCC5E01AB5B2BB88715799".to_string(),
             is_debug: false,
+            signature_self_check: true,
         };
 
         handler::init_token_core_x(&encode_message(param).unwrap()).expect("should init tcx");
@@ -258,6 +309,9 @@ mod tests {
             name: "test-wallet".to_string(),
             password_hint: "imtoken".to_string(),
             overwrite: true,
+            kdf: None,
+            passphrase: "".to_string(),
+            language: "".to_string(),
         };
         let ret = hd_store_import(&encode_message(param).unwrap()).unwrap();
         WalletResult::decode(ret.as_slice()).unwrap()
@@ -271,6 +325,7 @@ mod tests {
             password_hint: "".to_string(),
             overwrite: true,
             encoding: "".to_string(),
+            kdf: None,
         };
 
         let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -286,6 +341,7 @@ mod tests {
             password_hint: "".to_string(),
             overwrite: true,
             encoding: "".to_string(),
+            kdf: None,
         };
 
         let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -358,6 +414,9 @@ mod tests {
                 name: "call_tcx_api".to_string(),
                 password_hint: "".to_string(),
                 overwrite: true,
+                kdf: None,
+                passphrase: "".to_string(),
+                language: "".to_string(),
             };
             // let ret_bytes = call_api("hd_store_import", import_param).unwrap();
             let ret_bytes = hex::decode("0a2434656239623136392d323237392d343439332d616535342d62396233643761303630323512036161611a084d4e454d4f4e494328e9a1a2f305").unwrap();
@@ -373,6 +432,7 @@ mod tests {
             xpub_common_key: "B888D25EC8C12BD5043777B1AC49F872".to_string(),
             xpub_common_iv: "9C0C30889CBCC5E01AB5B2BB88715799".to_string(),
             is_debug: true,
+            signature_self_check: true,
         };
 
         handler::init_token_core_x(&encode_message(param).unwrap()).expect("should init tcx");
@@ -403,6 +463,10 @@ mod tests {
                 password: TEST_PASSWORD.to_string(),
                 password_hint: "".to_string(),
                 name: "aaa".to_string(),
+                kdf: None,
+                passphrase: "".to_string(),
+                language: "".to_string(),
+                word_count: 0,
             };
 
             let ret = call_api("hd_store_create", param).unwrap();
@@ -415,6 +479,140 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn test_hd_store_create_with_scrypt() {
+        run_test(|| {
+            let param = HdStoreCreateParam {
+                password: TEST_PASSWORD.to_string(),
+                password_hint: "".to_string(),
+                name: "scrypt".to_string(),
+                kdf: Some(KdfParam {
+                    kdf: "scrypt".to_string(),
+                    c: 0,
+                    n: 1024,
+                    r: 8,
+                    p: 1,
+                }),
+                passphrase: "".to_string(),
+                language: "".to_string(),
+                word_count: 0,
+            };
+
+            let ret = call_api("hd_store_create", param.clone()).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+
+            let json =
+                fs::read_to_string(format!("/tmp/imtoken/wallets/{}.json", wallet.id)).unwrap();
+            let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+            assert_eq!(json["crypto"]["kdf"], "scrypt");
+            assert_eq!(json["crypto"]["kdfparams"]["n"], 1024);
+
+            // load it back from the file
+            handler::scan_keystores().unwrap();
+            let param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("keystore_common_verify", param).unwrap();
+            let result: Response = Response::decode(ret.as_slice()).unwrap();
+            assert!(result.is_success);
+            remove_created_wallet(&wallet.id);
+
+            let param = PrivateKeyStoreImportParam {
+                private_key: "L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB".to_string(),
+                password: TEST_PASSWORD.to_string(),
+                name: "scrypt".to_string(),
+                password_hint: "".to_string(),
+                overwrite: true,
+                encoding: "".to_string(),
+                kdf: Some(KdfParam {
+                    kdf: "scrypt".to_string(),
+                    c: 0,
+                    n: 1000,
+                    r: 8,
+                    p: 1,
+                }),
+            };
+            let ret = call_api("private_key_store_import", param.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "kdf_params_invalid");
+
+            let param = PrivateKeyStoreImportParam {
+                kdf: Some(KdfParam {
+                    kdf: "argon2".to_string(),
+                    c: 0,
+                    n: 0,
+                    r: 0,
+                    p: 0,
+                }),
+                ..param
+            };
+            let ret = call_api("private_key_store_import", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_kdf");
+        })
+    }
+
+    #[test]
+    pub fn test_hd_store_import_with_passphrase() {
+        run_test(|| {
+            let param = HdStoreImportParam {
+                mnemonic: TEST_MNEMONIC.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                source: "MNEMONIC".to_string(),
+                name: "passphrase".to_string(),
+                password_hint: "".to_string(),
+                overwrite: true,
+                kdf: None,
+                passphrase: "TREZOR".to_string(),
+                language: "".to_string(),
+            };
+            let ret = call_api("hd_store_import", param).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+
+            let derivation = Derivation {
+                chain_type: "BITCOIN".to_string(),
+                path: "m/44'/0'/0'/0/0".to_string(),
+                network: "MAINNET".to_string(),
+                seg_wit: "NONE".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let param = KeystoreCommonDeriveParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let accounts: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                accounts.accounts[0].address,
+                "1A6NEMgXZx5QU5fZninDG2rSDkfi4jNGAS"
+            );
+
+            let param = KeystoreCommonExistsParam {
+                r#type: KeyType::Mnemonic as i32,
+                value: TEST_MNEMONIC.to_string(),
+                encoding: "".to_string(),
+                passphrase: "TREZOR".to_string(),
+                language: "".to_string(),
+            };
+            let ret = call_api("keystore_common_exists", param.clone()).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret.as_slice()).unwrap();
+            assert!(result.is_exists);
+            assert_eq!(result.id, wallet.id);
+
+            let param = KeystoreCommonExistsParam {
+                passphrase: "".to_string(),
+                ..param
+            };
+            let ret = call_api("keystore_common_exists", param).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret.as_slice()).unwrap();
+            assert_ne!(result.id, wallet.id);
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
     #[test]
     pub fn test_hd_store_import() {
         run_test(|| {
@@ -461,6 +659,9 @@ mod tests {
                     name: "test-wallet".to_string(),
                     password_hint: "imtoken".to_string(),
                     overwrite: true,
+                    kdf: None,
+                    passphrase: "".to_string(),
+                    language: "".to_string(),
                 };
 
                 let ret = call_api("hd_store_import", param);
@@ -500,6 +701,63 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn test_derive_btc_accounts() {
+        run_test(|| {
+            let import_result: WalletResult = import_default_wallet();
+
+            let derivation_params = vec![
+                ("m/44'/0'/0'/0/0", "MAINNET", "NONE"),
+                ("m/49'/0'/0'/0/0", "MAINNET", "P2WPKH"),
+                ("m/84'/0'/0'/0/0", "MAINNET", "SEGWIT"),
+                ("m/44'/1'/0'/0/0", "TESTNET", "NONE"),
+                ("m/49'/1'/0'/0/0", "TESTNET", "P2WPKH"),
+                ("m/84'/1'/0'/0/0", "TESTNET", "SEGWIT"),
+                ("m/86'/0'/0'/0/0", "MAINNET", "P2TR"),
+                ("m/86'/1'/0'/0/0", "TESTNET", "P2TR"),
+            ];
+            let derivations = derivation_params
+                .iter()
+                .map(|(path, network, seg_wit)| Derivation {
+                    chain_type: "BITCOIN".to_string(),
+                    path: path.to_string(),
+                    network: network.to_string(),
+                    seg_wit: seg_wit.to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                })
+                .collect();
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations,
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let addresses: Vec<String> = rsp.accounts.iter().map(|x| x.address.clone()).collect();
+            assert_eq!(
+                addresses,
+                vec![
+                    "12z6UzsA3tjpaeuvA2Zr9jwx19Azz74D6g",
+                    "3JmreiUEKn8P3SyLYmZ7C1YCd4r2nFy3Dp",
+                    "bc1q05ec6z8df2vlzkxjxfd2xr3veypzm93wqnazr2",
+                    "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN",
+                    "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB",
+                    "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95",
+                    "bc1pqvrla5hul9cqdtz60lwwn35zdcx363pyxua0trqnz3wx8hvjxzdsdevceu",
+                    "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4",
+                ]
+            );
+            assert!(rsp
+                .accounts
+                .iter()
+                .all(|x| x.chain_type == "BITCOIN" && !x.extended_xpub_key.is_empty()));
+
+            remove_created_wallet(&import_result.id);
+        })
+    }
+
     #[test]
     pub fn test_hd_store_export() {
         run_test(|| {
@@ -550,6 +808,96 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn test_export_mnemonic_in_language() {
+        run_test(|| {
+            let param = HdStoreCreateParam {
+                password: TEST_PASSWORD.to_string(),
+                password_hint: "".to_string(),
+                name: "japanese".to_string(),
+                kdf: None,
+                passphrase: "".to_string(),
+                language: "JAPANESE".to_string(),
+                word_count: 24,
+            };
+            let ret = call_api("hd_store_create", param.clone()).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+
+            // the language is loaded from the file
+            handler::scan_keystores().unwrap();
+            let export_param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("export_mnemonic", export_param).unwrap();
+            let result: KeystoreCommonExportResult =
+                KeystoreCommonExportResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.value.split('\u{3000}').count(), 24);
+            remove_created_wallet(&wallet.id);
+
+            let param = HdStoreCreateParam {
+                language: "CZECH".to_string(),
+                word_count: 12,
+                ..param
+            };
+            let ret = call_api("hd_store_create", param.clone()).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+            let export_param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("export_mnemonic", export_param).unwrap();
+            let result: KeystoreCommonExportResult =
+                KeystoreCommonExportResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.value.split(' ').count(), 12);
+            remove_created_wallet(&wallet.id);
+
+            let param = HdStoreCreateParam {
+                language: "KLINGON".to_string(),
+                ..param
+            };
+            let ret = call_api("hd_store_create", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_language");
+
+            let param = HdStoreImportParam {
+                mnemonic: "景 沿 兵 门 燥 芳 段 晒 团 染 晒 爬".to_string(),
+                password: TEST_PASSWORD.to_string(),
+                source: "MNEMONIC".to_string(),
+                name: "chinese".to_string(),
+                password_hint: "".to_string(),
+                overwrite: true,
+                kdf: None,
+                passphrase: "".to_string(),
+                language: "CHINESE_SIMPLIFIED".to_string(),
+            };
+            let ret = call_api("hd_store_import", param.clone()).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+
+            let exists_param = KeystoreCommonExistsParam {
+                r#type: KeyType::Mnemonic as i32,
+                value: param.mnemonic.to_string(),
+                encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "CHINESE_SIMPLIFIED".to_string(),
+            };
+            let ret = call_api("keystore_common_exists", exists_param).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret.as_slice()).unwrap();
+            assert!(result.is_exists);
+            assert_eq!(result.id, wallet.id);
+
+            let export_param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("export_mnemonic", export_param).unwrap();
+            let result: KeystoreCommonExportResult =
+                KeystoreCommonExportResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.value, param.mnemonic);
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
     #[test]
     pub fn test_keystore_common_store_derive() {
         run_test(|| {
@@ -560,6 +908,9 @@ mod tests {
                 name: "test-wallet".to_string(),
                 password_hint: "imtoken".to_string(),
                 overwrite: true,
+                kdf: None,
+                passphrase: "".to_string(),
+                language: "".to_string(),
             };
             let ret = call_api("hd_store_import", param).unwrap();
             let import_result: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
@@ -879,6 +1230,7 @@ mod tests {
                 password_hint: "".to_string(),
                 overwrite: true,
                 encoding: "TEZOS".to_string(),
+                kdf: None,
             };
 
             let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -911,6 +1263,8 @@ mod tests {
                 value: "edskRgu8wHxjwayvnmpLDDijzD3VZDoAH7ZLqJWuG4zg7LbxmSWZWhtkSyM5Uby41rGfsBGk4iPKWHSDniFyCRv3j7YFCknyHH"
                     .to_string(),
                 encoding: "TEZOS".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
             };
 
             let ret_bytes = call_api("keystore_common_exists", param).unwrap();
@@ -1008,6 +1362,7 @@ mod tests {
                 password_hint: "".to_string(),
                 overwrite: true,
                 encoding: "".to_string(),
+                kdf: None,
             };
 
             let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -1071,6 +1426,7 @@ mod tests {
                 password_hint: "".to_string(),
                 overwrite: true,
                 encoding: "".to_string(),
+                kdf: None,
             };
 
             let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -1133,6 +1489,7 @@ mod tests {
                 password_hint: "".to_string(),
                 overwrite: true,
                 encoding: "".to_string(),
+                kdf: None,
             };
 
             let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -1423,6 +1780,7 @@ mod tests {
                 password_hint: "".to_string(),
                 overwrite: true,
                 encoding: "".to_string(),
+                kdf: None,
             };
 
             let ret = private_key_store_import(&encode_message(param).unwrap()).unwrap();
@@ -1479,99 +1837,412 @@ mod tests {
     }
 
     #[test]
-    pub fn test_keystore_common_delete() {
+    pub fn test_keystore_change_password() {
         run_test(|| {
-            let param: PrivateKeyStoreImportParam = PrivateKeyStoreImportParam {
-                private_key: "5JZc7wGRUr4J1RHDcM9ySWKLfQ2xjRUEo612qC4RLJ3G7jzJ4qx".to_string(),
-                password: TEST_PASSWORD.to_string(),
-                name: "test_keystore_common_delete".to_string(),
-                password_hint: "".to_string(),
-                overwrite: true,
-                encoding: "".to_string(),
-            };
-
-            let ret_bytes = private_key_store_import(&encode_message(param).unwrap()).unwrap();
-            let import_result: WalletResult = WalletResult::decode(ret_bytes.as_slice()).unwrap();
+            let wallet = import_default_wallet();
 
-            let param: WalletKeyParam = WalletKeyParam {
-                id: import_result.id.to_string(),
-                password: "WRONG PASSWORD".to_string(),
+            let param = KeystoreChangePasswordParam {
+                id: wallet.id.to_string(),
+                old_password: "WRONG PASSWORD".to_string(),
+                new_password: "NEW PASSWORD".to_string(),
+                password_hint: "new hint".to_string(),
+                kdf: None,
             };
-
-            let ret = call_api("keystore_common_delete", param);
-            assert!(ret.is_err());
+            let ret = call_api("keystore_change_password", param.clone());
             assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
 
-            let param: WalletKeyParam = WalletKeyParam {
-                id: import_result.id.to_string(),
-                password: TEST_PASSWORD.to_string(),
+            let param = KeystoreChangePasswordParam {
+                old_password: TEST_PASSWORD.to_string(),
+                ..param
             };
+            let ret = call_api("keystore_change_password", param.clone()).unwrap();
+            let result: Response = Response::decode(ret.as_slice()).unwrap();
+            assert!(result.is_success);
 
-            let ret_bytes = call_api("keystore_common_delete", param).unwrap();
-            let ret: Response = Response::decode(ret_bytes.as_slice()).unwrap();
-            assert!(ret.is_success);
+            // the file is reloaded with the same id
+            handler::scan_keystores().unwrap();
+            assert!(!Path::new(&format!("/tmp/imtoken/wallets/{}.json.tmp", wallet.id)).exists());
+            let verify_param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("keystore_common_verify", verify_param.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
+            let verify_param = WalletKeyParam {
+                password: "NEW PASSWORD".to_string(),
+                ..verify_param
+            };
+            assert!(call_api("keystore_common_verify", verify_param).is_ok());
+            {
+                let map = KEYSTORE_MAP.read();
+                let keystore = map.get(&wallet.id).unwrap();
+                assert_eq!(keystore.meta().password_hint, "new hint");
+                assert_eq!(keystore.accounts().len(), wallet.accounts.len());
+            }
 
-            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
-                r#type: KeyType::PrivateKey as i32,
-                value: "5JZc7wGRUr4J1RHDcM9ySWKLfQ2xjRUEo612qC4RLJ3G7jzJ4qx".to_string(),
-                encoding: "".to_string(),
+            // the empty hint keeps the current one
+            let param = KeystoreChangePasswordParam {
+                old_password: "NEW PASSWORD".to_string(),
+                new_password: TEST_PASSWORD.to_string(),
+                password_hint: "".to_string(),
+                ..param
             };
+            call_api("keystore_change_password", param.clone()).unwrap();
+            {
+                let map = KEYSTORE_MAP.read();
+                let keystore = map.get(&wallet.id).unwrap();
+                assert_eq!(keystore.meta().password_hint, "new hint");
+            }
 
-            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
-            let ret: KeystoreCommonExistsResult =
-                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
+            let param = KeystoreChangePasswordParam {
+                id: "not_exists".to_string(),
+                ..param
+            };
+            let ret = call_api("keystore_change_password", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "wallet_not_found");
 
-            assert_eq!(false, ret.is_exists);
+            remove_created_wallet(&wallet.id);
         })
     }
 
     #[test]
-    pub fn test_keystore_common_exists() {
+    pub fn test_import_legacy_keystore() {
         run_test(|| {
-            let wallet = import_default_wallet();
-            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
-                r#type: KeyType::Mnemonic as i32,
-                value: format!("{}", TEST_MNEMONIC).to_string(),
-                encoding: "".to_string(),
+            let keystore_str = r#"{
+  "crypto": {
+    "cipher": "aes-128-ctr",
+    "cipherparams": {
+      "iv": "437ef8c8553df9910ad117ecec5b8c05"
+    },
+    "ciphertext": "acabec2bd6fab27d867ebabe0ded9c64c85aebd294d29ecf537e563474ebb931522dbb977e0644830516550255edde02c507863cb083b55f2f0f759c2f8a885a81a6518237e7b65b7cf3e912fb36e42a13a7b2df3d401e5ff778a412a6d4c5516645770c4b12f2e30551542c699eef",
+    "kdf": "pbkdf2",
+    "kdfparams": {
+      "c": 65535,
+      "dklen": 32,
+      "prf": "hmac-sha256",
+      "salt": "33c8f2d27fe994a1e7d51108c7811cdaa2b821cc6760ed760954b4b67a1bcd8c"
+    },
+    "mac": "6b86a18f4ba9f3f428e256e72a3d832dcf0cd1cb820ec61e413a64d83b012059"
+  },
+  "id": "02a55ab6-554a-4e78-bc26-6a7acced7e5e",
+  "version": 44,
+  "address": "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN",
+  "encMnemonic": {
+    "encStr": "840fad94f4bf4128f629bc1dec731d156283cc4099e3c7659a3bf382031443fcdce6debaaef444393c446d2b4007064c010f6a442b3ad0ff0851c1bd638ba251afa92d3106457bd78c49",
+    "nonce": "4d691a7f0cb6396e96e8dc3e4f35dccd"
+  },
+  "mnemonicPath": "m/44'/1'/0'",
+  "imTokenMeta": {
+    "chainType": "BITCOIN",
+    "network": "TESTNET",
+    "name": "BTC",
+    "passwordHint": "",
+    "source": "RECOVERED_IDENTITY",
+    "walletType": "HD",
+    "timestamp": 1519611221,
+    "segWit": "NONE"
+  }
+}"#;
+            let param = LegacyKeystoreImportParam {
+                keystore: keystore_str.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                overwrite: true,
+                kdf: None,
             };
+            let ret = call_api("legacy_keystore_import", param.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
 
-            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
-            let result: KeystoreCommonExistsResult =
-                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
-            assert!(result.is_exists);
-            assert_eq!(result.id, wallet.id);
-
-            let wallet = import_default_pk_store();
-            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
-                r#type: KeyType::PrivateKey as i32,
-                value: "L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB".to_string(),
-                encoding: "".to_string(),
+            let param = LegacyKeystoreImportParam {
+                password: "imtoken1".to_string(),
+                ..param
             };
+            let ret = call_api("legacy_keystore_import", param).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(wallet.name, "BTC");
+            assert_eq!(wallet.source, "MNEMONIC");
 
-            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
-            let result: KeystoreCommonExistsResult =
-                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
-            assert!(result.is_exists);
-            assert_eq!(result.id, wallet.id);
-
-            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
-                r#type: KeyType::PrivateKey as i32,
-                value: "a392604efc2fad9c0b3da43b5f698a2e3f270f170d859912be0d54742275c5f6"
-                    .to_string(),
-                encoding: "".to_string(),
+            let derivation = Derivation {
+                chain_type: "BITCOIN".to_string(),
+                path: "m/44'/1'/0'/0/0".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "NONE".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
             };
-
-            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
-            let result: KeystoreCommonExistsResult =
-                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
-            assert!(result.is_exists);
-            assert_eq!(result.id, wallet.id);
+            let param = KeystoreCommonDeriveParam {
+                id: wallet.id.to_string(),
+                password: "imtoken1".to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let accounts: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                accounts.accounts[0].address,
+                "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN"
+            );
+            remove_created_wallet(&wallet.id);
+
+            let keystore_str = r#"{
+  "crypto": {
+    "cipher": "aes-128-ctr",
+    "cipherparams": {
+      "iv": "6087dab2f9fdbbfaddc31a909735c1e6"
+    },
+    "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+    "kdf": "pbkdf2",
+    "kdfparams": {
+      "c": 262144,
+      "dklen": 32,
+      "prf": "hmac-sha256",
+      "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+    },
+    "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+  },
+  "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+  "version": 3
+}"#;
+            let param = LegacyKeystoreImportParam {
+                keystore: keystore_str.to_string(),
+                password: "testpassword".to_string(),
+                overwrite: true,
+                kdf: None,
+            };
+            let ret = call_api("legacy_keystore_import", param).unwrap();
+            let wallet: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(wallet.source, "PRIVATE");
+
+            let param = ExportPrivateKeyParam {
+                id: wallet.id.to_string(),
+                password: "testpassword".to_string(),
+                chain_type: "ETHEREUM".to_string(),
+                network: "".to_string(),
+                main_address: "".to_string(),
+                path: "".to_string(),
+            };
+            let derivation = Derivation {
+                chain_type: "ETHEREUM".to_string(),
+                path: "".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let derive_param = KeystoreCommonDeriveParam {
+                id: wallet.id.to_string(),
+                password: "testpassword".to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", derive_param).unwrap();
+            let accounts: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                accounts.accounts[0].address,
+                "0x008AeEda4D805471dF9b2A5B0f38A0C3bCBA786b"
+            );
+
+            let param = ExportPrivateKeyParam {
+                main_address: accounts.accounts[0].address.to_string(),
+                ..param
+            };
+            let ret = call_api("export_private_key", param).unwrap();
+            let export_result: KeystoreCommonExportResult =
+                KeystoreCommonExportResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                export_result.value,
+                "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+            );
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
+    #[test]
+    pub fn test_hd_store_shares() {
+        run_test(|| {
+            let wallet = import_default_wallet();
+
+            let param = HdStoreExportSharesParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                group_threshold: 1,
+                groups: vec![hd_store_export_shares_param::Group {
+                    member_threshold: 2,
+                    member_count: 3,
+                }],
+                passphrase: "slip39_passphrase".to_string(),
+            };
+            let ret = call_api("hd_store_export_shares", param).unwrap();
+            let result: HdStoreExportSharesResult =
+                HdStoreExportSharesResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.id, wallet.id);
+            assert_eq!(result.groups.len(), 1);
+            let shares = &result.groups[0].shares;
+            assert_eq!(shares.len(), 3);
+
+            let param = HdStoreImportSharesParam {
+                shares: vec![shares[2].to_string()],
+                password: TEST_PASSWORD.to_string(),
+                name: "test-wallet".to_string(),
+                password_hint: "imtoken".to_string(),
+                overwrite: false,
+                kdf: None,
+                passphrase: "slip39_passphrase".to_string(),
+            };
+            let ret = call_api("hd_store_import_shares", param.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "insufficient_shares");
+
+            let param = HdStoreImportSharesParam {
+                shares: vec![shares[2].to_string(), shares[0].to_string()],
+                ..param
+            };
+            let ret = call_api("hd_store_import_shares", param.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "address_already_exist");
+
+            // the seed of the mnemonic is the master secret, the same keystore is recovered
+            let param = HdStoreImportSharesParam {
+                overwrite: true,
+                ..param
+            };
+            let ret = call_api("hd_store_import_shares", param).unwrap();
+            let imported: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(imported.id, wallet.id);
+            assert_eq!(imported.source, "SLIP39");
+
+            let param = KeystoreCommonDeriveParam {
+                id: imported.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![Derivation {
+                    chain_type: "BITCOIN".to_string(),
+                    path: "m/44'/0'/0'/0/0".to_string(),
+                    network: "MAINNET".to_string(),
+                    seg_wit: "NONE".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                }],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                rsp.accounts[0].address,
+                "12z6UzsA3tjpaeuvA2Zr9jwx19Azz74D6g"
+            );
+
+            let param = WalletKeyParam {
+                id: imported.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret = call_api("hd_store_export", param);
+            assert_eq!(
+                format!("{}", ret.err().unwrap()),
+                "keystore_has_no_mnemonic"
+            );
+            remove_created_wallet(&imported.id);
+        })
+    }
+
+    #[test]
+    pub fn test_keystore_common_delete() {
+        run_test(|| {
+            let param: PrivateKeyStoreImportParam = PrivateKeyStoreImportParam {
+                private_key: "5JZc7wGRUr4J1RHDcM9ySWKLfQ2xjRUEo612qC4RLJ3G7jzJ4qx".to_string(),
+                password: TEST_PASSWORD.to_string(),
+                name: "test_keystore_common_delete".to_string(),
+                password_hint: "".to_string(),
+                overwrite: true,
+                encoding: "".to_string(),
+                kdf: None,
+            };
+
+            let ret_bytes = private_key_store_import(&encode_message(param).unwrap()).unwrap();
+            let import_result: WalletResult = WalletResult::decode(ret_bytes.as_slice()).unwrap();
+
+            let param: WalletKeyParam = WalletKeyParam {
+                id: import_result.id.to_string(),
+                password: "WRONG PASSWORD".to_string(),
+            };
+
+            let ret = call_api("keystore_common_delete", param);
+            assert!(ret.is_err());
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_incorrect");
+
+            let param: WalletKeyParam = WalletKeyParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+
+            let ret_bytes = call_api("keystore_common_delete", param).unwrap();
+            let ret: Response = Response::decode(ret_bytes.as_slice()).unwrap();
+            assert!(ret.is_success);
+
+            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
+                r#type: KeyType::PrivateKey as i32,
+                value: "5JZc7wGRUr4J1RHDcM9ySWKLfQ2xjRUEo612qC4RLJ3G7jzJ4qx".to_string(),
+                encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
+            };
+
+            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
+            let ret: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
+
+            assert_eq!(false, ret.is_exists);
+        })
+    }
+
+    #[test]
+    pub fn test_keystore_common_exists() {
+        run_test(|| {
+            let wallet = import_default_wallet();
+            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
+                r#type: KeyType::Mnemonic as i32,
+                value: format!("{}", TEST_MNEMONIC).to_string(),
+                encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
+            };
+
+            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
+            assert!(result.is_exists);
+            assert_eq!(result.id, wallet.id);
+
+            let wallet = import_default_pk_store();
+            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
+                r#type: KeyType::PrivateKey as i32,
+                value: "L2hfzPyVC1jWH7n2QLTe7tVTb6btg9smp5UVzhEBxLYaSFF7sCZB".to_string(),
+                encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
+            };
+
+            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
+            assert!(result.is_exists);
+            assert_eq!(result.id, wallet.id);
+
+            let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
+                r#type: KeyType::PrivateKey as i32,
+                value: "a392604efc2fad9c0b3da43b5f698a2e3f270f170d859912be0d54742275c5f6"
+                    .to_string(),
+                encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
+            };
+
+            let ret_bytes = call_api("keystore_common_exists", param).unwrap();
+            let result: KeystoreCommonExistsResult =
+                KeystoreCommonExistsResult::decode(ret_bytes.as_slice()).unwrap();
+            assert!(result.is_exists);
+            assert_eq!(result.id, wallet.id);
 
             let wallet = import_default_wallet();
             let param: KeystoreCommonExistsParam = KeystoreCommonExistsParam {
                 r#type: KeyType::Mnemonic as i32,
                 value: format!("{}", " inject  kidney  empty canal shadow  pact comfort  wife crush horse wife sketch  ").to_string(),//Badly formatted mnemonic
                 encoding: "".to_string(),
+                passphrase: "".to_string(),
+                language: "".to_string(),
             };
 
             let ret_bytes = call_api("keystore_common_exists", param).unwrap();
@@ -1776,86 +2447,223 @@ mod tests {
         })
     }
 
-    // #[test]
-    // pub fn test_sign_substrate_tx() {
-    //     run_test(|| {
-    //         let derivation = Derivation {
-    //             chain_type: "KUSAMA".to_string(),
-    //             path: "//kusama//imToken/0".to_string(),
-    //             network: "".to_string(),
-    //             seg_wit: "".to_string(),
-    //             chain_id: "".to_string(),
-    //         };
-    //
-    //         let wallet = import_and_derive(derivation);
-    //
-    //         let input = SubstrateTxIn {
-    //             method: "transfer".to_string(),
-    //             address: "EwDXBhgNrcNvMVhm9fRq5YCTdAsPRBPo3t4tUZ85Q9ydKNs".to_string(),
-    //             amount: 10000000000,
-    //             era: Some(ExtrinsicEra {
-    //                 current: 1202925,
-    //                 period: 2400,
-    //             }),
-    //             nonce: 5,
-    //             tip: 10000000000,
-    //             sepc_version: 1045,
-    //             genesis_hash: "b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe"
-    //                 .to_string(),
-    //             block_hash: "790628ced8e0649883f3dd20344d9e6b014f076e788742f0925cf3875997e883"
-    //                 .to_string(),
-    //         };
-    //
-    //         let input_value = encode_message(input).unwrap();
-    //         let tx = SignParam {
-    //             id: wallet.id.to_string(),
-    //             password: TEST_PASSWORD.to_string(),
-    //             chain_type: "KUSAMA".to_string(),
-    //             address: wallet.accounts.first().unwrap().address.to_string(),
-    //             input: Some(::prost_types::Any {
-    //                 type_url: "imtoken".to_string(),
-    //                 value: input_value.clone(),
-    //             }),
-    //         };
-    //
-    //         let ret = call_api("sign_tx", tx).unwrap();
-    //         let output: SubstrateTxOut = SubstrateTxOut::decode(&ret).unwrap();
-    //
-    //         let expected_ret_before_sig =
-    //             "550284ffce9e36de55716d91b1c50caa36a58cee6d28e532a710df0cf90609363947dd7801";
-    //         let expected_ret_after_sig = "dbae140700e40b54020400ff68686f29461fcc99ab3538c391e42556e49efc1ffa7933da42335aa626fae25a0700e40b5402";
-    //
-    //         assert_eq!(
-    //             output.signature[0..74].to_string(),
-    //             expected_ret_before_sig,
-    //             "before sig"
-    //         );
-    //         assert_eq!(
-    //             output.signature[202..].to_string(),
-    //             expected_ret_after_sig,
-    //             "after sig"
-    //         );
-    //
-    //         let sig_bytes = hex::decode(output.signature[74..202].to_string()).unwrap();
-    //         let signature = sp_core::sr25519::Signature::from_slice(&sig_bytes);
-    //
-    //         let pub_key =
-    //             hex::decode("ce9e36de55716d91b1c50caa36a58cee6d28e532a710df0cf90609363947dd78")
-    //                 .unwrap();
-    //         let singer = sp_core::sr25519::Public::from_slice(&pub_key);
-    //         let msg = hex::decode("0400ff68686f29461fcc99ab3538c391e42556e49efc1ffa7933da42335aa626fae25a0700e40b5402dbae140700e40b540215040000b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe790628ced8e0649883f3dd20344d9e6b014f076e788742f0925cf3875997e883").unwrap();
-    //
-    //         assert!(
-    //             sp_core::sr25519::Signature::verify(&signature, msg.as_slice(), &singer),
-    //             "assert sig"
-    //         );
-    //
-    //         remove_created_wallet(&wallet.id);
-    //     })
-    // }
-
     #[test]
-    pub fn test_import_substrate_keystore() {
+    pub fn test_decode_tx() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "TRON".to_string(),
+                path: "m/44'/195'/0'/0/0".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+
+            let wallet = import_and_derive(derivation);
+
+            let raw_data = "0a0202a22208e216e254e43ee10840c8cbe4e3df2d5a67080112630a2d747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e5472616e73666572436f6e747261637412320a15415c68cc82c87446f602f019e5fd797437f5b79cc212154156a6076cd1537fa317c2606e4edfa4acd3e8e92e18a08d06709084e1e3df2d".to_string();
+            let input = TronTxInput { raw_data };
+            // decoding does not unlock the wallet
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: None,
+                chain_type: "TRON".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input.clone()).unwrap(),
+                }),
+            };
+
+            let ret = call_api("decode_tx", tx.clone()).unwrap();
+            let output: DecodeTxResult = DecodeTxResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.chain_type, "TRON");
+            assert_eq!(output.senders, vec!["TJPpiG4r7K5WPydSvV1GDPwPF6DzKiJnFo"]);
+            assert_eq!(
+                output.recipients[0].address,
+                "THsN1TH3sox1emzc8PxeFHC8HiZHhZ1qDX"
+            );
+            assert_eq!(output.recipients[0].amount, "100000");
+            assert_eq!(output.contract, "");
+
+            let ret = call_api(
+                "decode_tx",
+                SignParam {
+                    id: "not_exists".to_string(),
+                    ..tx.clone()
+                },
+            );
+            assert_eq!(format!("{}", ret.err().unwrap()), "wallet_not_found");
+
+            let ret = call_api(
+                "decode_tx",
+                SignParam {
+                    chain_type: "TRON1".to_string(),
+                    ..tx
+                },
+            );
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_chain");
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
+    #[test]
+    pub fn test_sign_eth_tx() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "ETHEREUM".to_string(),
+                path: "m/44'/60'/0'/0/0".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+
+            let wallet = import_and_derive(derivation);
+            assert_eq!(
+                wallet.accounts.first().unwrap().address,
+                "0x6031564e7b2F5cc33737807b2E58DaFF870B590b"
+            );
+
+            let input = EthTxInput {
+                nonce: "0x08".to_string(),
+                gas_price: "".to_string(),
+                gas_limit: "0x5208".to_string(),
+                to: "0xe0B3c8E0D3Ce5b80cAF6a5E0db6F6A83E0C8B5e9".to_string(),
+                value: "0x2386f26fc10000".to_string(),
+                data: "".to_string(),
+                chain_id: "0x01".to_string(),
+                tx_type: 2,
+                max_fee_per_gas: "0x0b2d05e000".to_string(),
+                max_priority_fee_per_gas: "0x3b9aca00".to_string(),
+                access_list: vec![],
+            };
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "ETHEREUM".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input.clone()).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx).unwrap();
+            let output: EthTxOutput = EthTxOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.signature, "0x02f8720108843b9aca00850b2d05e00082520894e0b3c8e0d3ce5b80caf6a5e0db6f6a83e0c8b5e9872386f26fc1000080c001a0940c3262d8c710e6f5f26350f69d017f8ebd72044f5e6319f2a53e39819e75a4a03f44f021654170dc8c9563e5e902d3696942951201f19c115663a45d48fc61d0");
+            assert_eq!(
+                output.tx_hash,
+                "0x45e235e2a83841048c774789a6b8197f0a2d2dc2f69c19e63b06916024e66e14"
+            );
+
+            let input = EthTxInput {
+                to: "0xe0B3c8E0D3Ce5b80cAF6a5E0db6F6A83E0C8B5E9".to_string(),
+                ..input
+            };
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "ETHEREUM".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+            let ret = call_api("sign_tx", tx.clone());
+            assert_eq!(format!("{}", ret.err().unwrap()), "address_invalid");
+
+            let mut param = tx;
+            param.input = None;
+            let ret = call_api("sign_tx", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_tx_input");
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
+    // #[test]
+    // pub fn test_sign_substrate_tx() {
+    //     run_test(|| {
+    //         let derivation = Derivation {
+    //             chain_type: "KUSAMA".to_string(),
+    //             path: "//kusama//imToken/0".to_string(),
+    //             network: "".to_string(),
+    //             seg_wit: "".to_string(),
+    //             chain_id: "".to_string(),
+    //         };
+    //
+    //         let wallet = import_and_derive(derivation);
+    //
+    //         let input = SubstrateTxIn {
+    //             method: "transfer".to_string(),
+    //             address: "EwDXBhgNrcNvMVhm9fRq5YCTdAsPRBPo3t4tUZ85Q9ydKNs".to_string(),
+    //             amount: 10000000000,
+    //             era: Some(ExtrinsicEra {
+    //                 current: 1202925,
+    //                 period: 2400,
+    //             }),
+    //             nonce: 5,
+    //             tip: 10000000000,
+    //             sepc_version: 1045,
+    //             genesis_hash: "b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe"
+    //                 .to_string(),
+    //             block_hash: "790628ced8e0649883f3dd20344d9e6b014f076e788742f0925cf3875997e883"
+    //                 .to_string(),
+    //         };
+    //
+    //         let input_value = encode_message(input).unwrap();
+    //         let tx = SignParam {
+    //             id: wallet.id.to_string(),
+    //             password: TEST_PASSWORD.to_string(),
+    //             chain_type: "KUSAMA".to_string(),
+    //             address: wallet.accounts.first().unwrap().address.to_string(),
+    //             input: Some(::prost_types::Any {
+    //                 type_url: "imtoken".to_string(),
+    //                 value: input_value.clone(),
+    //             }),
+    //         };
+    //
+    //         let ret = call_api("sign_tx", tx).unwrap();
+    //         let output: SubstrateTxOut = SubstrateTxOut::decode(&ret).unwrap();
+    //
+    //         let expected_ret_before_sig =
+    //             "550284ffce9e36de55716d91b1c50caa36a58cee6d28e532a710df0cf90609363947dd7801";
+    //         let expected_ret_after_sig = "dbae140700e40b54020400ff68686f29461fcc99ab3538c391e42556e49efc1ffa7933da42335aa626fae25a0700e40b5402";
+    //
+    //         assert_eq!(
+    //             output.signature[0..74].to_string(),
+    //             expected_ret_before_sig,
+    //             "before sig"
+    //         );
+    //         assert_eq!(
+    //             output.signature[202..].to_string(),
+    //             expected_ret_after_sig,
+    //             "after sig"
+    //         );
+    //
+    //         let sig_bytes = hex::decode(output.signature[74..202].to_string()).unwrap();
+    //         let signature = sp_core::sr25519::Signature::from_slice(&sig_bytes);
+    //
+    //         let pub_key =
+    //             hex::decode("ce9e36de55716d91b1c50caa36a58cee6d28e532a710df0cf90609363947dd78")
+    //                 .unwrap();
+    //         let singer = sp_core::sr25519::Public::from_slice(&pub_key);
+    //         let msg = hex::decode("0400ff68686f29461fcc99ab3538c391e42556e49efc1ffa7933da42335aa626fae25a0700e40b5402dbae140700e40b540215040000b0a8d493285c2df73290dfb7e61f870f17b41801197a149ca93654499ea3dafe790628ced8e0649883f3dd20344d9e6b014f076e788742f0925cf3875997e883").unwrap();
+    //
+    //         assert!(
+    //             sp_core::sr25519::Signature::verify(&signature, msg.as_slice(), &singer),
+    //             "assert sig"
+    //         );
+    //
+    //         remove_created_wallet(&wallet.id);
+    //     })
+    // }
+
+    #[test]
+    pub fn test_import_substrate_keystore() {
         run_test(|| {
             let wrong_keystore_str: &str = r#"{
   "address": "JHBkzZJnLZ3S3HLvxjpFAjd6ywP7WAk5miL7MwVCn9a7jHS",
@@ -2288,219 +3096,1206 @@ mod tests {
             let tx = SignParam {
                 id: import_result.id.to_string(),
                 key: Some(Key::Password(TEST_PASSWORD.to_string())),
-                chain_type: "FILECOIN".to_string(),
-                address: rsp.accounts.first().unwrap().address.to_string(),
+                chain_type: "FILECOIN".to_string(),
+                address: rsp.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(message).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx).unwrap();
+            let signed = SignedMessage::decode(ret.as_slice()).unwrap();
+            let expected_sign = "YJLfRrV7WovsWUY4nhKRp8Vs9AGC9J61zV8InwWM6IwxBVhtc20mJC7cxWdVMBQ45Mem2yS7bqQe7alkSxQvpwA=" ;
+            assert_eq!(expected_sign, signed.signature.unwrap().data);
+
+            remove_created_wallet(&import_result.id);
+        })
+    }
+
+    #[test]
+    pub fn test_sign_by_dk_in_pk_store() {
+        run_test(|| {
+            let import_result = import_default_pk_store();
+
+            let derivation = Derivation {
+                chain_type: "TRON".to_string(),
+                path: "".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let param = WalletKeyParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+            let ret_bytes = get_derived_key(&encode_message(param).unwrap()).unwrap();
+            let ret: DerivedKeyResult = DerivedKeyResult::decode(ret_bytes.as_slice()).unwrap();
+            let raw_data = "0a0202a22208e216e254e43ee10840c8cbe4e3df2d5a67080112630a2d747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e5472616e73666572436f6e747261637412320a15415c68cc82c87446f602f019e5fd797437f5b79cc212154156a6076cd1537fa317c2606e4edfa4acd3e8e92e18a08d06709084e1e3df2d".to_string();
+            let input = TronTxInput { raw_data };
+            let tx = SignParam {
+                id: import_result.id.to_string(),
+                key: Some(Key::DerivedKey(ret.derived_key)),
+                chain_type: "TRON".to_string(),
+                address: rsp.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input.clone()).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx).unwrap();
+            let output: TronTxOutput = TronTxOutput::decode(ret.as_slice()).unwrap();
+            let expected_sign = "7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001";
+            assert_eq!(expected_sign, output.signatures[0]);
+
+            let tx = SignParam {
+                id: import_result.id.to_string(),
+                key: Some(Key::DerivedKey("7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001".to_string())),
+                chain_type: "TRON".to_string(),
+                address: rsp.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input.clone()).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx);
+            assert!(ret.is_err());
+            assert_eq!("derived_key_not_matched", format!("{}", ret.err().unwrap()));
+
+            remove_created_wallet(&import_result.id);
+        })
+    }
+
+    #[test]
+    fn test_sign_message() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "TRON".to_string(),
+                path: "m/44'/195'/0'/0/0".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let wallet = import_and_derive(derivation);
+
+            let input_expects = vec![
+                (TronMessageInput {
+                    value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
+                        .to_string(),
+                    is_hex: true,
+                    is_tron_header: true,
+                }, "16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b"),
+                (TronMessageInput {
+                    value: "0x645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
+                        .to_string(),
+                    is_hex: true,
+                    is_tron_header: true,
+                }, "16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b"),
+                (TronMessageInput {
+                    value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
+                        .to_string(),
+                    is_hex: true,
+                    is_tron_header: false,
+                }, "06ff3c5f98b8e8e257f47a66ce8e953c7a7d0f96eb6687da6a98b66a36c2a725759cab3df94d014bd17760328adf860649303c68c4fa6644d9f307e2f32cc3311c"),
+                (TronMessageInput {
+                    value: "abcdef"
+                        .to_string(),
+                    is_hex: false,
+                    is_tron_header: true,
+                }, "a87eb6ae7e97621b6ba2e2f70db31fe0c744c6adcfdc005044026506b70ac11a33f415f4478b6cf84af32b3b5d70a13a77e53287613449b345bb16fe012c04081b"),
+            ];
+            for (input, expected) in input_expects {
+                let tx = SignParam {
+                    id: wallet.id.to_string(),
+                    key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                    chain_type: "TRON".to_string(),
+                    address: wallet.accounts.first().unwrap().address.to_string(),
+                    input: Some(::prost_types::Any {
+                        type_url: "imtoken".to_string(),
+                        value: encode_message(input).unwrap(),
+                    }),
+                };
+
+                let sign_result = call_api("tron_sign_msg", tx).unwrap();
+                let ret: TronMessageOutput =
+                    TronMessageOutput::decode(sign_result.as_slice()).unwrap();
+                assert_eq!(expected, ret.signature);
+            }
+            //            let input = TronMessageInput {
+            //                value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
+            //                    .to_string(),
+            //                is_hex: true,
+            //                is_tron_header: true,
+            //            };
+        });
+    }
+
+    #[test]
+    fn test_sign_message_of_chains() {
+        run_test(|| {
+            let wallet = import_default_wallet();
+            let derivations = vec![
+                Derivation {
+                    chain_type: "BITCOIN".to_string(),
+                    path: "m/44'/1'/0'/0/0".to_string(),
+                    network: "TESTNET".to_string(),
+                    seg_wit: "NONE".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+                Derivation {
+                    chain_type: "KUSAMA".to_string(),
+                    path: "//kusama//imToken/0".to_string(),
+                    network: "".to_string(),
+                    seg_wit: "".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+                Derivation {
+                    chain_type: "TRON".to_string(),
+                    path: "m/44'/195'/0'/0/0".to_string(),
+                    network: "".to_string(),
+                    seg_wit: "".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+            ];
+            let param = KeystoreCommonDeriveParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations,
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let accounts: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let input = BtcForkMessageInput {
+                message: "hello world".to_string(),
+                is_hex: false,
+            };
+            let param = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: accounts.accounts[0].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+            let ret = call_api("sign_message", param).unwrap();
+            let output: BtcForkMessageOutput =
+                BtcForkMessageOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                output.signature,
+                "H+MGzZzNkCApBSqSpn4O1h5c2hIdIPNFF/fb7+0uPoMwK21FCd8pkLFWZH1KYltM0mKGFQys30MRSH8QaZ2Z5ic="
+            );
+
+            let input = SubstrateMessageInput {
+                message: "0x68656c6c6f20776f726c64".to_string(),
+                is_hex: true,
+            };
+            let param = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "KUSAMA".to_string(),
+                address: accounts.accounts[1].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+            let ret = call_api("sign_message", param).unwrap();
+            let output: SubstrateMessageOutput =
+                SubstrateMessageOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.signature[0..4].to_string(), "0x01");
+            let sig_bytes = hex::decode(output.signature[4..].to_string()).unwrap();
+            let signature = sp_core::sr25519::Signature::from_slice(&sig_bytes);
+            let pub_key =
+                hex::decode("90742a577c8515391a46b7881c98c80ec92fe04255bb5b5fec862c7d633ada21")
+                    .unwrap();
+            let singer = sp_core::sr25519::Public::from_slice(&pub_key);
+            assert!(sp_core::sr25519::Signature::verify(
+                &signature,
+                "<Bytes>hello world</Bytes>".as_bytes(),
+                &singer
+            ));
+
+            let input = TronMessageInput {
+                value: "abcdef".to_string(),
+                is_hex: false,
+                is_tron_header: true,
+            };
+            let param = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "TRON".to_string(),
+                address: accounts.accounts[2].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+            let ret = call_api("sign_message", param).unwrap();
+            let output: TronMessageOutput = TronMessageOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.signature, "a87eb6ae7e97621b6ba2e2f70db31fe0c744c6adcfdc005044026506b70ac11a33f415f4478b6cf84af32b3b5d70a13a77e53287613449b345bb16fe012c04081b");
+
+            let param = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "ETHEREUM".to_string(),
+                address: accounts.accounts[2].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: vec![],
+                }),
+            };
+            let ret = call_api("sign_message", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_chain");
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        run_test(|| {
+            let verify = |curve: &str, public_key: &str, data: &str, signature: &str| {
+                let param = VerifySignatureParam {
+                    curve: curve.to_string(),
+                    public_key: public_key.to_string(),
+                    data: data.to_string(),
+                    signature: signature.to_string(),
+                };
+                call_api("verify_signature", param)
+                    .map(|ret| VerifySignatureResult::decode(ret.as_slice()).unwrap())
+            };
+            let public_key = "02506bc1dc099358e5137292f4efdd57e400f29ba5132aa5d12b18dac1c1f6aaba";
+            let hash = "47d8dd5610badd62bd707531386db9aa25130167d685d801cac9820607330901";
+            let der = "304402202514266dc7d807ecd69f6d5d03dae7d68619b2c562d8ac77f60e186f4fde4f2202207fbedf5642b095e4a37e71432c99e2b1144f8b9d73a0018be04e6d5ddbd26146";
+            let recoverable = "2514266dc7d807ecd69f6d5d03dae7d68619b2c562d8ac77f60e186f4fde4f227fbedf5642b095e4a37e71432c99e2b1144f8b9d73a0018be04e6d5ddbd2614600";
+
+            let ret = verify("SECP256k1", public_key, hash, der).unwrap();
+            assert!(ret.is_valid);
+            assert_eq!(ret.recovered_public_key, "");
+
+            let other_hash = "0000000000000000000000000000000000000000000000000000000000000000";
+            let ret = verify("SECP256k1", public_key, other_hash, der).unwrap();
+            assert!(!ret.is_valid);
+
+            let ret = verify("SECP256k1", public_key, hash, recoverable).unwrap();
+            assert!(ret.is_valid);
+            assert_eq!(ret.recovered_public_key, public_key);
+
+            // without the public key the signature is not verified
+            let ret = verify("SECP256k1", "", hash, recoverable).unwrap();
+            assert!(!ret.is_valid);
+            assert_eq!(ret.recovered_public_key, public_key);
+
+            let ret = verify("SECP256k1", "", other_hash, recoverable).unwrap();
+            assert!(!ret.is_valid);
+            assert_ne!(ret.recovered_public_key, public_key);
+
+            let ret = verify("SECP256k1", "", hash, der);
+            assert_eq!(format!("{}", ret.err().unwrap()), "public_key_required");
+
+            let ret = verify("secp256k1", public_key, hash, der);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_curve_type");
+        })
+    }
+
+    #[test]
+    fn test_encrypt_and_decrypt_with_account() {
+        run_test(|| {
+            let wallet = import_default_wallet();
+            let derivations = vec![
+                Derivation {
+                    chain_type: "TEZOS".to_string(),
+                    path: "m/44'/1729'/0'/0'".to_string(),
+                    network: "MAINNET".to_string(),
+                    seg_wit: "".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+                Derivation {
+                    chain_type: "ETHEREUM".to_string(),
+                    path: "m/44'/60'/0'/0/0".to_string(),
+                    network: "".to_string(),
+                    seg_wit: "".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+            ];
+            let param = KeystoreCommonDeriveParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations,
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let accounts: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let account_param = |index: usize, input: Vec<u8>| SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: accounts.accounts[index].chain_type.to_string(),
+                address: accounts.accounts[index].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: input,
+                }),
+            };
+            let public_key = |index: usize, curve: &str| -> Result<EncryptionPublicKeyResult> {
+                let input = EncryptionKeyInput {
+                    curve: curve.to_string(),
+                };
+                // the keystore is not unlocked for the public key
+                let mut param = account_param(index, encode_message(input).unwrap());
+                param.key = None;
+                let ret = call_api("get_encryption_public_key", param)?;
+                Ok(EncryptionPublicKeyResult::decode(ret.as_slice()).unwrap())
+            };
+            let encrypt = |key: &EncryptionPublicKeyResult, plaintext: &str| {
+                let param = EncryptToPublicKeyParam {
+                    curve: key.curve.to_string(),
+                    public_key: key.public_key.to_string(),
+                    plaintext: hex::encode(plaintext),
+                };
+                let ret = call_api("encrypt_to_public_key", param).unwrap();
+                EncryptToPublicKeyResult::decode(ret.as_slice())
+                    .unwrap()
+                    .ciphertext
+            };
+            let decrypt = |index: usize, curve: &str, ciphertext: &str| -> Result<String> {
+                let input = DecryptWithAccountInput {
+                    curve: curve.to_string(),
+                    ciphertext: ciphertext.to_string(),
+                };
+                let param = account_param(index, encode_message(input).unwrap());
+                let ret = call_api("decrypt_with_account", param)?;
+                let plaintext = DecryptWithAccountResult::decode(ret.as_slice())
+                    .unwrap()
+                    .plaintext;
+                Ok(String::from_utf8(hex::decode(plaintext)?)?)
+            };
+
+            // the ed25519 key of the tezos account
+            let key = public_key(0, "").unwrap();
+            assert_eq!(key.curve, "ED25519");
+            let ciphertext = encrypt(&key, "hello from the other wallet");
+            assert_eq!(
+                decrypt(0, "", &ciphertext).unwrap(),
+                "hello from the other wallet"
+            );
+
+            // the X25519 key of the ed25519 key of the tezos account
+            let ed25519_key = TypedPublicKey::from_slice(
+                CurveType::ED25519,
+                &hex::decode(&key.public_key).unwrap(),
+            )
+            .unwrap();
+            let key = public_key(0, "Curve25519").unwrap();
+            assert_eq!(key.curve, "Curve25519");
+            assert_eq!(
+                key.public_key,
+                hex::encode(
+                    TypedPublicKey::Curve25519(x25519_public_key(&ed25519_key).unwrap()).to_bytes()
+                )
+            );
+            let ciphertext = encrypt(&key, "backup share");
+            assert_eq!(
+                decrypt(0, "Curve25519", &ciphertext).unwrap(),
+                "backup share"
+            );
+            assert_eq!(decrypt(0, "", &ciphertext).unwrap(), "backup share");
+
+            // the secp256k1 key of the ethereum account
+            let key = public_key(1, "").unwrap();
+            assert_eq!(key.curve, "SECP256k1");
+            let ciphertext = encrypt(&key, "hello from the other wallet");
+            assert_eq!(
+                decrypt(1, "", &ciphertext).unwrap(),
+                "hello from the other wallet"
+            );
+            assert_eq!(
+                format!("{}", public_key(1, "Curve25519").err().unwrap()),
+                "invalid_curve_type"
+            );
+            assert_eq!(
+                format!("{}", public_key(1, "SECP256k1").err().unwrap()),
+                "invalid_curve_type"
+            );
+
+            // the keystore must be unlocked to decrypt
+            let input = DecryptWithAccountInput {
+                curve: "".to_string(),
+                ciphertext,
+            };
+            let mut param = account_param(1, encode_message(input).unwrap());
+            param.key = None;
+            let ret = call_api("decrypt_with_account", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_required");
+
+            let mut param = account_param(1, vec![]);
+            param.input = None;
+            let ret = call_api("get_encryption_public_key", param.clone());
+            assert_eq!(
+                format!("{}", ret.err().unwrap()),
+                "invalid_encryption_key_input"
+            );
+            let ret = call_api("decrypt_with_account", param);
+            assert_eq!(
+                format!("{}", ret.err().unwrap()),
+                "invalid_decrypt_with_account_input"
+            );
+
+            let param = EncryptToPublicKeyParam {
+                curve: "SubSr25519".to_string(),
+                public_key: "90742a577c8515391a46b7881c98c80ec92fe04255bb5b5fec862c7d633ada21"
+                    .to_string(),
+                plaintext: "".to_string(),
+            };
+            let ret = call_api("encrypt_to_public_key", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_curve_type");
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
+
+    #[test]
+    fn test_tron_sign_typed_data() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "TRON".to_string(),
+                path: "m/44'/195'/0'/0/0".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let wallet = import_and_derive(derivation);
+
+            let input = TronTypedDataInput {
+                data: r#"{
+                    "types": {
+                        "EIP712Domain": [
+                            {"name": "name", "type": "string"},
+                            {"name": "version", "type": "string"},
+                            {"name": "chainId", "type": "uint256"},
+                            {"name": "verifyingContract", "type": "address"}
+                        ],
+                        "Person": [
+                            {"name": "name", "type": "string"},
+                            {"name": "wallet", "type": "address"}
+                        ],
+                        "Mail": [
+                            {"name": "from", "type": "Person"},
+                            {"name": "to", "type": "Person"},
+                            {"name": "contents", "type": "string"}
+                        ]
+                    },
+                    "primaryType": "Mail",
+                    "domain": {
+                        "name": "Ether Mail",
+                        "version": "1",
+                        "chainId": 1,
+                        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+                    },
+                    "message": {
+                        "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                        "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                        "contents": "Hello, Bob!"
+                    }
+                }"#
+                .to_string(),
+            };
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "TRON".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+
+            let sign_result = call_api("tron_sign_typed_data", tx).unwrap();
+            let ret: TronMessageOutput = TronMessageOutput::decode(sign_result.as_slice()).unwrap();
+            assert_eq!("90125790eae4cb484dbb7470f9a9aafcb95c166843ae319d9876399481e5d350738a86b971532c51b2cf74108e43b32a1ed8658031869471c0e0414fd5aa3cd81b", ret.signature);
+
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "TRON".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(TronTypedDataInput {
+                        data: "{}".to_string(),
+                    })
+                    .unwrap(),
+                }),
+            };
+            let ret = call_api("tron_sign_typed_data", tx.clone());
+            assert_eq!(
+                format!("{}", ret.err().unwrap()),
+                "eip712_invalid_typed_data"
+            );
+
+            let mut param = tx.clone();
+            param.key = None;
+            let ret = call_api("tron_sign_typed_data", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_required");
+
+            let mut param = tx;
+            param.input = None;
+            let ret = call_api("tron_sign_typed_data", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_message_input");
+
+            remove_created_wallet(&wallet.id);
+        });
+    }
+
+    #[test]
+    fn test_sign_by_dk_hd_store() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "TRON".to_string(),
+                path: "m/44'/195'/0'/0/0".to_string(),
+                network: "".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let wallet = import_and_derive(derivation);
+
+            let input = TronMessageInput {
+                value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
+                    .to_string(),
+                is_hex: true,
+                is_tron_header: true,
+            };
+
+            let dk_param = WalletKeyParam {
+                id: wallet.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+            };
+
+            let ret_bytes = get_derived_key(&encode_message(dk_param).unwrap()).unwrap();
+            let ret: DerivedKeyResult = DerivedKeyResult::decode(ret_bytes.as_slice()).unwrap();
+
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::DerivedKey(ret.derived_key)),
+                chain_type: "TRON".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input.clone()).unwrap(),
+                }),
+            };
+
+            let sign_result = call_api("tron_sign_msg", tx).unwrap();
+            let ret: TronMessageOutput = TronMessageOutput::decode(sign_result.as_slice()).unwrap();
+            assert_eq!("16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b", ret.signature);
+
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::DerivedKey("7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001".to_string())),
+                chain_type: "TRON".to_string(),
+                address: wallet.accounts.first().unwrap().address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+
+            let ret = call_api("tron_sign_msg", tx);
+            assert!(ret.is_err());
+            assert_eq!("derived_key_not_matched", format!("{}", ret.err().unwrap()));
+
+            remove_created_wallet(&wallet.id);
+        });
+    }
+
+    #[test]
+    pub fn test_sign_btc_tx() {
+        run_test(|| {
+            let import_result: WalletResult = import_default_wallet();
+
+            let derivations = vec![
+                Derivation {
+                    chain_type: "BITCOIN".to_string(),
+                    path: "m/44'/1'/0'/0/0".to_string(),
+                    network: "TESTNET".to_string(),
+                    seg_wit: "NONE".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+                Derivation {
+                    chain_type: "BITCOIN".to_string(),
+                    path: "m/49'/1'/0'/0/0".to_string(),
+                    network: "TESTNET".to_string(),
+                    seg_wit: "P2WPKH".to_string(),
+                    chain_id: "".to_string(),
+                    curve: "".to_string(),
+                },
+            ];
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations,
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let p2pkh_input = BtcForkTxInput {
+                to: "mrU9pEmAx26HcbKVrABvgL7AwA5fjNFoDc".to_string(),
+                amount: 500000,
+                unspents: vec![Utxo {
+                    tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                        .to_string(),
+                    vout: 0,
+                    amount: 1000000,
+                    address: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+                    script_pub_key: "76a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac"
+                        .to_string(),
+                    derived_path: "0/0".to_string(),
+                    sequence: 0,
+                    has_sequence: false,
+                }],
+                fee: 100000,
+                change_address_index: 1u32,
+                change_address: "mgBCJAsvzgT2qNNeXsoECg2uPKrUsZ76up".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "NONE".to_string(),
+                outputs: vec![],
+                op_return: "".to_string(),
+                lock_time: 0,
+                rbf: false,
+                version: 0,
+                fee_rate: 0,
+            };
+            let p2shwpkh_input = BtcForkTxInput {
+                to: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                amount: 88000,
+                unspents: vec![Utxo {
+                    tx_hash: "c2ceb5088cf39b677705526065667a3992c68cc18593a9af12607e057672717f"
+                        .to_string(),
+                    vout: 0,
+                    amount: 100000,
+                    address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                    script_pub_key: "a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987".to_string(),
+                    derived_path: "0/0".to_string(),
+                    sequence: 0,
+                    has_sequence: false,
+                }],
+                fee: 1000,
+                change_address_index: 0u32,
+                change_address: "2MwN441dq8qudMvtM5eLVwC3u4zfKuGSQAB".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "P2WPKH".to_string(),
+                outputs: vec![],
+                op_return: "".to_string(),
+                lock_time: 0,
+                rbf: false,
+                version: 0,
+                fee_rate: 0,
+            };
+            let expects = vec![
+                (p2pkh_input, "01000000015884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a4000000006a47304402202daccaf7ed5bc0114dc6deb84989239bf8b5552e2ee5ab01f0080180c46aa6710220075643955497b6818a41d990a2bd5ea54d67b341a1566fe8360dd86752044f170121033d710ab45bb54ac99618ad23b3c1da661631aa25f23bfe9d22b41876f1d46e4effffffff0220a10700000000001976a9147821c0a3768aa9d1a37e16cf76002aef5373f1a888ac801a0600000000001976a914073b7eae2823efa349e3b9155b8a735526463a0f88ac00000000", "bace69f30a4d067528ccd3c8cc2707aa1e5d71dfd0fe378b6093cd0c70e07526"),
+                (p2shwpkh_input, "020000000001017f717276057e6012afa99385c18cc692397a666560520577679bf38c08b5cec20000000017160014654fbb08267f3d50d715a8f1abb55979b160dd5bffffffff02c05701000000000017a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987f82a00000000000017a9142d2b1ef5ee4cf6c3ebc8cf66a602783798f7875987024730440220785a15cf6a28c2f18124428bab4313dcdb34535dbe3267b589f91d746b6ea55902200e2a1d82392efb9680a7152b9e001c883501391231db5773826a0e827638de3c0121031aee5e20399d68cf0035d1a21564868f22bc448ab205292b4279136b15ecaebc00000000", "024916e236c103ab2676575c8c653835a88deab2a2b00353edad422cadd58422"),
+            ];
+
+            for (i, (input, expected_signature, expected_tx_hash)) in
+                expects.into_iter().enumerate()
+            {
+                let tx = SignParam {
+                    id: import_result.id.to_string(),
+                    key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                    chain_type: "BITCOIN".to_string(),
+                    address: rsp.accounts[i].address.to_string(),
+                    input: Some(::prost_types::Any {
+                        type_url: "imtoken".to_string(),
+                        value: encode_message(input).unwrap(),
+                    }),
+                };
+
+                let ret = call_api("sign_tx", tx).unwrap();
+                let output: BtcForkSignedTxOutput =
+                    BtcForkSignedTxOutput::decode(ret.as_slice()).unwrap();
+                assert_eq!(output.signature, expected_signature);
+                assert_eq!(output.tx_hash, expected_tx_hash);
+            }
+
+            remove_created_wallet(&import_result.id);
+        })
+    }
+
+    #[test]
+    pub fn test_sign_btc_p2wpkh_tx() {
+        run_test(|| {
+            let import_result: WalletResult = import_default_wallet();
+
+            let derivation = Derivation {
+                chain_type: "BITCOIN".to_string(),
+                path: "m/84'/1'/0'/0/0".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "SEGWIT".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+
+            let input = BtcForkTxInput {
+                to: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+                amount: 60000,
+                unspents: vec![Utxo {
+                    tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                        .to_string(),
+                    vout: 2,
+                    amount: 100000,
+                    address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                    script_pub_key: "00141a7a98a2b9fa09685d28edecb2741250e85882c3".to_string(),
+                    derived_path: "0/0".to_string(),
+                    sequence: 0,
+                    has_sequence: false,
+                }],
+                fee: 1000,
+                change_address_index: 0u32,
+                change_address: "tb1qrfaf3g4elgykshfgahktyaqj2r593qkrae5v95".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "SEGWIT".to_string(),
+                outputs: vec![],
+                op_return: "".to_string(),
+                lock_time: 0,
+                rbf: false,
+                version: 0,
+                fee_rate: 0,
+            };
+            let tx = SignParam {
+                id: import_result.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx).unwrap();
+            let output: BtcForkSignedTxOutput =
+                BtcForkSignedTxOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.signature, "020000000001015884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a40200000000ffffffff0260ea0000000000001976a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac58980000000000001600141a7a98a2b9fa09685d28edecb2741250e85882c302483045022100e2ea44156c7f7a92884b5cb45d4ae4cb33378877d7477b34647158a8a24d4bc502200ac69f002f5b4021d9e35cbfd1c847f76fb972de2afd05599697fc850894c10b012102e24f625a31c9a8bae42239f2bf945a306c01a450a03fd123316db0e837a660c000000000");
+            assert_eq!(
+                output.tx_hash,
+                "8d8eb587d441040d589469fbb8bfeadd38b183d60f93d987458387b658c37073"
+            );
+
+            remove_created_wallet(&import_result.id);
+        })
+    }
+
+    #[test]
+    pub fn test_sign_btc_p2tr_tx() {
+        run_test(|| {
+            let import_result: WalletResult = import_default_wallet();
+
+            let derivation = Derivation {
+                chain_type: "BITCOIN".to_string(),
+                path: "m/86'/1'/0'/0/0".to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "P2TR".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                rsp.accounts[0].address,
+                "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4"
+            );
+
+            let input = BtcForkTxInput {
+                to: "mkeNU5nVnozJiaACDELLCsVUc8Wxoh1rQN".to_string(),
+                amount: 40000,
+                unspents: vec![Utxo {
+                    tx_hash: "a477af6b2667c29670467e4e0728b685ee07b240235771862318e29ddbe58458"
+                        .to_string(),
+                    vout: 1,
+                    amount: 50000,
+                    address: "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4"
+                        .to_string(),
+                    script_pub_key:
+                        "51208f4ca6a7384f50a1fe00cba593d5a834b480c65692a76ae6202e1ce46cb1c233"
+                            .to_string(),
+                    derived_path: "0/0".to_string(),
+                    sequence: 0,
+                    has_sequence: false,
+                }],
+                fee: 1000,
+                change_address_index: 0u32,
+                change_address: "tb1p3ax2dfecfag2rlsqewje84dgxj6gp3jkj2nk4e3q9cwwgm93cgesa0zwj4"
+                    .to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: "P2TR".to_string(),
+                outputs: vec![],
+                op_return: "".to_string(),
+                lock_time: 0,
+                rbf: false,
+                version: 0,
+                fee_rate: 0,
+            };
+            let tx = SignParam {
+                id: import_result.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
                 input: Some(::prost_types::Any {
                     type_url: "imtoken".to_string(),
-                    value: encode_message(message).unwrap(),
+                    value: encode_message(input).unwrap(),
                 }),
             };
 
             let ret = call_api("sign_tx", tx).unwrap();
-            let signed = SignedMessage::decode(ret.as_slice()).unwrap();
-            let expected_sign = "YJLfRrV7WovsWUY4nhKRp8Vs9AGC9J61zV8InwWM6IwxBVhtc20mJC7cxWdVMBQ45Mem2yS7bqQe7alkSxQvpwA=" ;
-            assert_eq!(expected_sign, signed.signature.unwrap().data);
+            let output: BtcForkSignedTxOutput =
+                BtcForkSignedTxOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.signature, "020000000001015884e5db9de218238671572340b207ee85b628074e7e467096c267266baf77a40100000000ffffffff02409c0000000000001976a914383fb81cb0a3fc724b5e08cf8bbd404336d711f688ac28230000000000002251208f4ca6a7384f50a1fe00cba593d5a834b480c65692a76ae6202e1ce46cb1c2330140acb0a733cd5ecd3e5fa0f162eafa58d032e76854583d818d9ce5d0a06f348b1769804246d042941d54e9746ee4683504ad58858bb11ea890841c489b3af4075900000000");
+            assert_eq!(
+                output.tx_hash,
+                "3df7491b2692a0fe2b80a9704e3d386cf0e0ac291dc0c7194226449757d43379"
+            );
 
             remove_created_wallet(&import_result.id);
         })
     }
 
     #[test]
-    pub fn test_sign_by_dk_in_pk_store() {
+    pub fn test_sign_psbt() {
         run_test(|| {
-            let import_result = import_default_pk_store();
+            let import_result: WalletResult = import_default_wallet();
 
-            let derivation = Derivation {
-                chain_type: "TRON".to_string(),
-                path: "".to_string(),
-                network: "".to_string(),
-                seg_wit: "".to_string(),
+            let derivations = vec![
+                ("m/44'/1'/0'/0/0", "NONE"),
+                ("m/49'/1'/0'/0/0", "P2WPKH"),
+                ("m/84'/1'/0'/0/0", "SEGWIT"),
+            ]
+            .into_iter()
+            .map(|(path, seg_wit)| Derivation {
+                chain_type: "BITCOIN".to_string(),
+                path: path.to_string(),
+                network: "TESTNET".to_string(),
+                seg_wit: seg_wit.to_string(),
                 chain_id: "".to_string(),
                 curve: "".to_string(),
-            };
+            })
+            .collect();
             let param = KeystoreCommonDeriveParam {
                 id: import_result.id.to_string(),
                 password: TEST_PASSWORD.to_string(),
-                derivations: vec![derivation],
+                derivations,
             };
-
             let ret = call_api("keystore_common_derive", param).unwrap();
             let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
 
-            let param = WalletKeyParam {
-                id: import_result.id.to_string(),
-                password: TEST_PASSWORD.to_string(),
+            let input = PsbtInput {
+                psbt: "cHNidP8BAMYCAAAAA61iQcPcjTA1s1To1oPcPQEIsTsM4bUzbY5h27ww5K49AAAAAAD9////rWJBw9yNMDWzVOjWg9w9AQixOwzhtTNtjmHbvDDkrj0BAAAAAP3///+tYkHD3I0wNbNU6NaD3D0BCLE7DOG1M22OYdu8MOSuPQIAAAAA/f///wKQ0AMAAAAAABYAFBp6mKK5+gloXSjt7LJ0ElDoWILDaL8AAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrAAAAAAAAQCVAgAAAAEAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAP////8BUf////8DoIYBAAAAAAAZdqkUOD+4HLCj/HJLXgjPi71AQzbXEfaIrKCGAQAAAAAAF6kULSse9e5M9sPryM9mpgJ4N5j3h1mHoIYBAAAAAAAWABQaepiiufoJaF0o7eyydBJQ6FiCwwAAAAAiBgM9cQq0W7VKyZYYrSOzwdpmFjGqJfI7/p0itBh28dRuThgUaNupLAAAgAEAAIAAAACAAAAAAAAAAAAAAQEgoIYBAAAAAAAXqRQtKx717kz2w+vIz2amAng3mPeHWYcBBBYAFGVPuwgmfz1Q1xWo8au1WXmxYN1bIgYDGu5eIDmdaM8ANdGiFWSGjyK8RIqyBSkrQnkTaxXsrrwYFGjbqTEAAIABAACAAAAAgAAAAAAAAAAAAAEBH6CGAQAAAAAAFgAUGnqYorn6CWhdKO3ssnQSUOhYgsMiBgLiT2JaMcmouuQiOfK/lFowbAGkUKA/0SMxbbDoN6ZgwBgUaNupVAAAgAEAAIAAAACAAAAAAAAAAAAAAAA=".to_string(),
+                auto_finalize: true,
             };
-            let ret_bytes = get_derived_key(&encode_message(param).unwrap()).unwrap();
-            let ret: DerivedKeyResult = DerivedKeyResult::decode(ret_bytes.as_slice()).unwrap();
-            let raw_data = "0a0202a22208e216e254e43ee10840c8cbe4e3df2d5a67080112630a2d747970652e676f6f676c65617069732e636f6d2f70726f746f636f6c2e5472616e73666572436f6e747261637412320a15415c68cc82c87446f602f019e5fd797437f5b79cc212154156a6076cd1537fa317c2606e4edfa4acd3e8e92e18a08d06709084e1e3df2d".to_string();
-            let input = TronTxInput { raw_data };
             let tx = SignParam {
                 id: import_result.id.to_string(),
-                key: Some(Key::DerivedKey(ret.derived_key)),
-                chain_type: "TRON".to_string(),
-                address: rsp.accounts.first().unwrap().address.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
                 input: Some(::prost_types::Any {
                     type_url: "imtoken".to_string(),
                     value: encode_message(input.clone()).unwrap(),
                 }),
             };
+            let ret = call_api("sign_psbt", tx.clone()).unwrap();
+            let output: PsbtOutput = PsbtOutput::decode(ret.as_slice()).unwrap();
+            assert!(output.is_finalized);
+            assert_ne!(output.psbt, input.psbt);
 
-            let ret = call_api("sign_tx", tx).unwrap();
-            let output: TronTxOutput = TronTxOutput::decode(ret.as_slice()).unwrap();
-            let expected_sign = "7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001";
-            assert_eq!(expected_sign, output.signatures[0]);
+            let mut param = tx.clone();
+            param.key = None;
+            let ret = call_api("sign_psbt", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_required");
+
+            let mut param = tx;
+            param.input = None;
+            let ret = call_api("sign_psbt", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_tx_input");
 
             let tx = SignParam {
                 id: import_result.id.to_string(),
-                key: Some(Key::DerivedKey("7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001".to_string())),
-                chain_type: "TRON".to_string(),
-                address: rsp.accounts.first().unwrap().address.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOINCASH".to_string(),
+                address: rsp.accounts[0].address.to_string(),
                 input: Some(::prost_types::Any {
                     type_url: "imtoken".to_string(),
-                    value: encode_message(input.clone()).unwrap(),
+                    value: encode_message(input).unwrap(),
                 }),
             };
-
-            let ret = call_api("sign_tx", tx);
-            assert!(ret.is_err());
-            assert_eq!("derived_key_not_matched", format!("{}", ret.err().unwrap()));
+            let ret = call_api("sign_psbt", tx);
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_chain");
 
             remove_created_wallet(&import_result.id);
         })
     }
 
     #[test]
-    fn test_sign_message() {
+    pub fn test_keystore_common_derive_addresses() {
         run_test(|| {
+            let import_result: WalletResult = import_default_wallet();
+
             let derivation = Derivation {
-                chain_type: "TRON".to_string(),
-                path: "m/44'/195'/0'/0/0".to_string(),
-                network: "".to_string(),
-                seg_wit: "".to_string(),
+                chain_type: "BITCOIN".to_string(),
+                path: "m/44'/0'/0'/0/0".to_string(),
+                network: "MAINNET".to_string(),
+                seg_wit: "NONE".to_string(),
                 chain_id: "".to_string(),
                 curve: "".to_string(),
             };
-            let wallet = import_and_derive(derivation);
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                rsp.accounts[0].address,
+                "12z6UzsA3tjpaeuvA2Zr9jwx19Azz74D6g"
+            );
 
-            let input_expects = vec![
-                (TronMessageInput {
-                    value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
-                        .to_string(),
-                    is_hex: true,
-                    is_tron_header: true,
-                }, "16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b"),
-                (TronMessageInput {
-                    value: "0x645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
-                        .to_string(),
-                    is_hex: true,
-                    is_tron_header: true,
-                }, "16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b"),
-                (TronMessageInput {
-                    value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
-                        .to_string(),
-                    is_hex: true,
-                    is_tron_header: false,
-                }, "06ff3c5f98b8e8e257f47a66ce8e953c7a7d0f96eb6687da6a98b66a36c2a725759cab3df94d014bd17760328adf860649303c68c4fa6644d9f307e2f32cc3311c"),
-                (TronMessageInput {
-                    value: "abcdef"
-                        .to_string(),
-                    is_hex: false,
-                    is_tron_header: true,
-                }, "a87eb6ae7e97621b6ba2e2f70db31fe0c744c6adcfdc005044026506b70ac11a33f415f4478b6cf84af32b3b5d70a13a77e53287613449b345bb16fe012c04081b"),
-            ];
-            for (input, expected) in input_expects {
-                let tx = SignParam {
-                    id: wallet.id.to_string(),
-                    key: Some(Key::Password(TEST_PASSWORD.to_string())),
-                    chain_type: "TRON".to_string(),
-                    address: wallet.accounts.first().unwrap().address.to_string(),
-                    input: Some(::prost_types::Any {
-                        type_url: "imtoken".to_string(),
-                        value: encode_message(input).unwrap(),
-                    }),
-                };
+            // the password is not required
+            let param = KeystoreCommonDeriveAddressesParam {
+                id: import_result.id.to_string(),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
+                change: 0,
+                start: 0,
+                count: 10,
+                discover: false,
+                gap_limit: 0,
+                used_addresses: vec![],
+            };
+            let ret = call_api("keystore_common_derive_addresses", param.clone()).unwrap();
+            let result = KeystoreCommonDeriveAddressesResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.addresses.len(), 10);
+            assert_eq!(
+                result.addresses[1].address,
+                "1962gsZ8PoPUYHneFakkCTrukdFMVQ4i4T"
+            );
+            assert_eq!(result.addresses[9].path, "m/44'/0'/0'/0/9");
+            assert!(result.next_address.is_none());
+
+            let ret = call_api(
+                "keystore_common_derive_addresses",
+                KeystoreCommonDeriveAddressesParam {
+                    change: 1,
+                    count: 1,
+                    ..param.clone()
+                },
+            )
+            .unwrap();
+            let result = KeystoreCommonDeriveAddressesResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                result.addresses[0].address,
+                "19vddWhyq637bqDfuKadsoy5mTNRgfb3hr"
+            );
 
-                let sign_result = call_api("tron_sign_msg", tx).unwrap();
-                let ret: TronMessageOutput =
-                    TronMessageOutput::decode(sign_result.as_slice()).unwrap();
-                assert_eq!(expected, ret.signature);
-            }
-            //            let input = TronMessageInput {
-            //                value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
-            //                    .to_string(),
-            //                is_hex: true,
-            //                is_tron_header: true,
-            //            };
-        });
+            let ret = call_api(
+                "keystore_common_derive_addresses",
+                KeystoreCommonDeriveAddressesParam {
+                    discover: true,
+                    used_addresses: vec!["1962gsZ8PoPUYHneFakkCTrukdFMVQ4i4T".to_string()],
+                    ..param.clone()
+                },
+            )
+            .unwrap();
+            let result = KeystoreCommonDeriveAddressesResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(result.addresses.len(), 1);
+            assert_eq!(result.addresses[0].path, "m/44'/0'/0'/0/1");
+            assert_eq!(result.next_address.unwrap().path, "m/44'/0'/0'/0/2");
+
+            let ret = call_api(
+                "keystore_common_derive_addresses",
+                KeystoreCommonDeriveAddressesParam {
+                    chain_type: "POLKADOT".to_string(),
+                    ..param
+                },
+            );
+            assert_eq!(format!("{}", ret.err().unwrap()), "unsupported_chain");
+
+            remove_created_wallet(&import_result.id);
+        })
     }
 
     #[test]
-    fn test_sign_by_dk_hd_store() {
+    pub fn test_watch_keystore() {
         run_test(|| {
+            let param = WatchKeystoreImportParam {
+                public_key: "[1468dba9/84h/0h/0h]zpub6qytVKvhi1obMLUuCvgvVYCnHQbwh9W8NWZJcu9LjYSr8UbKjjCbKEhBQWBdRrfXrtBbFETgvNo6sjKQrCNGouiMNKhmrYrcmm5UL17MzUV".to_string(),
+                password: TEST_PASSWORD.to_string(),
+                name: "watch-wallet".to_string(),
+                password_hint: "".to_string(),
+                overwrite: true,
+                kdf: None,
+            };
+            let ret = call_api("watch_keystore_import", param.clone()).unwrap();
+            let watch_result: WalletResult = WalletResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(watch_result.source, "WATCH");
+
+            let ret = call_api(
+                "watch_keystore_import",
+                WatchKeystoreImportParam {
+                    overwrite: false,
+                    ..param
+                },
+            );
+            assert_eq!(format!("{}", ret.err().unwrap()), "address_already_exist");
+
             let derivation = Derivation {
-                chain_type: "TRON".to_string(),
-                path: "m/44'/195'/0'/0/0".to_string(),
-                network: "".to_string(),
-                seg_wit: "".to_string(),
+                chain_type: "BITCOIN".to_string(),
+                path: "m/84'/0'/0'/0/0".to_string(),
+                network: "MAINNET".to_string(),
+                seg_wit: "SEGWIT".to_string(),
                 chain_id: "".to_string(),
                 curve: "".to_string(),
             };
-            let wallet = import_and_derive(derivation);
-
-            let input = TronMessageInput {
-                value: "645c0b7b58158babbfa6c6cd5a48aa7340a8749176b120e8516216787a13dc76"
-                    .to_string(),
-                is_hex: true,
-                is_tron_header: true,
+            let param = KeystoreCommonDeriveParam {
+                id: watch_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation.clone()],
             };
+            let ret = call_api("keystore_common_derive", param).unwrap();
+            let rsp: AccountsResponse = AccountsResponse::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                rsp.accounts[0].address,
+                "bc1q05ec6z8df2vlzkxjxfd2xr3veypzm93wqnazr2"
+            );
 
-            let dk_param = WalletKeyParam {
-                id: wallet.id.to_string(),
+            let param = ExportPrivateKeyParam {
+                id: watch_result.id.to_string(),
                 password: TEST_PASSWORD.to_string(),
+                chain_type: "BITCOIN".to_string(),
+                network: "MAINNET".to_string(),
+                main_address: rsp.accounts[0].address.to_string(),
+                path: "0/0".to_string(),
             };
+            let ret = call_api("export_private_key", param);
+            assert_eq!(
+                format!("{}", ret.err().unwrap()),
+                "watch_only_keystore_has_no_private_key"
+            );
 
-            let ret_bytes = get_derived_key(&encode_message(dk_param).unwrap()).unwrap();
-            let ret: DerivedKeyResult = DerivedKeyResult::decode(ret_bytes.as_slice()).unwrap();
-
+            let input = BtcForkTxInput {
+                to: "bc1qak0g6t8syjpq36t8z3768sfz7n0uf0lcz7sj8s".to_string(),
+                amount: 50000,
+                unspents: vec![Utxo {
+                    tx_hash: "3dae4e30bcdb618e6d33b5e10c3bb108013ddc83d6e854b335308ddcc34162ad"
+                        .to_string(),
+                    vout: 2,
+                    amount: 100000,
+                    address: rsp.accounts[0].address.to_string(),
+                    script_pub_key: "".to_string(),
+                    derived_path: "0/0".to_string(),
+                    sequence: 0,
+                    has_sequence: false,
+                }],
+                fee: 1000,
+                change_address_index: 0,
+                change_address: "bc1qvhmxeap62tqakcnpnkj8gthrvvj9zncfylp46g".to_string(),
+                network: "MAINNET".to_string(),
+                seg_wit: "SEGWIT".to_string(),
+                ..BtcForkTxInput::default()
+            };
             let tx = SignParam {
-                id: wallet.id.to_string(),
-                key: Some(Key::DerivedKey(ret.derived_key)),
-                chain_type: "TRON".to_string(),
-                address: wallet.accounts.first().unwrap().address.to_string(),
+                id: watch_result.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
                 input: Some(::prost_types::Any {
                     type_url: "imtoken".to_string(),
-                    value: encode_message(input.clone()).unwrap(),
+                    value: encode_message(input).unwrap(),
                 }),
             };
+            let ret = call_api("create_psbt", tx.clone()).unwrap();
+            let output: PsbtOutput = PsbtOutput::decode(ret.as_slice()).unwrap();
+            assert!(!output.is_finalized);
 
-            let sign_result = call_api("tron_sign_msg", tx).unwrap();
-            let ret: TronMessageOutput = TronMessageOutput::decode(sign_result.as_slice()).unwrap();
-            assert_eq!("16417c6489da3a88ef980bf0a42551b9e76181d03e7334548ab3cb36e7622a484482722882a29e2fe4587b95c739a68624ebf9ada5f013a9340d883f03fcf9af1b", ret.signature);
+            let mut param = tx.clone();
+            param.key = None;
+            let ret = call_api("create_psbt", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "password_required");
+
+            let mut param = tx;
+            param.input = None;
+            let ret = call_api("create_psbt", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_tx_input");
+
+            // the hd wallet of the same mnemonic signs the psbt
+            let import_result: WalletResult = import_default_wallet();
+            let param = KeystoreCommonDeriveParam {
+                id: import_result.id.to_string(),
+                password: TEST_PASSWORD.to_string(),
+                derivations: vec![derivation],
+            };
+            call_api("keystore_common_derive", param).unwrap();
 
+            let input = PsbtInput {
+                psbt: output.psbt,
+                auto_finalize: true,
+            };
             let tx = SignParam {
-                id: wallet.id.to_string(),
-                key: Some(Key::DerivedKey("7758c92df76d50774a67fdca6c90b922fc84be68c69164d4c7f500327bfa4b9655709b6b1f88e07e3bda266d7ca4b48c934557917692f63a31e301d79d7107d001".to_string())),
-                chain_type: "TRON".to_string(),
-                address: wallet.accounts.first().unwrap().address.to_string(),
+                id: import_result.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "BITCOIN".to_string(),
+                address: rsp.accounts[0].address.to_string(),
                 input: Some(::prost_types::Any {
                     type_url: "imtoken".to_string(),
                     value: encode_message(input).unwrap(),
                 }),
             };
+            let ret = call_api("sign_psbt", tx).unwrap();
+            let output: PsbtOutput = PsbtOutput::decode(ret.as_slice()).unwrap();
+            assert!(output.is_finalized);
 
-            let ret = call_api("tron_sign_msg", tx);
-            assert!(ret.is_err());
-            assert_eq!("derived_key_not_matched", format!("{}", ret.err().unwrap()));
-
-            remove_created_wallet(&wallet.id);
-        });
+            remove_created_wallet(&watch_result.id);
+            remove_created_wallet(&import_result.id);
+        })
     }
 
     #[test]
     pub fn test_sign_btc_fork_invalid_address() {
         run_test(|| {
-            let chain_types = vec!["BITCOINCASH", "LITECOIN"];
+            let chain_types = vec!["BITCOIN", "BITCOINCASH", "LITECOIN"];
 
             let import_result: WalletResult = import_default_wallet();
 
@@ -2532,6 +4327,7 @@ mod tests {
                         .to_string(),
                     derived_path: "0/0".to_string(),
                     sequence: 0,
+                    has_sequence: false,
                 }];
                 let tx_input = BtcForkTxInput {
                     to: "invalid_address".to_string(),
@@ -2542,6 +4338,12 @@ mod tests {
                     change_address: "".to_string(),
                     network: "TESTNET".to_string(),
                     seg_wit: "NONE".to_string(),
+                    outputs: vec![],
+                    op_return: "".to_string(),
+                    lock_time: 0,
+                    rbf: false,
+                    version: 0,
+                    fee_rate: 0,
                 };
                 let input_value = encode_message(tx_input).unwrap();
                 let tx = SignParam {
@@ -2620,6 +4422,7 @@ mod tests {
             xpub_common_key: "B888D25EC8C12BD5043777B1AC49F872".to_string(),
             xpub_common_iv: "9C0C30889CBCC5E01AB5B2BB88715799".to_string(),
             is_debug: true,
+            signature_self_check: true,
         };
 
         handler::init_token_core_x(&encode_message(param).unwrap()).expect("should init tcx");
@@ -2772,4 +4575,70 @@ mod tests {
             remove_created_wallet(&wallet.id);
         })
     }
+
+    #[test]
+    pub fn test_sign_nano_block() {
+        run_test(|| {
+            let derivation = Derivation {
+                chain_type: "NANO".to_string(),
+                path: "m/44'/165'/0'".to_string(),
+                network: "MAINNET".to_string(),
+                seg_wit: "".to_string(),
+                chain_id: "".to_string(),
+                curve: "".to_string(),
+            };
+
+            let wallet = import_and_derive(derivation);
+            let account = wallet.accounts.first().unwrap();
+            assert_eq!(
+                account.address,
+                "nano_337o1g55munwh9f3piswk6bg8ka9ik5ytxk1m6cagmxf65je95wcamgyyso7"
+            );
+
+            let input = NanoTxInput {
+                previous: "991cf190094c00f0b68e2e5f75f6bee95a2e0bd93ceaa4a6734db9f19b728948"
+                    .to_string(),
+                representative: "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7"
+                    .to_string(),
+                balance: "1000000000000000000000000000000".to_string(),
+                link: "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+                    .to_string(),
+            };
+            let tx = SignParam {
+                id: wallet.id.to_string(),
+                key: Some(Key::Password(TEST_PASSWORD.to_string())),
+                chain_type: "NANO".to_string(),
+                address: account.address.to_string(),
+                input: Some(::prost_types::Any {
+                    type_url: "imtoken".to_string(),
+                    value: encode_message(input).unwrap(),
+                }),
+            };
+
+            let ret = call_api("sign_tx", tx.clone()).unwrap();
+            let output: NanoTxOutput = NanoTxOutput::decode(ret.as_slice()).unwrap();
+            assert_eq!(
+                output.hash,
+                "1e6d8dc5a03205db5dfe7cebfa1caddf1864d596a6e241a400b28a7c85188cc2"
+            );
+            assert_eq!(output.signature, "4bbca1a670a0d4514f932b34511fc8c7d445db62d637343cf7b19f059e8ef170357f3f1a5e4930c0c660711fdfdb2a9ff3ec192e55477e59a407790597224b0d");
+
+            let ret = call_api("decode_tx", tx.clone()).unwrap();
+            let output: DecodeTxResult = DecodeTxResult::decode(ret.as_slice()).unwrap();
+            assert_eq!(output.chain_type, "NANO");
+            assert_eq!(output.senders, vec![account.address.to_string()]);
+            assert_eq!(
+                output.recipients[0].address,
+                "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+            );
+            assert_eq!(output.recipients[0].amount, "");
+
+            let mut param = tx;
+            param.input = None;
+            let ret = call_api("sign_tx", param);
+            assert_eq!(format!("{}", ret.err().unwrap()), "invalid_tx_input");
+
+            remove_created_wallet(&wallet.id);
+        })
+    }
 }